@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use wiremix::config::NameTemplate;
+
+fuzz_target!(|data: &str| {
+    if let Ok(template) = data.parse::<NameTemplate>() {
+        let _ = template.render(|tag| Some(tag.to_string()));
+    }
+});
@@ -0,0 +1,129 @@
+# English text for the wiremix interface, which translations are based on.
+#
+# To translate wiremix, copy this file to locales/<language>.ftl next to the
+# configuration file, such as ~/.config/wiremix/locales/de.ftl or pt_BR.ftl,
+# and change the text after each "=". Keep the message IDs before the "=" and
+# the { $name } placeholders as they are. Messages left out are shown in
+# English.
+
+## Tabs
+
+tab-playback = Playback
+tab-recording = Recording
+tab-output = Output Devices
+tab-input = Input Devices
+tab-configuration = Configuration
+tab-network = Network
+tab-jack = JACK
+
+## Status bar
+
+status-dnd = Do not disturb: { $count } muted
+status-recording = { $marker } Recording: { $clients }
+status-camera = { $marker } Camera: { $clients }
+status-microphone-muted = Microphone muted
+status-recording-macro = Recording macro { $key }
+status-visual = -- VISUAL --
+
+## Dialogs
+
+prompt-cleanup = Unload { $count } modules left loaded by a crashed session? (y/n)
+mic-check-title = Microphone check: { $name }
+mic-check-instructions = Speak into the microphone as loudly as you normally would
+mic-check-peak = Loudest peak: { $dbfs } dBFS
+mic-check-clipping = Clipping!
+mic-check-listening = Listening… { $percent }%
+mic-check-too-quiet = Nothing heard. Is the microphone muted or unplugged?
+mic-check-suggestion = Suggested volume: { $suggested }% (now { $volume }%)
+mic-check-hint = Enter: apply  r: listen again  Esc: cancel
+help-title = Configuration reference
+help-hint = j/k scroll, g/G top/bottom, q close
+wait = Waiting for PipeWire…
+wait-remote = Waiting for PipeWire remote { $remote }…
+wait-hint = Press q to quit
+
+## First-run setup
+
+wizard-title = Setting up wiremix ({ $step } of { $steps })
+wizard-hint = j/k to move, Enter to choose, Esc for the defaults, q to skip
+wizard-theme = Which theme?
+wizard-theme-default = Colors and attributes
+wizard-theme-nocolor = Attributes such as bold and dim, but no color
+wizard-theme-plain = The terminal's default style only
+wizard-theme-highcontrast = Bright, bold colors
+wizard-theme-monochrome = Bold, reversed and underlined text only
+wizard-char-set = Which characters for borders, bars and meters?
+wizard-char-set-default = Symbols some terminals can't render
+wizard-char-set-compat = Symbols most terminals can render
+wizard-char-set-extracompat = ASCII except for dropdown borders
+wizard-char-set-ascii = Plain ASCII
+wizard-input-mode = How should keys work?
+wizard-input-mode-default = Each key does one thing
+wizard-input-mode-vi = vi-style counts, gg/G, search and visual mode
+wizard-monitors = Offer output device monitors as recording inputs?
+wizard-monitors-true = Streams can record what an output device plays
+wizard-monitors-false = Only microphones and other inputs
+wizard-peaks = Which peak meters?
+wizard-peaks-auto = Left and right meters for stereo streams
+wizard-peaks-mono = One meter for every item
+wizard-peaks-off = No meters
+
+## Messages
+
+flash-paused = Paused { $players }
+flash-not-found = Not found: { $query }
+flash-unknown-command = Unknown command: :{ $command }
+flash-unpinned = Unpinned { $stream }
+flash-pinned = Pinned { $stream } to { $device }
+flash-dnd-off = Do not disturb off, unmuted { $count }
+flash-test-stopped = Stopped the test
+flash-testing = Testing { $channels } of { $device }
+flash-measuring = Measuring latency from { $sink } to { $source }…
+flash-latency = Round-trip latency: { $ms } ms
+flash-upmix-on = Upmix on for { $name }
+flash-upmix-off = Upmix off for { $name }
+flash-set-property = Set { $key }={ $value }
+flash-reverted = Reverted { $count } properties
+flash-loaded-module = Loaded { $module }
+flash-unloaded-module = Unloaded { $module }
+flash-copied = Copied { $text }
+flash-exported = Exported to { $path }
+flash-added-rule = Added rule to { $path }, restart to apply it
+flash-recorded-macro = Recorded macro { $key }
+flash-moved-back = Moved { $stream } back to { $device }
+flash-started-microphone = { $client } started using the microphone
+flash-started-camera = { $client } started using the camera
+flash-set-volume = Set { $name } to { $percent }%
+flash-unloaded-modules = Unloaded { $count } modules
+flash-low-battery = Low battery: { $device } { $percent }%
+flash-volume-capped = { $name }: output volume capped at { $percent }%
+flash-volume-uncapped = { $name }: output volume uncapped
+watchdog-silent = { $name } is silent while streams play to it
+port-plugged = { $port } plugged in
+port-unplugged = { $port } unplugged
+default-replaced = { $name } is now the default
+default-restored = Restored { $name } as the default
+
+## Errors
+
+error-no-pro-audio = No Pro Audio profile
+error-no-previous-profile = No previous profile
+error-no-pin-target = { $stream } has no device to pin to
+error-select-output = Select an output device to test
+error-no-positions = No channel positions for { $device }
+error-peaks-off = Peak meters are off
+error-select-input = Select an input device to check
+error-already-measuring = Already measuring latency
+error-no-latency-devices = No output and input device to measure
+error-metering-peaks-off = Peak meters are off, so levels aren't logged
+error-no-upmix = No upmix setting for { $name }
+error-no-metadata = No metadata to write properties to
+error-no-edited-properties = No edited properties
+error-nothing-selected = Nothing selected
+error-export = Failed to export to { $path }: { $error }
+error-no-config-path = No configuration path (set HOME or use --config)
+error-no-macro = No macro { $key }
+error-no-default-source = No default source
+error-no-rtp = No RTP stream { $index }
+error-profile-unchanged = { $device } didn't switch profiles
+error-profile-switch = { $device } couldn't switch profiles: { $error }
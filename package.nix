@@ -16,7 +16,10 @@ rustPlatform.buildRustPackage {
     root = ./.;
     fileset = fs.unions [
       (fs.fileFilter (file: builtins.any file.hasExt [ "rs" ]) ./src)
+      (fs.fileFilter (file: builtins.any file.hasExt [ "rs" ]) ./wiremix-core/src)
+      ./wiremix-core/Cargo.toml
       ./wiremix.toml
+      ./locales
       ./Cargo.lock
       ./Cargo.toml
     ];
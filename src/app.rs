@@ -1,63 +1,166 @@
 //! Main rendering and event processing for the application.
 
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
 use std::sync::mpsc;
 use std::time::{Duration, Instant};
 
-use crate::config::{Config, Peaks};
+use crate::config::{Config, InputMode, Notice, Peaks, StartupSelection};
 use crate::event::MonitorEvent;
 
 use anyhow::{anyhow, Result};
 
 use ratatui::{
-    prelude::{Buffer, Constraint, Direction, Layout, Position, Rect},
+    backend::Backend,
+    prelude::{
+        Alignment, Buffer, Constraint, Direction, Layout, Position, Rect,
+    },
+    style::Modifier,
     text::{Line, Span},
     widgets::{StatefulWidget, Widget},
-    DefaultTerminal, Frame,
+    DefaultTerminal, Frame, Terminal,
 };
 
 use crossterm::event::{
-    Event as CrosstermEvent, KeyEvent, KeyEventKind, MouseButton, MouseEvent,
-    MouseEventKind,
+    Event as CrosstermEvent, KeyCode, KeyEvent, KeyEventKind, KeyEventState,
+    MouseButton, MouseEvent, MouseEventKind,
 };
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use smallvec::{smallvec, SmallVec};
+use unicode_width::UnicodeWidthStr;
 
 use crate::capture_manager::CaptureManager;
-use crate::command::Command;
+use crate::channel_map;
+use crate::clipboard;
+use crate::coalesce::Coalescer;
+use crate::command::{Command, MixCommand};
+use crate::crash;
 use crate::device_kind::DeviceKind;
-use crate::event::Event;
+use crate::dnd::Dnd;
+use crate::ducking::Ducker;
+use crate::ended::EndedStreams;
+use crate::error;
+use crate::event::{self, Event};
+use crate::export::{self, ExportFormat};
+use crate::fade::{Curve, Fade, Fades, FADE_STEP};
+use crate::feedback::Feedback;
+use crate::graph::{self, GraphFormat};
+use crate::help::Help;
+use crate::help_widget::HelpWidget;
+use crate::history::{History, HISTORY_STEP};
+use crate::history_widget::{HistoryWidget, HISTORY_HEIGHT};
+use crate::hook;
+use crate::hotplug::Hotplug;
+use crate::initial_volume::InitialVolumes;
+use crate::input::{ContextStack, KeyContext};
+use crate::latency::{LatencyTest, LATENCY_POLL};
+use crate::linked;
+use crate::loaded_modules::{self, LoadedModule, LoadedModules};
+use crate::macros::{self, Macros};
+use crate::metering::MeteringLog;
+use crate::mic_check::{MicCheck, Suggestion};
+use crate::mic_check_widget::MicCheckWidget;
+use crate::mpris::{self, PausePlayers, PAUSE_POLL};
+use crate::node_widget;
+use crate::notification_group::NotificationGroup;
 use crate::object::ObjectId;
-use crate::object_list::{ObjectList, ObjectListWidget};
+use crate::object_list::{ObjectList, ObjectListWidget, MARQUEE_STEP};
+use crate::optimistic::{Optimistic, Setting};
+use crate::osc;
+use crate::paused::PausedStreams;
+use crate::pins::{self, Pins};
+use crate::ports::Ports;
+use crate::profile_switch::{ProfileSwitches, SPINNER_STEP};
+use crate::property_editor::{self, Property, PropertyEditor};
+use crate::pulse_module;
+use crate::recording::{self, RecordingClients};
+use crate::rule::{self, RuleKind};
+use crate::schedule::{Scheduler, TimeOfDay, SCHEDULE_STEP};
+use crate::session::{self, Session};
+use crate::signals;
+use crate::sleep::{self, Inhibitor, Resume};
 use crate::state::{State, StateDirty};
-use crate::view::{self, ListKind, View};
+use crate::systemd;
+use crate::test_tone::TestTone;
+use crate::vi::{Vi, ViCommand};
+use crate::view::{self, ListKind, View, VolumeAdjustment};
+use crate::watchdog::Watchdog;
 
 #[cfg(feature = "trace")]
 use crate::{trace, trace_dbg};
 
+/// Rate of discrete meter updates in reduced motion mode.
+const REDUCED_MOTION_PEAKS_FPS: f32 = 2.0;
+
+/// How long a flash message stays in the tab bar.
+const FLASH_DURATION: Duration = Duration::from_secs(2);
+
+/// How long to wait for streams muted by do-not-disturb to be unmuted, or
+/// ducked streams to be restored, when exiting.
+const RESTORE_TIMEOUT: Duration = Duration::from_secs(1);
+
 /// A UI action.
 ///
 /// Used internally as the result of input events.
 ///
 /// Also generated by interaction with [`MouseArea`]s.
-#[derive(Debug, Clone, Copy, Deserialize)]
-#[cfg_attr(test, derive(PartialEq))]
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
 pub enum Action {
     SelectTab(usize),
+    SwitchFocus,
+    #[serde(skip)]
+    FocusSplit(bool),
     MoveUp,
     MoveDown,
+    MovePageUp,
+    MovePageDown,
+    MoveToTop,
+    MoveToBottom,
     TabLeft,
     TabRight,
     CloseDropdown,
     ActivateDropdown,
-    #[serde(skip_deserializing)]
+    ChannelMap,
+    ToggleUpmix,
+    EditProperties,
+    RevertProperties,
+    TogglePin,
+    ToggleDnd,
+    TestTone,
+    MicCheck,
+    MeasureLatency,
+    ToggleHistory,
+    Help,
+    #[serde(skip)]
     SelectObject(ObjectId),
-    #[serde(skip_deserializing)]
+    #[serde(skip)]
     SetTarget(view::Target),
     ToggleMute,
     SetAbsoluteVolume(f32),
     SetRelativeVolume(f32),
+    Fade(Fade),
     SetDefault,
+    CycleDefaultSink,
+    CycleDefaultSource,
+    ToggleDefaultSourceMute,
+    ToggleProAudio,
+    ToggleNetworkDiscovery,
+    ToggleZeroconfPublish,
+    ToggleZeroconfDiscover,
+    ToggleRtp(usize),
+    ToggleCompact,
+    CopyName,
+    CopyId,
+    CopyProperties,
+    CopyRule(RuleKind),
+    AppendRule(RuleKind),
+    Export(ExportFormat),
+    ExportGraph(GraphFormat),
+    RecordMacro(char),
+    PlayMacro(char),
+    Suspend,
     Exit,
     // This can be used to delete a default keybinding - make it do nothing.
     Nothing,
@@ -75,7 +178,17 @@ impl Tab {
 }
 
 #[derive(
-    Deserialize, Default, Debug, Clone, Copy, PartialEq, clap::ValueEnum,
+    Deserialize,
+    Serialize,
+    Default,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    clap::ValueEnum,
 )]
 #[serde(rename_all = "lowercase")]
 #[cfg_attr(test, derive(strum::EnumIter))]
@@ -86,12 +199,31 @@ pub enum TabKind {
     Output,
     Input,
     Configuration,
+    Network,
+    Jack,
 }
 
 impl TabKind {
+    /// The number of built-in tabs, which come before any role tabs
+    pub const COUNT: usize = TabKind::Jack as usize + 1;
+
     pub fn index(&self) -> usize {
         *self as usize
     }
+
+    pub fn from_index(index: usize) -> Option<TabKind> {
+        [
+            TabKind::Playback,
+            TabKind::Recording,
+            TabKind::Output,
+            TabKind::Input,
+            TabKind::Configuration,
+            TabKind::Network,
+            TabKind::Jack,
+        ]
+        .get(index)
+        .copied()
+    }
 }
 
 // Mouse events matching one of the MouseEventKinds within the Rect will
@@ -106,8 +238,75 @@ pub type MouseArea =
 pub struct App {
     /// If set, tells the main loop it's time to exit
     exit: bool,
-    /// [`Command`](`crate::command::Command`) channel
-    tx: pipewire::channel::Sender<Command>,
+    /// If set, tells the main loop to stop until it's continued
+    suspend: bool,
+    /// [`MixCommand`](`crate::command::MixCommand`) channel
+    tx: pipewire::channel::Sender<MixCommand>,
+    /// The ID for the next command sent
+    next_command_id: u64,
+    /// Volume and mute changes which haven't been confirmed yet
+    optimistic: Optimistic,
+    /// Volume commands held back to limit the rate of writes
+    coalescer: Coalescer,
+    /// Volumes being ramped by [`Action::Fade`] and ducking
+    fades: Fades,
+    /// Streams lowered by ducking
+    ducker: Ducker,
+    /// The volume of notification sounds, shown as a row in Playback
+    notification_group: NotificationGroup,
+    /// Streams seen, for setting the volume of new ones
+    initial_volumes: InitialVolumes,
+    /// Streams muted by do-not-disturb
+    dnd: Dnd,
+    /// Tones played after volume changes
+    feedback: Feedback,
+    /// Noise played on each channel of an output device
+    test_tone: TestTone,
+    /// The schedule entry in effect
+    scheduler: Scheduler,
+    /// Devices being plugged in or unplugged, for holding back the view
+    hotplug: Hotplug,
+    /// PulseAudio modules loaded by wiremix
+    loaded_modules: LoadedModules,
+    /// Modules left loaded by a session which crashed, while asking whether
+    /// to unload them
+    cleanup_prompt: Option<Vec<LoadedModule>>,
+    /// The microphone level check being shown
+    mic_check: Option<MicCheck>,
+    /// The configuration reference being shown
+    help: Option<Help>,
+    /// Round-trip latency being measured
+    latency_test: LatencyTest,
+    /// Players being paused after their output device disappeared
+    pause_players: PausePlayers,
+    /// Recent peaks and volumes of each node
+    history: History,
+    /// Whether the selected node's history is shown
+    show_history: bool,
+    /// The file levels are logged to
+    metering_log: Option<MeteringLog>,
+    /// Whether the default sink has been silent while streams play to it
+    watchdog: Watchdog,
+    /// Held so that outputs can be muted before the system sleeps
+    inhibitor: Inhibitor,
+    /// What to restore after the system wakes
+    resume: Resume,
+    /// When streams were paused, for hiding them
+    paused: PausedStreams,
+    /// Streams which ended recently, kept in the view for a while
+    ended: EndedStreams,
+    /// Streams pinned to their devices
+    pins: Pins,
+    /// Applications recording, for noticing new ones
+    recording_clients: RecordingClients,
+    /// Applications using a camera, for noticing new ones
+    camera_clients: RecordingClients,
+    /// Devices which have been warned about for having a low battery
+    low_batteries: HashSet<ObjectId>,
+    /// Port availability, for noticing jacks being plugged and unplugged
+    ports: Ports,
+    /// Profile switches which haven't taken effect yet
+    profile_switches: ProfileSwitches,
     /// [`Event`](`crate::event::Event`) channel
     rx: mpsc::Receiver<Event>,
     /// An error message to return on exit
@@ -116,6 +315,10 @@ pub struct App {
     tabs: Vec<Tab>,
     /// The index of the currently-visible tab
     current_tab_index: usize,
+    /// The index of the tab shown beside the current tab in split mode
+    split_tab_index: Option<usize>,
+    /// Whether the split tab has focus rather than the current tab
+    is_split_focused: bool,
     /// Areas populated during rendering which define actions corresponding to
     /// mouse activity
     mouse_areas: Vec<MouseArea>,
@@ -133,135 +336,2160 @@ pub struct App {
     /// button is held down, this is used in place of the real row to allow the
     /// mouse to move on the vertical axis during horizontal dragging.
     drag_row: Option<u16>,
+    /// A brief message shown in the tab bar and when it was set
+    flash: Option<(String, Instant)>,
+    /// Macros recorded at runtime, which take precedence over configured
+    /// macros
+    macros: Macros,
+    /// The key and actions of the macro being recorded
+    recording: Option<(char, Vec<Action>)>,
+    /// A macro is being played, so don't play any others
+    is_playing_macro: bool,
+    /// The profile each device had before switching to Pro Audio
+    previous_profiles: HashMap<ObjectId, i32>,
+    /// The vi command being typed in vi input mode
+    vi: Vi,
+    /// The node property being typed
+    property_editor: Option<PropertyEditor>,
+    /// Properties written by the editor and the values they replaced, in
+    /// the order they were first edited
+    property_edits: Vec<(ObjectId, Property, Option<String>)>,
+    /// Whether the title and notifications are written to the terminal, which
+    /// is only done by [`Self::run()`]
+    writes_terminal: bool,
+    /// The title last set
+    title: Option<String>,
 }
 
 macro_rules! current_list {
-    ($self:expr) => {
-        $self.tabs[$self.current_tab_index].list
-    };
+    ($self:expr) => {{
+        let index = $self.focused_tab_index();
+        &mut $self.tabs[index].list
+    }};
 }
 
 impl App {
     pub fn new(
-        tx: pipewire::channel::Sender<Command>,
+        tx: pipewire::channel::Sender<MixCommand>,
         rx: mpsc::Receiver<Event>,
         config: Config,
     ) -> Self {
-        let tabs = vec![
+        let mut tabs = vec![
             Tab::new(
-                String::from("Playback"),
+                String::from(config.locale.get("tab-playback")),
                 ObjectList::new(ListKind::Node(view::NodeKind::Playback), None),
             ),
             Tab::new(
-                String::from("Recording"),
+                String::from(config.locale.get("tab-recording")),
                 ObjectList::new(
                     ListKind::Node(view::NodeKind::Recording),
                     None,
                 ),
             ),
             Tab::new(
-                String::from("Output Devices"),
+                String::from(config.locale.get("tab-output")),
                 ObjectList::new(
                     ListKind::Node(view::NodeKind::Output),
                     Some(DeviceKind::Sink),
                 ),
             ),
             Tab::new(
-                String::from("Input Devices"),
+                String::from(config.locale.get("tab-input")),
                 ObjectList::new(
                     ListKind::Node(view::NodeKind::Input),
                     Some(DeviceKind::Source),
                 ),
             ),
             Tab::new(
-                String::from("Configuration"),
+                String::from(config.locale.get("tab-configuration")),
                 ObjectList::new(ListKind::Device, None),
             ),
+            Tab::new(
+                String::from(config.locale.get("tab-network")),
+                ObjectList::new(
+                    ListKind::Node(view::NodeKind::Network),
+                    Some(DeviceKind::Sink),
+                ),
+            ),
+            Tab::new(
+                String::from(config.locale.get("tab-jack")),
+                ObjectList::new(ListKind::Node(view::NodeKind::Jack), None),
+            ),
+        ];
+        let columns = [
+            &config.columns.playback,
+            &config.columns.recording,
+            &config.columns.output,
+            &config.columns.input,
         ];
+        for (tab, columns) in tabs.iter_mut().zip(columns) {
+            tab.list.columns = columns.clone();
+        }
+        tabs[TabKind::Jack.index()].list.columns = config.columns.jack.clone();
+        for (index, role_tab) in config.role_tab.iter().enumerate() {
+            let mut list = ObjectList::new(
+                ListKind::Node(view::NodeKind::Role(index)),
+                None,
+            );
+            list.columns = role_tab.columns.clone();
+            tabs.push(Tab::new(role_tab.title.clone(), list));
+        }
+        for tab in tabs.iter_mut() {
+            tab.list.compact = config.compact;
+            tab.list.orientation = config.orientation;
+            tab.list.flat_volume = config.flat_volume;
+        }
+        let (macros, flash) =
+            match config.macros_path.as_deref().map(macros::load) {
+                Some(Ok(macros)) => (macros, None),
+                Some(Err(err)) => (
+                    Macros::new(),
+                    Some((format!("{:#}", err), Instant::now())),
+                ),
+                None => (Macros::new(), None),
+            };
+
         App {
             exit: false,
+            suspend: false,
             tx,
+            next_command_id: 0,
+            optimistic: Default::default(),
+            coalescer: Coalescer::new(config.volume_rate),
+            fades: Fades::default(),
+            ducker: Ducker::default(),
+            notification_group: NotificationGroup::default(),
+            initial_volumes: InitialVolumes::default(),
+            dnd: Dnd::default(),
+            feedback: Feedback::default(),
+            test_tone: TestTone::default(),
+            scheduler: Scheduler::default(),
+            hotplug: Hotplug::default(),
+            loaded_modules: LoadedModules::default(),
+            cleanup_prompt: None,
+            mic_check: None,
+            help: None,
+            latency_test: LatencyTest::default(),
+            pause_players: PausePlayers::default(),
+            history: History::new(config.history),
+            show_history: false,
+            metering_log: None,
+            watchdog: Watchdog::default(),
+            inhibitor: Inhibitor::default(),
+            resume: Resume::default(),
+            paused: PausedStreams::default(),
+            ended: EndedStreams::default(),
+            pins: Pins::default(),
+            recording_clients: RecordingClients::default(),
+            camera_clients: RecordingClients::default(),
+            low_batteries: HashSet::new(),
+            ports: Ports::default(),
+            profile_switches: ProfileSwitches::default(),
             rx,
             error_message: None,
             tabs,
             current_tab_index: config.tab.index(),
+            split_tab_index: config.split.map(|split| split.index()),
+            is_split_focused: false,
             mouse_areas: Vec::new(),
             is_ready: false,
-            state: State::default(),
+            state: State {
+                hold_peaks: config.reduced_motion,
+                ..Default::default()
+            },
             capture_manager: CaptureManager::default(),
             view: View::default(),
             config,
             drag_row: None,
+            flash,
+            macros,
+            recording: None,
+            is_playing_macro: false,
+            previous_profiles: HashMap::new(),
+            vi: Vi::default(),
+            property_editor: None,
+            property_edits: Vec::new(),
+            writes_terminal: false,
+            title: None,
+        }
+    }
+
+    pub fn run(mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        #[cfg(feature = "trace")]
+        trace::initialize_logging()?;
+
+        self.writes_terminal = true;
+        if !self.config.osc.title.is_empty() {
+            osc::push_title();
+        }
+
+        // Wait until we've received all initial data from PipeWire
+        let _ = terminal.draw(|frame| {
+            frame.render_widget(Line::from("Initializing..."), frame.area());
+        });
+        while !self.exit && !self.is_ready {
+            let _ = self.handle_events(None);
+        }
+
+        self.view = View::from(&self.state, &self.config.names);
+        self.view.set_role_tabs(&self.config.role_tab);
+        if !self.config.monitors {
+            self.view.hide_monitors();
+        }
+        if self.config.notification_group {
+            for command in self.notification_group.update(&self.view) {
+                self.send(command);
+            }
+            self.notification_group.add_to(&mut self.view);
+        }
+        let session = self.load_session();
+        if let Some(session) =
+            session.as_ref().filter(|_| self.config.restore_session)
+        {
+            self.restore_session(session);
+        }
+        self.select_startup_object(session.as_ref());
+        self.take_sleep_inhibitor();
+        self.check_loaded_modules();
+        self.open_metering_log();
+
+        let mut pacer = RenderPacer::new(self.config.fps);
+        // Paces discrete meter updates in reduced motion mode
+        let mut peaks_pacer = RenderPacer::new(Some(REDUCED_MOTION_PEAKS_FPS));
+
+        // Did we handle any events and thus need to re-render?
+        let mut needs_render = true;
+
+        while !self.exit {
+            if signals::take_terminate() {
+                break;
+            }
+            if std::mem::take(&mut self.suspend) || signals::take_suspend() {
+                signals::suspend(terminal, &self.config)?;
+                needs_render = true;
+            } else if signals::take_resumed() {
+                signals::resume(terminal, &self.config)?;
+                needs_render = true;
+            }
+
+            if self
+                .flash
+                .as_ref()
+                .is_some_and(|(_, time)| time.elapsed() >= FLASH_DURATION)
+            {
+                self.flash = None;
+                needs_render = true;
+            }
+
+            if self.optimistic.expire(&mut self.state, Instant::now()) {
+                needs_render = true;
+            }
+
+            let hide_paused_after =
+                self.config.hide_paused_after.map(Duration::from_secs_f32);
+            if let Some(timeout) = hide_paused_after {
+                let until_hide =
+                    self.paused.until_next_hide(timeout, Instant::now());
+                if until_hide == Some(Duration::ZERO) {
+                    self.state.dirty = StateDirty::Everything;
+                    needs_render = true;
+                }
+            }
+
+            let keep_ended_for =
+                self.config.keep_ended_for.map(Duration::from_secs_f32);
+            if let Some(keep_for) = keep_ended_for {
+                let until_expiry =
+                    self.ended.until_next_expiry(keep_for, Instant::now());
+                if until_expiry == Some(Duration::ZERO) {
+                    self.state.dirty = StateDirty::Everything;
+                    needs_render = true;
+                }
+            }
+
+            self.step_volumes();
+            self.apply_schedule();
+
+            // Update view if needed
+            match self.state.dirty {
+                // Held back while a hotplugged device settles
+                StateDirty::Everything
+                    if !self.hotplug.is_settling(Instant::now()) =>
+                {
+                    self.rebuild_view(hide_paused_after);
+                }
+                StateDirty::PeaksOnly
+                    if !self.config.reduced_motion
+                        || peaks_pacer.is_time_to_render() =>
+                {
+                    self.view.update_peaks(&self.state);
+                    self.state.dirty = StateDirty::Clean;
+                    if self.config.reduced_motion {
+                        self.state.reset_held_peaks();
+                    }
+                }
+                // Peaks are held until it's time for the next discrete update
+                _ => {}
+            }
+            self.update_mic_check();
+            self.history.sample(&self.view, Instant::now());
+            self.log_metering();
+            self.check_silence();
+
+            if !self.profile_switches.is_empty() {
+                self.update_profile_switches();
+                needs_render = true;
+            }
+
+            if let Some(result) = self.latency_test.poll() {
+                self.report_latency(result);
+                needs_render = true;
+            }
+
+            if let Some(result) = self.pause_players.poll() {
+                match result {
+                    Ok(players) if players.is_empty() => {}
+                    Ok(players) => self.flash(self.config.locale.format(
+                        "flash-paused",
+                        &[("players", &players.join(", "))],
+                    )),
+                    Err(err) => self.flash(format!("{:#}", err)),
+                }
+                needs_render = true;
+            }
+
+            #[cfg(feature = "trace")]
+            trace_dbg!(&self.view);
+
+            if needs_render && pacer.is_time_to_render() {
+                needs_render = false;
+
+                self.mouse_areas.clear();
+
+                terminal.draw(|frame| self.render(frame))?;
+            }
+
+            // Keep rendering periodically while titles might be scrolling.
+            let animating = self.config.marquee && !self.config.reduced_motion;
+            let timeout = [
+                // If there's no fps limit, we definitely rendered in this
+                // iteration, so needs_render is false, and there is no timeout.
+                needs_render.then_some(pacer.duration_until_next_frame()),
+                animating.then_some(MARQUEE_STEP),
+                self.flash.as_ref().map(|(_, time)| {
+                    FLASH_DURATION.saturating_sub(time.elapsed())
+                }),
+                self.coalescer.until_next_flush(Instant::now()),
+                self.optimistic.until_next_expiry(Instant::now()),
+                (!self.fades.is_empty()).then_some(FADE_STEP),
+                (!self.profile_switches.is_empty()).then_some(SPINNER_STEP),
+                self.latency_test.is_running().then_some(LATENCY_POLL),
+                self.pause_players.is_running().then_some(PAUSE_POLL),
+                self.show_history.then_some(HISTORY_STEP),
+                self.metering_log.as_ref().map(|_| {
+                    Duration::from_secs_f32(self.config.metering.interval)
+                }),
+                self.config.watchdog.silent_after.and_then(|after| {
+                    let after = Duration::from_secs_f32(after);
+                    self.watchdog.until_warning(after, Instant::now())
+                }),
+                (!self.config.schedule.is_empty()).then_some(SCHEDULE_STEP),
+                self.hotplug.until_settled(Instant::now()),
+                hide_paused_after.and_then(|timeout| {
+                    self.paused.until_next_hide(timeout, Instant::now())
+                }),
+                keep_ended_for.and_then(|keep_for| {
+                    self.ended.until_next_expiry(keep_for, Instant::now())
+                }),
+            ]
+            .into_iter()
+            .flatten()
+            .min();
+            needs_render |=
+                self.handle_events(timeout)? || animating || self.show_history;
+        }
+
+        self.shut_down();
+        if self.uses_session() {
+            self.save_session();
+        }
+        if self.title.is_some() {
+            osc::pop_title();
+        }
+
+        self.error_message.map_or(Ok(()), |s| Err(anyhow!(s)))
+    }
+
+    /// Run without an interface, only applying the automation such as
+    /// replacing lost defaults, ducking and hooks. Messages which would be
+    /// flashed are printed to stderr instead.
+    pub fn run_daemon(mut self) -> Result<()> {
+        // Nothing is shown, so peaks are only captured for the watchdog
+        if self.config.watchdog.silent_after.is_none() {
+            self.config.peaks = Peaks::Off;
+        }
+
+        while !self.exit && !self.is_ready {
+            let _ = self.handle_events(None);
+        }
+        self.view = View::from(&self.state, &self.config.names);
+        self.take_sleep_inhibitor();
+        if let Err(err) = systemd::notify("READY=1") {
+            eprintln!("Couldn't notify systemd: {}", err);
+        }
+
+        while !self.exit {
+            if signals::take_terminate() {
+                break;
+            }
+            self.optimistic.expire(&mut self.state, Instant::now());
+            self.step_volumes();
+            self.apply_schedule();
+            match self.state.dirty {
+                StateDirty::Everything
+                    if self.hotplug.is_settling(Instant::now()) => {}
+                StateDirty::Everything => self.rebuild_view(None),
+                StateDirty::PeaksOnly => {
+                    self.view.update_peaks(&self.state);
+                    self.state.dirty = StateDirty::Clean;
+                }
+                StateDirty::Clean => {}
+            }
+            self.check_silence();
+            if let Some((message, _)) = self.flash.take() {
+                eprintln!("{}", message);
+            }
+
+            let timeout = [
+                self.coalescer.until_next_flush(Instant::now()),
+                self.optimistic.until_next_expiry(Instant::now()),
+                (!self.fades.is_empty()).then_some(FADE_STEP),
+                self.config.watchdog.silent_after.and_then(|after| {
+                    let after = Duration::from_secs_f32(after);
+                    self.watchdog.until_warning(after, Instant::now())
+                }),
+                (!self.config.schedule.is_empty()).then_some(SCHEDULE_STEP),
+                self.hotplug.until_settled(Instant::now()),
+            ]
+            .into_iter()
+            .flatten()
+            .min();
+            self.handle_events(timeout)?;
+        }
+
+        let _ = systemd::notify("STOPPING=1");
+        self.shut_down();
+        self.error_message.map_or(Ok(()), |s| Err(anyhow!(s)))
+    }
+
+    /// Apply the schedule entry which takes effect now, if any.
+    fn apply_schedule(&mut self) {
+        let Some(index) = self
+            .scheduler
+            .update(&self.config.schedule, TimeOfDay::now())
+        else {
+            return;
+        };
+        let entry = &self.config.schedule[index];
+        let name = entry.name.clone().unwrap_or_else(|| entry.at.to_string());
+        let message = match entry.max_volume {
+            Some(max_volume) => self.config.locale.format(
+                "flash-volume-capped",
+                &[("name", &name), ("percent", &(max_volume * 100.0).round())],
+            ),
+            None => self
+                .config
+                .locale
+                .format("flash-volume-uncapped", &[("name", &name)]),
+        };
+        self.flash(message);
+        self.cap_volumes();
+    }
+
+    /// Fade output devices above the scheduled `max_volume` down to it.
+    fn cap_volumes(&mut self) {
+        let Some(entry) = self
+            .scheduler
+            .current()
+            .and_then(|index| self.config.schedule.get(index))
+        else {
+            return;
+        };
+        let Some(max_volume) = entry.max_volume else {
+            return;
+        };
+        let fade = Fade {
+            volume: max_volume,
+            duration_ms: entry.fade_ms,
+            curve: Curve::EaseInOut,
+        };
+        let now = Instant::now();
+        for node in self.view.nodes.values() {
+            if !node.media_class.is_sink() || self.fades.is_fading(node.id) {
+                continue;
+            }
+            match node.volume() {
+                Some(volume) if volume > max_volume + f32::EPSILON => {
+                    self.fades.start(node.id, volume, fade, now);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Send the volumes of fades in progress and any held back volume
+    /// commands which are due.
+    fn step_volumes(&mut self) {
+        for (node_id, volume) in self.fades.step(Instant::now()) {
+            let adjustment = VolumeAdjustment::Absolute(volume);
+            if let Some(command) = self.view.volume(node_id, adjustment) {
+                self.send(command);
+            }
+        }
+
+        for command in self.coalescer.flush(Instant::now()) {
+            self.optimistic.on_sent(command.id, Instant::now());
+            let _ = self.tx.send(command);
+        }
+    }
+
+    /// Rebuild the view after the state has changed and apply the automation
+    /// which depends on it.
+    fn rebuild_view(&mut self, hide_paused_after: Option<Duration>) {
+        let previous = std::mem::replace(
+            &mut self.view,
+            View::from(&self.state, &self.config.names),
+        );
+        if let Some(timeout) = hide_paused_after {
+            self.paused.update(&mut self.view, timeout, Instant::now());
+        }
+        if let Some(keep_for) = self.config.keep_ended_for {
+            self.ended.update(
+                &previous,
+                &mut self.view,
+                Duration::from_secs_f32(keep_for),
+                Instant::now(),
+            );
+        }
+        self.view.set_role_tabs(&self.config.role_tab);
+        if !self.config.monitors {
+            self.view.hide_monitors();
+        }
+        if self.config.notification_group {
+            for command in self.notification_group.update(&self.view) {
+                self.send(command);
+            }
+            self.notification_group.add_to(&mut self.view);
+        }
+        self.replace_lost_defaults(&previous);
+        self.pause_disconnected_players(&previous);
+        self.restore_after_wake();
+        for (stream_id, target_id) in self.pins.update(&mut self.view) {
+            for command in pins::commands(&self.view, stream_id, target_id) {
+                self.send(command);
+            }
+            let titles = [stream_id, target_id]
+                .map(|id| self.view.nodes.get(&id).map(|node| &node.title));
+            if let [Some(stream), Some(target)] = titles {
+                let message = self.config.locale.format(
+                    "flash-moved-back",
+                    &[("stream", stream), ("device", target)],
+                );
+                self.flash(message);
+            }
+        }
+        for command in self.initial_volumes.update(
+            &self.config.initial_volume,
+            &self.state,
+            &self.view,
+        ) {
+            self.send(command);
+        }
+        let fades =
+            self.ducker
+                .update(&self.config.ducking, &self.state, &self.view);
+        for (node_id, from, fade) in fades {
+            self.fades.start(node_id, from, fade, Instant::now());
+        }
+        self.cap_volumes();
+        for node_id in
+            self.dnd.update(&self.config.dnd, &self.state, &self.view)
+        {
+            if let Some(command) = self.view.set_mute(node_id, true) {
+                self.send(command);
+            }
+        }
+        self.warn_low_batteries();
+        self.report_port_changes();
+        let microphone = self
+            .recording_clients
+            .update(&self.view.recording_clients())
+            .into_iter()
+            .map(|client| ("microphone", client));
+        let camera = self
+            .camera_clients
+            .update(&self.view.camera_clients)
+            .into_iter()
+            .map(|client| ("camera", client));
+        let started: Vec<_> = microphone.chain(camera).collect();
+        for (kind, client) in &started {
+            let id = match *kind {
+                "camera" => "flash-started-camera",
+                _ => "flash-started-microphone",
+            };
+            let message = self.config.locale.format(id, &[("client", client)]);
+            self.notify(Notice::Recording, &message);
+        }
+        if let Some(command) = &self.config.recording.on_start {
+            let failed = started.iter().find_map(|(kind, client)| {
+                recording::on_start(command, kind, client).err()
+            });
+            if let Some(err) = failed {
+                self.flash(format!("{:#}", err));
+            }
+        }
+        self.update_title();
+        crash::record_state(&self.state, &self.view);
+        self.state.dirty = StateDirty::Clean;
+    }
+
+    /// Set the terminal title from the default sink if it has changed.
+    fn update_title(&mut self) {
+        if !self.writes_terminal || self.config.osc.title.is_empty() {
+            return;
+        }
+        let Some(view::Target::Node(node_id)) = self.view.default_sink else {
+            return;
+        };
+        let Some(node) = self.view.nodes.get(&node_id) else {
+            return;
+        };
+        let title = self.config.osc.title.render_node(&self.state, node);
+        if self.title.as_ref() != Some(&title) {
+            osc::set_title(&title);
+            self.title = Some(title);
+        }
+    }
+
+    /// Whether the session is saved on exit and loaded on launch.
+    fn uses_session(&self) -> bool {
+        self.config.restore_session
+            || self.config.startup_selection == StartupSelection::Last
+    }
+
+    /// Load the session saved on the last exit, flashing any error.
+    fn load_session(&mut self) -> Option<Session> {
+        if !self.uses_session() {
+            return None;
+        }
+        match session::load(self.config.session_path.as_deref()?) {
+            Ok(session) => Some(session),
+            Err(err) => {
+                self.flash(format!("{:#}", err));
+                None
+            }
+        }
+    }
+
+    /// Restore each tab's selection and the display toggles from a session.
+    fn restore_session(&mut self, session: &Session) {
+        for (tab, name) in &session.selected {
+            self.tabs[tab.index()].list.select_name(&self.view, name);
+        }
+        self.is_split_focused =
+            self.split_tab_index.is_some() && session.split_focused;
+        if let Some(compact) = session.compact {
+            self.config.compact = compact;
+            for tab in self.tabs.iter_mut() {
+                tab.list.compact = compact;
+            }
+        }
+    }
+
+    /// Select the object configured by `startup_selection` and show its tab.
+    fn select_startup_object(&mut self, session: Option<&Session>) {
+        let (tab, name) = match self.config.startup_selection {
+            StartupSelection::First => return,
+            StartupSelection::DefaultSink => (TabKind::Output, None),
+            StartupSelection::DefaultSource => (TabKind::Input, None),
+            StartupSelection::Last => {
+                let Some(tab) = session.and_then(|session| session.tab) else {
+                    return;
+                };
+                (tab, session.and_then(|session| session.selected.get(&tab)))
+            }
+        };
+
+        let list = &mut self.tabs[tab.index()].list;
+        match name {
+            Some(name) => {
+                list.select_name(&self.view, name);
+            }
+            None => {
+                let default = match tab {
+                    TabKind::Output => self.view.default_sink,
+                    _ => self.view.default_source,
+                };
+                if let Some(view::Target::Node(object_id)) = default {
+                    list.selected = Some(object_id);
+                }
+            }
+        }
+
+        if self.split_tab_index == Some(tab.index()) {
+            self.is_split_focused = true;
+        } else {
+            self.is_split_focused = false;
+            self.current_tab_index = tab.index();
+        }
+    }
+
+    /// Save the focused tab, each tab's selected object and the display
+    /// toggles for the next launch.
+    fn save_session(&mut self) {
+        let selected = self
+            .tabs
+            .iter()
+            .enumerate()
+            .filter_map(|(index, tab)| {
+                let name = tab.list.selected_name(&self.view)?;
+                Some((TabKind::from_index(index)?, String::from(name)))
+            })
+            .collect();
+        let session = Session {
+            tab: TabKind::from_index(self.focused_tab_index()),
+            selected,
+            split_focused: self.is_split_focused,
+            compact: Some(self.config.compact),
+        };
+        if let Some(path) = self.config.session_path.as_deref() {
+            if let Err(err) = session::save(path, &session) {
+                self.error_message.get_or_insert(format!("{:#}", err));
+            }
+        }
+    }
+
+    /// Handle the events received so far and draw a single frame, as the main
+    /// loop would. This is for testing rendering without a real terminal.
+    pub fn draw_received<B: Backend>(
+        &mut self,
+        terminal: &mut Terminal<B>,
+    ) -> Result<()> {
+        while let Ok(event) = self.rx.try_recv() {
+            event.handle(self)?;
+        }
+        self.rebuild_view(None);
+        terminal.draw(|frame| self.render(frame))?;
+        Ok(())
+    }
+
+    /// Lay out the visible lists for the frame and draw it.
+    fn render(&mut self, frame: &mut Frame) {
+        self.tabs[self.current_tab_index]
+            .list
+            .update(frame.area(), &self.view);
+        if let Some(split_tab_index) = self.split_tab_index {
+            self.tabs[split_tab_index]
+                .list
+                .update(frame.area(), &self.view);
+        }
+
+        self.draw(frame);
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let flash = self
+            .cleanup_prompt
+            .as_ref()
+            .map(|leftovers| {
+                self.config
+                    .locale
+                    .format("prompt-cleanup", &[("count", &leftovers.len())])
+            })
+            .or_else(|| {
+                self.property_editor.as_ref().map(PropertyEditor::prompt)
+            })
+            .or_else(|| self.flash.as_ref().map(|(message, _)| message.clone()))
+            .or_else(|| {
+                self.recording.as_ref().map(|(key, _)| {
+                    self.config
+                        .locale
+                        .format("status-recording-macro", &[("key", key)])
+                })
+            })
+            .or_else(|| self.vi.prompt())
+            .or_else(|| {
+                self.tabs[self.focused_tab_index()].list.visual.map(|_| {
+                    String::from(self.config.locale.get("status-visual"))
+                })
+            });
+        let selected = self.tabs[self.focused_tab_index()].list.selected;
+        let widget = AppWidget {
+            current_tab_index: self.current_tab_index,
+            split_tab_index: self.split_tab_index,
+            is_split_focused: self.is_split_focused,
+            flash: flash.as_deref(),
+            dnd: self.dnd.is_active().then(|| self.dnd.muted_count()),
+            history: self
+                .show_history
+                .then(|| {
+                    selected.and_then(|node_id| self.view.nodes.get(&node_id))
+                })
+                .flatten()
+                .map(|node| (node, &self.history)),
+            view: &self.view,
+            config: &self.config,
+        };
+        let mut widget_state = AppWidgetState {
+            mouse_areas: &mut self.mouse_areas,
+            tabs: &mut self.tabs,
+        };
+
+        frame.render_stateful_widget(widget, frame.area(), &mut widget_state);
+
+        if let Some(mic_check) = &self.mic_check {
+            if let Some(node) = self.view.nodes.get(&mic_check.node_id) {
+                let widget = MicCheckWidget::new(
+                    mic_check,
+                    node,
+                    &self.config,
+                    Instant::now(),
+                );
+                frame.render_widget(widget, frame.area());
+            }
+        }
+
+        if let Some(help) = &mut self.help {
+            let widget = HelpWidget::new(&self.config);
+            frame.render_stateful_widget(widget, frame.area(), help);
+        }
+
+        if self.config.reduced_motion {
+            for cell in frame.buffer_mut().content.iter_mut() {
+                cell.modifier
+                    .remove(Modifier::SLOW_BLINK | Modifier::RAPID_BLINK);
+            }
+        }
+    }
+
+    /// Returns the index of the tab which has focus.
+    fn focused_tab_index(&self) -> usize {
+        match self.split_tab_index {
+            Some(split_tab_index) if self.is_split_focused => split_tab_index,
+            _ => self.current_tab_index,
+        }
+    }
+
+    /// The contexts whose keybindings apply to the next key press.
+    fn key_contexts(&self) -> ContextStack {
+        let mut contexts = ContextStack::default();
+        contexts.push(KeyContext::List);
+        if self.tabs[self.focused_tab_index()].list.is_dropdown_open() {
+            contexts.push(KeyContext::Dropdown);
+        }
+        contexts
+    }
+
+    /// Handle the action bound to a key, recording it if a macro is being
+    /// recorded.
+    fn handle_key(&mut self, key: KeyEvent) -> Result<bool> {
+        let contexts = self.key_contexts();
+        let Some(action) = self.config.keybindings.get(&contexts, &key) else {
+            return Ok(false);
+        };
+        if let Some((_, actions)) = &mut self.recording {
+            if !matches!(action, Action::RecordMacro(_) | Action::PlayMacro(_))
+            {
+                actions.push(action);
+            }
+        }
+        action.handle(self)
+    }
+
+    /// Handle a command completed in vi input mode.
+    fn handle_vi_command(&mut self, command: ViCommand) -> Result<bool> {
+        match command {
+            ViCommand::Action(action) => {
+                if let Some((_, actions)) = &mut self.recording {
+                    actions.push(action);
+                }
+                return action.handle(self);
+            }
+            ViCommand::Key(key) => return self.handle_key(key),
+            ViCommand::Search { query, forward } => {
+                if !current_list!(self).search(&self.view, &query, forward) {
+                    self.flash(
+                        self.config
+                            .locale
+                            .format("flash-not-found", &[("query", &query)]),
+                    );
+                }
+            }
+            ViCommand::ToggleVisual => {
+                let list = current_list!(self);
+                list.visual = match list.visual {
+                    Some(_) => None,
+                    None => list.selected,
+                };
+            }
+            ViCommand::ExitVisual => current_list!(self).visual = None,
+            ViCommand::Command(command) => {
+                match command.trim() {
+                    "help" | "help config" => self.help = Some(Help::default()),
+                    _ => self.flash(self.config.locale.format(
+                        "flash-unknown-command",
+                        &[("command", &command)],
+                    )),
+                }
+            }
+        }
+        Ok(true)
+    }
+
+    /// Returns a mutable reference to the index of the tab which has focus.
+    fn focused_tab_index_mut(&mut self) -> &mut usize {
+        match self.split_tab_index {
+            Some(ref mut split_tab_index) if self.is_split_focused => {
+                split_tab_index
+            }
+            _ => &mut self.current_tab_index,
+        }
+    }
+
+    /// Make the next sink or source in priority order the default and flash
+    /// its name.
+    fn cycle_default(&mut self, device_kind: DeviceKind) {
+        let priorities = match device_kind {
+            DeviceKind::Sink => &self.config.priorities.sinks,
+            DeviceKind::Source => &self.config.priorities.sources,
+        };
+        let Some(node_id) = self.view.next_default(device_kind, priorities)
+        else {
+            return;
+        };
+        if let Some(command) = self.view.set_default(node_id, device_kind) {
+            self.send(command);
+        }
+        if let Some(node) = self.view.nodes.get(&node_id) {
+            self.flash(node.title.clone());
+        }
+    }
+
+    /// Switch the selected device to its Pro Audio profile, or back to the
+    /// profile it had before.
+    fn toggle_pro_audio(&mut self) {
+        let Some(device_id) = current_list!(self).selected else {
+            return;
+        };
+        let Some(device) = self.view.devices.get(&device_id) else {
+            return;
+        };
+        let Some(pro_audio_profile) = device.pro_audio_profile else {
+            self.flash(String::from(
+                self.config.locale.get("error-no-pro-audio"),
+            ));
+            return;
+        };
+        let profile_index = match device.target {
+            Some(view::Target::Profile(_, index)) => Some(index),
+            _ => None,
+        };
+
+        let profile_index = if profile_index == Some(pro_audio_profile) {
+            let Some(previous) = self.previous_profiles.remove(&device_id)
+            else {
+                self.flash(String::from(
+                    self.config.locale.get("error-no-previous-profile"),
+                ));
+                return;
+            };
+            previous
+        } else {
+            if let Some(profile_index) = profile_index {
+                self.previous_profiles.insert(device_id, profile_index);
+            }
+            pro_audio_profile
+        };
+
+        let title = device
+            .profiles
+            .iter()
+            .find(|(target, _)| {
+                *target == view::Target::Profile(device_id, profile_index)
+            })
+            .map(|(_, title)| title.clone());
+        self.send(Command::DeviceSetProfile(device_id, profile_index));
+        if let Some(title) = title {
+            self.flash(title);
+        }
+    }
+
+    /// Pin the selected stream to its device, or unpin it.
+    fn toggle_pin(&mut self) {
+        let Some(stream_id) = current_list!(self).selected else {
+            return;
+        };
+        let Some(stream) = self.view.nodes.get(&stream_id) else {
+            return;
+        };
+        let stream_title = stream.title.clone();
+        if self.pins.unpin(stream_id) {
+            self.flash(
+                self.config
+                    .locale
+                    .format("flash-unpinned", &[("stream", &stream_title)]),
+            );
+            return;
+        }
+        let Some(target_id) = self.pins.pin(&self.view, stream_id) else {
+            self.flash(
+                self.config.locale.format(
+                    "error-no-pin-target",
+                    &[("stream", &stream_title)],
+                ),
+            );
+            return;
+        };
+        for command in pins::commands(&self.view, stream_id, target_id) {
+            self.send(command);
+        }
+        if let Some(target) = self.view.nodes.get(&target_id) {
+            let message = self.config.locale.format(
+                "flash-pinned",
+                &[("stream", &stream_title), ("device", &target.title)],
+            );
+            self.flash(message);
+        }
+    }
+
+    /// Turn do-not-disturb on or off. Turning it off unmutes the streams it
+    /// muted.
+    fn toggle_dnd(&mut self) {
+        if !self.dnd.is_active() {
+            self.dnd.start();
+            // Mute the streams which are already present
+            self.state.dirty = StateDirty::Everything;
+            return;
+        }
+        let unmuted = self.stop_dnd();
+        self.flash(
+            self.config
+                .locale
+                .format("flash-dnd-off", &[("count", &unmuted.len())]),
+        );
+    }
+
+    /// Turn do-not-disturb off and unmute the streams it muted, returning
+    /// the IDs of the commands sent.
+    fn stop_dnd(&mut self) -> Vec<u64> {
+        let commands: Vec<_> = self
+            .dnd
+            .stop()
+            .into_iter()
+            .filter_map(|node_id| self.view.set_mute(node_id, false))
+            .collect();
+        commands
+            .into_iter()
+            .map(|command| self.send(command))
+            .collect()
+    }
+
+    /// Undo the changes which only last while wiremix runs, and keep the
+    /// record of modules left loaded, before exiting.
+    fn shut_down(&mut self) {
+        self.restore_dnd();
+        self.restore_ducked();
+        if self.loaded_modules.pid.take().is_some() {
+            self.save_loaded_modules();
+        }
+    }
+
+    /// Put ducked streams back at their volumes before exiting, waiting
+    /// briefly for the changes to be made.
+    fn restore_ducked(&mut self) {
+        let mut pending = Vec::new();
+        for (node_id, volume) in self.ducker.restore() {
+            self.fades.cancel(node_id);
+            let adjustment = VolumeAdjustment::Absolute(volume);
+            if let Some(command) = self.view.volume(node_id, adjustment) {
+                pending.push(self.send(command));
+            }
+        }
+        for command in self.coalescer.drain() {
+            let _ = self.tx.send(command);
+        }
+        self.wait_for_results(pending, RESTORE_TIMEOUT);
+    }
+
+    /// Unmute the streams muted by do-not-disturb before exiting, waiting
+    /// briefly for the changes to be made.
+    fn restore_dnd(&mut self) {
+        if !self.dnd.is_active() {
+            return;
+        }
+        let pending = self.stop_dnd();
+        self.wait_for_results(pending, RESTORE_TIMEOUT);
+    }
+
+    /// Handle events until the commands with the given IDs have been carried
+    /// out or `timeout` has passed.
+    fn wait_for_results(&mut self, pending: Vec<u64>, timeout: Duration) {
+        let mut pending: HashSet<u64> = pending.into_iter().collect();
+        let deadline = Instant::now() + timeout;
+        while !pending.is_empty() {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            let Ok(event) = self.rx.recv_timeout(timeout) else {
+                break;
+            };
+            if let Event::CommandResult(id, _) = event {
+                pending.remove(&id);
+            }
+            let _ = event.handle(self);
+        }
+    }
+
+    /// Hold a delay inhibitor for sleep so outputs can be muted first.
+    fn take_sleep_inhibitor(&mut self) {
+        if !self.config.sleep.mute {
+            return;
+        }
+        if let Err(err) = self.inhibitor.take() {
+            self.flash(format!("{:#}", err));
+        }
+    }
+
+    /// Remember the default devices and mute the outputs before the system
+    /// sleeps, then let it sleep.
+    fn prepare_for_sleep(&mut self) {
+        if self.config.sleep.restore_defaults {
+            self.resume.defaults = [
+                (DeviceKind::Sink, self.view.default_sink),
+                (DeviceKind::Source, self.view.default_source),
+            ]
+            .into_iter()
+            .filter_map(|(device_kind, default)| match default? {
+                view::Target::Node(node_id) => {
+                    let node = self.view.nodes.get(&node_id)?;
+                    Some((device_kind, node.name.clone()))
+                }
+                _ => None,
+            })
+            .collect();
+        }
+        if self.config.sleep.mute {
+            let outputs: Vec<_> = self
+                .view
+                .nodes
+                .values()
+                .filter(|node| node.media_class.is_sink() && !node.mute)
+                .filter_map(|node| {
+                    let command = self.view.set_mute(node.id, true)?;
+                    Some((node.name.clone(), command))
+                })
+                .collect();
+            let mut pending = Vec::new();
+            for (name, command) in outputs {
+                self.resume.muted.push(name);
+                pending.push(self.send(command));
+            }
+            self.wait_for_results(pending, sleep::MUTE_TIMEOUT);
+        }
+        self.inhibitor.release();
+    }
+
+    /// Start restoring what was changed before sleeping.
+    fn wake(&mut self) {
+        self.resume.woke = Some(Instant::now());
+        self.take_sleep_inhibitor();
+        self.state.dirty = StateDirty::Everything;
+    }
+
+    /// Unmute the outputs muted before sleeping and set the default devices
+    /// back as they reappear after waking.
+    fn restore_after_wake(&mut self) {
+        let Some(woke) = self.resume.woke else {
+            return;
+        };
+        if woke.elapsed() > sleep::WAKE_GRACE {
+            self.resume = Resume::default();
+            return;
+        }
+
+        let mut commands = Vec::new();
+        let view = &self.view;
+        let find = |name: &str| view.nodes.values().find(|n| n.name == name);
+        self.resume.muted.retain(|name| {
+            let Some(node) = find(name) else {
+                return true;
+            };
+            if node.mute {
+                commands.extend(view.set_mute(node.id, false));
+            }
+            false
+        });
+        let mut restored = Vec::new();
+        self.resume.defaults.retain(|(device_kind, name)| {
+            let Some(node) = find(name) else {
+                return true;
+            };
+            let default = match device_kind {
+                DeviceKind::Sink => view.default_sink,
+                DeviceKind::Source => view.default_source,
+            };
+            if default != Some(view::Target::Node(node.id)) {
+                commands.extend(view.set_default(node.id, *device_kind));
+                restored.push(node.title.clone());
+            }
+            false
+        });
+        if self.resume.muted.is_empty() && self.resume.defaults.is_empty() {
+            self.resume.woke = None;
+        }
+
+        for command in commands {
+            self.send(command);
+        }
+        for title in restored {
+            let message = self
+                .config
+                .locale
+                .format("default-restored", &[("name", &title)]);
+            self.notice(Notice::Default, message);
+        }
+    }
+
+    /// Play noise on each channel of the selected output device in turn, or
+    /// stop the test if it's playing.
+    fn test_tone(&mut self) {
+        if self.test_tone.is_playing() {
+            self.test_tone.stop();
+            self.flash(String::from(
+                self.config.locale.get("flash-test-stopped"),
+            ));
+            return;
+        }
+        let Some(node) = current_list!(self)
+            .selected
+            .and_then(|node_id| self.view.nodes.get(&node_id))
+            .filter(|node| node.media_class.is_sink())
+        else {
+            self.flash(String::from(
+                self.config.locale.get("error-select-output"),
+            ));
+            return;
+        };
+        let Some(positions) = node.positions.clone() else {
+            let message = self
+                .config
+                .locale
+                .format("error-no-positions", &[("device", &node.title)]);
+            self.flash(message);
+            return;
+        };
+        let message = self.config.locale.format(
+            "flash-testing",
+            &[
+                ("channels", &channel_map::names(&positions)),
+                ("device", &node.title),
+            ],
+        );
+        match self.test_tone.play(node.object_serial, &positions) {
+            Ok(()) => self.flash(message),
+            Err(err) => self.flash(format!("{:#}", err)),
+        }
+    }
+
+    /// Show the level check for the selected input device, or the default
+    /// one.
+    fn start_mic_check(&mut self) {
+        if self.config.peaks == Peaks::Off {
+            self.flash(String::from(self.config.locale.get("error-peaks-off")));
+            return;
+        }
+        let node_id = current_list!(self)
+            .selected
+            .and_then(|node_id| self.view.nodes.get(&node_id))
+            .filter(|node| node.media_class.is_source())
+            .or_else(|| self.view.default_source_node())
+            .map(|node| node.id);
+        match node_id {
+            Some(node_id) => {
+                self.mic_check = Some(MicCheck::new(node_id, Instant::now()));
+            }
+            None => self.flash(String::from(
+                self.config.locale.get("error-select-input"),
+            )),
+        }
+    }
+
+    /// Measure the round-trip latency from the selected output device to the
+    /// default input device, or from the default output device to the
+    /// selected input device.
+    fn measure_latency(&mut self) {
+        if self.latency_test.is_running() {
+            self.flash(String::from(
+                self.config.locale.get("error-already-measuring"),
+            ));
+            return;
+        }
+        let selected = current_list!(self)
+            .selected
+            .and_then(|node_id| self.view.nodes.get(&node_id));
+        let sink = selected
+            .filter(|node| node.media_class.is_sink())
+            .or_else(|| self.view.default_sink_node());
+        let source = selected
+            .filter(|node| node.media_class.is_source())
+            .or_else(|| self.view.default_source_node());
+        let (Some(sink), Some(source)) = (sink, source) else {
+            self.flash(String::from(
+                self.config.locale.get("error-no-latency-devices"),
+            ));
+            return;
+        };
+        let message = self.config.locale.format(
+            "flash-measuring",
+            &[("sink", &sink.title), ("source", &source.title)],
+        );
+        self.latency_test
+            .start(sink.object_serial, source.object_serial);
+        self.flash(message);
+    }
+
+    fn report_latency(&mut self, result: Result<Duration>) {
+        match result {
+            Ok(latency) => self.flash(self.config.locale.format(
+                "flash-latency",
+                &[("ms", &format!("{:.1}", latency.as_secs_f64() * 1000.0))],
+            )),
+            Err(err) => self.flash(format!("{:#}", err)),
+        }
+    }
+
+    /// Start logging levels if a file is configured.
+    fn open_metering_log(&mut self) {
+        if self.config.metering.path.is_none() {
+            return;
+        }
+        if self.config.peaks == Peaks::Off {
+            self.flash(String::from(
+                self.config.locale.get("error-metering-peaks-off"),
+            ));
+            return;
+        }
+        match MeteringLog::open(&self.config.metering) {
+            Ok(metering_log) => self.metering_log = metering_log,
+            Err(err) => self.flash(format!("{:#}", err)),
+        }
+    }
+
+    /// Log levels if they're due, stopping if the file can't be written.
+    fn log_metering(&mut self) {
+        let Some(metering_log) = &mut self.metering_log else {
+            return;
+        };
+        let result =
+            metering_log.log(&self.config.metering, &self.view, Instant::now());
+        if let Err(err) = result {
+            self.metering_log = None;
+            self.flash(format!("{:#}", err));
+        }
+    }
+
+    /// Warn and run `on_silent` if the default sink has been silent for too
+    /// long while streams play to it.
+    fn check_silence(&mut self) {
+        let Some(after) = self.config.watchdog.silent_after else {
+            return;
+        };
+        let after = Duration::from_secs_f32(after);
+        let Some(sink_id) =
+            self.watchdog.update(&self.view, after, Instant::now())
+        else {
+            return;
+        };
+        let Some(sink) = self.view.nodes.get(&sink_id) else {
+            return;
+        };
+        let name = sink.name.clone();
+        let message = self
+            .config
+            .locale
+            .format("watchdog-silent", &[("name", &sink.title)]);
+        self.notice(Notice::Silent, message);
+
+        let Some(command) = &self.config.watchdog.on_silent else {
+            return;
+        };
+        let env = [("WIREMIX_SINK", name.as_str())];
+        if let Err(err) = hook::spawn("on_silent", command, &env) {
+            self.flash(format!("{:#}", err));
+        }
+    }
+
+    /// Note the peaks of the source being checked, closing the check if it
+    /// went away.
+    fn update_mic_check(&mut self) {
+        let Some(mic_check) = &mut self.mic_check else {
+            return;
+        };
+        match self.view.nodes.get(&mic_check.node_id) {
+            Some(node) => {
+                mic_check.update(node.peaks.as_deref().unwrap_or(&[]))
+            }
+            None => self.mic_check = None,
+        }
+    }
+
+    /// Handle a key while the level check is shown. Enter sets the suggested
+    /// volume and r listens again. Any other key closes the check.
+    fn mic_check_key(&mut self, key: KeyEvent) {
+        let Some(mic_check) = &mut self.mic_check else {
+            return;
+        };
+        let now = Instant::now();
+        match key.code {
+            KeyCode::Char('r') => mic_check.restart(now),
+            KeyCode::Enter => {
+                let node_id = mic_check.node_id;
+                let Some(node) = self.view.nodes.get(&node_id) else {
+                    self.mic_check = None;
+                    return;
+                };
+                let volume = node.volume().unwrap_or_default();
+                let Suggestion::Volume(suggested) =
+                    mic_check.suggestion(volume, now)
+                else {
+                    // Nothing to apply yet
+                    return;
+                };
+                let title = node.title.clone();
+                self.mic_check = None;
+                self.cancel_fades();
+                let adjustment = VolumeAdjustment::Absolute(suggested);
+                if let Some(command) = self.view.volume(node_id, adjustment) {
+                    self.send(command);
+                }
+                self.flash(self.config.locale.format(
+                    "flash-set-volume",
+                    &[
+                        ("name", &title),
+                        ("percent", &(suggested * 100.0).round()),
+                    ],
+                ));
+            }
+            _ => self.mic_check = None,
+        }
+    }
+
+    /// Turn the selected sink's upmixing on or off and flash the result.
+    fn toggle_upmix(&mut self) {
+        let Some(node_id) = current_list!(self).selected else {
+            return;
+        };
+        if !current_list!(self).list_kind().is_node() {
+            return;
+        }
+        let Some(title) =
+            self.view.nodes.get(&node_id).map(|node| node.title.clone())
+        else {
+            return;
+        };
+        let Some((command, upmix)) = self.view.toggle_upmix(node_id) else {
+            self.flash(
+                self.config
+                    .locale
+                    .format("error-no-upmix", &[("name", &title)]),
+            );
+            return;
+        };
+        let id = if upmix {
+            "flash-upmix-on"
+        } else {
+            "flash-upmix-off"
+        };
+        let message = self.config.locale.format(id, &[("name", &title)]);
+        self.send(command);
+        self.flash(message);
+    }
+
+    /// The value of a property in the default metadata for a node.
+    fn metadata_property(
+        &self,
+        node_id: ObjectId,
+        key: &str,
+    ) -> Option<String> {
+        self.state
+            .metadatas
+            .get(&self.view.metadata_id?)?
+            .properties
+            .get(&u32::from(node_id))?
+            .get(key)
+            .cloned()
+    }
+
+    /// Open a dropdown of the selected node's editable properties.
+    fn edit_properties(&mut self) {
+        if !current_list!(self).list_kind().is_node() {
+            return;
+        }
+        let Some(node_id) = current_list!(self).selected else {
+            return;
+        };
+        let targets = Property::ALL
+            .iter()
+            .map(|&property| {
+                let value = self
+                    .metadata_property(node_id, property.key())
+                    .unwrap_or_else(|| String::from("unset"));
+                let title = format!("{}: {}", property.key(), value);
+                (view::Target::Property(property), title)
+            })
+            .collect();
+        current_list!(self).dropdown_open_with(targets, 0);
+    }
+
+    /// Start typing a value for a property of the selected node.
+    fn start_property_editor(&mut self, property: Property) {
+        current_list!(self).dropdown_close();
+        let Some(node_id) = current_list!(self).selected else {
+            return;
+        };
+        let previous = self.metadata_property(node_id, property.key());
+        self.property_editor =
+            Some(PropertyEditor::new(node_id, property, previous));
+    }
+
+    /// Write a property typed in the editor, keeping the value it replaced.
+    fn write_property(&mut self, editor: PropertyEditor, value: String) {
+        let Some(metadata_id) = self.view.metadata_id else {
+            self.flash(String::from(
+                self.config.locale.get("error-no-metadata"),
+            ));
+            return;
+        };
+        let PropertyEditor {
+            node_id,
+            property,
+            previous,
+            ..
+        } = editor;
+        if !self
+            .property_edits
+            .iter()
+            .any(|&(id, edited, _)| id == node_id && edited == property)
+        {
+            self.property_edits.push((node_id, property, previous));
+        }
+        self.send(Command::MetadataSetProperty(
+            metadata_id,
+            node_id.into(),
+            String::from(property.key()),
+            property.type_().map(String::from),
+            Some(value.clone()),
+        ));
+        self.flash(self.config.locale.format(
+            "flash-set-property",
+            &[("key", &property.key()), ("value", &value)],
+        ));
+    }
+
+    /// Restore the properties of the selected node written by the editor.
+    fn revert_properties(&mut self) {
+        let Some(node_id) = current_list!(self).selected else {
+            return;
+        };
+        let Some(metadata_id) = self.view.metadata_id else {
+            return;
+        };
+        let (reverted, kept) = std::mem::take(&mut self.property_edits)
+            .into_iter()
+            .partition::<Vec<_>, _>(|&(id, _, _)| id == node_id);
+        self.property_edits = kept;
+        if reverted.is_empty() {
+            self.flash(String::from(
+                self.config.locale.get("error-no-edited-properties"),
+            ));
+            return;
+        }
+
+        let count = reverted.len();
+        for (node_id, property, previous) in reverted {
+            // Properties which weren't set before are removed
+            let type_ = previous.as_ref().and(property.type_());
+            self.send(Command::MetadataSetProperty(
+                metadata_id,
+                node_id.into(),
+                String::from(property.key()),
+                type_.map(String::from),
+                previous,
+            ));
+        }
+        self.flash(
+            self.config
+                .locale
+                .format("flash-reverted", &[("count", &count)]),
+        );
+    }
+
+    /// Load or unload a PulseAudio module and flash the result.
+    fn toggle_module(&mut self, name: &str, args: &[&str]) {
+        match pulse_module::toggle(name, args) {
+            Ok(Some(index)) => {
+                self.loaded_modules.add(index, name, args);
+                self.flash(
+                    self.config
+                        .locale
+                        .format("flash-loaded-module", &[("module", &name)]),
+                );
+            }
+            Ok(None) => {
+                self.loaded_modules.remove(name, args);
+                self.flash(
+                    self.config
+                        .locale
+                        .format("flash-unloaded-module", &[("module", &name)]),
+                );
+            }
+            Err(err) => self.flash(format!("{:#}", err)),
+        }
+        self.save_loaded_modules();
+    }
+
+    /// Read the record of loaded modules and mark this session as running.
+    /// If the last session crashed, the modules it left loaded are offered
+    /// for unloading.
+    fn check_loaded_modules(&mut self) {
+        let Some(path) = loaded_modules::default_path() else {
+            return;
+        };
+        let previous = match loaded_modules::load(&path) {
+            Ok(previous) => previous,
+            Err(err) => {
+                self.flash(format!("{:#}", err));
+                return;
+            }
+        };
+        let crashed = previous.crashed();
+        // Forget modules which have been unloaded since
+        let (leftovers, kept): (Vec<_>, Vec<_>) = previous
+            .modules
+            .into_iter()
+            .filter(|module| {
+                pulse_module::loaded(&module.name, &module.args())
+                    .is_ok_and(|indices| indices.contains(&module.index))
+            })
+            .partition(|_| crashed);
+        self.loaded_modules = LoadedModules {
+            pid: Some(std::process::id() as i32),
+            modules: kept,
+        };
+        if !leftovers.is_empty() {
+            self.cleanup_prompt = Some(leftovers);
+        }
+        self.save_loaded_modules();
+    }
+
+    fn save_loaded_modules(&mut self) {
+        let Some(path) = loaded_modules::default_path() else {
+            return;
+        };
+        if let Err(err) = loaded_modules::save(&path, &self.loaded_modules) {
+            self.flash(format!("{:#}", err));
+        }
+    }
+
+    /// Unload the modules left by a session which crashed.
+    fn unload_leftovers(&mut self, leftovers: Vec<LoadedModule>) {
+        let count = leftovers.len();
+        for module in leftovers {
+            if let Err(err) = pulse_module::unload(module.index) {
+                self.flash(format!("{:#}", err));
+                return;
+            }
+        }
+        self.flash(
+            self.config
+                .locale
+                .format("flash-unloaded-modules", &[("count", &count)]),
+        );
+    }
+
+    /// Copy text about the selected object to the clipboard.
+    fn copy(&mut self, text: Option<String>) {
+        let Some(text) = text else {
+            self.flash(String::from(
+                self.config.locale.get("error-nothing-selected"),
+            ));
+            return;
+        };
+        match clipboard::copy(&text) {
+            Ok(()) => {
+                let first_line = text.lines().next().unwrap_or_default();
+                let more = if text.lines().nth(1).is_some() {
+                    "…"
+                } else {
+                    ""
+                };
+                let text = format!("{}{}", first_line, more);
+                self.flash(
+                    self.config
+                        .locale
+                        .format("flash-copied", &[("text", &text)]),
+                )
+            }
+            Err(err) => self.flash(format!("{:#}", err)),
+        }
+    }
+
+    /// Write the contents of the focused tab to a file.
+    fn export(&mut self, format: ExportFormat) {
+        let tab = TabKind::from_index(self.focused_tab_index());
+        let path = self.config.export_path.clone().unwrap_or_else(|| {
+            let tab = tab.map_or(String::new(), |tab| {
+                format!("-{:?}", tab).to_lowercase()
+            });
+            PathBuf::from(format!("wiremix{}.{}", tab, format.extension()))
+        });
+        let list_kind = current_list!(self).list_kind();
+        let text = export::export(&self.view, list_kind, format);
+        match fs::write(&path, text) {
+            Ok(()) => self.flash(
+                self.config
+                    .locale
+                    .format("flash-exported", &[("path", &path.display())]),
+            ),
+            Err(err) => self.flash(self.config.locale.format(
+                "error-export",
+                &[("path", &path.display()), ("error", &err)],
+            )),
+        }
+    }
+
+    /// Add a rule made from an object to the end of the configuration file.
+    fn append_rule(&mut self, object_id: ObjectId, kind: RuleKind) {
+        let Some(path) = self.config.path.clone() else {
+            let message = self.config.locale.get("error-no-config-path");
+            self.flash(String::from(message));
+            return;
+        };
+        let result = rule::snippet(&self.view, object_id, kind)
+            .and_then(|snippet| rule::append(&path, &snippet));
+        match result {
+            Ok(()) => self.flash(
+                self.config
+                    .locale
+                    .format("flash-added-rule", &[("path", &path.display())]),
+            ),
+            Err(err) => self.flash(format!("{:#}", err)),
+        }
+    }
+
+    /// Write the nodes and links to a file in the current directory.
+    fn export_graph(&mut self, format: GraphFormat) {
+        let path =
+            PathBuf::from(format!("wiremix-graph.{}", format.extension()));
+        let text = graph::graph(&self.state, &self.view, format);
+        match fs::write(&path, text) {
+            Ok(()) => self.flash(
+                self.config
+                    .locale
+                    .format("flash-exported", &[("path", &path.display())]),
+            ),
+            Err(err) => self.flash(self.config.locale.format(
+                "error-export",
+                &[("path", &path.display()), ("error", &err)],
+            )),
+        }
+    }
+
+    /// Show a message in the tab bar for a moment.
+    fn flash(&mut self, message: String) {
+        self.flash = Some((message, Instant::now()));
+    }
+
+    /// Flash a message, and send it as a notification if `notice` is one of
+    /// the kinds configured in `osc.notify`.
+    fn notice(&mut self, notice: Notice, message: String) {
+        self.notify(notice, &message);
+        self.flash(message);
+    }
+
+    /// Send a notification through the terminal if `notice` is one of the
+    /// kinds configured in `osc.notify`.
+    fn notify(&self, notice: Notice, message: &str) {
+        if self.writes_terminal && self.config.osc.notify.contains(&notice) {
+            osc::notify(self.config.osc.protocol, message);
+        }
+    }
+
+    /// Start recording a macro for key, or stop and save the macro being
+    /// recorded.
+    fn toggle_macro_recording(&mut self, key: char) {
+        let Some((key, actions)) = self.recording.take() else {
+            self.recording = Some((key, Vec::new()));
+            return;
+        };
+
+        self.macros.insert(key.to_string(), actions);
+        let saved = self
+            .config
+            .macros_path
+            .as_deref()
+            .map_or(Ok(()), |path| macros::save(path, &self.macros));
+        match saved {
+            Ok(()) => self.flash(
+                self.config
+                    .locale
+                    .format("flash-recorded-macro", &[("key", &key)]),
+            ),
+            Err(err) => self.flash(format!("{:#}", err)),
+        }
+    }
+
+    /// Perform the actions of the macro for key.
+    fn play_macro(&mut self, key: char) {
+        if self.is_playing_macro {
+            return;
+        }
+        let Some(actions) = self
+            .macros
+            .get(&key.to_string())
+            .or_else(|| self.config.macros.get(&key.to_string()))
+            .cloned()
+        else {
+            self.flash(
+                self.config
+                    .locale
+                    .format("error-no-macro", &[("key", &key)]),
+            );
+            return;
+        };
+
+        self.is_playing_macro = true;
+        for action in actions {
+            let _ = action.handle(self);
+        }
+        self.is_playing_macro = false;
+    }
+
+    /// Pause the players of streams which were playing to an output device
+    /// that has disappeared, if `pause_on_disconnect` is set.
+    fn pause_disconnected_players(&mut self, previous: &View) {
+        if !self.config.pause_on_disconnect {
+            return;
+        }
+        let view = &self.view;
+        let lost: Vec<ObjectId> = previous
+            .nodes
+            .values()
+            .filter(|node| {
+                // A device switching profiles removes its sinks but remains
+                node.media_class.is_sink()
+                    && !view.nodes.contains_key(&node.id)
+                    && node.device_info.map_or(true, |(device_id, ..)| {
+                        !view.devices.contains_key(&device_id)
+                    })
+            })
+            .map(|node| node.id)
+            .collect();
+        if lost.is_empty() {
+            return;
+        }
+
+        let state = &self.state;
+        let clients: Vec<mpris::Client> = previous
+            .nodes
+            .values()
+            .filter(|node| {
+                node.media_class.is_sink_input()
+                    && !node.paused
+                    && matches!(
+                        node.target,
+                        Some(view::Target::Node(target)) if lost.contains(&target)
+                    )
+            })
+            .filter_map(|node| {
+                let client_id = state.nodes.get(&node.id)?.client_id?;
+                let client = state.clients.get(&client_id)?;
+                Some(mpris::Client {
+                    process_id: client
+                        .application_process_id
+                        .as_deref()
+                        .and_then(|pid| pid.parse().ok()),
+                    binary: client.application_process_binary.clone(),
+                })
+            })
+            .collect();
+        if !clients.is_empty() {
+            self.pause_players.start(clients);
         }
     }
 
-    pub fn run(mut self, terminal: &mut DefaultTerminal) -> Result<()> {
-        #[cfg(feature = "trace")]
-        trace::initialize_logging()?;
-
-        // Wait until we've received all initial data from PipeWire
-        let _ = terminal.draw(|frame| {
-            frame.render_widget(Line::from("Initializing..."), frame.area());
-        });
-        while !self.exit && !self.is_ready {
-            let _ = self.handle_events(None);
+    /// If the default sink or source in the previous view has been removed,
+    /// make the highest-priority remaining one the default.
+    fn replace_lost_defaults(&mut self, previous: &View) {
+        let priorities = &self.config.priorities;
+        let mut commands = Vec::new();
+        let mut replacements = Vec::new();
+        for (device_kind, default, priorities) in [
+            (DeviceKind::Sink, previous.default_sink, &priorities.sinks),
+            (
+                DeviceKind::Source,
+                previous.default_source,
+                &priorities.sources,
+            ),
+        ] {
+            let Some(view::Target::Node(node_id)) = default else {
+                continue;
+            };
+            if self.view.nodes.contains_key(&node_id) {
+                continue;
+            }
+            let Some(node) = self
+                .view
+                .preferred_default(device_kind, priorities)
+                .and_then(|node_id| self.view.nodes.get(&node_id))
+            else {
+                continue;
+            };
+            if let Some(command) = self.view.set_default(node.id, device_kind) {
+                commands.push(command);
+            }
+            replacements.push(node.title.clone());
         }
+        for command in commands {
+            self.send(command);
+        }
+        for title in replacements {
+            let message = self
+                .config
+                .locale
+                .format("default-replaced", &[("name", &title)]);
+            self.notice(Notice::Default, message);
+        }
+    }
 
-        let mut pacer = RenderPacer::new(self.config.fps);
-
-        // Did we handle any events and thus need to re-render?
-        let mut needs_render = true;
+    /// Start fading the marked nodes of the focused list.
+    fn fade(&mut self, fade: Fade) {
+        let now = Instant::now();
+        for node_id in current_list!(self).marked(&self.view) {
+            let Some(from) =
+                self.view.nodes.get(&node_id).and_then(|node| node.volume())
+            else {
+                continue;
+            };
+            self.fades.start(node_id, from, fade, now);
+        }
+    }
 
-        while !self.exit {
-            // Update view if needed
-            match self.state.dirty {
-                StateDirty::Everything => {
-                    self.view = View::from(&self.state, &self.config.names);
-                }
-                StateDirty::PeaksOnly => {
-                    self.view.update_peaks(&self.state);
-                }
-                _ => {}
-            }
-            self.state.dirty = StateDirty::Clean;
+    /// Stop fading the marked nodes because their volume is being set, and
+    /// keep the new volume after ducking.
+    fn cancel_fades(&mut self) {
+        for node_id in current_list!(self).marked(&self.view) {
+            self.fades.cancel(node_id);
+            self.ducker.forget(node_id);
+        }
+    }
 
-            #[cfg(feature = "trace")]
-            trace_dbg!(&self.view);
+    /// Play a tone on the output device whose volume was just changed, if
+    /// `volume_feedback` is enabled.
+    fn play_feedback(&mut self) {
+        if !self.config.volume_feedback {
+            return;
+        }
+        let Some(object_serial) = current_list!(self)
+            .marked(&self.view)
+            .into_iter()
+            .filter_map(|node_id| self.view.nodes.get(&node_id))
+            .find(|node| node.media_class.is_sink())
+            .map(|node| node.object_serial)
+        else {
+            return;
+        };
+        if let Err(err) = self.feedback.play(object_serial, Instant::now()) {
+            self.flash(format!("{:#}", err));
+        }
+    }
 
-            if needs_render && pacer.is_time_to_render() {
-                needs_render = false;
+    /// Send a command, and the same change for any endpoints linked with the
+    /// one it's for. Returns the ID of the command.
+    fn send(&mut self, command: Command) -> u64 {
+        // There's nothing left to send it to
+        if self.ended.is_for_ended(&command) {
+            return 0;
+        }
+        if let Some(commands) = self
+            .notification_group
+            .commands(&self.view, &command)
+            .filter(|_| self.config.notification_group)
+        {
+            self.notification_group.add_to(&mut self.view);
+            return commands
+                .into_iter()
+                .map(|command| self.send(command))
+                .last()
+                .unwrap_or_default();
+        }
+        let linked =
+            linked::commands(&self.view, &self.config.linked, &command);
+        let id = self.send_unlinked(command);
+        for command in linked {
+            self.send_unlinked(command);
+        }
+        id
+    }
 
-                self.mouse_areas.clear();
+    fn send_unlinked(&mut self, command: Command) -> u64 {
+        let id = self.next_command_id;
+        self.next_command_id += 1;
+        self.optimistic.on_command(&mut self.state, id, &command);
+        let setting = Setting::volumes(&command);
+        let command = MixCommand { id, command };
+        let command = match setting {
+            Some(setting) => {
+                self.coalescer.push(setting, command, Instant::now())
+            }
+            None => Some(command),
+        };
+        if let Some(command) = command {
+            if let Command::DeviceSetProfile(device_id, profile_index) =
+                command.command
+            {
+                self.profile_switches.start(
+                    device_id,
+                    profile_index,
+                    id,
+                    Instant::now(),
+                );
+            }
+            self.optimistic.on_sent(id, Instant::now());
+            let _ = self.tx.send(command);
+        }
+        id
+    }
 
-                terminal.draw(|frame| {
-                    current_list!(self).update(frame.area(), &self.view);
+    /// Flash a warning when a device's battery level falls to
+    /// `low_battery_warning`, once until it charges.
+    fn warn_low_batteries(&mut self) {
+        let low: HashSet<_> = self
+            .state
+            .devices
+            .values()
+            .filter(|device| {
+                device.battery.is_some_and(|battery| {
+                    node_widget::is_low_battery(battery, &self.config)
+                })
+            })
+            .map(|device| device.id)
+            .collect();
+        let newly_low: Vec<_> = low
+            .iter()
+            .filter(|id| !self.low_batteries.contains(id))
+            .filter_map(|id| {
+                let title = &self.view.devices.get(id)?.title;
+                let battery = self.state.devices.get(id)?.battery?;
+                Some(self.config.locale.format(
+                    "flash-low-battery",
+                    &[("device", title), ("percent", &battery)],
+                ))
+            })
+            .collect();
+        for warning in newly_low {
+            self.notice(Notice::Battery, warning);
+        }
+        self.low_batteries = low;
+    }
 
-                    self.draw(frame);
-                })?;
+    /// Flash a message and run `on_port_change` for each port which became
+    /// available or unavailable, such as a headphone jack.
+    fn report_port_changes(&mut self) {
+        for change in self.ports.update(&self.state) {
+            let id = if change.available {
+                "port-plugged"
+            } else {
+                "port-unplugged"
+            };
+            let message = self
+                .config
+                .locale
+                .format(id, &[("port", &change.description)]);
+            self.notice(Notice::Port, message);
+
+            let Some(command) = &self.config.on_port_change else {
+                continue;
+            };
+            let device = self
+                .view
+                .devices
+                .get(&change.device_id)
+                .map(|device| device.title.as_str())
+                .unwrap_or_default();
+            let available = if change.available { "1" } else { "0" };
+            let env = [
+                ("WIREMIX_DEVICE", device),
+                ("WIREMIX_PORT", change.description.as_str()),
+                ("WIREMIX_PORT_AVAILABLE", available),
+            ];
+            if let Err(err) = hook::spawn("on_port_change", command, &env) {
+                self.flash(format!("{:#}", err));
             }
+        }
+    }
 
-            needs_render |= self.handle_events(
-                // If there's no fps limit, we definitely rendered in this
-                // iteration, so needs_render is false, and there is no timeout.
-                needs_render.then_some(pacer.duration_until_next_frame()),
-            )?;
+    /// Report profile switches which have timed out, and show the spinners of
+    /// devices still switching.
+    fn update_profile_switches(&mut self) {
+        let now = Instant::now();
+        for device_id in self.profile_switches.update(&self.state, now) {
+            let title = self.device_title(device_id);
+            self.flash(
+                self.config
+                    .locale
+                    .format("error-profile-unchanged", &[("device", &title)]),
+            );
+        }
+        for device in self.view.devices.values_mut() {
+            device.switching = self.profile_switches.frame(device.id, now);
         }
+    }
 
-        self.error_message.map_or(Ok(()), |s| Err(anyhow!(s)))
+    /// Report an error from an object, such as why a device couldn't switch
+    /// profiles.
+    fn object_error(&mut self, object_id: ObjectId, message: String) {
+        if event::is_ignored_error(&message) {
+            return;
+        }
+        if self.profile_switches.on_error(object_id) {
+            let title = self.device_title(object_id);
+            self.flash(self.config.locale.format(
+                "error-profile-switch",
+                &[("device", &title), ("error", &message)],
+            ));
+        } else {
+            self.flash(message);
+        }
     }
 
-    fn draw(&mut self, frame: &mut Frame) {
-        let widget = AppWidget {
-            current_tab_index: self.current_tab_index,
-            view: &self.view,
-            config: &self.config,
-        };
-        let mut widget_state = AppWidgetState {
-            mouse_areas: &mut self.mouse_areas,
-            tabs: &mut self.tabs,
-        };
+    fn device_title(&self, device_id: ObjectId) -> String {
+        self.view
+            .devices
+            .get(&device_id)
+            .map(|device| device.title.clone())
+            .unwrap_or_else(|| device_id.to_string())
+    }
 
-        frame.render_stateful_widget(widget, frame.area(), &mut widget_state);
+    /// Mute or unmute the default source, whichever tab is open.
+    fn toggle_default_source_mute(&mut self) {
+        let Some(node) = self.view.default_source_node() else {
+            self.flash(String::from(
+                self.config.locale.get("error-no-default-source"),
+            ));
+            return;
+        };
+        if let Some(command) = self.view.mute(node.id) {
+            self.send(command);
+        }
     }
 
     fn exit(&mut self, error_message: Option<String>) {
@@ -274,15 +2502,23 @@ impl App {
     fn handle_events(&mut self, timeout: Option<Duration>) -> Result<bool> {
         let mut were_events_handled = match timeout {
             Some(timeout) => match self.rx.recv_timeout(timeout) {
-                Ok(event) => event.handle(self)?,
+                Ok(event) => {
+                    crash::record_event(&event);
+                    event.handle(self)?
+                }
                 Err(mpsc::RecvTimeoutError::Timeout) => return Ok(false),
                 Err(e) => return Err(e.into()),
             },
             // Block on the next event.
-            None => self.rx.recv()?.handle(self)?,
+            None => {
+                let event = self.rx.recv()?;
+                crash::record_event(&event);
+                event.handle(self)?
+            }
         };
         // Then handle the rest that are available.
         while let Ok(event) = self.rx.try_recv() {
+            crash::record_event(&event);
             were_events_handled |= event.handle(self)?;
         }
 
@@ -342,10 +2578,33 @@ impl Handle for Event {
             Event::Input(event) => event.handle(app),
             Event::Monitor(event) => event.handle(app),
             Event::Error(event) => event.handle(app),
+            Event::CommandResult(_, Ok(())) => Ok(false),
+            Event::CommandResult(id, Err(error)) => {
+                app.optimistic.on_failure(&mut app.state, id);
+                match app.profile_switches.on_failure(id) {
+                    Some(device_id) => {
+                        let title = app.device_title(device_id);
+                        app.flash(app.config.locale.format(
+                            "error-profile-switch",
+                            &[("device", &title), ("error", &error)],
+                        ));
+                    }
+                    None => app.flash(error.to_string()),
+                }
+                Ok(true)
+            }
             Event::Ready => {
                 app.is_ready = true;
                 Ok(true)
             }
+            Event::Sleep(true) => {
+                app.prepare_for_sleep();
+                Ok(true)
+            }
+            Event::Sleep(false) => {
+                app.wake();
+                Ok(true)
+            }
         }
     }
 }
@@ -366,12 +2625,61 @@ impl Handle for KeyEvent {
         if self.kind != KeyEventKind::Press {
             return Ok(false);
         }
+        // The kitty keyboard protocol reports Caps Lock and Num Lock, which
+        // would stop keys from matching their bindings
+        let key = KeyEvent {
+            state: KeyEventState::NONE,
+            ..self
+        };
+
+        if let Some(leftovers) = app.cleanup_prompt.take() {
+            if key.code == KeyCode::Char('y') {
+                app.unload_leftovers(leftovers);
+            } else {
+                // Keep them in the record for unloading by hand
+                app.loaded_modules.modules.extend(leftovers);
+                app.save_loaded_modules();
+            }
+            return Ok(true);
+        }
+
+        if let Some(help) = &mut app.help {
+            if !help.key(key) {
+                app.help = None;
+            }
+            return Ok(true);
+        }
+
+        if app.mic_check.is_some() {
+            app.mic_check_key(key);
+            return Ok(true);
+        }
+
+        if let Some(editor) = &mut app.property_editor {
+            match editor.key(key) {
+                property_editor::Outcome::Editing => {}
+                property_editor::Outcome::Cancelled => {
+                    app.property_editor = None;
+                }
+                property_editor::Outcome::Submitted(value) => {
+                    if let Some(editor) = app.property_editor.take() {
+                        app.write_property(editor, value);
+                    }
+                }
+            }
+            return Ok(true);
+        }
 
-        if let Some(&action) = app.config.keybindings.get(&self) {
-            return action.handle(app);
+        if app.config.input_mode == InputMode::Vi {
+            let visual = current_list!(app).visual.is_some();
+            for command in app.vi.key(key, visual) {
+                app.handle_vi_command(command)?;
+            }
+            // Redraw whenever the prompt might have changed
+            return Ok(true);
         }
 
-        Ok(false)
+        app.handle_key(key)
     }
 }
 
@@ -380,69 +2688,225 @@ impl Handle for Action {
         match self {
             Action::SelectTab(index) => {
                 if index < app.tabs.len() {
-                    app.current_tab_index = index;
+                    *app.focused_tab_index_mut() = index;
                 }
             }
+            Action::SwitchFocus => {
+                app.is_split_focused =
+                    app.split_tab_index.is_some() && !app.is_split_focused;
+            }
+            Action::FocusSplit(is_split_focused) => {
+                app.is_split_focused =
+                    app.split_tab_index.is_some() && is_split_focused;
+            }
             Action::MoveDown => {
                 current_list!(app).down(&app.view);
             }
             Action::MoveUp => {
                 current_list!(app).up(&app.view);
             }
+            Action::MovePageDown => {
+                current_list!(app).page_down(&app.view);
+            }
+            Action::MovePageUp => {
+                current_list!(app).page_up(&app.view);
+            }
+            Action::MoveToTop => {
+                current_list!(app).top(&app.view);
+            }
+            Action::MoveToBottom => {
+                current_list!(app).bottom(&app.view);
+            }
             Action::TabLeft => {
-                app.current_tab_index = app
-                    .current_tab_index
-                    .checked_sub(1)
-                    .unwrap_or(app.tabs.len() - 1)
+                let tabs_len = app.tabs.len();
+                let index = app.focused_tab_index_mut();
+                *index = index.checked_sub(1).unwrap_or(tabs_len - 1)
             }
             Action::TabRight => {
-                app.current_tab_index =
-                    (app.current_tab_index + 1) % app.tabs.len()
+                let tabs_len = app.tabs.len();
+                let index = app.focused_tab_index_mut();
+                *index = (*index + 1) % tabs_len
             }
             Action::CloseDropdown => {
                 current_list!(app).dropdown_close();
             }
             Action::ActivateDropdown => {
+                if let Some(&view::Target::Property(property)) =
+                    current_list!(app).selected_target()
+                {
+                    app.start_property_editor(property);
+                    return Ok(true);
+                }
                 let commands = current_list!(app).dropdown_activate(&app.view);
                 for command in commands {
-                    let _ = app.tx.send(command);
+                    app.send(command);
                 }
             }
+            Action::ChannelMap => {
+                current_list!(app).channel_map_open(&app.view);
+            }
+            Action::SetTarget(view::Target::Property(property)) => {
+                app.start_property_editor(property);
+            }
             Action::SetTarget(target) => {
                 let commands = current_list!(app).set_target(&app.view, target);
                 for command in commands {
-                    let _ = app.tx.send(command);
+                    app.send(command);
                 }
             }
             Action::SelectObject(object_id) => {
-                app.tabs[app.current_tab_index].list.selected = Some(object_id)
+                current_list!(app).selected = Some(object_id)
             }
             Action::ToggleMute => {
                 let commands = current_list!(app).toggle_mute(&app.view);
                 for command in commands {
-                    let _ = app.tx.send(command);
+                    app.send(command);
                 }
             }
             Action::SetAbsoluteVolume(volume) => {
+                app.cancel_fades();
                 let commands =
                     current_list!(app).set_absolute_volume(&app.view, volume);
                 for command in commands {
-                    let _ = app.tx.send(command);
+                    app.send(command);
                 }
+                app.play_feedback();
             }
             Action::SetRelativeVolume(volume) => {
+                app.cancel_fades();
                 let commands =
                     current_list!(app).set_relative_volume(&app.view, volume);
                 for command in commands {
-                    let _ = app.tx.send(command);
+                    app.send(command);
                 }
+                app.play_feedback();
+            }
+            Action::Fade(fade) => {
+                app.fade(fade);
             }
             Action::SetDefault => {
                 let commands = current_list!(app).set_default(&app.view);
                 for command in commands {
-                    let _ = app.tx.send(command);
+                    app.send(command);
+                }
+            }
+            Action::CycleDefaultSink => {
+                app.cycle_default(DeviceKind::Sink);
+            }
+            Action::CycleDefaultSource => {
+                app.cycle_default(DeviceKind::Source);
+            }
+            Action::ToggleDefaultSourceMute => {
+                app.toggle_default_source_mute();
+            }
+            Action::ToggleProAudio => {
+                app.toggle_pro_audio();
+            }
+            Action::ToggleUpmix => {
+                app.toggle_upmix();
+            }
+            Action::TogglePin => {
+                app.toggle_pin();
+            }
+            Action::ToggleDnd => {
+                app.toggle_dnd();
+            }
+            Action::TestTone => {
+                app.test_tone();
+            }
+            Action::MicCheck => {
+                app.start_mic_check();
+            }
+            Action::MeasureLatency => {
+                app.measure_latency();
+            }
+            Action::ToggleHistory => {
+                app.show_history = !app.show_history;
+            }
+            Action::Help => {
+                app.help = Some(Help::default());
+            }
+            Action::EditProperties => {
+                app.edit_properties();
+            }
+            Action::RevertProperties => {
+                app.revert_properties();
+            }
+            Action::ToggleNetworkDiscovery => {
+                app.toggle_module("module-raop-discover", &[]);
+            }
+            Action::ToggleZeroconfPublish => {
+                app.toggle_module("module-zeroconf-publish", &[]);
+            }
+            Action::ToggleZeroconfDiscover => {
+                app.toggle_module("module-zeroconf-discover", &[]);
+            }
+            Action::ToggleRtp(index) => {
+                let Some(rtp) = app.config.rtp.get(index) else {
+                    app.flash(
+                        app.config
+                            .locale
+                            .format("error-no-rtp", &[("index", &index)]),
+                    );
+                    return Ok(false);
+                };
+                let (name, args) = rtp.module();
+                let args: Vec<&str> = args.iter().map(String::as_str).collect();
+                app.toggle_module(name, &args);
+            }
+            Action::ToggleCompact => {
+                app.config.compact = !app.config.compact;
+                for tab in app.tabs.iter_mut() {
+                    tab.list.compact = app.config.compact;
+                }
+            }
+            Action::CopyName => {
+                let name = current_list!(app).selected_name(&app.view);
+                app.copy(name.map(String::from));
+            }
+            Action::CopyId => {
+                let id = current_list!(app).selected.map(|id| id.to_string());
+                app.copy(id);
+            }
+            Action::CopyProperties => {
+                let Some(id) = current_list!(app).selected else {
+                    return Ok(false);
+                };
+                match clipboard::dump(app.config.remote.as_deref(), id.into()) {
+                    Ok(dump) => app.copy(Some(dump)),
+                    Err(err) => app.flash(format!("{:#}", err)),
+                }
+            }
+            Action::CopyRule(kind) => {
+                let Some(id) = current_list!(app).selected else {
+                    return Ok(false);
+                };
+                match rule::snippet(&app.view, id, kind) {
+                    Ok(snippet) => app.copy(Some(snippet)),
+                    Err(err) => app.flash(format!("{:#}", err)),
                 }
             }
+            Action::AppendRule(kind) => {
+                let Some(id) = current_list!(app).selected else {
+                    return Ok(false);
+                };
+                app.append_rule(id, kind);
+            }
+            Action::Export(format) => {
+                app.export(format);
+            }
+            Action::ExportGraph(format) => {
+                app.export_graph(format);
+            }
+            Action::RecordMacro(key) => {
+                app.toggle_macro_recording(key);
+            }
+            Action::PlayMacro(key) => {
+                app.play_macro(key);
+            }
+            Action::Suspend => {
+                app.suspend = true;
+            }
             Action::Exit => {
                 app.exit(None);
             }
@@ -492,7 +2956,16 @@ impl Handle for MouseEvent {
 
 impl Handle for MonitorEvent {
     fn handle(self, app: &mut App) -> Result<bool> {
+        if let MonitorEvent::ObjectError(object_id, message) = self {
+            app.object_error(object_id, message);
+            return Ok(true);
+        }
+        if app.is_ready {
+            app.hotplug.on_event(&app.state, &self, Instant::now());
+        }
+        let settings = Optimistic::settings(&self);
         app.state.update(&mut app.capture_manager, self);
+        app.optimistic.reconcile(&mut app.state, &settings);
         for command in app.capture_manager.flush() {
             // Filter out capture commands if capture is disabled
             match command {
@@ -500,7 +2973,7 @@ impl Handle for MonitorEvent {
                 | Command::NodeCaptureStop(..)
                     if app.config.peaks == Peaks::Off => {}
                 command => {
-                    let _ = app.tx.send(command);
+                    app.send(command);
                 }
             }
         }
@@ -508,26 +2981,30 @@ impl Handle for MonitorEvent {
     }
 }
 
-impl Handle for String {
+impl Handle for error::Error {
     fn handle(self, app: &mut App) -> Result<bool> {
-        // Handle errors
-        match self {
-            // These happen when objects are removed while the monitor
-            // is still in the process of setting up listeners
-            error if error.starts_with("no global ") => {}
-            error if error.starts_with("unknown resource ") => {}
-            // I see this one when disconnecting a Bluetooth sink
-            error if error == "Received error event" => {}
-            // Not sure where this originates
-            error if error == "Error: Buffer allocation failed" => {}
-            _ => app.exit(Some(self)),
-        }
-        Ok(false) // This makes sense for now
+        if self.is_fatal() {
+            app.exit(Some(self.to_string()));
+            Ok(false)
+        } else if self.is_ignored() {
+            Ok(false)
+        } else {
+            // Something failed, but the monitor is still running
+            app.notice(Notice::Error, self.to_string());
+            Ok(true)
+        }
     }
 }
 
 pub struct AppWidget<'a> {
     current_tab_index: usize,
+    split_tab_index: Option<usize>,
+    is_split_focused: bool,
+    flash: Option<&'a str>,
+    /// The number of streams muted by do-not-disturb, if it's on
+    dnd: Option<usize>,
+    /// The selected node and the history to show for it
+    history: Option<(&'a view::Node, &'a History)>,
     view: &'a View,
     config: &'a Config,
 }
@@ -541,20 +3018,83 @@ impl<'a> StatefulWidget for AppWidget<'a> {
     type State = AppWidgetState<'a>;
 
     fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let mut indicators = Vec::new();
+        if let Some(muted) = self.dnd {
+            indicators.push(
+                self.config
+                    .locale
+                    .format("status-dnd", &[("count", &muted)]),
+            );
+        }
+        if self.config.recording.indicator {
+            let marker = &self.config.char_set.recording;
+            let microphone = self.view.recording_clients();
+            if !microphone.is_empty() {
+                let clients = microphone.join(", ");
+                indicators.push(self.config.locale.format(
+                    "status-recording",
+                    &[("marker", marker), ("clients", &clients)],
+                ));
+            }
+            if !self.view.camera_clients.is_empty() {
+                let clients = self.view.camera_clients.join(", ");
+                indicators.push(self.config.locale.format(
+                    "status-camera",
+                    &[("marker", marker), ("clients", &clients)],
+                ));
+            }
+        }
+        if self.config.recording.muted_banner
+            && self
+                .view
+                .default_source_node()
+                .is_some_and(|node| node.mute)
+        {
+            indicators.push(String::from(
+                self.config.locale.get("status-microphone-muted"),
+            ));
+        }
         let layout = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Min(0),    // list_area
+                // recording_area
+                Constraint::Length(u16::from(!indicators.is_empty())),
+                Constraint::Min(0), // list_area
+                // history_area
+                Constraint::Length(if self.history.is_some() {
+                    HISTORY_HEIGHT
+                } else {
+                    0
+                }),
                 Constraint::Length(1), // menu_area
             ])
             .split(area);
-        let list_area = layout[0];
-        let menu_area = layout[1];
+        let recording_area = layout[0];
+        let list_area = layout[1];
+        let history_area = layout[2];
+        let menu_area = layout[3];
+
+        if let Some((node, history)) = self.history {
+            HistoryWidget::new(history, node, self.config)
+                .render(history_area, buf);
+        }
+
+        if !indicators.is_empty() {
+            Line::from(Span::styled(
+                indicators.join("  "),
+                self.config.theme.recording,
+            ))
+            .render(recording_area, buf);
+        }
 
         let constraints: Vec<_> = state
             .tabs
             .iter()
-            .map(|tab| Constraint::Length(tab.title.len() as u16 + 2))
+            .map(|tab| {
+                let width = UnicodeWidthStr::width(tab.title.as_str());
+                Constraint::Length(width as u16 + 2)
+            })
+            .chain(std::iter::once(Constraint::Min(0))) // flash_area
             .collect();
 
         let menu_areas = Layout::default()
@@ -562,8 +3102,13 @@ impl<'a> StatefulWidget for AppWidget<'a> {
             .constraints(constraints)
             .split(menu_area);
 
+        let focused_tab_index = match self.split_tab_index {
+            Some(split_tab_index) if self.is_split_focused => split_tab_index,
+            _ => self.current_tab_index,
+        };
+
         for (i, tab) in state.tabs.iter().enumerate() {
-            let title_line = if i == self.current_tab_index {
+            let title_line = if i == focused_tab_index {
                 Line::from(vec![
                     Span::styled(
                         &self.config.char_set.tab_marker_left,
@@ -575,6 +3120,14 @@ impl<'a> StatefulWidget for AppWidget<'a> {
                         self.config.theme.tab_marker,
                     ),
                 ])
+            } else if i == self.current_tab_index
+                || Some(i) == self.split_tab_index
+            {
+                // Visible in the unfocused half of a split
+                Line::from(Span::styled(
+                    format!(" {} ", tab.title),
+                    self.config.theme.tab_selected,
+                ))
             } else {
                 Line::from(Span::styled(
                     format!(" {} ", tab.title),
@@ -590,12 +3143,50 @@ impl<'a> StatefulWidget for AppWidget<'a> {
             ));
         }
 
-        let mut widget = ObjectListWidget {
-            object_list: &mut state.tabs[self.current_tab_index].list,
-            view: self.view,
-            config: self.config,
+        if let Some(flash) = self.flash {
+            let flash_area = menu_areas[state.tabs.len()];
+            Line::from(Span::styled(flash, self.config.theme.flash))
+                .alignment(Alignment::Right)
+                .render(flash_area, buf);
+        }
+
+        let Some(split_tab_index) = self.split_tab_index else {
+            let mut widget = ObjectListWidget {
+                object_list: &mut state.tabs[self.current_tab_index].list,
+                view: self.view,
+                config: self.config,
+            };
+            widget.render(list_area, buf, state.mouse_areas);
+            return;
         };
-        widget.render(list_area, buf, state.mouse_areas);
+
+        let layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Fill(1), // current_area
+                Constraint::Fill(1), // split_area
+            ])
+            .spacing(1)
+            .split(list_area);
+
+        for (tab_index, pane_area, is_split) in [
+            (self.current_tab_index, layout[0], false),
+            (split_tab_index, layout[1], true),
+        ] {
+            let mut widget = ObjectListWidget {
+                object_list: &mut state.tabs[tab_index].list,
+                view: self.view,
+                config: self.config,
+            };
+            let mut pane_mouse_areas = Vec::new();
+            widget.render(pane_area, buf, &mut pane_mouse_areas);
+
+            // Interacting with a pane gives it focus first.
+            for (rect, kinds, mut actions) in pane_mouse_areas {
+                actions.insert(0, Action::FocusSplit(is_split));
+                state.mouse_areas.push((rect, kinds, actions));
+            }
+        }
     }
 }
 
@@ -604,22 +3195,71 @@ mod tests {
     use super::*;
     use strum::IntoEnumIterator;
 
-    #[test]
-    fn select_tab_bounds() {
-        let (command_tx, _) = pipewire::channel::channel::<Command>();
-        let (_, event_rx) = mpsc::channel();
-
-        let config = Config {
+    /// A configuration with defaults which don't depend on files or the
+    /// environment.
+    fn test_config() -> Config {
+        Config {
             remote: None,
             fps: None,
             mouse: false,
             peaks: Default::default(),
+            compact: false,
+            inline: None,
+            kitty_keyboard: false,
+            reduced_motion: false,
+            marquee: false,
+            loudness_warning: None,
+            hide_paused_after: None,
+            keep_ended_for: None,
+            low_battery_warning: 20,
+            on_port_change: None,
+            pause_on_disconnect: false,
+            flat_volume: false,
+            notification_group: false,
+            monitors: true,
+            volume_rate: 0.0,
+            volume_feedback: false,
+            history: 60.0,
+            macros: Default::default(),
+            macros_path: None,
+            orientation: Default::default(),
+            gradient: Default::default(),
             char_set: Default::default(),
             theme: Default::default(),
+            locale: Default::default(),
             keybindings: Default::default(),
+            input_mode: Default::default(),
             names: Default::default(),
+            priorities: Default::default(),
+            linked: Vec::new(),
+            ducking: Default::default(),
+            dnd: Default::default(),
+            sleep: Default::default(),
+            schedule: Vec::new(),
+            initial_volume: Vec::new(),
+            role_tab: Vec::new(),
+            recording: Default::default(),
+            metering: Default::default(),
+            watchdog: Default::default(),
+            osc: Default::default(),
+            columns: Default::default(),
+            rtp: Default::default(),
             tab: Default::default(),
-        };
+            split: None,
+            startup_selection: Default::default(),
+            restore_session: false,
+            session_path: None,
+            export_path: None,
+            path: None,
+        }
+    }
+
+    #[test]
+    fn select_tab_bounds() {
+        let (command_tx, _) = pipewire::channel::channel::<MixCommand>();
+        let (_, event_rx) = mpsc::channel();
+
+        let config = test_config();
         let mut app = App::new(command_tx, event_rx, config);
 
         let _ = Action::SelectTab(app.tabs.len()).handle(&mut app);
@@ -630,7 +3270,7 @@ mod tests {
     fn key_modifiers() {
         use crossterm::event::{KeyCode, KeyModifiers};
         use std::collections::HashMap;
-        let (command_tx, _) = pipewire::channel::channel::<Command>();
+        let (command_tx, _) = pipewire::channel::channel::<MixCommand>();
         let (_, event_rx) = mpsc::channel();
 
         let x = KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE);
@@ -641,15 +3281,8 @@ mod tests {
             (ctrl_x, Action::SelectTab(4)),
         ]);
         let config = Config {
-            remote: None,
-            fps: None,
-            mouse: false,
-            peaks: Default::default(),
-            char_set: Default::default(),
-            theme: Default::default(),
-            keybindings,
-            names: Default::default(),
-            tab: Default::default(),
+            keybindings: keybindings.into(),
+            ..test_config()
         };
         let mut app = App::new(command_tx, event_rx, config);
 
@@ -659,6 +3292,49 @@ mod tests {
         assert_eq!(app.current_tab_index, 4);
         let _ = x.handle(&mut app);
         assert_eq!(app.current_tab_index, 2);
+
+        // As reported by the kitty keyboard protocol with Num Lock on
+        let ctrl_x_num_lock = KeyEvent {
+            state: KeyEventState::NUM_LOCK,
+            ..ctrl_x
+        };
+        let _ = ctrl_x_num_lock.handle(&mut app);
+        assert_eq!(app.current_tab_index, 4);
+    }
+
+    #[test]
+    fn record_and_play_macro() {
+        use crossterm::event::{KeyCode, KeyModifiers};
+        use std::collections::HashMap;
+        let (command_tx, _) = pipewire::channel::channel::<MixCommand>();
+        let (_, event_rx) = mpsc::channel();
+
+        let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+        let keybindings = HashMap::from([
+            (key('r'), Action::RecordMacro('a')),
+            (key('p'), Action::PlayMacro('a')),
+            (key('x'), Action::SelectTab(2)),
+            (key('z'), Action::ToggleCompact),
+        ]);
+        let config = Config {
+            keybindings: keybindings.into(),
+            ..test_config()
+        };
+        let mut app = App::new(command_tx, event_rx, config);
+
+        for c in ['r', 'x', 'z', 'r'] {
+            let _ = key(c).handle(&mut app);
+        }
+        assert!(app.recording.is_none());
+        assert_eq!(
+            app.macros.get("a"),
+            Some(&vec![Action::SelectTab(2), Action::ToggleCompact])
+        );
+
+        let _ = Action::SelectTab(0).handle(&mut app);
+        let _ = key('p').handle(&mut app);
+        assert_eq!(app.current_tab_index, 2);
+        assert!(!app.config.compact);
     }
 
     /// Ensure that the tabs enum variants are in the same order as the app's
@@ -667,20 +3343,10 @@ mod tests {
     /// into the tab Vec.
     #[test]
     fn tab_enum_order_matches_tab_vec() {
-        let (command_tx, _) = pipewire::channel::channel::<Command>();
+        let (command_tx, _) = pipewire::channel::channel::<MixCommand>();
         let (_, event_rx) = mpsc::channel();
 
-        let config = Config {
-            remote: None,
-            fps: None,
-            mouse: false,
-            peaks: Default::default(),
-            char_set: Default::default(),
-            theme: Default::default(),
-            keybindings: Default::default(),
-            names: Default::default(),
-            tab: Default::default(),
-        };
+        let config = test_config();
         let app = App::new(command_tx, event_rx, config);
 
         assert_eq!(TabKind::iter().count(), app.tabs.len());
@@ -692,7 +3358,47 @@ mod tests {
                 TabKind::Output => assert_eq!(title, "Output Devices"),
                 TabKind::Input => assert_eq!(title, "Input Devices"),
                 TabKind::Configuration => assert_eq!(title, "Configuration"),
+                TabKind::Network => assert_eq!(title, "Network"),
+                TabKind::Jack => assert_eq!(title, "JACK"),
             }
         }
     }
+
+    /// `systemctl stop` sends SIGTERM, which should end the daemon's loop
+    /// normally rather than killing it before it can clean up.
+    #[test]
+    fn daemon_exits_on_sigterm() {
+        let (command_tx, _) = pipewire::channel::channel::<MixCommand>();
+        let (event_tx, event_rx) = mpsc::channel();
+        event_tx.send(Event::Ready).unwrap();
+        let app = App::new(command_tx, event_rx, test_config());
+
+        signals::request_terminate();
+        assert!(app.run_daemon().is_ok());
+    }
+
+    #[test]
+    fn split_focus() {
+        let (command_tx, _) = pipewire::channel::channel::<MixCommand>();
+        let (_, event_rx) = mpsc::channel();
+
+        let config = Config {
+            tab: TabKind::Playback,
+            split: Some(TabKind::Output),
+            ..test_config()
+        };
+        let mut app = App::new(command_tx, event_rx, config);
+        assert_eq!(app.focused_tab_index(), TabKind::Playback.index());
+
+        let _ = Action::SwitchFocus.handle(&mut app);
+        assert_eq!(app.focused_tab_index(), TabKind::Output.index());
+
+        // Tab selection applies to the focused half.
+        let _ = Action::TabRight.handle(&mut app);
+        assert_eq!(app.current_tab_index, TabKind::Playback.index());
+        assert_eq!(app.split_tab_index, Some(TabKind::Input.index()));
+
+        let _ = Action::FocusSplit(false).handle(&mut app);
+        assert_eq!(app.focused_tab_index(), TabKind::Playback.index());
+    }
 }
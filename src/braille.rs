@@ -0,0 +1,68 @@
+//! Braille patterns for drawing bars with sub-cell resolution.
+
+/// Direction in which a bar fills up.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Fill {
+    LeftToRight,
+    RightToLeft,
+    BottomToTop,
+}
+
+impl Fill {
+    /// Number of distinct steps within a single cell.
+    pub fn steps(self) -> usize {
+        self.symbols().len() - 1
+    }
+
+    /// Symbols indexed by the number of lit steps in a cell.
+    fn symbols(self) -> &'static [&'static str] {
+        match self {
+            Fill::LeftToRight => &["⠀", "⡇", "⣿"],
+            Fill::RightToLeft => &["⠀", "⢸", "⣿"],
+            Fill::BottomToTop => &["⠀", "⣀", "⣤", "⣶", "⣿"],
+        }
+    }
+
+    /// Symbol for each of `cells` cells, starting from where the bar begins
+    /// filling, when `lit` steps are lit in total. Returns [`None`] for cells
+    /// with no lit steps.
+    pub fn cells(
+        self,
+        lit: usize,
+        cells: usize,
+    ) -> impl Iterator<Item = Option<&'static str>> {
+        let steps = self.steps();
+        (0..cells).map(move |cell| {
+            match lit.saturating_sub(cell * steps).min(steps) {
+                0 => None,
+                count => Some(self.symbols()[count]),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cells_horizontal() {
+        let cells: Vec<_> = Fill::LeftToRight.cells(3, 3).collect();
+        assert_eq!(cells, vec![Some("⣿"), Some("⡇"), None]);
+
+        let cells: Vec<_> = Fill::RightToLeft.cells(1, 2).collect();
+        assert_eq!(cells, vec![Some("⢸"), None]);
+    }
+
+    #[test]
+    fn cells_vertical() {
+        let cells: Vec<_> = Fill::BottomToTop.cells(6, 3).collect();
+        assert_eq!(cells, vec![Some("⣿"), Some("⣤"), None]);
+    }
+
+    #[test]
+    fn cells_overfilled() {
+        let cells: Vec<_> = Fill::LeftToRight.cells(100, 2).collect();
+        assert_eq!(cells, vec![Some("⣿"), Some("⣿")]);
+    }
+}
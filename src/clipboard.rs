@@ -0,0 +1,83 @@
+//! Copying text to the system clipboard.
+//!
+//! Text is sent to the terminal with an OSC 52 escape sequence, which also
+//! works over SSH, and to wl-copy or xclip if there is a Wayland or X11
+//! display, for terminals which don't support OSC 52.
+
+use std::env;
+use std::io::{self, Write};
+use std::process::{self, Stdio};
+
+use anyhow::{anyhow, Context};
+use base64::Engine;
+
+/// Copy text to the clipboard.
+pub fn copy(text: &str) -> Result<(), anyhow::Error> {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(text);
+    let mut stdout = io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", encoded)
+        .and_then(|_| stdout.flush())
+        .context("Failed to write to terminal")?;
+
+    if env::var_os("WAYLAND_DISPLAY").is_some() {
+        run("wl-copy", &[], text)
+    } else if env::var_os("DISPLAY").is_some() {
+        run("xclip", &["-selection", "clipboard"], text)
+    } else {
+        Ok(())
+    }
+}
+
+/// Run a clipboard command with text as its input. A missing command isn't
+/// an error because the terminal may have handled OSC 52.
+fn run(command: &str, args: &[&str], text: &str) -> Result<(), anyhow::Error> {
+    let child = process::Command::new(command)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    let mut child = match child {
+        Ok(child) => child,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => {
+            return Err(err)
+                .with_context(|| format!("Failed to run {}", command))
+        }
+    };
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(text.as_bytes())
+            .with_context(|| format!("Failed to write to {}", command))?;
+    }
+    let status = child
+        .wait()
+        .with_context(|| format!("Failed to run {}", command))?;
+    if !status.success() {
+        return Err(anyhow!("{} failed: {}", command, status));
+    }
+
+    Ok(())
+}
+
+/// The properties of an object as JSON from pw-dump.
+pub fn dump(
+    remote: Option<&str>,
+    object_id: u32,
+) -> Result<String, anyhow::Error> {
+    let mut command = process::Command::new("pw-dump");
+    if let Some(remote) = remote {
+        command.args(["--remote", remote]);
+    }
+    let output = command
+        .arg(object_id.to_string())
+        .output()
+        .context("Failed to run pw-dump")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("pw-dump {}: {}", object_id, stderr.trim()));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
@@ -0,0 +1,161 @@
+//! Rate limiting of volume commands.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::command::MixCommand;
+use crate::optimistic::Setting;
+
+/// Coalesce volume commands so each volume setting gets at most a given
+/// number of writes per second.
+///
+/// Holding a volume key or dragging a slider produces many commands, but only
+/// the latest one held back for a setting is sent once its interval has
+/// passed. The UI doesn't wait for them thanks to
+/// [`Optimistic`](`crate::optimistic::Optimistic`).
+#[derive(Default, Debug)]
+pub struct Coalescer {
+    /// Minimum time between writes, or None for unlimited
+    interval: Option<Duration>,
+    /// When each setting was last written and the command held back for it
+    settings: HashMap<Setting, (Instant, Option<MixCommand>)>,
+}
+
+impl Coalescer {
+    /// A coalescer for `rate` writes per second, or unlimited writes if `rate`
+    /// is 0.
+    pub fn new(rate: f32) -> Self {
+        Self {
+            interval: (rate > 0.0)
+                .then(|| Duration::from_secs_f64(1.0 / f64::from(rate))),
+            settings: Default::default(),
+        }
+    }
+
+    /// Returns the command if it should be sent now, or holds it back,
+    /// replacing any command already held back for the setting.
+    pub fn push(
+        &mut self,
+        setting: Setting,
+        command: MixCommand,
+        now: Instant,
+    ) -> Option<MixCommand> {
+        let Some(interval) = self.interval else {
+            return Some(command);
+        };
+        match self.settings.get_mut(&setting) {
+            Some((sent, held))
+                if now.saturating_duration_since(*sent) < interval =>
+            {
+                *held = Some(command);
+                None
+            }
+            _ => {
+                self.settings.insert(setting, (now, None));
+                Some(command)
+            }
+        }
+    }
+
+    /// Returns the held back commands which can be sent now.
+    pub fn flush(&mut self, now: Instant) -> Vec<MixCommand> {
+        let Some(interval) = self.interval else {
+            return Vec::new();
+        };
+        let mut commands = Vec::new();
+        self.settings.retain(|_, (sent, held)| {
+            if now.saturating_duration_since(*sent) < interval {
+                return true;
+            }
+            match held.take() {
+                Some(command) => {
+                    commands.push(command);
+                    *sent = now;
+                    true
+                }
+                // Nothing has been sent for a whole interval
+                None => false,
+            }
+        });
+        commands
+    }
+
+    /// Returns every held back command, such as for sending before exiting.
+    pub fn drain(&mut self) -> Vec<MixCommand> {
+        self.settings
+            .drain()
+            .filter_map(|(_, (_, held))| held)
+            .collect()
+    }
+
+    /// How long until the next held back command can be sent.
+    pub fn until_next_flush(&self, now: Instant) -> Option<Duration> {
+        let interval = self.interval?;
+        self.settings
+            .values()
+            .filter(|(_, held)| held.is_some())
+            .map(|(sent, _)| {
+                interval.saturating_sub(now.saturating_duration_since(*sent))
+            })
+            .min()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::Command;
+    use crate::object::ObjectId;
+
+    fn volume_command(id: u64, volume: f32) -> MixCommand {
+        MixCommand {
+            id,
+            command: Command::NodeVolumes(
+                ObjectId::from_raw_id(1),
+                vec![volume],
+            ),
+        }
+    }
+
+    #[test]
+    fn coalesce_to_latest_command() {
+        let setting = Setting::NodeVolumes(ObjectId::from_raw_id(1));
+        let mut coalescer = Coalescer::new(10.0);
+        let now = Instant::now();
+
+        assert!(coalescer
+            .push(setting, volume_command(0, 0.1), now)
+            .is_some());
+        assert!(coalescer
+            .push(setting, volume_command(1, 0.2), now)
+            .is_none());
+        assert!(coalescer
+            .push(setting, volume_command(2, 0.3), now)
+            .is_none());
+        assert!(coalescer.flush(now).is_empty());
+        assert_eq!(
+            coalescer.until_next_flush(now),
+            Some(Duration::from_millis(100))
+        );
+
+        let later = now + Duration::from_millis(100);
+        let commands = coalescer.flush(later);
+        assert_eq!(commands.len(), 1);
+        assert_eq!(commands[0].id, 2);
+        assert_eq!(coalescer.until_next_flush(later), None);
+    }
+
+    #[test]
+    fn unlimited() {
+        let setting = Setting::NodeVolumes(ObjectId::from_raw_id(1));
+        let mut coalescer = Coalescer::new(0.0);
+        let now = Instant::now();
+
+        assert!(coalescer
+            .push(setting, volume_command(0, 0.1), now)
+            .is_some());
+        assert!(coalescer
+            .push(setting, volume_command(1, 0.2), now)
+            .is_some());
+    }
+}
@@ -1,9 +1,17 @@
 //! Mixer configuration.
 
 mod char_set;
+mod check;
+mod conditional;
+mod gradient;
+mod include;
 mod keybinding;
+mod list_format;
 mod name_template;
 mod names;
+mod reference;
+mod schema;
+mod selector;
 mod tag;
 mod theme;
 
@@ -15,12 +23,25 @@ use std::path::{Path, PathBuf};
 
 use anyhow::Context;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
-use ratatui::{style::Style, widgets::block::BorderType};
+use ratatui::{style::Style, symbols::border};
 use serde::Deserialize;
 use toml;
 
 use crate::app::{Action, TabKind};
+use crate::input::KeyContext;
+use crate::locale::Locale;
+use crate::macros::{self, Macros};
+use crate::metering::MeteringFormat;
 use crate::opt::Opt;
+use crate::osc;
+use crate::schedule::TimeOfDay;
+use crate::session;
+
+pub use list_format::ListFormat;
+pub use name_template::NameTemplate;
+pub use reference::{Entry, Section};
+pub use selector::Selector;
+pub use tag::Tag;
 
 #[derive(Debug)]
 #[cfg_attr(test, derive(PartialEq))]
@@ -29,11 +50,55 @@ pub struct Config {
     pub fps: Option<f32>,
     pub mouse: bool,
     pub peaks: Peaks,
+    pub compact: bool,
+    pub inline: Option<u16>,
+    pub kitty_keyboard: bool,
+    pub reduced_motion: bool,
+    pub marquee: bool,
+    pub loudness_warning: Option<f32>,
+    pub hide_paused_after: Option<f32>,
+    pub keep_ended_for: Option<f32>,
+    pub low_battery_warning: u8,
+    pub on_port_change: Option<String>,
+    pub pause_on_disconnect: bool,
+    pub flat_volume: bool,
+    pub notification_group: bool,
+    pub monitors: bool,
+    pub volume_rate: f32,
+    pub volume_feedback: bool,
+    pub history: f32,
+    pub orientation: Orientation,
+    pub gradient: Gradient,
     pub char_set: CharSet,
     pub theme: Theme,
-    pub keybindings: HashMap<KeyEvent, Action>,
+    pub locale: Locale,
+    pub keybindings: Keybindings,
+    pub input_mode: InputMode,
+    pub macros: Macros,
+    pub macros_path: Option<PathBuf>,
     pub names: Names,
+    pub priorities: Priorities,
+    pub linked: Vec<Vec<String>>,
+    pub ducking: Ducking,
+    pub dnd: Dnd,
+    pub sleep: Sleep,
+    pub recording: Recording,
+    pub metering: Metering,
+    pub watchdog: Watchdog,
+    pub osc: Osc,
+    pub columns: Columns,
+    pub rtp: Vec<RtpStream>,
+    pub schedule: Vec<ScheduleEntry>,
+    pub initial_volume: Vec<InitialVolume>,
+    pub role_tab: Vec<RoleTab>,
     pub tab: TabKind,
+    pub split: Option<TabKind>,
+    pub startup_selection: StartupSelection,
+    pub restore_session: bool,
+    pub session_path: Option<PathBuf>,
+    pub export_path: Option<PathBuf>,
+    /// The configuration file, whether or not it exists
+    pub path: Option<PathBuf>,
 }
 
 /// Represents a configuration deserialized from a file. This gets baked into a
@@ -42,23 +107,97 @@ pub struct Config {
 #[cfg_attr(test, derive(PartialEq))]
 #[serde(deny_unknown_fields)]
 struct ConfigFile {
+    #[serde(default)]
+    include: Vec<PathBuf>,
+    /// Options for specific hosts, applied by [`conditional::apply()`]
+    #[serde(default)]
+    host: HashMap<String, toml::Table>,
+    /// Options for specific environment variable values
+    #[serde(default)]
+    env: HashMap<String, HashMap<String, toml::Table>>,
     remote: Option<String>,
     fps: Option<f32>,
     #[serde(default = "default_mouse")]
     mouse: bool,
     #[serde(default = "default_peaks")]
     peaks: Option<Peaks>,
+    #[serde(default)]
+    compact: bool,
+    inline: Option<u16>,
+    #[serde(default = "default_kitty_keyboard")]
+    kitty_keyboard: bool,
+    #[serde(default)]
+    reduced_motion: bool,
+    #[serde(default)]
+    marquee: bool,
+    loudness_warning: Option<f32>,
+    hide_paused_after: Option<f32>,
+    keep_ended_for: Option<f32>,
+    #[serde(default = "default_low_battery_warning")]
+    low_battery_warning: u8,
+    on_port_change: Option<String>,
+    #[serde(default)]
+    pause_on_disconnect: bool,
+    #[serde(default)]
+    flat_volume: bool,
+    #[serde(default)]
+    notification_group: bool,
+    #[serde(default = "default_monitors")]
+    monitors: bool,
+    #[serde(default = "default_volume_rate")]
+    volume_rate: f32,
+    #[serde(default)]
+    volume_feedback: bool,
+    #[serde(default = "default_history")]
+    history: f32,
+    #[serde(default)]
+    orientation: Orientation,
+    #[serde(default)]
+    gradient: Gradient,
     #[serde(default = "default_char_set_name")]
     char_set: String,
     #[serde(default = "default_theme_name")]
     theme: String,
+    locale: Option<String>,
     #[serde(
         default = "Keybinding::defaults",
         deserialize_with = "Keybinding::merge"
     )]
-    keybindings: HashMap<KeyEvent, Action>,
+    keybindings: Keybindings,
+    #[serde(default)]
+    input_mode: InputMode,
+    #[serde(default)]
+    macros: Macros,
     #[serde(default)]
     names: Names,
+    #[serde(default)]
+    priorities: Priorities,
+    #[serde(default)]
+    linked: Vec<Vec<String>>,
+    #[serde(default)]
+    ducking: Ducking,
+    #[serde(default)]
+    dnd: Dnd,
+    #[serde(default)]
+    sleep: Sleep,
+    #[serde(default)]
+    recording: Recording,
+    #[serde(default)]
+    metering: Metering,
+    #[serde(default)]
+    watchdog: Watchdog,
+    #[serde(default)]
+    osc: Osc,
+    #[serde(default)]
+    columns: Columns,
+    #[serde(default)]
+    rtp: Vec<RtpStream>,
+    #[serde(default)]
+    schedule: Vec<ScheduleEntry>,
+    #[serde(default)]
+    initial_volume: Vec<InitialVolume>,
+    #[serde(default)]
+    role_tab: Vec<RoleTab>,
     #[serde(
         default = "CharSet::defaults",
         deserialize_with = "CharSet::merge"
@@ -68,6 +207,12 @@ struct ConfigFile {
     themes: HashMap<String, Theme>,
     #[serde(default = "default_tab")]
     tab: Option<TabKind>,
+    split: Option<TabKind>,
+    #[serde(default)]
+    startup_selection: StartupSelection,
+    #[serde(default)]
+    restore_session: bool,
+    export_path: Option<PathBuf>,
 }
 
 #[derive(Deserialize, Default, Debug, Clone, PartialEq, clap::ValueEnum)]
@@ -79,6 +224,76 @@ pub enum Peaks {
     Auto,
 }
 
+/// Direction of the volume sliders for streams and endpoints.
+#[derive(Deserialize, Default, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Orientation {
+    #[default]
+    Horizontal,
+    Vertical,
+}
+
+/// Which object is selected at startup.
+#[derive(Deserialize, Default, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum StartupSelection {
+    /// The first object in the initial tab
+    #[default]
+    First,
+    /// The default sink in the Output Devices tab
+    DefaultSink,
+    /// The default source in the Input Devices tab
+    DefaultSource,
+    /// The object that was selected on exit, in the tab it was in
+    Last,
+}
+
+/// How key presses are interpreted.
+#[derive(Deserialize, Default, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum InputMode {
+    /// Each key press is looked up in the keybindings
+    #[default]
+    Default,
+    /// Counts, multi-key commands, search and visual mode in the manner of vi
+    Vi,
+}
+
+/// Colors for the filled part of volume bars and meters that change from
+/// green to yellow to red along the bar.
+#[derive(Deserialize, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+#[serde(deny_unknown_fields)]
+pub struct Gradient {
+    #[serde(default)]
+    pub colors: GradientColors,
+    /// Volume percentages at which the gradient is green, yellow and red
+    #[serde(default = "Gradient::default_volume")]
+    pub volume: [f32; 3],
+    /// Peak levels in dB at which the gradient is green, yellow and red
+    #[serde(default = "Gradient::default_meter")]
+    pub meter: [f32; 3],
+}
+
+/// Color palette used for gradients.
+#[derive(Deserialize, Default, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum GradientColors {
+    /// Use the theme's styles instead of a gradient
+    #[default]
+    Off,
+    /// Detect the terminal's color support
+    Auto,
+    /// 24-bit RGB colors
+    TrueColor,
+    /// The 256-color palette
+    #[serde(rename = "256")]
+    Indexed,
+    /// Green, yellow and red from the basic 16 colors
+    #[serde(rename = "16")]
+    Basic,
+}
+
 #[derive(Deserialize, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct Keybinding {
@@ -86,6 +301,17 @@ pub struct Keybinding {
     #[serde(default = "Keybinding::default_modifiers")]
     pub modifiers: KeyModifiers,
     pub action: Action,
+    #[serde(default)]
+    pub context: Option<KeyContext>,
+}
+
+/// Keybindings which apply everywhere and keybindings which only apply in a
+/// [`KeyContext`].
+#[derive(Debug, Default)]
+#[cfg_attr(test, derive(PartialEq))]
+pub struct Keybindings {
+    pub global: HashMap<KeyEvent, Action>,
+    pub contexts: HashMap<KeyContext, HashMap<KeyEvent, Action>>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -120,19 +346,444 @@ pub struct NameOverride {
     pub templates: Vec<names::NameTemplate>,
 }
 
+/// Ranking of endpoints by node.name, most preferred first. Endpoints which
+/// aren't listed rank below those which are.
+#[derive(Deserialize, Default, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+#[serde(deny_unknown_fields)]
+pub struct Priorities {
+    #[serde(default)]
+    pub sinks: Vec<String>,
+    #[serde(default)]
+    pub sources: Vec<String>,
+}
+
+/// Lowering the volume of some streams while others are present, such as
+/// music during a call.
+#[derive(Deserialize, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+#[serde(deny_unknown_fields)]
+pub struct Ducking {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Fraction of their volume which ducked streams lose, on the scale of
+    /// the volume bars
+    #[serde(default = "Ducking::default_amount")]
+    pub amount: f32,
+    /// media.role values of the streams which are lowered
+    #[serde(default = "Ducking::default_ducked")]
+    pub ducked: Vec<String>,
+    /// media.role values of the streams which lower the others
+    #[serde(default = "Ducking::default_triggers")]
+    pub triggers: Vec<String>,
+}
+
+impl Ducking {
+    fn default_amount() -> f32 {
+        0.5
+    }
+
+    fn default_ducked() -> Vec<String> {
+        vec![String::from("Music")]
+    }
+
+    fn default_triggers() -> Vec<String> {
+        vec![String::from("Communication"), String::from("Phone")]
+    }
+}
+
+impl Default for Ducking {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            amount: Self::default_amount(),
+            ducked: Self::default_ducked(),
+            triggers: Self::default_triggers(),
+        }
+    }
+}
+
+/// Muting playback streams while do-not-disturb is on.
+#[derive(Deserialize, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+#[serde(deny_unknown_fields)]
+pub struct Dnd {
+    /// media.role values of the streams which aren't muted
+    #[serde(default = "Dnd::default_allow")]
+    pub allow: Vec<String>,
+}
+
+impl Dnd {
+    fn default_allow() -> Vec<String> {
+        vec![String::from("Communication"), String::from("Phone")]
+    }
+}
+
+impl Default for Dnd {
+    fn default() -> Self {
+        Self {
+            allow: Self::default_allow(),
+        }
+    }
+}
+
+/// Muting outputs and restoring the default devices around the system
+/// sleeping.
+#[derive(Deserialize, Default, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+#[serde(deny_unknown_fields)]
+pub struct Sleep {
+    /// Whether to mute output devices before sleeping and unmute them after
+    /// waking
+    #[serde(default)]
+    pub mute: bool,
+    /// Whether to set the default devices back after waking when they
+    /// reappear
+    #[serde(default)]
+    pub restore_defaults: bool,
+}
+
+impl Sleep {
+    pub fn is_enabled(&self) -> bool {
+        self.mute || self.restore_defaults
+    }
+}
+
+/// Showing which applications are recording from a microphone.
+#[derive(Deserialize, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+#[serde(deny_unknown_fields)]
+pub struct Recording {
+    /// Whether to show a line listing the applications recording
+    #[serde(default = "Recording::default_indicator")]
+    pub indicator: bool,
+    /// A shell command run when an application starts recording, with the
+    /// application's name in `WIREMIX_RECORDING_CLIENT`
+    #[serde(default)]
+    pub on_start: Option<String>,
+    /// Whether to show that the default source is muted on the same line
+    #[serde(default = "Recording::default_muted_banner")]
+    pub muted_banner: bool,
+}
+
+impl Recording {
+    fn default_indicator() -> bool {
+        true
+    }
+
+    fn default_muted_banner() -> bool {
+        true
+    }
+}
+
+impl Default for Recording {
+    fn default() -> Self {
+        Self {
+            indicator: Self::default_indicator(),
+            on_start: None,
+            muted_banner: Self::default_muted_banner(),
+        }
+    }
+}
+
+/// Logging the peak levels of nodes to a file while wiremix runs.
+#[derive(Deserialize, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+#[serde(deny_unknown_fields)]
+pub struct Metering {
+    /// The file to append levels to, or none to not log them
+    #[serde(default)]
+    pub path: Option<PathBuf>,
+    #[serde(default)]
+    pub format: MeteringFormat,
+    /// Names of the nodes to log, or the default source if empty
+    #[serde(default)]
+    pub nodes: Vec<String>,
+    /// Seconds between rows
+    #[serde(default = "Metering::default_interval")]
+    pub interval: f32,
+}
+
+impl Metering {
+    fn default_interval() -> f32 {
+        0.1
+    }
+}
+
+impl Default for Metering {
+    fn default() -> Self {
+        Self {
+            path: None,
+            format: MeteringFormat::default(),
+            nodes: Vec::new(),
+            interval: Self::default_interval(),
+        }
+    }
+}
+
+/// Warning when the default sink stays silent while streams play to it.
+#[derive(Deserialize, Debug, Default)]
+#[cfg_attr(test, derive(PartialEq))]
+#[serde(deny_unknown_fields)]
+pub struct Watchdog {
+    /// Seconds of silence before warning, or none to not check
+    #[serde(default)]
+    pub silent_after: Option<f32>,
+    /// Command run when warning, with the sink's name in `WIREMIX_SINK`
+    #[serde(default)]
+    pub on_silent: Option<String>,
+}
+
+/// Setting the terminal title and sending notifications through the terminal.
+#[derive(Deserialize, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+#[serde(deny_unknown_fields)]
+pub struct Osc {
+    /// Format of the title, rendered for the default sink like the lines of
+    /// `wiremix list`. An empty format leaves the title alone.
+    #[serde(default = "Osc::default_title")]
+    pub title: ListFormat,
+    /// Messages which are also sent as desktop notifications
+    #[serde(default)]
+    pub notify: Vec<Notice>,
+    #[serde(default)]
+    pub protocol: osc::Protocol,
+}
+
+impl Osc {
+    fn default_title() -> ListFormat {
+        "wiremix: {name} {volume}%".parse().unwrap()
+    }
+}
+
+impl Default for Osc {
+    fn default() -> Self {
+        Self {
+            title: Self::default_title(),
+            notify: Vec::new(),
+            protocol: Default::default(),
+        }
+    }
+}
+
+/// Kinds of messages which can be sent as notifications.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Notice {
+    /// The default sink or source was replaced after it disappeared
+    Default,
+    /// A jack was plugged in or unplugged
+    Port,
+    /// A Bluetooth device's battery is low
+    Battery,
+    /// An application started recording
+    Recording,
+    /// The default sink is silent while streams play to it
+    Silent,
+    /// Something failed
+    Error,
+}
+
+/// An RTP stream which can be started and stopped with
+/// [`ToggleRtp`](`crate::app::Action::ToggleRtp`).
+#[derive(Deserialize, Debug, Clone)]
+#[cfg_attr(test, derive(PartialEq))]
+#[serde(deny_unknown_fields)]
+pub struct RtpStream {
+    pub direction: RtpDirection,
+    /// Destination address when sending, or SAP address when receiving
+    pub address: Option<String>,
+    /// Destination port when sending
+    pub port: Option<u16>,
+    /// Latency in milliseconds when receiving
+    pub latency_msec: Option<u32>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RtpDirection {
+    Send,
+    Receive,
+}
+
+/// Settings applied at a time of day by the
+/// [`Scheduler`](`crate::schedule::Scheduler`), such as capping the volume
+/// at night.
+#[derive(Deserialize, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+#[serde(deny_unknown_fields)]
+pub struct ScheduleEntry {
+    /// Shown when the entry takes effect
+    pub name: Option<String>,
+    /// The local time the entry takes effect
+    pub at: TimeOfDay,
+    /// Highest volume of output devices until the next entry, on the scale
+    /// of the volume bars
+    pub max_volume: Option<f32>,
+    /// How long lowering a volume to max_volume takes
+    #[serde(default = "ScheduleEntry::default_fade_ms")]
+    pub fade_ms: u32,
+}
+
+impl ScheduleEntry {
+    fn default_fade_ms() -> u32 {
+        2000
+    }
+}
+
+/// A volume set by
+/// [`InitialVolumes`](`crate::initial_volume::InitialVolumes`) on streams
+/// matching a selector the first time they appear.
+#[derive(Deserialize, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+#[serde(deny_unknown_fields)]
+pub struct InitialVolume {
+    pub select: Selector,
+    /// On the scale of the volume bars
+    pub volume: f32,
+}
+
+impl RtpStream {
+    /// The pipewire-pulse module implementing this stream and its arguments.
+    pub fn module(&self) -> (&'static str, Vec<String>) {
+        let mut args = Vec::new();
+        match self.direction {
+            RtpDirection::Send => {
+                if let Some(address) = &self.address {
+                    args.push(format!("destination_ip={}", address));
+                }
+                if let Some(port) = self.port {
+                    args.push(format!("port={}", port));
+                }
+                ("module-rtp-send", args)
+            }
+            RtpDirection::Receive => {
+                if let Some(address) = &self.address {
+                    args.push(format!("sap_address={}", address));
+                }
+                if let Some(latency_msec) = self.latency_msec {
+                    args.push(format!("latency_msec={}", latency_msec));
+                }
+                ("module-rtp-recv", args)
+            }
+        }
+    }
+}
+
+/// Columns to show for each item in the node tabs. Tabs with columns show
+/// each item on a single line made up of the columns.
+#[derive(Deserialize, Default, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+#[serde(deny_unknown_fields)]
+pub struct Columns {
+    #[serde(default)]
+    pub playback: Vec<Column>,
+    #[serde(default)]
+    pub recording: Vec<Column>,
+    #[serde(default)]
+    pub output: Vec<Column>,
+    #[serde(default)]
+    pub input: Vec<Column>,
+    #[serde(default)]
+    pub jack: Vec<Column>,
+}
+
+/// An extra tab of the playback and recording streams with some media.roles,
+/// such as a "Calls" tab of Communication streams.
+#[derive(Deserialize, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+#[serde(deny_unknown_fields)]
+pub struct RoleTab {
+    pub title: String,
+    /// media.role values of the streams shown, such as "Music"
+    pub roles: Vec<String>,
+    #[serde(default)]
+    pub sort: TabSort,
+    /// Columns of the tab, like those in [`Columns`]
+    #[serde(default)]
+    pub columns: Vec<Column>,
+}
+
+/// The order of the objects in a tab.
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TabSort {
+    /// The order they appeared in
+    #[default]
+    Serial,
+    /// Alphabetically by title
+    Title,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Column {
+    pub kind: ColumnKind,
+    /// Width in characters. Columns without a width share the space left.
+    pub width: Option<u16>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnKind {
+    /// Name, with the default endpoint indicator
+    Name,
+    /// Volume bar
+    Volume,
+    /// Volume percentage
+    Percent,
+    /// Volume in decibels
+    Db,
+    MediaClass,
+    /// application.name of the client
+    Client,
+    /// Requested latency in milliseconds
+    Latency,
+    /// Negotiated sample format, rate and channel count
+    Format,
+    /// Negotiated channel positions
+    ChannelMap,
+    /// Stream and graph rates of resampled streams
+    Resample,
+    /// Target sink/source or route
+    Target,
+}
+
+/// How the filled portion of volume bars and meters is drawn.
+#[derive(Deserialize, Default, Debug, Clone, Copy, PartialEq)]
+pub enum Glyphs {
+    /// One character set symbol per cell
+    #[default]
+    Symbols,
+    /// Braille dots, giving 2 steps per cell horizontally and 4 vertically
+    Braille,
+}
+
 #[derive(Debug)]
 #[cfg_attr(test, derive(PartialEq))]
 pub struct CharSet {
     pub default_device: String,
     pub default_stream: String,
+    pub loud_warning: String,
+    pub resampled: String,
+    pub paused: String,
+    pub pinned: String,
+    pub recording: String,
     pub selector_top: String,
     pub selector_middle: String,
     pub selector_bottom: String,
     pub tab_marker_left: String,
     pub tab_marker_right: String,
     pub list_more: String,
+    pub scrollbar_track: String,
+    pub scrollbar_thumb: String,
+    pub scrollbar_horizontal_track: String,
+    pub scrollbar_horizontal_thumb: String,
     pub volume_empty: String,
     pub volume_filled: String,
+    pub volume_vertical_empty: String,
+    pub volume_vertical_filled: String,
+    pub volume_glyphs: Glyphs,
     pub meter_left_inactive: String,
     pub meter_left_active: String,
     pub meter_left_overload: String,
@@ -143,10 +794,14 @@ pub struct CharSet {
     pub meter_center_left_active: String,
     pub meter_center_right_inactive: String,
     pub meter_center_right_active: String,
+    pub meter_glyphs: Glyphs,
     pub dropdown_icon: String,
+    /// Frames of the spinner shown while a device switches profiles, one
+    /// character each
+    pub spinner: String,
     pub dropdown_selector: String,
     pub dropdown_more: String,
-    pub dropdown_border: BorderType,
+    pub dropdown_border: border::Set,
 }
 
 #[derive(Deserialize, Debug)]
@@ -154,11 +809,23 @@ pub struct CharSet {
 pub struct Theme {
     pub default_device: Style,
     pub default_stream: Style,
+    pub loud_warning: Style,
+    pub resampled: Style,
+    pub paused: Style,
+    pub pinned: Style,
+    pub recording: Style,
     pub selector: Style,
     pub tab: Style,
     pub tab_selected: Style,
     pub tab_marker: Style,
+    pub flash: Style,
     pub list_more: Style,
+    pub scrollbar_track: Style,
+    pub scrollbar_thumb: Style,
+    pub row_unselected: Style,
+    pub row_muted: Style,
+    pub row_ended: Style,
+    pub row_default: Style,
     pub node_title: Style,
     pub node_target: Style,
     pub volume: Style,
@@ -182,6 +849,26 @@ fn default_mouse() -> bool {
     true
 }
 
+fn default_kitty_keyboard() -> bool {
+    true
+}
+
+fn default_monitors() -> bool {
+    true
+}
+
+fn default_volume_rate() -> f32 {
+    30.0
+}
+
+fn default_history() -> f32 {
+    60.0
+}
+
+fn default_low_battery_warning() -> u8 {
+    20
+}
+
 fn default_peaks() -> Option<Peaks> {
     Some(Peaks::default())
 }
@@ -198,7 +885,90 @@ fn default_theme_name() -> String {
     String::from("default")
 }
 
+/// A table with a dotted key set to a value. Values are parsed as they would be
+/// in the file, except that strings don't need quotes.
+fn assignment(key: &str, value: &str) -> Result<toml::Table, toml::de::Error> {
+    toml::from_str(&format!("{} = {}", key, value)).or_else(|_| {
+        let value = toml::Value::String(String::from(value));
+        toml::from_str(&format!("{} = {}", key, value))
+    })
+}
+
+/// Parse a `--set key=value` option into a table to merge over the
+/// configuration file.
+pub fn parse_override(option: &str) -> Result<toml::Table, String> {
+    let Some((key, value)) = option.split_once('=') else {
+        return Err(String::from("expected KEY=VALUE"));
+    };
+    let table = assignment(key.trim(), value.trim())
+        .map_err(|err| String::from(err.message().trim_end()))?;
+    ConfigFile::deserialize(toml::Value::Table(table.clone()))
+        .map_err(|err| String::from(err.message().trim_end()))?;
+    Ok(table)
+}
+
 impl ConfigFile {
+    /// Override options with `WIREMIX_*` environment variables, such as
+    /// `WIREMIX_THEME` for `theme`. Values are parsed as they would be in the
+    /// file, except that strings don't need quotes.
+    pub fn apply_env(
+        &mut self,
+        var: impl Fn(&str) -> Option<String>,
+    ) -> Result<(), anyhow::Error> {
+        macro_rules! apply {
+            ($($field:ident),* $(,)?) => {$(
+                let name =
+                    format!("WIREMIX_{}", stringify!($field).to_uppercase());
+                if let Some(value) = var(&name) {
+                    self.$field = Self::from_env(stringify!($field), &value)
+                        .with_context(|| format!("Invalid {}", name))?
+                        .$field;
+                }
+            )*};
+        }
+
+        apply!(
+            remote,
+            fps,
+            mouse,
+            peaks,
+            compact,
+            inline,
+            kitty_keyboard,
+            reduced_motion,
+            marquee,
+            loudness_warning,
+            hide_paused_after,
+            keep_ended_for,
+            low_battery_warning,
+            on_port_change,
+            pause_on_disconnect,
+            flat_volume,
+            notification_group,
+            monitors,
+            volume_rate,
+            volume_feedback,
+            history,
+            orientation,
+            char_set,
+            theme,
+            locale,
+            input_mode,
+            tab,
+            split,
+            startup_selection,
+            restore_session,
+        );
+        Ok(())
+    }
+
+    /// A configuration with just one option set from an environment variable.
+    fn from_env(key: &str, value: &str) -> Result<Self, anyhow::Error> {
+        Ok(Self::deserialize(toml::Value::Table(assignment(
+            key, value,
+        )?))?)
+    }
+
     /// Override configuration with command-line arguments.
     pub fn apply_opt(&mut self, opt: &Opt) {
         if let Some(remote) = &opt.remote {
@@ -221,6 +991,26 @@ impl ConfigFile {
             self.peaks = Some(peaks.clone());
         }
 
+        if opt.no_compact {
+            self.compact = false;
+        }
+
+        if opt.compact {
+            self.compact = true;
+        }
+
+        if let Some(inline) = opt.inline {
+            self.inline = Some(inline);
+        }
+
+        if opt.no_reduced_motion {
+            self.reduced_motion = false;
+        }
+
+        if opt.reduced_motion {
+            self.reduced_motion = true;
+        }
+
         if let Some(char_set) = &opt.char_set {
             self.char_set = char_set.clone();
         }
@@ -231,6 +1021,12 @@ impl ConfigFile {
 
         if let Some(tab) = &opt.tab {
             self.tab = Some(*tab);
+            // Open the requested tab rather than the selection's
+            self.startup_selection = StartupSelection::First;
+        }
+
+        if let Some(split) = &opt.split {
+            self.split = Some(*split);
         }
     }
 }
@@ -252,16 +1048,61 @@ impl TryFrom<ConfigFile> for Config {
             anyhow::bail!("theme '{}' does not exist", &config_file.theme);
         };
 
+        let gradient = config_file.gradient.try_resolve()?;
+
         Ok(Self {
             remote: config_file.remote,
             fps: config_file.fps,
             mouse: config_file.mouse,
             peaks: config_file.peaks.unwrap_or_default(),
+            compact: config_file.compact,
+            inline: config_file.inline,
+            kitty_keyboard: config_file.kitty_keyboard,
+            reduced_motion: config_file.reduced_motion,
+            marquee: config_file.marquee,
+            loudness_warning: config_file.loudness_warning,
+            hide_paused_after: config_file.hide_paused_after,
+            keep_ended_for: config_file.keep_ended_for,
+            low_battery_warning: config_file.low_battery_warning,
+            on_port_change: config_file.on_port_change,
+            pause_on_disconnect: config_file.pause_on_disconnect,
+            flat_volume: config_file.flat_volume,
+            notification_group: config_file.notification_group,
+            monitors: config_file.monitors,
+            volume_rate: config_file.volume_rate,
+            volume_feedback: config_file.volume_feedback,
+            history: config_file.history,
+            orientation: config_file.orientation,
+            gradient,
             char_set,
             theme,
+            locale: Locale::default(),
             keybindings: config_file.keybindings,
+            input_mode: config_file.input_mode,
+            macros: config_file.macros,
+            macros_path: macros::default_path(),
             names: config_file.names,
+            priorities: config_file.priorities,
+            linked: config_file.linked,
+            ducking: config_file.ducking,
+            dnd: config_file.dnd,
+            sleep: config_file.sleep,
+            recording: config_file.recording,
+            metering: config_file.metering,
+            watchdog: config_file.watchdog,
+            osc: config_file.osc,
+            columns: config_file.columns,
+            rtp: config_file.rtp,
+            schedule: config_file.schedule,
+            initial_volume: config_file.initial_volume,
+            role_tab: config_file.role_tab,
             tab: config_file.tab.unwrap_or_default(),
+            split: config_file.split,
+            startup_selection: config_file.startup_selection,
+            restore_session: config_file.restore_session,
+            session_path: session::default_path(),
+            export_path: config_file.export_path,
+            path: None,
         })
     }
 }
@@ -280,36 +1121,125 @@ impl Config {
         None
     }
 
+    /// The configuration file with every option set to its default and
+    /// documented in comments.
+    pub const EXAMPLE: &'static str = include_str!("../wiremix.toml");
+
+    /// [`Config::EXAMPLE`] with every option commented out, so that it
+    /// documents the defaults without pinning them.
+    pub fn example_commented() -> String {
+        let mut commented = String::from(
+            "# Generated by wiremix init-config. Uncomment an option to change \
+             it.\n\n",
+        );
+        for line in Self::EXAMPLE.lines() {
+            if !line.is_empty() && !line.starts_with('#') {
+                commented.push('#');
+            }
+            commented.push_str(line);
+            commented.push('\n');
+        }
+        commented
+    }
+
+    /// Write [`Config::example_commented()`] to the supplied path, creating
+    /// its directory if needed. An existing file is only replaced if `force`
+    /// is set.
+    pub fn init(path: &Path, force: bool) -> Result<(), anyhow::Error> {
+        if path.exists() && !force {
+            anyhow::bail!(
+                "'{}' already exists (use --force to replace it)",
+                path.display()
+            );
+        }
+        let context = || {
+            format!(
+                "Failed to write configuration to file '{}'",
+                path.display()
+            )
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(context)?;
+        }
+        fs::write(path, Self::example_commented()).with_context(context)
+    }
+
+    /// JSON Schema describing the configuration file.
+    pub fn schema() -> serde_json::Value {
+        schema::schema()
+    }
+
+    /// The configuration reference, for the in-app help.
+    pub fn reference() -> Vec<Section> {
+        reference::sections()
+    }
+
+    /// The configuration reference as a wiremix.toml(5) man page.
+    pub fn man_page() -> String {
+        reference::man_page()
+    }
+
+    /// Check the configuration file at the supplied path for problems without
+    /// applying it. A missing file has no problems.
+    pub fn check(path: &Path) -> Result<(), anyhow::Error> {
+        if !path.exists() {
+            return Ok(());
+        }
+        check::check(
+            path,
+            &|path| fs::read_to_string(path),
+            &toml::Table::new(),
+        )?;
+        Ok(())
+    }
+
     /// Parse configuration from the file at the supplied path.
     pub fn try_new(
         path: Option<&Path>,
         opt: &Opt,
     ) -> Result<Self, anyhow::Error> {
+        let mut overrides = toml::Table::new();
+        for set in &opt.set {
+            include::merge(&mut overrides, set.clone());
+        }
         let mut config_file: ConfigFile = match path {
-            Some(path) if path.exists() => {
-                let context = || {
-                    format!(
-                        "Failed to read configuration from file '{}'",
-                        path.display()
-                    )
-                };
-
-                let toml_str =
-                    fs::read_to_string(path).with_context(context)?;
-
-                toml::from_str(&toml_str).with_context(context)?
+            Some(path) if path.exists() => check::check(
+                path,
+                &|path| fs::read_to_string(path),
+                &overrides,
+            )?,
+            _ => {
+                ConfigFile::deserialize(toml::Value::Table(overrides.clone()))?
             }
-            _ => toml::from_str("")?,
         };
-        // Override with command-line options
+        // Override with environment variables and then command-line options,
+        // except for environment variables for options given with --set
+        let set = |name: &str| {
+            overrides
+                .keys()
+                .any(|key| name == format!("WIREMIX_{}", key.to_uppercase()))
+        };
+        config_file
+            .apply_env(|name| env::var(name).ok().filter(|_| !set(name)))?;
         config_file.apply_opt(opt);
         // Emulate signals
         config_file
             .keybindings
+            .global
             .extend(Keybinding::control_char_keybindings());
         let config_file = config_file;
+        let locale = config_file.locale.clone();
 
-        Self::try_from(config_file)
+        let mut config = Self::try_from(config_file)?;
+        // Translations are kept next to the configuration file
+        let locales =
+            path.and_then(Path::parent).map(|dir| dir.join("locales"));
+        config.locale =
+            Locale::load(locale.as_deref(), locales.as_deref(), |name| {
+                env::var(name).ok()
+            })?;
+        config.path = path.map(Path::to_path_buf);
+        Ok(config)
     }
 }
 
@@ -335,6 +1265,69 @@ mod tests {
         assert!(toml::from_str::<Keybinding>(config).is_err());
     }
 
+    #[test]
+    fn context_keybinding_overrides_global() {
+        use crate::input::ContextStack;
+
+        let config = r#"
+        keybindings = [
+          { key = "Esc", action = "Exit", context = "list" },
+        ]
+        "#;
+        let config_file = toml::from_str::<ConfigFile>(config).unwrap();
+        let keybindings = config_file.keybindings;
+        let esc = KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE);
+
+        let mut contexts = ContextStack::default();
+        assert_eq!(
+            keybindings.get(&contexts, &esc),
+            Some(Action::CloseDropdown)
+        );
+        contexts.push(KeyContext::List);
+        assert_eq!(keybindings.get(&contexts, &esc), Some(Action::Exit));
+        contexts.push(KeyContext::Dropdown);
+        assert_eq!(keybindings.get(&contexts, &esc), Some(Action::Exit));
+    }
+
+    #[test]
+    fn env_overrides() {
+        let mut config_file = toml::from_str::<ConfigFile>("").unwrap();
+        config_file
+            .apply_env(|name| match name {
+                "WIREMIX_THEME" => Some(String::from("plain")),
+                "WIREMIX_FPS" => Some(String::from("30")),
+                "WIREMIX_COMPACT" => Some(String::from("true")),
+                "WIREMIX_TAB" => Some(String::from("output")),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(config_file.theme, "plain");
+        assert_eq!(config_file.fps, Some(30.0));
+        assert!(config_file.compact);
+        assert_eq!(config_file.tab, Some(TabKind::Output));
+
+        assert!(config_file
+            .apply_env(|name| (name == "WIREMIX_MOUSE").then(String::new))
+            .is_err());
+    }
+
+    #[test]
+    fn parse_overrides() {
+        let table = parse_override("theme=plain").unwrap();
+        assert_eq!(table["theme"].as_str(), Some("plain"));
+
+        let table =
+            parse_override("char_sets.x.default_device = \"*\"").unwrap();
+        assert_eq!(
+            table["char_sets"]["x"]["default_device"].as_str(),
+            Some("*")
+        );
+
+        assert!(parse_override("theme").is_err());
+        assert!(parse_override("mouse=sometimes").is_err());
+        assert!(parse_override("unknown=1").is_err());
+    }
+
     #[test]
     fn unknown_field_names() {
         let config = r#"
@@ -355,10 +1348,53 @@ mod tests {
         assert!(toml::from_str::<NameOverride>(config).is_err());
     }
 
+    #[test]
+    fn columns_per_tab() {
+        let config = r#"
+        [columns]
+        playback = [ { kind = "name" }, { kind = "db", width = 9 } ]
+        "#;
+        let config_file = toml::from_str::<ConfigFile>(config).unwrap();
+        let columns = config_file.columns;
+        assert_eq!(
+            columns.playback,
+            vec![
+                Column {
+                    kind: ColumnKind::Name,
+                    width: None
+                },
+                Column {
+                    kind: ColumnKind::Db,
+                    width: Some(9)
+                },
+            ]
+        );
+        assert!(columns.recording.is_empty());
+
+        let config = r#"
+        [columns]
+        output = [ { kind = "unknown" } ]
+        "#;
+        assert!(toml::from_str::<ConfigFile>(config).is_err());
+        let config = r#"
+        [columns]
+        monitor = [ { kind = "name" } ]
+        "#;
+        assert!(toml::from_str::<ConfigFile>(config).is_err());
+    }
+
+    #[test]
+    fn commented_example_is_empty() {
+        let commented: ConfigFile =
+            toml::from_str(&Config::example_commented()).unwrap();
+        let default: ConfigFile = toml::from_str("").unwrap();
+
+        assert_eq!(default, commented);
+    }
+
     #[test]
     fn example_config_file_matches_default_config_file() {
-        let toml_str = include_str!("../wiremix.toml");
-        let example: ConfigFile = toml::from_str(toml_str).unwrap();
+        let example: ConfigFile = toml::from_str(Config::EXAMPLE).unwrap();
         let default: ConfigFile = toml::from_str("").unwrap();
 
         assert_eq!(default, example);
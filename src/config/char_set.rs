@@ -3,10 +3,10 @@
 
 use std::collections::HashMap;
 
-use ratatui::widgets::block::BorderType;
+use ratatui::{symbols::border, widgets::block::BorderType};
 use serde::{de::Error, Deserialize};
 
-use crate::config::CharSet;
+use crate::config::{CharSet, Glyphs};
 
 // This is what actually gets parsed from the config.
 #[derive(Deserialize, Debug)]
@@ -15,14 +15,26 @@ pub struct CharSetOverlay {
     inherit: Option<String>,
     default_device: Option<String>,
     default_stream: Option<String>,
+    loud_warning: Option<String>,
+    resampled: Option<String>,
+    paused: Option<String>,
+    pinned: Option<String>,
+    recording: Option<String>,
     selector_top: Option<String>,
     selector_middle: Option<String>,
     selector_bottom: Option<String>,
     tab_marker_left: Option<String>,
     tab_marker_right: Option<String>,
     list_more: Option<String>,
+    scrollbar_track: Option<String>,
+    scrollbar_thumb: Option<String>,
+    scrollbar_horizontal_track: Option<String>,
+    scrollbar_horizontal_thumb: Option<String>,
     volume_empty: Option<String>,
     volume_filled: Option<String>,
+    volume_vertical_empty: Option<String>,
+    volume_vertical_filled: Option<String>,
+    volume_glyphs: Option<Glyphs>,
     meter_left_inactive: Option<String>,
     meter_left_active: Option<String>,
     meter_left_overload: Option<String>,
@@ -33,7 +45,9 @@ pub struct CharSetOverlay {
     meter_center_left_active: Option<String>,
     meter_center_right_inactive: Option<String>,
     meter_center_right_active: Option<String>,
+    meter_glyphs: Option<Glyphs>,
     dropdown_icon: Option<String>,
+    spinner: Option<String>,
     dropdown_selector: Option<String>,
     dropdown_more: Option<String>,
     dropdown_border: Option<BorderTypeDef>,
@@ -47,17 +61,35 @@ enum BorderTypeDef {
     Thick,
     QuadrantInside,
     QuadrantOutside,
+    Ascii,
 }
 
-impl From<BorderTypeDef> for BorderType {
+/// Border drawn using only ASCII characters.
+const ASCII_BORDER: border::Set = border::Set {
+    top_left: "+",
+    top_right: "+",
+    bottom_left: "+",
+    bottom_right: "+",
+    vertical_left: "|",
+    vertical_right: "|",
+    horizontal_top: "-",
+    horizontal_bottom: "-",
+};
+
+impl From<BorderTypeDef> for border::Set {
     fn from(def: BorderTypeDef) -> Self {
         match def {
-            BorderTypeDef::Plain => Self::Plain,
-            BorderTypeDef::Rounded => Self::Rounded,
-            BorderTypeDef::Double => Self::Double,
-            BorderTypeDef::Thick => Self::Thick,
-            BorderTypeDef::QuadrantInside => Self::QuadrantInside,
-            BorderTypeDef::QuadrantOutside => Self::QuadrantOutside,
+            BorderTypeDef::Plain => BorderType::Plain.to_border_set(),
+            BorderTypeDef::Rounded => BorderType::Rounded.to_border_set(),
+            BorderTypeDef::Double => BorderType::Double.to_border_set(),
+            BorderTypeDef::Thick => BorderType::Thick.to_border_set(),
+            BorderTypeDef::QuadrantInside => {
+                BorderType::QuadrantInside.to_border_set()
+            }
+            BorderTypeDef::QuadrantOutside => {
+                BorderType::QuadrantOutside.to_border_set()
+            }
+            BorderTypeDef::Ascii => ASCII_BORDER,
         }
     }
 }
@@ -70,6 +102,7 @@ impl TryFrom<CharSetOverlay> for CharSet {
             Some("default") => CharSet::default(),
             Some("compat") => CharSet::compat(),
             Some("extracompat") => CharSet::extracompat(),
+            Some("ascii") => CharSet::ascii(),
             Some(inherit) => {
                 anyhow::bail!("'{}' is not a built-in character set", inherit)
             }
@@ -98,14 +131,25 @@ impl TryFrom<CharSetOverlay> for CharSet {
 
         validate_and_set!(default_device, 1);
         validate_and_set!(default_stream, 1);
+        validate_and_set!(loud_warning, 1);
+        validate_and_set!(resampled, 1);
+        validate_and_set!(paused, 1);
+        validate_and_set!(pinned, 1);
+        validate_and_set!(recording, 1);
         validate_and_set!(selector_top, 1);
         validate_and_set!(selector_middle, 1);
         validate_and_set!(selector_bottom, 1);
         validate_and_set!(tab_marker_left, 1);
         validate_and_set!(tab_marker_right, 1);
         validate_and_set!(list_more, 0);
+        validate_and_set!(scrollbar_track, 1);
+        validate_and_set!(scrollbar_thumb, 1);
+        validate_and_set!(scrollbar_horizontal_track, 1);
+        validate_and_set!(scrollbar_horizontal_thumb, 1);
         validate_and_set!(volume_empty, 1);
         validate_and_set!(volume_filled, 1);
+        validate_and_set!(volume_vertical_empty, 1);
+        validate_and_set!(volume_vertical_filled, 1);
         validate_and_set!(meter_left_inactive, 1);
         validate_and_set!(meter_left_active, 1);
         validate_and_set!(meter_left_overload, 1);
@@ -117,9 +161,18 @@ impl TryFrom<CharSetOverlay> for CharSet {
         validate_and_set!(meter_center_right_inactive, 1);
         validate_and_set!(meter_center_right_active, 1);
         validate_and_set!(dropdown_icon, 1);
+        validate_and_set!(spinner, 0);
         validate_and_set!(dropdown_selector, 1);
         validate_and_set!(dropdown_more, 0);
 
+        if let Some(volume_glyphs) = overlay.volume_glyphs {
+            char_set.volume_glyphs = volume_glyphs;
+        }
+
+        if let Some(meter_glyphs) = overlay.meter_glyphs {
+            char_set.meter_glyphs = meter_glyphs;
+        }
+
         if let Some(dropdown_border) = overlay.dropdown_border {
             char_set.dropdown_border = dropdown_border.into();
         }
@@ -133,14 +186,26 @@ impl Default for CharSet {
         Self {
             default_device: String::from("◇"),
             default_stream: String::from("◇"),
+            loud_warning: String::from("!"),
+            resampled: String::from("≈"),
+            paused: String::from("⏸"),
+            pinned: String::from("⊙"),
+            recording: String::from("●"),
             selector_top: String::from("░"),
             selector_middle: String::from("▒"),
             selector_bottom: String::from("░"),
             tab_marker_left: String::from("["),
             tab_marker_right: String::from("]"),
             list_more: String::from("•••"),
+            scrollbar_track: String::from("│"),
+            scrollbar_thumb: String::from("┃"),
+            scrollbar_horizontal_track: String::from("─"),
+            scrollbar_horizontal_thumb: String::from("━"),
             volume_empty: String::from("╌"),
             volume_filled: String::from("━"),
+            volume_vertical_empty: String::from("╎"),
+            volume_vertical_filled: String::from("┃"),
+            volume_glyphs: Glyphs::Symbols,
             meter_left_inactive: String::from("▮"),
             meter_left_active: String::from("▮"),
             meter_left_overload: String::from("▮"),
@@ -151,10 +216,12 @@ impl Default for CharSet {
             meter_center_left_active: String::from("▮"),
             meter_center_right_inactive: String::from("▮"),
             meter_center_right_active: String::from("▮"),
+            meter_glyphs: Glyphs::Symbols,
             dropdown_icon: String::from("▼"),
+            spinner: String::from("⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏"),
             dropdown_selector: String::from(">"),
             dropdown_more: String::from("•••"),
-            dropdown_border: BorderType::Rounded,
+            dropdown_border: border::ROUNDED,
         }
     }
 }
@@ -165,6 +232,7 @@ impl CharSet {
             (String::from("default"), CharSet::default()),
             (String::from("compat"), CharSet::compat()),
             (String::from("extracompat"), CharSet::extracompat()),
+            (String::from("ascii"), CharSet::ascii()),
         ])
     }
 
@@ -172,14 +240,26 @@ impl CharSet {
         Self {
             default_device: String::from("◊"),
             default_stream: String::from("◊"),
+            loud_warning: String::from("!"),
+            resampled: String::from("~"),
+            paused: String::from("="),
+            pinned: String::from("@"),
+            recording: String::from("*"),
             selector_top: String::from("░"),
             selector_middle: String::from("▒"),
             selector_bottom: String::from("░"),
             tab_marker_left: String::from("["),
             tab_marker_right: String::from("]"),
             list_more: String::from("•••"),
+            scrollbar_track: String::from("│"),
+            scrollbar_thumb: String::from("█"),
+            scrollbar_horizontal_track: String::from("─"),
+            scrollbar_horizontal_thumb: String::from("█"),
             volume_empty: String::from("─"),
             volume_filled: String::from("━"),
+            volume_vertical_empty: String::from("│"),
+            volume_vertical_filled: String::from("┃"),
+            volume_glyphs: Glyphs::Symbols,
             meter_left_inactive: String::from("┃"),
             meter_left_active: String::from("┃"),
             meter_left_overload: String::from("┃"),
@@ -190,10 +270,12 @@ impl CharSet {
             meter_center_left_active: String::from("█"),
             meter_center_right_inactive: String::from("█"),
             meter_center_right_active: String::from("█"),
+            meter_glyphs: Glyphs::Symbols,
             dropdown_icon: String::from("▼"),
+            spinner: String::from("|/-\\"),
             dropdown_selector: String::from(">"),
             dropdown_more: String::from("•••"),
-            dropdown_border: BorderType::Plain,
+            dropdown_border: border::PLAIN,
         }
     }
 
@@ -201,14 +283,26 @@ impl CharSet {
         Self {
             default_device: String::from("*"),
             default_stream: String::from("*"),
+            loud_warning: String::from("!"),
+            resampled: String::from("~"),
+            paused: String::from("="),
+            pinned: String::from("@"),
+            recording: String::from("*"),
             selector_top: String::from("-"),
             selector_middle: String::from("="),
             selector_bottom: String::from("-"),
             tab_marker_left: String::from("["),
             tab_marker_right: String::from("]"),
             list_more: String::from("~~~"),
+            scrollbar_track: String::from("|"),
+            scrollbar_thumb: String::from("#"),
+            scrollbar_horizontal_track: String::from("-"),
+            scrollbar_horizontal_thumb: String::from("#"),
             volume_empty: String::from("-"),
             volume_filled: String::from("="),
+            volume_vertical_empty: String::from("|"),
+            volume_vertical_filled: String::from("#"),
+            volume_glyphs: Glyphs::Symbols,
             meter_left_inactive: String::from("="),
             meter_left_active: String::from("#"),
             meter_left_overload: String::from("!"),
@@ -219,10 +313,19 @@ impl CharSet {
             meter_center_left_active: String::from("["),
             meter_center_right_inactive: String::from("]"),
             meter_center_right_active: String::from("]"),
+            meter_glyphs: Glyphs::Symbols,
             dropdown_icon: String::from("\\"),
+            spinner: String::from("|/-\\"),
             dropdown_selector: String::from(">"),
             dropdown_more: String::from("~~~"),
-            dropdown_border: BorderType::Plain,
+            dropdown_border: border::PLAIN,
+        }
+    }
+
+    fn ascii() -> CharSet {
+        Self {
+            dropdown_border: ASCII_BORDER,
+            ..CharSet::extracompat()
         }
     }
 
@@ -252,6 +355,9 @@ impl CharSet {
         if !merged.contains_key("extracompat") {
             merged.insert(String::from("extracompat"), CharSet::extracompat());
         }
+        if !merged.contains_key("ascii") {
+            merged.insert(String::from("ascii"), CharSet::ascii());
+        }
         Ok(merged)
     }
 }
@@ -363,6 +469,76 @@ mod tests {
         assert_eq!(char_set.dropdown_more, "$$$$$$$$$$$$$$$$$$$$$$$$");
     }
 
+    #[test]
+    fn ascii_only() {
+        let char_set = CharSet::ascii();
+        let border = char_set.dropdown_border;
+
+        for symbol in [
+            &char_set.default_device,
+            &char_set.default_stream,
+            &char_set.paused,
+            &char_set.pinned,
+            &char_set.recording,
+            &char_set.selector_top,
+            &char_set.selector_middle,
+            &char_set.selector_bottom,
+            &char_set.tab_marker_left,
+            &char_set.tab_marker_right,
+            &char_set.list_more,
+            &char_set.scrollbar_track,
+            &char_set.scrollbar_thumb,
+            &char_set.scrollbar_horizontal_track,
+            &char_set.scrollbar_horizontal_thumb,
+            &char_set.volume_empty,
+            &char_set.volume_filled,
+            &char_set.volume_vertical_empty,
+            &char_set.volume_vertical_filled,
+            &char_set.meter_left_inactive,
+            &char_set.meter_left_active,
+            &char_set.meter_left_overload,
+            &char_set.meter_right_inactive,
+            &char_set.meter_right_active,
+            &char_set.meter_right_overload,
+            &char_set.meter_center_left_inactive,
+            &char_set.meter_center_left_active,
+            &char_set.meter_center_right_inactive,
+            &char_set.meter_center_right_active,
+            &char_set.dropdown_icon,
+            &char_set.spinner,
+            &char_set.dropdown_selector,
+            &char_set.dropdown_more,
+        ] {
+            assert!(symbol.is_ascii(), "{symbol:?} is not ASCII");
+        }
+
+        for symbol in [
+            border.top_left,
+            border.top_right,
+            border.bottom_left,
+            border.bottom_right,
+            border.vertical_left,
+            border.vertical_right,
+            border.horizontal_top,
+            border.horizontal_bottom,
+        ] {
+            assert!(symbol.is_ascii(), "{symbol:?} is not ASCII");
+        }
+    }
+
+    #[test]
+    fn inherit_ascii_border() {
+        let config = r#"
+        inherit = "ascii"
+        dropdown_icon = "v"
+        "#;
+
+        let overlay = toml::from_str::<CharSetOverlay>(config).unwrap();
+        let char_set = CharSet::try_from(overlay).unwrap();
+        assert_eq!(char_set.dropdown_icon, "v");
+        assert_eq!(char_set.dropdown_border, ASCII_BORDER);
+    }
+
     #[test]
     fn inherit_nonexistent() {
         let config = r#"
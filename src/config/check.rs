@@ -0,0 +1,476 @@
+//! Validation of configuration files with the location of each problem.
+//!
+//! Parsing catches malformed values such as bad name templates and colors.
+//! [`check()`] also catches values which parse but refer to things that don't
+//! exist, such as unknown themes and out-of-range tab indexes.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::ops::Range;
+use std::path::Path;
+
+use crossterm::event::KeyEvent;
+use serde::Deserialize;
+use toml::Spanned;
+
+use crate::app::{Action, TabKind};
+use crate::config::conditional;
+use crate::config::include::{self, Source};
+use crate::config::{ConfigFile, Keybinding};
+
+/// A problem in a configuration file.
+#[derive(Debug, PartialEq)]
+pub struct Diagnostic {
+    pub path: String,
+    /// 1-based line and column of the problem, if it can be located
+    pub location: Option<(usize, usize)>,
+    pub message: String,
+}
+
+impl Diagnostic {
+    /// A problem at a byte range of a file's source.
+    pub fn new(
+        source: &Source,
+        span: Option<Range<usize>>,
+        message: String,
+    ) -> Self {
+        Self {
+            path: source.path.display().to_string(),
+            location: span.map(|span| line_column(&source.text, span.start)),
+            message,
+        }
+    }
+
+    pub fn from_toml(source: &Source, error: toml::de::Error) -> Self {
+        Self::new(
+            source,
+            error.span(),
+            String::from(error.message().trim_end()),
+        )
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.location {
+            Some((line, column)) => {
+                write!(f, "{}:{}:{}: {}", self.path, line, column, self.message)
+            }
+            None => write!(f, "{}: {}", self.path, self.message),
+        }
+    }
+}
+
+/// Every problem found in a configuration file and the files it includes,
+/// formatted as `file:line:column: message` lines.
+#[derive(Debug)]
+pub struct Diagnostics(pub Vec<Diagnostic>);
+
+impl fmt::Display for Diagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, diagnostic) in self.0.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", diagnostic)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Diagnostics {}
+
+/// The parts of a configuration file which are checked beyond parsing, with
+/// their locations.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Located {
+    fps: Option<Spanned<f32>>,
+    volume_rate: Option<Spanned<f32>>,
+    loudness_warning: Option<Spanned<f32>>,
+    hide_paused_after: Option<Spanned<f32>>,
+    keep_ended_for: Option<Spanned<f32>>,
+    history: Option<Spanned<f32>>,
+    low_battery_warning: Option<Spanned<u8>>,
+    inline: Option<Spanned<u16>>,
+    ducking: LocatedDucking,
+    metering: LocatedMetering,
+    watchdog: LocatedWatchdog,
+    schedule: Vec<LocatedScheduleEntry>,
+    initial_volume: Vec<LocatedInitialVolume>,
+    char_set: Option<Spanned<String>>,
+    theme: Option<Spanned<String>>,
+    gradient: Option<Spanned<toml::Value>>,
+    keybindings: Vec<Spanned<Keybinding>>,
+    macros: BTreeMap<Spanned<String>, Spanned<Vec<Action>>>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct LocatedDucking {
+    amount: Option<Spanned<f32>>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct LocatedMetering {
+    interval: Option<Spanned<f32>>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct LocatedWatchdog {
+    silent_after: Option<Spanned<f32>>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct LocatedScheduleEntry {
+    max_volume: Option<Spanned<f32>>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct LocatedInitialVolume {
+    volume: Option<Spanned<f32>>,
+}
+
+/// Parse a configuration file and the files it includes with `overrides`
+/// merged over them, returning every problem found.
+pub(super) fn check(
+    path: &Path,
+    read: include::Read,
+    overrides: &toml::Table,
+) -> Result<ConfigFile, anyhow::Error> {
+    let (config_file, sources) = include::load(path, read, overrides)?;
+
+    let mut problems = Vec::new();
+    for source in &sources {
+        let located =
+            toml::from_str::<Located>(&source.text).map_err(|error| {
+                Diagnostics(vec![Diagnostic::from_toml(source, error)])
+            })?;
+        problems.extend(located.problems(&config_file).into_iter().map(
+            |(span, message)| Diagnostic::new(source, Some(span), message),
+        ));
+    }
+
+    // Conditional tables are checked even when they don't apply here
+    for (name, table) in conditional::tables(&config_file) {
+        if let Err(error) =
+            ConfigFile::deserialize(toml::Value::Table(table.clone()))
+        {
+            problems.push(Diagnostic {
+                path: path.display().to_string(),
+                location: None,
+                message: format!("[{}]: {}", name, error.message().trim_end()),
+            });
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(config_file)
+    } else {
+        Err(Diagnostics(problems).into())
+    }
+}
+
+impl Located {
+    fn problems(
+        &self,
+        config_file: &ConfigFile,
+    ) -> Vec<(Range<usize>, String)> {
+        let mut problems = Vec::new();
+        let mut problem = |span: Range<usize>, message: String| {
+            problems.push((span, message))
+        };
+
+        for (name, value, allow_zero) in [
+            ("fps", &self.fps, true),
+            ("volume_rate", &self.volume_rate, true),
+            ("loudness_warning", &self.loudness_warning, false),
+            ("hide_paused_after", &self.hide_paused_after, true),
+            ("keep_ended_for", &self.keep_ended_for, false),
+            ("history", &self.history, false),
+        ] {
+            let Some(value) = value else {
+                continue;
+            };
+            let number = *value.get_ref();
+            if number < 0.0 || (number == 0.0 && !allow_zero) {
+                let bound = if allow_zero { "negative" } else { "positive" };
+                let verb = if allow_zero { "must not be" } else { "must be" };
+                problem(value.span(), format!("{} {} {}", name, verb, bound));
+            }
+        }
+
+        if let Some(warning) = &self.low_battery_warning {
+            if *warning.get_ref() > 100 {
+                problem(
+                    warning.span(),
+                    String::from("low_battery_warning must be at most 100"),
+                );
+            }
+        }
+
+        if let Some(inline) = &self.inline {
+            if *inline.get_ref() == 0 {
+                problem(inline.span(), String::from("inline must be positive"));
+            }
+        }
+
+        if let Some(amount) = &self.ducking.amount {
+            if !(0.0..=1.0).contains(amount.get_ref()) {
+                problem(
+                    amount.span(),
+                    String::from("ducking.amount must be between 0 and 1"),
+                );
+            }
+        }
+
+        if let Some(interval) = &self.metering.interval {
+            if *interval.get_ref() <= 0.0 {
+                problem(
+                    interval.span(),
+                    String::from("metering.interval must be positive"),
+                );
+            }
+        }
+
+        if let Some(silent_after) = &self.watchdog.silent_after {
+            if *silent_after.get_ref() <= 0.0 {
+                problem(
+                    silent_after.span(),
+                    String::from("watchdog.silent_after must be positive"),
+                );
+            }
+        }
+
+        for max_volume in self
+            .schedule
+            .iter()
+            .filter_map(|entry| entry.max_volume.as_ref())
+        {
+            if *max_volume.get_ref() < 0.0 {
+                problem(
+                    max_volume.span(),
+                    String::from("schedule.max_volume must not be negative"),
+                );
+            }
+        }
+
+        for volume in self
+            .initial_volume
+            .iter()
+            .filter_map(|rule| rule.volume.as_ref())
+        {
+            if *volume.get_ref() < 0.0 {
+                problem(
+                    volume.span(),
+                    String::from("initial_volume.volume must not be negative"),
+                );
+            }
+        }
+
+        if let Some(char_set) = &self.char_set {
+            if !config_file.char_sets.contains_key(char_set.get_ref()) {
+                problem(
+                    char_set.span(),
+                    format!("char_set '{}' does not exist", char_set.get_ref()),
+                );
+            }
+        }
+
+        if let Some(theme) = &self.theme {
+            if !config_file.themes.contains_key(theme.get_ref()) {
+                problem(
+                    theme.span(),
+                    format!("theme '{}' does not exist", theme.get_ref()),
+                );
+            }
+        }
+
+        if let Some(gradient) = &self.gradient {
+            if let Err(err) = config_file.gradient.validate() {
+                problem(gradient.span(), err.to_string());
+            }
+        }
+
+        let rtp_len = config_file.rtp.len();
+        let tabs_len = TabKind::COUNT + config_file.role_tab.len();
+        let mut bound = HashMap::new();
+        for keybinding in &self.keybindings {
+            let binding = keybinding.get_ref();
+            if let Some(message) =
+                action_problem(binding.action, rtp_len, tabs_len)
+            {
+                problem(keybinding.span(), message);
+            }
+
+            // Bindings override the defaults, but not each other
+            let key = KeyEvent::new(binding.key, binding.modifiers);
+            match bound.insert((binding.context, key), binding.action) {
+                Some(action) if action != binding.action => problem(
+                    keybinding.span(),
+                    format!(
+                        "key is bound to both {:?} and {:?}{}",
+                        action,
+                        binding.action,
+                        binding.context.map_or(String::new(), |context| {
+                            format!(" in {:?} context", context).to_lowercase()
+                        })
+                    ),
+                ),
+                _ => {}
+            }
+        }
+
+        for (key, actions) in &self.macros {
+            if key.get_ref().chars().count() != 1 {
+                problem(
+                    key.span(),
+                    format!(
+                        "macro '{}' must be named by a single character",
+                        key.get_ref()
+                    ),
+                );
+            }
+            for &action in actions.get_ref() {
+                if let Some(message) = action_problem(action, rtp_len, tabs_len)
+                {
+                    problem(actions.span(), message);
+                }
+            }
+        }
+
+        problems
+    }
+}
+
+/// Describe what's wrong with an action, if anything.
+fn action_problem(
+    action: Action,
+    rtp_len: usize,
+    tabs_len: usize,
+) -> Option<String> {
+    match action {
+        Action::SelectTab(index) if index >= tabs_len => {
+            Some(format!("SelectTab {} is not a tab", index))
+        }
+        Action::ToggleRtp(index) if index >= rtp_len => Some(format!(
+            "ToggleRtp {} is not an [[rtp]] section (there are {})",
+            index, rtp_len
+        )),
+        _ => None,
+    }
+}
+
+/// The 1-based line and column of a byte offset.
+fn line_column(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset.min(source.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before
+        .rsplit('\n')
+        .next()
+        .map_or(0, |line| line.chars().count())
+        + 1;
+    (line, column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_str(source: &str) -> Vec<String> {
+        let read = |_: &Path| Ok(String::from(source));
+        match check(Path::new("wiremix.toml"), &read, &toml::Table::new()) {
+            Ok(_) => Vec::new(),
+            Err(err) => {
+                format!("{:#}", err).lines().map(String::from).collect()
+            }
+        }
+    }
+
+    #[test]
+    fn parse_error_location() {
+        let source = "mouse = true\npeaks = \"sometimes\"\n";
+        let problems = check_str(source);
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].starts_with("wiremix.toml:2:9: "));
+    }
+
+    #[test]
+    fn semantic_error_locations() {
+        let source = r#"
+theme = "nope"
+low_battery_warning = 150
+inline = 0
+keybindings = [
+  { key = { Char = "x" }, action = { SelectTab = 7 } },
+  { key = { Char = "y" }, action = { SelectTab = 6 } },
+]
+[ducking]
+amount = 1.5
+"#;
+        assert_eq!(
+            check_str(source),
+            vec![
+                "wiremix.toml:3:23: low_battery_warning must be at most 100",
+                "wiremix.toml:4:10: inline must be positive",
+                "wiremix.toml:10:10: ducking.amount must be between 0 and 1",
+                "wiremix.toml:2:9: theme 'nope' does not exist",
+                "wiremix.toml:6:3: SelectTab 7 is not a tab",
+            ]
+        );
+    }
+
+    #[test]
+    fn role_tabs_can_be_selected() {
+        let source = r#"
+keybindings = [
+  { key = { Char = "x" }, action = { SelectTab = 7 } },
+  { key = { Char = "y" }, action = { SelectTab = 8 } },
+]
+[[role_tab]]
+title = "Calls"
+roles = ["Communication"]
+"#;
+        assert_eq!(
+            check_str(source),
+            vec!["wiremix.toml:4:3: SelectTab 8 is not a tab"]
+        );
+    }
+
+    #[test]
+    fn keybinding_conflicts() {
+        let source = r#"
+keybindings = [
+  { key = { Char = "x" }, action = "ToggleMute" },
+  { key = { Char = "x" }, action = "ToggleMute" },
+  { key = { Char = "x" }, action = "Exit", context = "dropdown" },
+  { key = { Char = "x" }, modifiers = "CONTROL", action = "Exit" },
+  { key = { Char = "x" }, action = "SetDefault" },
+]
+"#;
+        assert_eq!(
+            check_str(source),
+            vec![
+                "wiremix.toml:7:3: key is bound to both ToggleMute and \
+                  SetDefault"
+            ]
+        );
+    }
+
+    #[test]
+    fn conditional_table_errors() {
+        let source = "[host.laptop]\ntheme = 7\n";
+        let problems = check_str(source);
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].starts_with("wiremix.toml: [host.laptop]: "));
+    }
+
+    #[test]
+    fn example_config_file_is_valid() {
+        assert!(check_str(crate::config::Config::EXAMPLE).is_empty());
+    }
+}
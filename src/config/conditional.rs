@@ -0,0 +1,110 @@
+//! Options which only apply on some hosts or in some environments.
+//!
+//! `[host.<hostname>]` tables apply when the hostname matches, and
+//! `[env.<VARIABLE>.<value>]` tables apply when an environment variable has
+//! the value. They are merged over the rest of the configuration, host tables
+//! first and then environment tables in order of variable name.
+
+use crate::config::{include, ConfigFile};
+
+/// Merge the tables whose conditions hold into a configuration, returning
+/// whether any did.
+pub fn apply(
+    table: &mut toml::Table,
+    hostname: Option<&str>,
+    var: &dyn Fn(&str) -> Option<String>,
+) -> bool {
+    let mut overlays = Vec::new();
+
+    if let (Some(hostname), Some(toml::Value::Table(hosts))) =
+        (hostname, table.get("host"))
+    {
+        if let Some(toml::Value::Table(overlay)) = hosts.get(hostname) {
+            overlays.push(overlay.clone());
+        }
+    }
+
+    if let Some(toml::Value::Table(vars)) = table.get("env") {
+        for (name, values) in vars {
+            let (Some(value), toml::Value::Table(values)) = (var(name), values)
+            else {
+                continue;
+            };
+            if let Some(toml::Value::Table(overlay)) = values.get(&value) {
+                overlays.push(overlay.clone());
+            }
+        }
+    }
+
+    let applied = !overlays.is_empty();
+    for overlay in overlays {
+        include::merge(table, overlay);
+    }
+    applied
+}
+
+/// Every conditional table in a configuration with its name, such as
+/// `host.laptop`.
+pub fn tables(config_file: &ConfigFile) -> Vec<(String, &toml::Table)> {
+    let hosts = config_file
+        .host
+        .iter()
+        .map(|(hostname, table)| (format!("host.{}", hostname), table));
+    let vars = config_file.env.iter().flat_map(|(name, values)| {
+        values.iter().map(move |(value, table)| {
+            (format!("env.{}.{}", name, value), table)
+        })
+    });
+    let mut tables = hosts.chain(vars).collect::<Vec<_>>();
+    tables.sort_by(|a, b| a.0.cmp(&b.0));
+    tables
+}
+
+/// The name of this machine.
+pub fn hostname() -> Option<String> {
+    nix::unistd::gethostname().ok()?.into_string().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matching_tables_are_merged() {
+        let mut table = toml::from_str::<toml::Table>(
+            r#"
+            theme = "default"
+            fps = 60.0
+
+            [host.laptop]
+            theme = "plain"
+            fps = 30.0
+
+            [host.desktop]
+            theme = "nocolor"
+
+            [env.DESKTOP_SESSION.sway]
+            fps = 20.0
+
+            [env.XDG_SESSION_TYPE.x11]
+            compact = true
+            "#,
+        )
+        .unwrap();
+        let var = |name: &str| {
+            (name == "DESKTOP_SESSION").then(|| String::from("sway"))
+        };
+
+        assert!(apply(&mut table, Some("laptop"), &var));
+        assert_eq!(table["theme"].as_str(), Some("plain"));
+        assert_eq!(table["fps"].as_float(), Some(20.0));
+        assert!(!table.contains_key("compact"));
+
+        let mut table = toml::from_str::<toml::Table>(
+            "theme = \"default\"\n[host.laptop]\ntheme = \"plain\"",
+        )
+        .unwrap();
+        assert!(!apply(&mut table, Some("desktop"), &|_| None));
+        assert_eq!(table["theme"].as_str(), Some("default"));
+    }
+}
@@ -0,0 +1,197 @@
+//! Implementation for [`Gradient`](`crate::config::Gradient`). Defines default
+//! thresholds and maps levels to colors.
+
+use std::env;
+
+use ratatui::style::Color;
+
+use crate::config::{Gradient, GradientColors};
+
+impl Gradient {
+    pub fn default_volume() -> [f32; 3] {
+        [50.0, 100.0, 150.0]
+    }
+
+    pub fn default_meter() -> [f32; 3] {
+        [-24.0, -12.0, 0.0]
+    }
+
+    /// Check that the thresholds are in increasing order.
+    pub fn validate(&self) -> Result<(), anyhow::Error> {
+        for (name, thresholds) in
+            [("volume", self.volume), ("meter", self.meter)]
+        {
+            if thresholds.windows(2).any(|pair| pair[0] > pair[1]) {
+                anyhow::bail!("gradient.{} must be in increasing order", name);
+            }
+        }
+        Ok(())
+    }
+
+    /// Validate thresholds and replace [`GradientColors::Auto`] with the
+    /// colors supported by the terminal.
+    pub fn try_resolve(mut self) -> Result<Self, anyhow::Error> {
+        self.validate()?;
+
+        if self.colors == GradientColors::Auto {
+            self.colors = GradientColors::detect(
+                env::var("COLORTERM").ok().as_deref(),
+                env::var("TERM").ok().as_deref(),
+            );
+        }
+
+        Ok(self)
+    }
+
+    /// Color for a volume in percent, if gradients are enabled.
+    pub fn volume_color(&self, percent: f32) -> Option<Color> {
+        self.color(percent, self.volume)
+    }
+
+    /// Color for a peak level in dB, if gradients are enabled.
+    pub fn meter_color(&self, db: f32) -> Option<Color> {
+        self.color(db, self.meter)
+    }
+
+    fn color(
+        &self,
+        value: f32,
+        [green, yellow, red]: [f32; 3],
+    ) -> Option<Color> {
+        // Position between two thresholds, from 0.0 to 1.0
+        fn position(value: f32, from: f32, to: f32) -> f32 {
+            if to > from {
+                ((value - from) / (to - from)).clamp(0.0, 1.0)
+            } else if value < from {
+                0.0
+            } else {
+                1.0
+            }
+        }
+
+        let (r, g) = if value < yellow {
+            ((255.0 * position(value, green, yellow)) as u8, 255)
+        } else {
+            (255, (255.0 * (1.0 - position(value, yellow, red))) as u8)
+        };
+
+        match self.colors {
+            GradientColors::Off | GradientColors::Auto => None,
+            GradientColors::TrueColor => Some(Color::Rgb(r, g, 0)),
+            GradientColors::Indexed => Some(Color::Indexed(
+                16 + 36 * cube_index(r) + 6 * cube_index(g),
+            )),
+            GradientColors::Basic if value < yellow => Some(Color::Green),
+            GradientColors::Basic if value < red => Some(Color::Yellow),
+            GradientColors::Basic => Some(Color::Red),
+        }
+    }
+}
+
+impl Default for Gradient {
+    fn default() -> Self {
+        Self {
+            colors: GradientColors::default(),
+            volume: Self::default_volume(),
+            meter: Self::default_meter(),
+        }
+    }
+}
+
+impl GradientColors {
+    /// Guess color support from the COLORTERM and TERM environment variables.
+    fn detect(colorterm: Option<&str>, term: Option<&str>) -> Self {
+        if matches!(colorterm, Some("truecolor" | "24bit")) {
+            GradientColors::TrueColor
+        } else if term.is_some_and(|term| term.contains("256color")) {
+            GradientColors::Indexed
+        } else {
+            GradientColors::Basic
+        }
+    }
+}
+
+/// Nearest level in the 6x6x6 color cube of the 256-color palette.
+fn cube_index(component: u8) -> u8 {
+    match component {
+        0..=47 => 0,
+        48..=114 => 1,
+        _ => (component - 35) / 40,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gradient(colors: GradientColors) -> Gradient {
+        Gradient {
+            colors,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn off() {
+        assert_eq!(gradient(GradientColors::Off).volume_color(100.0), None);
+    }
+
+    #[test]
+    fn truecolor() {
+        let gradient = gradient(GradientColors::TrueColor);
+        assert_eq!(gradient.volume_color(0.0), Some(Color::Rgb(0, 255, 0)));
+        assert_eq!(gradient.volume_color(75.0), Some(Color::Rgb(127, 255, 0)));
+        assert_eq!(gradient.volume_color(100.0), Some(Color::Rgb(255, 255, 0)));
+        assert_eq!(gradient.volume_color(150.0), Some(Color::Rgb(255, 0, 0)));
+        assert_eq!(gradient.meter_color(6.0), Some(Color::Rgb(255, 0, 0)));
+    }
+
+    #[test]
+    fn indexed() {
+        let gradient = gradient(GradientColors::Indexed);
+        // Pure green, yellow and red in the color cube
+        assert_eq!(gradient.meter_color(-60.0), Some(Color::Indexed(46)));
+        assert_eq!(gradient.meter_color(-12.0), Some(Color::Indexed(226)));
+        assert_eq!(gradient.meter_color(0.0), Some(Color::Indexed(196)));
+    }
+
+    #[test]
+    fn basic() {
+        let gradient = gradient(GradientColors::Basic);
+        assert_eq!(gradient.volume_color(99.0), Some(Color::Green));
+        assert_eq!(gradient.volume_color(100.0), Some(Color::Yellow));
+        assert_eq!(gradient.volume_color(150.0), Some(Color::Red));
+    }
+
+    #[test]
+    fn detect() {
+        assert_eq!(
+            GradientColors::detect(Some("truecolor"), Some("xterm")),
+            GradientColors::TrueColor
+        );
+        assert_eq!(
+            GradientColors::detect(None, Some("xterm-256color")),
+            GradientColors::Indexed
+        );
+        assert_eq!(
+            GradientColors::detect(None, Some("linux")),
+            GradientColors::Basic
+        );
+        assert_eq!(GradientColors::detect(None, None), GradientColors::Basic);
+    }
+
+    #[test]
+    fn thresholds_out_of_order() {
+        let gradient = Gradient {
+            volume: [100.0, 50.0, 150.0],
+            ..Default::default()
+        };
+        assert!(gradient.try_resolve().is_err());
+    }
+
+    #[test]
+    fn auto_resolved() {
+        let gradient = gradient(GradientColors::Auto).try_resolve().unwrap();
+        assert_ne!(gradient.colors, GradientColors::Auto);
+    }
+}
@@ -0,0 +1,181 @@
+//! Loading of configuration files which include other files.
+//!
+//! A file's `include` list is loaded first, in order, and each file overrides
+//! the ones before it. Tables are merged key by key, `keybindings` lists are
+//! concatenated, and any other value replaces the earlier one. Conditional
+//! tables and then `--set` overrides are applied to the merged configuration.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use serde::Deserialize;
+
+use crate::config::check::{Diagnostic, Diagnostics};
+use crate::config::conditional;
+use crate::config::ConfigFile;
+
+/// Reads a file into a string.
+pub type Read<'a> = &'a dyn Fn(&Path) -> io::Result<String>;
+
+/// A loaded configuration file.
+pub struct Source {
+    pub path: PathBuf,
+    pub text: String,
+}
+
+/// Load the configuration file at a path merged with the files it includes
+/// and `overrides`, and return it along with the source of every file.
+pub fn load(
+    path: &Path,
+    read: Read,
+    overrides: &toml::Table,
+) -> Result<(ConfigFile, Vec<Source>), anyhow::Error> {
+    let mut sources = Vec::new();
+    let (config_file, mut table) =
+        load_file(path, read, &mut Vec::new(), &mut sources)?;
+    let conditional = conditional::apply(
+        &mut table,
+        conditional::hostname().as_deref(),
+        &|name| std::env::var(name).ok(),
+    );
+
+    merge(&mut table, overrides.clone());
+
+    if sources.len() == 1 && !conditional && overrides.is_empty() {
+        return Ok((config_file, sources));
+    }
+
+    // Each file was valid by itself, so problems in the merged file don't have
+    // a single location.
+    let config_file = ConfigFile::deserialize(toml::Value::Table(table))
+        .map_err(|error| {
+            Diagnostics(vec![Diagnostic {
+                path: path.display().to_string(),
+                location: None,
+                message: String::from(error.message().trim_end()),
+            }])
+        })?;
+    Ok((config_file, sources))
+}
+
+/// Load a file and its includes. `stack` holds the files being loaded to
+/// detect cycles.
+fn load_file(
+    path: &Path,
+    read: Read,
+    stack: &mut Vec<PathBuf>,
+    sources: &mut Vec<Source>,
+) -> Result<(ConfigFile, toml::Table), anyhow::Error> {
+    if stack.iter().any(|including| including == path) {
+        anyhow::bail!("'{}' includes itself", path.display());
+    }
+
+    let text = read(path).with_context(|| {
+        format!(
+            "Failed to read configuration from file '{}'",
+            path.display()
+        )
+    })?;
+    let source = Source {
+        path: path.to_path_buf(),
+        text,
+    };
+    let config_file =
+        toml::from_str::<ConfigFile>(&source.text).map_err(|error| {
+            Diagnostics(vec![Diagnostic::from_toml(&source, error)])
+        })?;
+    let mut table = toml::from_str::<toml::Table>(&source.text)?;
+    table.remove("include");
+
+    if config_file.include.is_empty() {
+        sources.push(source);
+        return Ok((config_file, table));
+    }
+
+    let directory = path.parent().unwrap_or(Path::new(""));
+    let mut merged = toml::Table::new();
+    stack.push(path.to_path_buf());
+    for include in &config_file.include {
+        let (_, included) =
+            load_file(&directory.join(include), read, stack, sources)?;
+        merge(&mut merged, included);
+    }
+    stack.pop();
+    merge(&mut merged, table);
+    sources.push(source);
+
+    Ok((config_file, merged))
+}
+
+/// Override the values in one table with those in another.
+pub(super) fn merge(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(overlay)) => {
+                merge(base, overlay);
+            }
+            (Some(toml::Value::Array(base)), toml::Value::Array(overlay))
+                if key == "keybindings" =>
+            {
+                base.extend(overlay);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn files(
+        files: &[(&'static str, &'static str)],
+    ) -> impl Fn(&Path) -> io::Result<String> {
+        let files: HashMap<PathBuf, &str> = files
+            .iter()
+            .map(|(path, text)| (PathBuf::from(path), *text))
+            .collect();
+        move |path| {
+            files
+                .get(path)
+                .map(|text| String::from(*text))
+                .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))
+        }
+    }
+
+    #[test]
+    fn later_files_override_earlier() {
+        let read = files(&[
+            (
+                "config/wiremix.toml",
+                r#"
+                include = ["a.toml", "b.toml"]
+                compact = true
+                "#,
+            ),
+            ("config/a.toml", "theme = \"nocolor\"\nfps = 30.0"),
+            ("config/b.toml", "theme = \"plain\""),
+        ]);
+        let (config_file, sources) =
+            load(Path::new("config/wiremix.toml"), &read, &toml::Table::new())
+                .unwrap();
+
+        assert_eq!(sources.len(), 3);
+        assert_eq!(config_file.theme, "plain");
+        assert_eq!(config_file.fps, Some(30.0));
+        assert!(config_file.compact);
+    }
+
+    #[test]
+    fn cycles_are_errors() {
+        let read = files(&[
+            ("a.toml", "include = [\"b.toml\"]"),
+            ("b.toml", "include = [\"a.toml\"]"),
+        ]);
+        assert!(load(Path::new("a.toml"), &read, &toml::Table::new()).is_err());
+    }
+}
@@ -1,5 +1,7 @@
-//! Implementation for [`Keybinding`](`crate::config::Keybinding`). Defines
-//! default bindings and handles merging of configured bindings with defaults.
+//! Implementation for [`Keybinding`](`crate::config::Keybinding`) and
+//! [`Keybindings`](`crate::config::Keybindings`). Defines default bindings,
+//! handles merging of configured bindings with defaults and looks up bindings
+//! for the active contexts.
 
 use std::collections::HashMap;
 use std::os::fd::AsFd;
@@ -8,16 +10,28 @@ use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use nix::sys::termios::{self, SpecialCharacterIndices};
 use serde::Deserialize;
 
-use crate::config::{Action, Keybinding};
+use crate::config::{Action, Keybinding, Keybindings};
+use crate::input::ContextStack;
 
 impl Keybinding {
-    pub fn defaults() -> HashMap<KeyEvent, Action> {
+    pub fn defaults() -> Keybindings {
+        Keybindings::from(Self::default_global())
+    }
+
+    fn default_global() -> HashMap<KeyEvent, Action> {
         let event = |code| KeyEvent::new(code, KeyModifiers::NONE);
 
         HashMap::from([
             (event(KeyCode::Char('q')), Action::Exit),
             (event(KeyCode::Char('m')), Action::ToggleMute),
+            (event(KeyCode::Char('M')), Action::ToggleDefaultSourceMute),
             (event(KeyCode::Char('d')), Action::SetDefault),
+            (event(KeyCode::Char('D')), Action::CycleDefaultSink),
+            (event(KeyCode::Char('P')), Action::ToggleProAudio),
+            (event(KeyCode::Char('U')), Action::ToggleUpmix),
+            (event(KeyCode::Char('z')), Action::ToggleCompact),
+            (event(KeyCode::Char('y')), Action::CopyName),
+            (event(KeyCode::Char('Y')), Action::CopyProperties),
             (event(KeyCode::Char('l')), Action::SetRelativeVolume(0.01)),
             (event(KeyCode::Right), Action::SetRelativeVolume(0.01)),
             (event(KeyCode::Char('h')), Action::SetRelativeVolume(-0.01)),
@@ -25,10 +39,24 @@ impl Keybinding {
             (event(KeyCode::Esc), Action::CloseDropdown),
             (event(KeyCode::Char('c')), Action::ActivateDropdown),
             (event(KeyCode::Enter), Action::ActivateDropdown),
+            (event(KeyCode::Char('C')), Action::ChannelMap),
+            (event(KeyCode::Char('e')), Action::EditProperties),
+            (event(KeyCode::Char('E')), Action::RevertProperties),
+            (event(KeyCode::Char('p')), Action::TogglePin),
+            (event(KeyCode::Char('N')), Action::ToggleDnd),
+            (event(KeyCode::Char('T')), Action::TestTone),
+            (event(KeyCode::Char('I')), Action::MicCheck),
+            (event(KeyCode::Char('R')), Action::MeasureLatency),
+            (event(KeyCode::Char('S')), Action::ToggleHistory),
+            (event(KeyCode::Char('?')), Action::Help),
             (event(KeyCode::Char('j')), Action::MoveDown),
             (event(KeyCode::Down), Action::MoveDown),
             (event(KeyCode::Char('k')), Action::MoveUp),
             (event(KeyCode::Up), Action::MoveUp),
+            (event(KeyCode::PageDown), Action::MovePageDown),
+            (event(KeyCode::PageUp), Action::MovePageUp),
+            (event(KeyCode::Home), Action::MoveToTop),
+            (event(KeyCode::End), Action::MoveToBottom),
             (event(KeyCode::Char('H')), Action::TabLeft),
             (event(KeyCode::Char('L')), Action::TabRight),
             (
@@ -36,6 +64,7 @@ impl Keybinding {
                 Action::TabLeft,
             ),
             (event(KeyCode::Tab), Action::TabRight),
+            (event(KeyCode::Char('f')), Action::SwitchFocus),
             (event(KeyCode::Char('`')), Action::SetAbsoluteVolume(0.00)),
             (event(KeyCode::Char('1')), Action::SetAbsoluteVolume(0.10)),
             (event(KeyCode::Char('2')), Action::SetAbsoluteVolume(0.20)),
@@ -55,9 +84,7 @@ impl Keybinding {
     }
 
     /// Merge deserialized keybindings with defaults
-    pub fn merge<'de, D>(
-        deserializer: D,
-    ) -> Result<HashMap<KeyEvent, Action>, D::Error>
+    pub fn merge<'de, D>(deserializer: D) -> Result<Keybindings, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
@@ -66,10 +93,14 @@ impl Keybinding {
         let configured = Vec::<Keybinding>::deserialize(deserializer)?;
 
         for keybinding in configured.into_iter() {
-            keybindings.insert(
-                KeyEvent::new(keybinding.key, keybinding.modifiers),
-                keybinding.action,
-            );
+            let key_event = KeyEvent::new(keybinding.key, keybinding.modifiers);
+            let bindings = match keybinding.context {
+                Some(context) => {
+                    keybindings.contexts.entry(context).or_default()
+                }
+                None => &mut keybindings.global,
+            };
+            bindings.insert(key_event, keybinding.action);
         }
 
         Ok(keybindings)
@@ -84,13 +115,14 @@ impl Keybinding {
             return bindings;
         };
 
-        const SPECIAL_CHAR_INDICES: &[SpecialCharacterIndices] = &[
-            SpecialCharacterIndices::VINTR,
-            SpecialCharacterIndices::VQUIT,
-            SpecialCharacterIndices::VEOF,
+        const SPECIAL_CHAR_INDICES: &[(SpecialCharacterIndices, Action)] = &[
+            (SpecialCharacterIndices::VINTR, Action::Exit),
+            (SpecialCharacterIndices::VQUIT, Action::Exit),
+            (SpecialCharacterIndices::VEOF, Action::Exit),
+            (SpecialCharacterIndices::VSUSP, Action::Suspend),
         ];
 
-        for &index in SPECIAL_CHAR_INDICES {
+        for &(index, action) in SPECIAL_CHAR_INDICES {
             let byte = termios.control_chars[index as usize];
 
             let key_event = match byte {
@@ -116,9 +148,41 @@ impl Keybinding {
                 _ => continue,
             };
 
-            bindings.insert(key_event, Action::Exit);
+            bindings.insert(key_event, action);
+            // With the kitty keyboard protocol, Ctrl-\ is reported as is
+            if byte == 28 {
+                let key_event =
+                    KeyEvent::new(KeyCode::Char('\\'), KeyModifiers::CONTROL);
+                bindings.insert(key_event, action);
+            }
         }
 
         bindings
     }
 }
+
+impl Keybindings {
+    /// Find the action bound to a key, preferring bindings of the innermost
+    /// active context and falling back to the global bindings.
+    pub fn get(
+        &self,
+        contexts: &ContextStack,
+        key_event: &KeyEvent,
+    ) -> Option<Action> {
+        contexts
+            .innermost_first()
+            .filter_map(|context| self.contexts.get(&context))
+            .chain(std::iter::once(&self.global))
+            .find_map(|bindings| bindings.get(key_event))
+            .copied()
+    }
+}
+
+impl From<HashMap<KeyEvent, Action>> for Keybindings {
+    fn from(global: HashMap<KeyEvent, Action>) -> Self {
+        Self {
+            global,
+            contexts: HashMap::new(),
+        }
+    }
+}
@@ -0,0 +1,180 @@
+//! Format strings for the lines printed by `wiremix list`.
+//!
+//! These use the syntax of name templates, with fields such as `{id}` and
+//! `{volume}` in addition to the name template tags. Tags which can't be
+//! resolved are left empty, and `\t`, `\n` and `\\` are unescaped so that
+//! formats can be given in single quotes on the command line.
+
+use serde_with::DeserializeFromStr;
+
+use crate::config::name_template::{self, Part};
+use crate::config::names::TagResolver;
+use crate::config::tag::Tag;
+use crate::state;
+use crate::view;
+
+/// A value which can be printed for each object.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum Field {
+    Id,
+    Serial,
+    /// The name shown in the UI
+    Name,
+    /// Volume percentage
+    Volume,
+    /// "true" or "false"
+    Mute,
+    MediaClass,
+    /// The target sink or source for streams, route for endpoints, or profile
+    /// for devices
+    Target,
+    /// "true" for the default sink and source, otherwise "false"
+    Default,
+    Tag(Tag),
+}
+
+impl std::str::FromStr for Field {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "id" => Ok(Field::Id),
+            "serial" => Ok(Field::Serial),
+            "name" => Ok(Field::Name),
+            "volume" => Ok(Field::Volume),
+            "mute" => Ok(Field::Mute),
+            "media_class" => Ok(Field::MediaClass),
+            "target" => Ok(Field::Target),
+            "default" => Ok(Field::Default),
+            _ => s.parse().map(Field::Tag),
+        }
+    }
+}
+
+#[derive(Debug, DeserializeFromStr)]
+#[cfg_attr(test, derive(PartialEq))]
+pub struct ListFormat {
+    parts: Vec<Part<Field>>,
+}
+
+impl std::str::FromStr for ListFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut unescaped = String::new();
+        let mut chars = s.chars();
+        while let Some(ch) = chars.next() {
+            match (ch, chars.clone().next()) {
+                ('\\', Some('t')) => unescaped.push('\t'),
+                ('\\', Some('n')) => unescaped.push('\n'),
+                ('\\', Some('\\')) => unescaped.push('\\'),
+                _ => {
+                    unescaped.push(ch);
+                    continue;
+                }
+            }
+            chars.next();
+        }
+
+        Ok(ListFormat {
+            parts: name_template::parse_parts(&unescaped)?,
+        })
+    }
+}
+
+impl ListFormat {
+    /// The default format for nodes.
+    pub fn nodes() -> Self {
+        "{id}\\t{media_class}\\t{volume}\\t{mute}\\t{name}"
+            .parse()
+            .unwrap()
+    }
+
+    /// The default format for devices.
+    pub fn devices() -> Self {
+        "{id}\\t{target}\\t{name}".parse().unwrap()
+    }
+
+    /// Whether the format has nothing to print.
+    pub fn is_empty(&self) -> bool {
+        self.parts.is_empty()
+    }
+
+    fn render(&self, lookup: impl Fn(&Field) -> Option<String>) -> String {
+        self.parts
+            .iter()
+            .map(|part| match part {
+                Part::Literal(literal) => literal.clone(),
+                Part::Tag(field) => lookup(field).unwrap_or_default(),
+            })
+            .collect()
+    }
+
+    /// Render a line for a node.
+    pub fn render_node(
+        &self,
+        state: &state::State,
+        node: &view::Node,
+    ) -> String {
+        self.render(|field| match field {
+            Field::Id => Some(node.id.to_string()),
+            Field::Serial => Some(node.object_serial.to_string()),
+            Field::Name => Some(node.title.clone()),
+            Field::Volume => node.volume_percent().map(|v| v.to_string()),
+            Field::Mute => Some(node.mute.to_string()),
+            Field::MediaClass => Some(String::from(node.media_class.as_str())),
+            Field::Target => Some(node.target_title.clone()),
+            Field::Default => Some(
+                (node.is_default_sink || node.is_default_source).to_string(),
+            ),
+            Field::Tag(tag) => {
+                state.nodes.get(&node.id)?.resolve_tag(state, *tag).cloned()
+            }
+        })
+    }
+
+    /// Render a line for a device.
+    pub fn render_device(
+        &self,
+        state: &state::State,
+        device: &view::Device,
+    ) -> String {
+        self.render(|field| match field {
+            Field::Id => Some(device.id.to_string()),
+            Field::Serial => Some(device.object_serial.to_string()),
+            Field::Name => Some(device.title.clone()),
+            Field::Target => Some(device.target_title.clone()),
+            Field::Tag(tag) => state
+                .devices
+                .get(&device.id)?
+                .resolve_tag(state, *tag)
+                .cloned(),
+            _ => None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::tag::NodeTag;
+
+    #[test]
+    fn fields_tags_and_escapes() {
+        let format = "{id}\\t{node:node.name}\\\\n{{x}}"
+            .parse::<ListFormat>()
+            .unwrap();
+        assert_eq!(
+            format.parts,
+            vec![
+                Part::Tag(Field::Id),
+                Part::Literal(String::from("\t")),
+                Part::Tag(Field::Tag(Tag::Node(NodeTag::NodeName))),
+                Part::Literal(String::from("\\n{x}")),
+            ]
+        );
+
+        assert!("{nope}".parse::<ListFormat>().is_err());
+    }
+}
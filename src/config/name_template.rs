@@ -15,90 +15,96 @@ pub struct NameTemplate {
     parts: Vec<Part>,
 }
 
+/// A part of a template. Other templates, such as
+/// [`ListFormat`](`crate::config::ListFormat`), use their own types of tags.
 #[derive(Debug)]
 #[cfg_attr(test, derive(PartialEq))]
-enum Part {
+pub enum Part<T = Tag> {
     Literal(String),
-    Tag(Tag),
+    Tag(T),
 }
 
 impl std::str::FromStr for NameTemplate {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Self::parse_string(s)
+        Ok(NameTemplate {
+            parts: parse_parts(s)?,
+        })
     }
 }
 
-impl NameTemplate {
-    fn parse_string(s: &str) -> Result<Self, anyhow::Error> {
-        // Sort string into literal and tag parts while unescaping {{ and }}
-        // to { and }.
-        let mut parts = Vec::new();
-        let mut chars = s.chars().peekable();
-        let mut current_part = String::new();
-
-        while let Some(ch) = chars.next() {
-            match ch {
-                '{' => {
-                    // Handle escaped brace: {{.
-                    if chars.peek() == Some(&'{') {
-                        current_part.push('{');
-                        chars.next(); // Consume the extra.
-                        continue;
-                    } else {
-                        // Start of a tag.
-                        if !current_part.is_empty() {
-                            parts.push(Part::Literal(current_part));
-                            current_part = String::new();
-                        }
-
-                        let tag_content = Self::parse_tag(&mut chars)?;
-                        let tag = tag_content.parse::<Tag>().map_err(|_| {
-                            anyhow!("\"{}\" is not implemented", tag_content)
-                        })?;
-
-                        parts.push(Part::Tag(tag));
+/// Sort a template string into literal and tag parts while unescaping {{ and
+/// }} to { and }.
+pub fn parse_parts<T: std::str::FromStr>(
+    s: &str,
+) -> Result<Vec<Part<T>>, anyhow::Error> {
+    let mut parts = Vec::new();
+    let mut chars = s.chars().peekable();
+    let mut current_part = String::new();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '{' => {
+                // Handle escaped brace: {{.
+                if chars.peek() == Some(&'{') {
+                    current_part.push('{');
+                    chars.next(); // Consume the extra.
+                    continue;
+                } else {
+                    // Start of a tag.
+                    if !current_part.is_empty() {
+                        parts.push(Part::Literal(current_part));
+                        current_part = String::new();
                     }
+
+                    let tag_content = parse_tag(&mut chars)?;
+                    let tag = tag_content.parse::<T>().map_err(|_| {
+                        anyhow!("\"{}\" is not implemented", tag_content)
+                    })?;
+
+                    parts.push(Part::Tag(tag));
                 }
-                '}' => {
-                    // Handle escaped brace: }}.
-                    if chars.peek() == Some(&'}') {
-                        current_part.push('}');
-                        chars.next(); // Consume the extra.
-                    } else {
-                        bail!("'}}' without '{{'");
-                    }
+            }
+            '}' => {
+                // Handle escaped brace: }}.
+                if chars.peek() == Some(&'}') {
+                    current_part.push('}');
+                    chars.next(); // Consume the extra.
+                } else {
+                    bail!("'}}' without '{{'");
                 }
-                _ => current_part.push(ch),
             }
+            _ => current_part.push(ch),
         }
+    }
 
-        if !current_part.is_empty() {
-            parts.push(Part::Literal(current_part));
-        }
-
-        Ok(NameTemplate { parts })
+    if !current_part.is_empty() {
+        parts.push(Part::Literal(current_part));
     }
 
-    fn parse_tag(
-        chars: &mut std::iter::Peekable<std::str::Chars>,
-    ) -> Result<String, anyhow::Error> {
-        let mut content = String::new();
+    Ok(parts)
+}
 
-        for ch in chars.by_ref() {
-            match ch {
-                '}' => {
-                    return Ok(content);
-                }
-                '{' => bail!("'{{' without '}}'"),
-                _ => content.push(ch),
+fn parse_tag(
+    chars: &mut std::iter::Peekable<std::str::Chars>,
+) -> Result<String, anyhow::Error> {
+    let mut content = String::new();
+
+    for ch in chars.by_ref() {
+        match ch {
+            '}' => {
+                return Ok(content);
             }
+            '{' => bail!("'{{' without '}}'"),
+            _ => content.push(ch),
         }
-
-        Err(anyhow!("'{{' without '}}'"))
     }
 
+    Err(anyhow!("'{{' without '}}'"))
+}
+
+impl NameTemplate {
     /// Renders a template string using the provided lookup function to convert
     /// Tags into replacement strings.
     pub fn render<T: AsRef<str>>(
@@ -255,4 +261,125 @@ mod tests {
         });
         assert_eq!(rendered, Some(String::from("let foo = bar;")));
     }
+
+    /// Property values seen on real systems, including ones with braces,
+    /// escapes and non-ASCII text.
+    const CORPUS: &[&str] = &[
+        "alsa_output.pci-0000_00_1f.3.analog-stereo",
+        "alsa_input.usb-Blue_Microphones_Yeti_Stereo_Microphone-00.analog-stereo",
+        "bluez_output.00_1B_66_AA_BB_CC.1",
+        "Built-in Audio Analog Stereo",
+        "HD-Audio Generic",
+        "Firefox",
+        "AudioIPC Server",
+        "Spotify \u{2014} Song (Remastered 2011)",
+        "WEBRTC VoiceEngine",
+        "/usr/lib/firefox/firefox",
+        "{placeholder}",
+        "}{",
+        "{{",
+        "\u{1F3A7} Kopfh\u{f6}rer",
+        "\u{97f3}\u{58f0}\u{51fa}\u{529b}",
+        "",
+    ];
+
+    fn all_tags() -> Vec<Tag> {
+        use crate::config::tag::ClientTag;
+        use strum::IntoEnumIterator;
+
+        DeviceTag::iter()
+            .map(Tag::Device)
+            .chain(NodeTag::iter().map(Tag::Node))
+            .chain(ClientTag::iter().map(Tag::Client))
+            .collect()
+    }
+
+    fn escape(literal: &str) -> String {
+        literal.replace('{', "{{").replace('}', "}}")
+    }
+
+    /// The template string for parts, and the parts which parsing it should
+    /// produce, with adjacent literals merged and empty ones dropped.
+    fn template_string(parts: &[Part]) -> (String, Vec<Part>) {
+        let mut s = String::new();
+        let mut expected: Vec<Part> = Vec::new();
+        for part in parts {
+            match part {
+                Part::Literal(literal) => {
+                    s.push_str(&escape(literal));
+                    if literal.is_empty() {
+                        continue;
+                    }
+                    match expected.last_mut() {
+                        Some(Part::Literal(last)) => last.push_str(literal),
+                        _ => expected.push(Part::Literal(literal.clone())),
+                    }
+                }
+                Part::Tag(tag) => {
+                    s.push_str(&format!("{{{}}}", tag.to_string()));
+                    expected.push(Part::Tag(*tag));
+                }
+            }
+        }
+        (s, expected)
+    }
+
+    fn part() -> impl proptest::strategy::Strategy<Value = Part> {
+        use proptest::prelude::*;
+
+        let tags = all_tags();
+        prop_oneof![
+            any::<String>().prop_map(Part::Literal),
+            proptest::sample::select(CORPUS)
+                .prop_map(|literal| Part::Literal(String::from(literal))),
+            proptest::sample::select(tags).prop_map(Part::Tag),
+        ]
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn parse_arbitrary(s in "\\PC*") {
+            // Only checking that nothing panics
+            if let Ok(template) = s.parse::<NameTemplate>() {
+                let _ = template.render(|tag| Some(tag.to_string()));
+            }
+        }
+
+        #[test]
+        fn parse_braces_and_tags(s in "([{}]|node:node\\.name|[a-z.: ]){0,32}") {
+            if let Ok(template) = s.parse::<NameTemplate>() {
+                // Anything accepted means the same once escaped again
+                let (s, expected) = template_string(&template.parts);
+                let reparsed: NameTemplate = s.parse().unwrap();
+                proptest::prop_assert_eq!(&reparsed.parts, &expected);
+                proptest::prop_assert_eq!(&reparsed.parts, &template.parts);
+            }
+        }
+
+        #[test]
+        fn round_trip(parts in proptest::collection::vec(part(), 0..8)) {
+            let (s, expected) = template_string(&parts);
+            let template: NameTemplate = s.parse().unwrap();
+            proptest::prop_assert_eq!(&template.parts, &expected);
+        }
+
+        #[test]
+        fn render_corpus_values(
+            parts in proptest::collection::vec(part(), 0..8),
+            value in proptest::sample::select(CORPUS),
+        ) {
+            let (s, expected) = template_string(&parts);
+            let template: NameTemplate = s.parse().unwrap();
+            let rendered = template.render(|_| Some(value)).unwrap();
+            let expected: String = expected
+                .iter()
+                .map(|part| match part {
+                    Part::Literal(literal) => literal.as_str(),
+                    Part::Tag(_) => value,
+                })
+                .collect();
+            // Values are inserted as they are, never parsed as templates
+            proptest::prop_assert_eq!(rendered, expected);
+        }
+    }
 }
@@ -12,7 +12,18 @@ use crate::config::{
 
 impl Names {
     pub fn default_stream() -> Vec<NameTemplate> {
-        vec!["{node:node.name}: {node:media.name}".parse().unwrap()]
+        vec![
+            // JACK clients don't have a media.name
+            "{node:jack.client.name}".parse().unwrap(),
+            // Show the sandboxed application rather than the portal
+            "{client:pipewire.access.portal.app_id}: {node:media.name}"
+                .parse()
+                .unwrap(),
+            "{client:pipewire.snap.app.id}: {node:media.name}"
+                .parse()
+                .unwrap(),
+            "{node:node.name}: {node:media.name}".parse().unwrap(),
+        ]
     }
 
     pub fn default_endpoint() -> Vec<NameTemplate> {
@@ -148,6 +159,10 @@ impl TagResolver for state::Node {
             Tag::Node(NodeTag::NodeNick) => self.nick.as_ref(),
             Tag::Node(NodeTag::NodeDescription) => self.description.as_ref(),
             Tag::Node(NodeTag::MediaName) => self.media_name.as_ref(),
+            Tag::Node(NodeTag::MediaRole) => self.media_role.as_ref(),
+            Tag::Node(NodeTag::JackClientName) => {
+                self.name.as_ref().filter(|_| self.is_jack())
+            }
             Tag::Device(_) => {
                 let device = state.devices.get(&self.device_id?)?;
                 device.resolve_tag(state, tag)
@@ -206,6 +221,27 @@ impl TagResolver for state::Client {
             Tag::Client(ClientTag::ApplicationProcessBinary) => {
                 self.application_process_binary.as_ref()
             }
+            Tag::Client(ClientTag::ApplicationProcessId) => {
+                self.application_process_id.as_ref()
+            }
+            Tag::Client(ClientTag::ProcessCmdline) => {
+                self.process_cmdline.as_ref()
+            }
+            Tag::Client(ClientTag::ProcessCgroup) => {
+                self.process_cgroup.as_ref()
+            }
+            Tag::Client(ClientTag::ProcessFlatpakAppId) => {
+                self.flatpak_app_id.as_ref()
+            }
+            Tag::Client(ClientTag::PipewireAccess) => {
+                self.pipewire_access.as_ref()
+            }
+            Tag::Client(ClientTag::PipewireAccessPortalAppId) => {
+                self.portal_app_id.as_ref()
+            }
+            Tag::Client(ClientTag::PipewireSnapAppId) => {
+                self.snap_app_id.as_ref()
+            }
             Tag::Node(_) => None,
             Tag::Device(_) => None,
         }
@@ -437,6 +473,32 @@ mod tests {
         assert_eq!(result, Some(String::from("Node nick")))
     }
 
+    #[test]
+    fn render_stream_jack_client_name() {
+        let mut fixture = Fixture::default();
+
+        let names = Names {
+            stream: vec![
+                "{node:jack.client.name}".parse().unwrap(),
+                "{node:node.nick}".parse().unwrap(),
+            ],
+            ..Default::default()
+        };
+
+        let node = fixture.state.nodes.get(&fixture.node_id).unwrap();
+        let result = names.resolve(&fixture.state, node);
+        assert_eq!(result, Some(String::from("Node nick")));
+
+        fixture.state.update(
+            &mut fixture.capture_manager,
+            MonitorEvent::NodeClientApi(fixture.node_id, String::from("jack")),
+        );
+
+        let node = fixture.state.nodes.get(&fixture.node_id).unwrap();
+        let result = names.resolve(&fixture.state, node);
+        assert_eq!(result, Some(String::from("Node name")))
+    }
+
     #[test]
     fn render_stream_linked_client() {
         let mut fixture = Fixture::default();
@@ -456,6 +518,57 @@ mod tests {
         assert_eq!(result, Some(String::from("Client name")))
     }
 
+    #[test]
+    fn render_stream_portal_app() {
+        let mut fixture = Fixture::default();
+
+        let events = vec![
+            MonitorEvent::NodeClientId(fixture.node_id, fixture.client_id),
+            MonitorEvent::NodeMediaName(
+                fixture.node_id,
+                String::from("Screen share"),
+            ),
+        ];
+        for event in events {
+            fixture.state.update(&mut fixture.capture_manager, event);
+        }
+
+        let names = Names::default();
+        let resolve = |state: &State| {
+            let node = state.nodes.get(&fixture.node_id).unwrap();
+            names.resolve(state, node)
+        };
+        assert_eq!(
+            resolve(&fixture.state),
+            Some(String::from("Node name: Screen share"))
+        );
+
+        fixture.state.update(
+            &mut fixture.capture_manager,
+            MonitorEvent::ClientSnapAppId(
+                fixture.client_id,
+                String::from("firefox"),
+            ),
+        );
+        assert_eq!(
+            resolve(&fixture.state),
+            Some(String::from("firefox: Screen share"))
+        );
+
+        // The portal's app ID takes precedence
+        fixture.state.update(
+            &mut fixture.capture_manager,
+            MonitorEvent::ClientPortalAppId(
+                fixture.client_id,
+                String::from("org.mozilla.firefox"),
+            ),
+        );
+        assert_eq!(
+            resolve(&fixture.state),
+            Some(String::from("org.mozilla.firefox: Screen share"))
+        );
+    }
+
     #[test]
     fn render_precedence() {
         let fixture = Fixture::default();
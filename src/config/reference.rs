@@ -0,0 +1,328 @@
+//! The configuration reference shown by the in-app help and `wiremix man`.
+//!
+//! The options, their types and the actions come from the
+//! [schema](`super::schema`), which is derived from the Deserialize
+//! implementations, and the name template tags from [`Tag::ALL`], so the
+//! reference lists exactly what the configuration accepts. Defaults and
+//! descriptions come from the values and comments in the example
+//! configuration file.
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::config::{schema, Config, Tag};
+
+/// A documented option, table field, tag or action.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub name: String,
+    /// What the value can be, such as `number` or `"off" | "mono" | "auto"`
+    pub kind: String,
+    /// The value in the example configuration, if it's short
+    pub default: Option<String>,
+    pub description: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Section {
+    pub title: &'static str,
+    pub entries: Vec<Entry>,
+}
+
+/// Values longer than this aren't shown as defaults.
+const MAX_DEFAULT: usize = 40;
+
+/// The comments before each option, table and action in the example
+/// configuration, keyed by `option`, `table.field` or the action's name.
+fn descriptions() -> HashMap<String, Vec<String>> {
+    let mut descriptions = HashMap::new();
+    let mut comments: Vec<String> = Vec::new();
+    let mut table: Option<String> = None;
+    for line in Config::EXAMPLE.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            comments.clear();
+            continue;
+        }
+        let header = line
+            .trim_start_matches("# ")
+            .strip_prefix('[')
+            .map(|header| header.trim_matches(|c| c == '[' || c == ']'));
+        if let Some(header) = header.filter(|header| is_key(header)) {
+            descriptions
+                .entry(String::from(header))
+                .or_insert_with(|| comments.clone());
+            if !line.starts_with('#') {
+                table = Some(String::from(header));
+            }
+            comments.clear();
+            continue;
+        }
+        if let Some(name) = action_name(line).filter(|_| !line.starts_with('#'))
+        {
+            descriptions.entry(name).or_insert_with(|| comments.clone());
+            comments.clear();
+            continue;
+        }
+        // Options which are unset by default are commented out
+        let option = line.strip_prefix('#').unwrap_or(line);
+        if let Some((key, _)) = option.split_once(" = ") {
+            if is_key(key) {
+                let key = match &table {
+                    Some(table) => format!("{}.{}", table, key),
+                    None => String::from(key),
+                };
+                descriptions.entry(key).or_insert_with(|| comments.clone());
+                comments.clear();
+                continue;
+            }
+        }
+        if let Some(comment) = line.strip_prefix('#') {
+            let comment = comment.strip_prefix(' ').unwrap_or(comment);
+            comments.push(String::from(comment));
+        }
+    }
+    descriptions
+}
+
+fn is_key(s: &str) -> bool {
+    !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_ascii_lowercase() || "._".contains(c))
+}
+
+/// The action in a keybinding line, such as `ToggleMute` in
+/// `{ key = { Char = "m" }, action = "ToggleMute" },`.
+fn action_name(line: &str) -> Option<String> {
+    let (_, action) = line.split_once("action = ")?;
+    let action = action.trim_start_matches(['{', ' ', '"']);
+    let end = action.find(|c: char| !c.is_ascii_alphanumeric())?;
+    Some(String::from(&action[..end])).filter(|name| !name.is_empty())
+}
+
+/// A short description of the values a schema allows.
+fn kind(schema: &Value) -> String {
+    if let Some(values) = schema["enum"].as_array() {
+        return values
+            .iter()
+            .map(|value| value.to_string())
+            .collect::<Vec<_>>()
+            .join(" | ");
+    }
+    if let Some(alternatives) = schema["oneOf"].as_array() {
+        return alternatives
+            .iter()
+            .map(kind)
+            .collect::<Vec<_>>()
+            .join(" | ");
+    }
+    match schema["type"].as_str() {
+        Some("array") => format!("array of {}", kind(&schema["items"])),
+        Some("object") if schema.get("properties").is_some() => {
+            match schema["properties"].as_object() {
+                // A variant with a payload, such as { SelectTab = 0 }
+                Some(properties) if properties.len() == 1 => {
+                    let (name, payload) = properties.iter().next().unwrap();
+                    format!("{{ {} = {} }}", name, kind(payload))
+                }
+                _ => String::from("table"),
+            }
+        }
+        Some("object") => String::from("table"),
+        Some(other) => String::from(other),
+        None => String::from("any"),
+    }
+}
+
+/// The example configuration's value at a path, if it's short enough to
+/// show.
+fn default(example: &toml::Table, path: &[&str]) -> Option<String> {
+    let (last, tables) = path.split_last()?;
+    let mut table = example;
+    for name in tables {
+        table = table.get(*name)?.as_table()?;
+    }
+    let value = table.get(*last)?.to_string();
+    (value.len() <= MAX_DEFAULT).then_some(value)
+}
+
+/// The sections of the reference.
+pub fn sections() -> Vec<Section> {
+    let schema = schema::schema();
+    let example = Config::EXAMPLE.parse::<toml::Table>().unwrap_or_default();
+    let mut descriptions = descriptions();
+    let mut describe =
+        |name: &str| descriptions.remove(name).unwrap_or_default();
+
+    let mut options = Vec::new();
+    let mut tables = Vec::new();
+    let properties = schema["properties"].as_object();
+    for (name, property) in properties.into_iter().flatten() {
+        options.push(Entry {
+            name: name.clone(),
+            kind: kind(property),
+            default: default(&example, &[name]),
+            description: describe(name),
+        });
+        let fields = property["properties"].as_object();
+        for (field, field_schema) in fields.into_iter().flatten() {
+            let path = format!("{}.{}", name, field);
+            tables.push(Entry {
+                kind: kind(field_schema),
+                default: default(&example, &[name, field]),
+                description: describe(&path),
+                name: path,
+            });
+        }
+    }
+
+    let tags = Tag::ALL
+        .iter()
+        .map(|tag| Entry {
+            name: format!("{{{}}}", tag.to_string()),
+            kind: String::new(),
+            default: None,
+            description: Vec::new(),
+        })
+        .collect();
+
+    let mut actions = Vec::new();
+    let action = &schema["properties"]["macros"]["additionalProperties"]
+        ["items"]["oneOf"];
+    for alternative in action.as_array().into_iter().flatten() {
+        if let Some(units) = alternative["enum"].as_array() {
+            for unit in units.iter().filter_map(Value::as_str) {
+                actions.push(Entry {
+                    name: String::from(unit),
+                    kind: String::new(),
+                    default: None,
+                    description: describe(unit),
+                });
+            }
+        }
+        let payload = alternative["properties"].as_object();
+        for (name, payload) in payload.into_iter().flatten() {
+            actions.push(Entry {
+                name: name.clone(),
+                kind: kind(payload),
+                default: None,
+                description: describe(name),
+            });
+        }
+    }
+
+    vec![
+        Section {
+            title: "Options",
+            entries: options,
+        },
+        Section {
+            title: "Table fields",
+            entries: tables,
+        },
+        Section {
+            title: "Name template tags",
+            entries: tags,
+        },
+        Section {
+            title: "Actions",
+            entries: actions,
+        },
+    ]
+}
+
+/// Escape text for roff.
+fn roff(text: &str) -> String {
+    let text = text.replace('\\', "\\e").replace('-', "\\-");
+    if text.starts_with(['.', '\'']) {
+        format!("\\&{}", text)
+    } else {
+        text
+    }
+}
+
+/// The reference as a wiremix.toml(5) man page.
+pub fn man_page() -> String {
+    let mut page = format!(
+        ".TH WIREMIX.TOML 5 \"\" \"wiremix {}\" \"File Formats\"\n\
+         .SH NAME\n\
+         wiremix.toml \\- wiremix configuration file\n\
+         .SH DESCRIPTION\n\
+         wiremix reads its configuration from \
+         $XDG_CONFIG_HOME/wiremix/wiremix.toml or \
+         ~/.config/wiremix/wiremix.toml, or the file given with \
+         \\fB\\-\\-config\\fR. Run \\fBwiremix init\\-config\\fR to write a \
+         file documenting every option.\n",
+        env!("CARGO_PKG_VERSION")
+    );
+    for section in sections() {
+        page.push_str(&format!(".SH {}\n", section.title.to_uppercase()));
+        for entry in section.entries {
+            page.push_str(&format!(".TP\n.B {}\n", roff(&entry.name)));
+            let mut summary = Vec::new();
+            if !entry.kind.is_empty() {
+                summary.push(roff(&entry.kind));
+            }
+            if let Some(default) = &entry.default {
+                summary.push(format!("default {}", roff(default)));
+            }
+            if !summary.is_empty() {
+                page.push_str(&format!("{}\n", summary.join(", ")));
+            }
+            for description in &entry.description {
+                page.push_str(".br\n");
+                page.push_str(&roff(description));
+                page.push('\n');
+            }
+        }
+    }
+    page
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry<'a>(sections: &'a [Section], name: &str) -> &'a Entry {
+        sections
+            .iter()
+            .flat_map(|section| &section.entries)
+            .find(|entry| entry.name == name)
+            .unwrap()
+    }
+
+    #[test]
+    fn options_from_config() {
+        let sections = sections();
+        let peaks = entry(&sections, "peaks");
+        assert_eq!(peaks.kind, "\"off\" | \"mono\" | \"auto\"");
+        assert_eq!(peaks.default.as_deref(), Some("\"auto\""));
+        assert_eq!(peaks.description[0], "Peak meter mode");
+
+        let unset = entry(&sections, "hide_paused_after");
+        assert_eq!(unset.default, None);
+        assert!(!unset.description.is_empty());
+
+        assert_eq!(entry(&sections, "watchdog.silent_after").kind, "number");
+        assert!(!entry(&sections, "watchdog").description.is_empty());
+        assert!(!entry(&sections, "ToggleMute").description.is_empty());
+        assert_eq!(entry(&sections, "SelectTab").kind, "integer");
+        entry(&sections, "{node:node.name}");
+    }
+
+    /// Every top-level option which isn't a table is described in the
+    /// example configuration.
+    #[test]
+    fn options_are_described() {
+        let sections = sections();
+        let undescribed: Vec<&str> = sections[0]
+            .entries
+            .iter()
+            .filter(|entry| entry.kind != "table")
+            .filter(|entry| entry.description.is_empty())
+            .map(|entry| entry.name.as_str())
+            .collect();
+        assert!(undescribed.is_empty(), "{:?}", undescribed);
+    }
+}
@@ -0,0 +1,501 @@
+//! JSON Schema for the configuration file, for editors with TOML or JSON
+//! language servers.
+//!
+//! The schema is derived from the Deserialize implementations rather than
+//! written by hand. A [`Probe`] deserializer follows a path of fields,
+//! elements and variants into a type and reports the [`Shape`] of whatever
+//! the Deserialize implementation asks for there.
+
+use std::fmt;
+
+use serde::de::{self, IntoDeserializer, Visitor};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+use crate::config::ConfigFile;
+
+/// What a Deserialize implementation expects at some point in a path.
+#[derive(Debug)]
+enum Shape {
+    Bool,
+    Integer,
+    Number,
+    String,
+    Sequence,
+    Map,
+    Struct(&'static [&'static str]),
+    Enum(&'static [&'static str]),
+    /// A unit enum variant
+    Unit,
+    /// Anything, such as values deserialized with `deserialize_any`
+    Unknown,
+}
+
+/// A step into a value.
+#[derive(Debug, Clone, Copy)]
+enum Step {
+    Field(&'static str),
+    Element,
+    /// A value of a map
+    Value,
+    Variant(&'static str),
+}
+
+/// Error which ends a probe.
+#[derive(Debug)]
+enum Probed {
+    Shape(Shape),
+    Other(String),
+}
+
+impl fmt::Display for Probed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Probed::Shape(shape) => write!(f, "{:?}", shape),
+            Probed::Other(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for Probed {}
+
+impl de::Error for Probed {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Probed::Other(msg.to_string())
+    }
+}
+
+/// The shape of `T` at the end of a path.
+fn shape<'de, T: Deserialize<'de>>(path: &[Step]) -> Shape {
+    match T::deserialize(Probe(path)) {
+        Ok(_) => Shape::Unit,
+        Err(Probed::Shape(shape)) => shape,
+        Err(Probed::Other(_)) => Shape::Unknown,
+    }
+}
+
+/// The error for a path which ends in a unit variant, which deserializes
+/// successfully.
+fn unit() -> Probed {
+    Probed::Shape(Shape::Unit)
+}
+
+/// Deserializer which follows a path and then fails with the shape found.
+struct Probe<'a>(&'a [Step]);
+
+impl Probe<'_> {
+    fn leaf<T>(self, shape: Shape) -> Result<T, Probed> {
+        match self.0 {
+            [] => Err(Probed::Shape(shape)),
+            _ => Err(Probed::Other(String::from("path continues"))),
+        }
+    }
+}
+
+macro_rules! leaf {
+    ($($method:ident => $shape:expr),* $(,)?) => {$(
+        fn $method<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Probed> {
+            self.leaf($shape)
+        }
+    )*};
+}
+
+impl<'de> de::Deserializer<'de> for Probe<'_> {
+    type Error = Probed;
+
+    leaf! {
+        deserialize_any => Shape::Unknown,
+        deserialize_bool => Shape::Bool,
+        deserialize_i8 => Shape::Integer,
+        deserialize_i16 => Shape::Integer,
+        deserialize_i32 => Shape::Integer,
+        deserialize_i64 => Shape::Integer,
+        deserialize_u8 => Shape::Integer,
+        deserialize_u16 => Shape::Integer,
+        deserialize_u32 => Shape::Integer,
+        deserialize_u64 => Shape::Integer,
+        deserialize_f32 => Shape::Number,
+        deserialize_f64 => Shape::Number,
+        deserialize_char => Shape::String,
+        deserialize_str => Shape::String,
+        deserialize_string => Shape::String,
+        deserialize_identifier => Shape::String,
+        deserialize_bytes => Shape::Unknown,
+        deserialize_byte_buf => Shape::Unknown,
+        deserialize_unit => Shape::Unknown,
+        deserialize_ignored_any => Shape::Unknown,
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, Probed> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: V,
+    ) -> Result<V::Value, Probed> {
+        self.leaf(Shape::Unknown)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Probed> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, Probed> {
+        match self.0 {
+            [] => Err(Probed::Shape(Shape::Sequence)),
+            [Step::Element, rest @ ..] => {
+                visitor.visit_seq(Element(Some(Probe(rest))))
+            }
+            _ => Err(Probed::Other(String::from("not a field or variant"))),
+        }
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _: usize,
+        visitor: V,
+    ) -> Result<V::Value, Probed> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: usize,
+        visitor: V,
+    ) -> Result<V::Value, Probed> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, Probed> {
+        match self.0 {
+            [] => Err(Probed::Shape(Shape::Map)),
+            [Step::Value, rest @ ..] => visitor.visit_map(Entry {
+                key: Some("name"),
+                value: rest,
+            }),
+            _ => Err(Probed::Other(String::from("not an element or variant"))),
+        }
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Probed> {
+        match self.0 {
+            [] => Err(Probed::Shape(Shape::Struct(fields))),
+            [Step::Field(field), rest @ ..] => visitor.visit_map(Entry {
+                key: Some(field),
+                value: rest,
+            }),
+            _ => Err(Probed::Other(String::from("not an element or variant"))),
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Probed> {
+        match self.0 {
+            [] => Err(Probed::Shape(Shape::Enum(variants))),
+            [Step::Variant(variant), rest @ ..] => {
+                visitor.visit_enum(Variant {
+                    name: variant,
+                    payload: Probe(rest),
+                })
+            }
+            _ => Err(Probed::Other(String::from("not a field or element"))),
+        }
+    }
+}
+
+/// A sequence of one element.
+struct Element<'a>(Option<Probe<'a>>);
+
+impl<'de> de::SeqAccess<'de> for Element<'_> {
+    type Error = Probed;
+
+    fn next_element_seed<T: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Probed> {
+        match self.0.take() {
+            // Stop before the sequence can fail for being too short
+            Some(probe) => seed.deserialize(probe).and(Err(unit())),
+            None => Ok(None),
+        }
+    }
+}
+
+/// A map or struct of one entry.
+struct Entry<'a> {
+    key: Option<&'static str>,
+    value: &'a [Step],
+}
+
+impl<'de> de::MapAccess<'de> for Entry<'_> {
+    type Error = Probed;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Probed> {
+        self.key
+            .take()
+            .map(|key| seed.deserialize(key.into_deserializer()))
+            .transpose()
+    }
+
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Probed> {
+        // Stop before the struct can fail for missing fields
+        seed.deserialize(Probe(self.value)).and(Err(unit()))
+    }
+}
+
+/// An enum variant.
+struct Variant<'a> {
+    name: &'static str,
+    payload: Probe<'a>,
+}
+
+impl<'de, 'a> de::EnumAccess<'de> for Variant<'a> {
+    type Error = Probed;
+    type Variant = Probe<'a>;
+
+    fn variant_seed<V: de::DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Probe<'a>), Probed> {
+        let name = seed.deserialize(self.name.into_deserializer())?;
+        Ok((name, self.payload))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for Probe<'_> {
+    type Error = Probed;
+
+    fn unit_variant(self) -> Result<(), Probed> {
+        match self.0 {
+            [] => Ok(()),
+            _ => Err(Probed::Other(String::from("unit variant"))),
+        }
+    }
+
+    fn newtype_variant_seed<T: de::DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, Probed> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Probed> {
+        de::Deserializer::deserialize_tuple(self, len, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Probed> {
+        de::Deserializer::deserialize_struct(self, "", fields, visitor)
+    }
+}
+
+/// The JSON Schema of the configuration file.
+pub fn schema() -> Value {
+    let mut schema = schema_at(&mut Vec::new());
+    let root = json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "wiremix configuration",
+    });
+    if let (Value::Object(schema), Value::Object(root)) = (&mut schema, root) {
+        schema.extend(root);
+    }
+
+    // Conditional tables hold configuration
+    if let Some(host) = schema.pointer_mut("/properties/host") {
+        *host = json!({
+            "type": "object",
+            "additionalProperties": { "$ref": "#" },
+        });
+    }
+    if let Some(env) = schema.pointer_mut("/properties/env") {
+        *env = json!({
+            "type": "object",
+            "additionalProperties": {
+                "type": "object",
+                "additionalProperties": { "$ref": "#" },
+            },
+        });
+    }
+
+    schema
+}
+
+/// The schema of the configuration at the end of a path.
+fn schema_at(path: &mut Vec<Step>) -> Value {
+    match shape::<ConfigFile>(path) {
+        Shape::Bool => json!({ "type": "boolean" }),
+        Shape::Integer => json!({ "type": "integer" }),
+        Shape::Number => json!({ "type": "number" }),
+        Shape::String => json!({ "type": "string" }),
+        Shape::Sequence => json!({
+            "type": "array",
+            "items": child(path, Step::Element),
+        }),
+        Shape::Map => json!({
+            "type": "object",
+            "additionalProperties": child(path, Step::Value),
+        }),
+        Shape::Struct(fields) => {
+            let properties = fields
+                .iter()
+                .map(|&field| {
+                    (String::from(field), child(path, Step::Field(field)))
+                })
+                .collect::<Map<_, _>>();
+            json!({
+                "type": "object",
+                "properties": properties,
+                "additionalProperties": false,
+            })
+        }
+        Shape::Enum(variants) => {
+            let mut units = Vec::new();
+            let mut schemas = Vec::new();
+            for &variant in variants {
+                let step = Step::Variant(variant);
+                match child(path, step) {
+                    Value::Null => units.push(variant),
+                    schema => schemas.push(json!({
+                        "type": "object",
+                        "properties": { variant: schema },
+                        "required": [variant],
+                        "additionalProperties": false,
+                    })),
+                }
+            }
+            if !units.is_empty() {
+                schemas.insert(0, json!({ "enum": units }));
+            }
+            match schemas.len() {
+                1 => schemas.remove(0),
+                _ => json!({ "oneOf": schemas }),
+            }
+        }
+        // Unit variants are told apart from variants with any payload
+        Shape::Unit => Value::Null,
+        Shape::Unknown => json!({}),
+    }
+}
+
+/// The schema of a step further into the configuration.
+fn child(path: &mut Vec<Step>, step: Step) -> Value {
+    path.push(step);
+    let schema = schema_at(path);
+    path.pop();
+    schema
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Assert that the schema describes every key in a value.
+    fn assert_described(schema: &Value, value: &toml::Value, path: &str) {
+        let schema = match schema.get("$ref") {
+            Some(_) => return,
+            None => schema,
+        };
+        match value {
+            toml::Value::Table(table) => {
+                for (key, value) in table {
+                    let path = format!("{}.{}", path, key);
+                    let alternatives = match schema["oneOf"].as_array() {
+                        Some(alternatives) => alternatives.iter().collect(),
+                        None => vec![schema],
+                    };
+                    let described = alternatives.iter().find_map(|schema| {
+                        schema.pointer(&format!("/properties/{}", key)).or_else(
+                            || {
+                                schema
+                                    .get("additionalProperties")
+                                    .filter(|schema| schema.is_object())
+                            },
+                        )
+                    });
+                    match described {
+                        Some(schema) => assert_described(schema, value, &path),
+                        None => panic!("{} is not in the schema", path),
+                    }
+                }
+            }
+            toml::Value::Array(array) => {
+                if let Some(items) = schema.get("items") {
+                    for value in array {
+                        assert_described(items, value, path);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn example_config_file_is_described() {
+        let example =
+            toml::from_str::<toml::Value>(crate::config::Config::EXAMPLE)
+                .unwrap();
+        assert_described(&schema(), &example, "");
+    }
+
+    #[test]
+    fn enums_with_payloads() {
+        let schema = schema();
+        let action =
+            schema.pointer("/properties/macros/additionalProperties/items");
+        let variants = action.and_then(|action| action["oneOf"].as_array());
+        let variants = variants.unwrap();
+        assert!(variants[0]["enum"]
+            .as_array()
+            .unwrap()
+            .contains(&json!("ToggleMute")));
+        assert!(variants.contains(&json!({
+            "type": "object",
+            "properties": { "SelectTab": { "type": "integer" } },
+            "required": ["SelectTab"],
+            "additionalProperties": false,
+        })));
+
+        assert_eq!(
+            schema.pointer("/properties/peaks"),
+            Some(&json!({ "enum": ["off", "mono", "auto"] }))
+        );
+    }
+}
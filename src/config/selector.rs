@@ -0,0 +1,356 @@
+//! Selectors for choosing nodes and devices by their properties, for the
+//! command line and configuration.
+//!
+//! A selector is one of:
+//!
+//! - `@DEFAULT_SINK@` or `@DEFAULT_SOURCE@` for the default endpoints
+//! - An object ID, such as `42`
+//! - Conditions joined by `&`, such as
+//!   `application.name="Firefox" & media.role=Music`
+//!
+//! A condition compares a property with `=` or `!=`. Properties are those
+//! available to name templates, with or without the `node:`, `device:` or
+//! `client:` prefix, along with `media.class`, `object.id` and
+//! `object.serial`. `*` in a value matches any text, and values containing
+//! spaces or `&` can be quoted. A condition on a property which an object
+//! doesn't have never holds, even with `!=`.
+//!
+//! When several objects match, the oldest, with the lowest object serial,
+//! comes first.
+
+use serde_with::DeserializeFromStr;
+
+use crate::config::names::TagResolver;
+use crate::config::tag::Tag;
+use crate::media_class::MediaClass;
+use crate::object::ObjectId;
+use crate::state::{self, State};
+use crate::view;
+
+#[derive(Debug, Clone, DeserializeFromStr)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum Selector {
+    DefaultSink,
+    DefaultSource,
+    Id(u32),
+    Conditions(Vec<Condition>),
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(PartialEq))]
+pub struct Condition {
+    key: Key,
+    negated: bool,
+    /// Glob pattern where `*` matches any text
+    pattern: String,
+}
+
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(test, derive(PartialEq))]
+enum Key {
+    Tag(Tag),
+    MediaClass,
+    ObjectId,
+    ObjectSerial,
+}
+
+impl std::str::FromStr for Key {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "media.class" => return Ok(Key::MediaClass),
+            "object.id" => return Ok(Key::ObjectId),
+            "object.serial" => return Ok(Key::ObjectSerial),
+            _ => {}
+        }
+        if s.contains(':') {
+            return s.parse().map(Key::Tag);
+        }
+        ["node", "device", "client"]
+            .iter()
+            .find_map(|kind| format!("{}:{}", kind, s).parse().ok())
+            .map(Key::Tag)
+            .ok_or_else(|| format!("Unknown property \"{}\"", s))
+    }
+}
+
+impl std::str::FromStr for Selector {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "@DEFAULT_SINK@" | "@DEFAULT_AUDIO_SINK@" => {
+                return Ok(Selector::DefaultSink)
+            }
+            "@DEFAULT_SOURCE@" | "@DEFAULT_AUDIO_SOURCE@" => {
+                return Ok(Selector::DefaultSource)
+            }
+            s if s.starts_with('@') => {
+                return Err(format!("Unknown selector \"{}\"", s))
+            }
+            s => {
+                if let Ok(id) = s.parse() {
+                    return Ok(Selector::Id(id));
+                }
+            }
+        }
+
+        let mut conditions = Vec::new();
+        let mut rest = s;
+        loop {
+            let (condition, after) = parse_condition(rest)?;
+            conditions.push(condition);
+            match after.trim_start().strip_prefix('&') {
+                Some(after) => rest = after,
+                None if after.trim().is_empty() => break,
+                None => {
+                    return Err(format!(
+                        "Expected \"&\" before \"{}\"",
+                        after.trim()
+                    ))
+                }
+            }
+        }
+
+        Ok(Selector::Conditions(conditions))
+    }
+}
+
+/// Parse a condition from the start of `s`, returning it and what follows.
+fn parse_condition(s: &str) -> Result<(Condition, &str), String> {
+    let s = s.trim_start();
+    let key_end = s
+        .find(|c: char| !(c.is_alphanumeric() || "._:-".contains(c)))
+        .unwrap_or(s.len());
+    let (name, rest) = s.split_at(key_end);
+    if name.is_empty() {
+        return Err(format!("Expected a property at \"{}\"", s));
+    }
+    let key = name.parse()?;
+
+    let rest = rest.trim_start();
+    let (negated, rest) = if let Some(rest) = rest.strip_prefix("!=") {
+        (true, rest)
+    } else if let Some(rest) = rest.strip_prefix('=') {
+        (false, rest)
+    } else {
+        return Err(format!("Expected \"=\" or \"!=\" after \"{}\"", name));
+    };
+
+    let rest = rest.trim_start();
+    let (pattern, rest) = match rest.strip_prefix('"') {
+        Some(quoted) => {
+            let mut pattern = String::new();
+            let mut chars = quoted.char_indices();
+            loop {
+                match chars.next() {
+                    Some((_, '\\')) => match chars.next() {
+                        Some((_, ch)) => pattern.push(ch),
+                        None => return Err(String::from("Unclosed quote")),
+                    },
+                    Some((i, '"')) => break (pattern, &quoted[i + 1..]),
+                    Some((_, ch)) => pattern.push(ch),
+                    None => return Err(String::from("Unclosed quote")),
+                }
+            }
+        }
+        None => {
+            let end = rest.find('&').unwrap_or(rest.len());
+            (String::from(rest[..end].trim_end()), &rest[end..])
+        }
+    };
+
+    Ok((
+        Condition {
+            key,
+            negated,
+            pattern,
+        },
+        rest,
+    ))
+}
+
+/// Whether `text` matches `pattern`, where `*` matches any text.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts = parts.peekable();
+    while let Some(part) = parts.next() {
+        if parts.peek().is_none() {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.is_empty()
+}
+
+impl Condition {
+    fn holds(
+        &self,
+        state: &State,
+        object: &impl TagResolver,
+        id: ObjectId,
+        object_serial: Option<i32>,
+        media_class: Option<&MediaClass>,
+    ) -> bool {
+        let value = match self.key {
+            Key::Tag(tag) => object.resolve_tag(state, tag).cloned(),
+            Key::MediaClass => media_class.map(|c| String::from(c.as_str())),
+            Key::ObjectId => Some(id.to_string()),
+            Key::ObjectSerial => object_serial.map(|serial| serial.to_string()),
+        };
+        value.is_some_and(|value| {
+            glob_match(&self.pattern, &value) != self.negated
+        })
+    }
+}
+
+impl Selector {
+    /// The nodes which match, oldest first.
+    pub fn nodes(&self, state: &State) -> Vec<ObjectId> {
+        let default_name = |which| view::default_for(state, which);
+        let mut nodes: Vec<&state::Node> = state
+            .nodes
+            .values()
+            .filter(|node| match self {
+                Selector::DefaultSink => {
+                    node.media_class.as_ref().is_some_and(|c| c.is_sink())
+                        && node.name.is_some()
+                        && node.name == default_name("default.audio.sink")
+                }
+                Selector::DefaultSource => {
+                    node.media_class.as_ref().is_some_and(|c| c.is_source())
+                        && node.name.is_some()
+                        && node.name == default_name("default.audio.source")
+                }
+                Selector::Id(id) => u32::from(node.id) == *id,
+                Selector::Conditions(conditions) => {
+                    conditions.iter().all(|condition| {
+                        condition.holds(
+                            state,
+                            *node,
+                            node.id,
+                            node.object_serial,
+                            node.media_class.as_ref(),
+                        )
+                    })
+                }
+            })
+            .collect();
+        nodes.sort_by_key(|node| oldest_first(node.object_serial, node.id));
+        nodes.into_iter().map(|node| node.id).collect()
+    }
+
+    /// The devices which match, oldest first.
+    pub fn devices(&self, state: &State) -> Vec<ObjectId> {
+        let mut devices: Vec<&state::Device> = state
+            .devices
+            .values()
+            .filter(|device| match self {
+                Selector::DefaultSink | Selector::DefaultSource => false,
+                Selector::Id(id) => u32::from(device.id) == *id,
+                Selector::Conditions(conditions) => {
+                    conditions.iter().all(|condition| {
+                        condition.holds(
+                            state,
+                            *device,
+                            device.id,
+                            device.object_serial,
+                            device.media_class.as_ref(),
+                        )
+                    })
+                }
+            })
+            .collect();
+        devices.sort_by_key(|device| {
+            oldest_first(device.object_serial, device.id)
+        });
+        devices.into_iter().map(|device| device.id).collect()
+    }
+}
+
+/// Sort key putting objects without a serial last and breaking ties by ID.
+fn oldest_first(object_serial: Option<i32>, id: ObjectId) -> (bool, i32, u32) {
+    (
+        object_serial.is_none(),
+        object_serial.unwrap_or_default(),
+        id.into(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::tag::{ClientTag, NodeTag};
+    use crate::event::MonitorEvent;
+
+    #[test]
+    fn parse() {
+        assert_eq!("@DEFAULT_SINK@".parse(), Ok(Selector::DefaultSink));
+        assert_eq!(" 42 ".parse(), Ok(Selector::Id(42)));
+        assert_eq!(
+            r#"application.name="Fire \"fox\"" & node:media.role != Mus*c"#
+                .parse(),
+            Ok(Selector::Conditions(vec![
+                Condition {
+                    key: Key::Tag(Tag::Client(ClientTag::ApplicationName)),
+                    negated: false,
+                    pattern: String::from("Fire \"fox\""),
+                },
+                Condition {
+                    key: Key::Tag(Tag::Node(NodeTag::MediaRole)),
+                    negated: true,
+                    pattern: String::from("Mus*c"),
+                },
+            ]))
+        );
+
+        assert!("@NOPE@".parse::<Selector>().is_err());
+        assert!("nope=1".parse::<Selector>().is_err());
+        assert!("node.name".parse::<Selector>().is_err());
+        assert!("node.name=\"x".parse::<Selector>().is_err());
+        assert!("node.name=\"x\" y".parse::<Selector>().is_err());
+    }
+
+    #[test]
+    fn globs() {
+        assert!(glob_match("alsa_output.*", "alsa_output.pci"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("a*b*c", "abbc"));
+        assert!(glob_match("exact", "exact"));
+        assert!(!glob_match("a*b", "ba"));
+        assert!(!glob_match("exact", "exactly"));
+        assert!(!glob_match("*.monitor", "sink"));
+    }
+
+    #[test]
+    fn oldest_match_first() {
+        let mut state = State::default();
+        let mut capture_manager = Default::default();
+        for (id, serial, name) in [(30, 3, "b"), (20, 1, "a"), (10, 2, "a")] {
+            let id = ObjectId::from_raw_id(id);
+            for event in [
+                MonitorEvent::NodeName(id, String::from(name)),
+                MonitorEvent::NodeObjectSerial(id, serial),
+            ] {
+                state.update(&mut capture_manager, event);
+            }
+        }
+
+        let selector: Selector = "node.name=a".parse().unwrap();
+        assert_eq!(
+            selector.nodes(&state),
+            vec![ObjectId::from_raw_id(20), ObjectId::from_raw_id(10)]
+        );
+        let selector: Selector = "node.name != a".parse().unwrap();
+        assert_eq!(selector.nodes(&state), vec![ObjectId::from_raw_id(30)]);
+        assert!(Selector::DefaultSink.nodes(&state).is_empty());
+    }
+}
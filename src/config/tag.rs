@@ -27,6 +27,9 @@ pub enum NodeTag {
     NodeNick,
     NodeDescription,
     MediaName,
+    MediaRole,
+    /// node.name, but only for nodes of JACK clients
+    JackClientName,
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -34,6 +37,37 @@ pub enum NodeTag {
 pub enum ClientTag {
     ApplicationName,
     ApplicationProcessBinary,
+    ApplicationProcessId,
+    ProcessCmdline,
+    ProcessCgroup,
+    ProcessFlatpakAppId,
+    PipewireAccess,
+    PipewireAccessPortalAppId,
+    PipewireSnapAppId,
+}
+
+impl Tag {
+    /// Every tag, for documenting them.
+    pub const ALL: [Tag; 18] = [
+        Tag::Device(DeviceTag::DeviceName),
+        Tag::Device(DeviceTag::DeviceNick),
+        Tag::Device(DeviceTag::DeviceDescription),
+        Tag::Node(NodeTag::NodeName),
+        Tag::Node(NodeTag::NodeNick),
+        Tag::Node(NodeTag::NodeDescription),
+        Tag::Node(NodeTag::MediaName),
+        Tag::Node(NodeTag::MediaRole),
+        Tag::Node(NodeTag::JackClientName),
+        Tag::Client(ClientTag::ApplicationName),
+        Tag::Client(ClientTag::ApplicationProcessBinary),
+        Tag::Client(ClientTag::ApplicationProcessId),
+        Tag::Client(ClientTag::ProcessCmdline),
+        Tag::Client(ClientTag::ProcessCgroup),
+        Tag::Client(ClientTag::ProcessFlatpakAppId),
+        Tag::Client(ClientTag::PipewireAccess),
+        Tag::Client(ClientTag::PipewireAccessPortalAppId),
+        Tag::Client(ClientTag::PipewireSnapAppId),
+    ];
 }
 
 #[allow(clippy::to_string_trait_impl)] // This is not for display.
@@ -55,12 +89,37 @@ impl ToString for Tag {
                 String::from("node:node.description")
             }
             Tag::Node(NodeTag::MediaName) => String::from("node:media.name"),
+            Tag::Node(NodeTag::MediaRole) => String::from("node:media.role"),
+            Tag::Node(NodeTag::JackClientName) => {
+                String::from("node:jack.client.name")
+            }
             Tag::Client(ClientTag::ApplicationName) => {
                 String::from("client:application.name")
             }
             Tag::Client(ClientTag::ApplicationProcessBinary) => {
                 String::from("client:application.process.binary")
             }
+            Tag::Client(ClientTag::ApplicationProcessId) => {
+                String::from("client:application.process.id")
+            }
+            Tag::Client(ClientTag::ProcessCmdline) => {
+                String::from("client:process.cmdline")
+            }
+            Tag::Client(ClientTag::ProcessCgroup) => {
+                String::from("client:process.cgroup")
+            }
+            Tag::Client(ClientTag::ProcessFlatpakAppId) => {
+                String::from("client:process.flatpak_app_id")
+            }
+            Tag::Client(ClientTag::PipewireAccess) => {
+                String::from("client:pipewire.access")
+            }
+            Tag::Client(ClientTag::PipewireAccessPortalAppId) => {
+                String::from("client:pipewire.access.portal.app_id")
+            }
+            Tag::Client(ClientTag::PipewireSnapAppId) => {
+                String::from("client:pipewire.snap.app.id")
+            }
         }
     }
 }
@@ -79,12 +138,35 @@ impl std::str::FromStr for Tag {
             "node:node.nick" => Ok(Tag::Node(NodeTag::NodeNick)),
             "node:node.description" => Ok(Tag::Node(NodeTag::NodeDescription)),
             "node:media.name" => Ok(Tag::Node(NodeTag::MediaName)),
+            "node:media.role" => Ok(Tag::Node(NodeTag::MediaRole)),
+            "node:jack.client.name" => Ok(Tag::Node(NodeTag::JackClientName)),
             "client:application.name" => {
                 Ok(Tag::Client(ClientTag::ApplicationName))
             }
             "client:application.process.binary" => {
                 Ok(Tag::Client(ClientTag::ApplicationProcessBinary))
             }
+            "client:application.process.id" => {
+                Ok(Tag::Client(ClientTag::ApplicationProcessId))
+            }
+            "client:process.cmdline" => {
+                Ok(Tag::Client(ClientTag::ProcessCmdline))
+            }
+            "client:process.cgroup" => {
+                Ok(Tag::Client(ClientTag::ProcessCgroup))
+            }
+            "client:process.flatpak_app_id" => {
+                Ok(Tag::Client(ClientTag::ProcessFlatpakAppId))
+            }
+            "client:pipewire.access" => {
+                Ok(Tag::Client(ClientTag::PipewireAccess))
+            }
+            "client:pipewire.access.portal.app_id" => {
+                Ok(Tag::Client(ClientTag::PipewireAccessPortalAppId))
+            }
+            "client:pipewire.snap.app.id" => {
+                Ok(Tag::Client(ClientTag::PipewireSnapAppId))
+            }
             _ => Err(format!("\"{}\" is not implemented", s)),
         }
     }
@@ -127,4 +209,21 @@ mod tests {
             assert_eq!(tag, parsed_tag);
         }
     }
+
+    #[test]
+    fn all_variants() {
+        let count = DeviceTag::iter().count()
+            + NodeTag::iter().count()
+            + ClientTag::iter().count();
+        assert_eq!(Tag::ALL.len(), count);
+        for device_tag in DeviceTag::iter() {
+            assert!(Tag::ALL.contains(&Tag::Device(device_tag)));
+        }
+        for node_tag in NodeTag::iter() {
+            assert!(Tag::ALL.contains(&Tag::Node(node_tag)));
+        }
+        for client_tag in ClientTag::iter() {
+            assert!(Tag::ALL.contains(&Tag::Client(client_tag)));
+        }
+    }
 }
@@ -12,11 +12,23 @@ pub struct ThemeOverlay {
     inherit: Option<String>,
     default_device: Option<StyleDef>,
     default_stream: Option<StyleDef>,
+    loud_warning: Option<StyleDef>,
+    resampled: Option<StyleDef>,
+    paused: Option<StyleDef>,
+    pinned: Option<StyleDef>,
+    recording: Option<StyleDef>,
     selector: Option<StyleDef>,
     tab: Option<StyleDef>,
     tab_selected: Option<StyleDef>,
     tab_marker: Option<StyleDef>,
+    flash: Option<StyleDef>,
     list_more: Option<StyleDef>,
+    scrollbar_track: Option<StyleDef>,
+    scrollbar_thumb: Option<StyleDef>,
+    row_unselected: Option<StyleDef>,
+    row_muted: Option<StyleDef>,
+    row_ended: Option<StyleDef>,
+    row_default: Option<StyleDef>,
     node_title: Option<StyleDef>,
     node_target: Option<StyleDef>,
     volume: Option<StyleDef>,
@@ -72,6 +84,8 @@ impl TryFrom<ThemeOverlay> for Theme {
             Some("default") => Theme::default(),
             Some("nocolor") => Theme::nocolor(),
             Some("plain") => Theme::plain(),
+            Some("highcontrast") => Theme::highcontrast(),
+            Some("monochrome") => Theme::monochrome(),
             Some(inherit) => {
                 anyhow::bail!("'{}' is not a built-in theme", inherit)
             }
@@ -88,11 +102,23 @@ impl TryFrom<ThemeOverlay> for Theme {
 
         set!(default_device);
         set!(default_stream);
+        set!(loud_warning);
+        set!(resampled);
+        set!(paused);
+        set!(pinned);
+        set!(recording);
         set!(selector);
         set!(tab);
         set!(tab_selected);
         set!(tab_marker);
+        set!(flash);
         set!(list_more);
+        set!(scrollbar_track);
+        set!(scrollbar_thumb);
+        set!(row_unselected);
+        set!(row_muted);
+        set!(row_ended);
+        set!(row_default);
         set!(node_title);
         set!(node_target);
         set!(volume);
@@ -120,11 +146,23 @@ impl Default for Theme {
         Self {
             default_device: Style::default(),
             default_stream: Style::default(),
+            loud_warning: Style::default().fg(Color::LightRed),
+            resampled: Style::default().fg(Color::Yellow),
+            paused: Style::default().fg(Color::DarkGray),
+            pinned: Style::default().fg(Color::LightBlue),
+            recording: Style::default().fg(Color::LightRed),
             selector: Style::default().fg(Color::LightCyan),
             tab: Style::default(),
             tab_selected: Style::default().fg(Color::LightCyan),
             tab_marker: Style::default().fg(Color::LightCyan),
+            flash: Style::default().fg(Color::LightCyan),
             list_more: Style::default().fg(Color::DarkGray),
+            scrollbar_track: Style::default().fg(Color::DarkGray),
+            scrollbar_thumb: Style::default().fg(Color::Gray),
+            row_unselected: Style::default(),
+            row_muted: Style::default(),
+            row_ended: Style::default().fg(Color::DarkGray),
+            row_default: Style::default(),
             node_title: Style::default(),
             node_target: Style::default(),
             volume: Style::default(),
@@ -154,6 +192,8 @@ impl Theme {
             (String::from("default"), Theme::default()),
             (String::from("nocolor"), Theme::nocolor()),
             (String::from("plain"), Theme::plain()),
+            (String::from("highcontrast"), Theme::highcontrast()),
+            (String::from("monochrome"), Theme::monochrome()),
         ])
     }
 
@@ -161,11 +201,23 @@ impl Theme {
         Self {
             default_device: Style::default(),
             default_stream: Style::default(),
+            loud_warning: Style::default().add_modifier(Modifier::BOLD),
+            resampled: Style::default(),
+            paused: Style::default(),
+            pinned: Style::default(),
+            recording: Style::default(),
             selector: Style::default().add_modifier(Modifier::BOLD),
             tab: Style::default(),
             tab_selected: Style::default().add_modifier(Modifier::BOLD),
             tab_marker: Style::default().add_modifier(Modifier::BOLD),
+            flash: Style::default().add_modifier(Modifier::BOLD),
             list_more: Style::default(),
+            scrollbar_track: Style::default().add_modifier(Modifier::DIM),
+            scrollbar_thumb: Style::default().add_modifier(Modifier::BOLD),
+            row_unselected: Style::default(),
+            row_muted: Style::default(),
+            row_ended: Style::default().add_modifier(Modifier::DIM),
+            row_default: Style::default(),
             node_title: Style::default(),
             node_target: Style::default(),
             volume: Style::default(),
@@ -191,11 +243,23 @@ impl Theme {
         Self {
             default_device: Style::default(),
             default_stream: Style::default(),
+            loud_warning: Style::default(),
+            resampled: Style::default(),
+            paused: Style::default(),
+            pinned: Style::default(),
+            recording: Style::default(),
             selector: Style::default(),
             tab: Style::default(),
             tab_selected: Style::default(),
             tab_marker: Style::default(),
+            flash: Style::default(),
             list_more: Style::default(),
+            scrollbar_track: Style::default(),
+            scrollbar_thumb: Style::default(),
+            row_unselected: Style::default(),
+            row_muted: Style::default(),
+            row_ended: Style::default(),
+            row_default: Style::default(),
             node_title: Style::default(),
             node_target: Style::default(),
             volume: Style::default(),
@@ -216,6 +280,124 @@ impl Theme {
         }
     }
 
+    /// Bright, bold colors. Overloaded meters are also reversed.
+    fn highcontrast() -> Self {
+        Self {
+            default_device: Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+            default_stream: Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+            loud_warning: Style::default()
+                .fg(Color::LightRed)
+                .add_modifier(Modifier::BOLD),
+            resampled: Style::default().fg(Color::LightYellow),
+            paused: Style::default().fg(Color::White),
+            pinned: Style::default().fg(Color::LightBlue),
+            recording: Style::default().fg(Color::LightRed),
+            selector: Style::default()
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD),
+            tab: Style::default().fg(Color::White),
+            tab_selected: Style::default()
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD | Modifier::REVERSED),
+            tab_marker: Style::default()
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD),
+            flash: Style::default()
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD),
+            list_more: Style::default().fg(Color::White),
+            scrollbar_track: Style::default().fg(Color::Gray),
+            scrollbar_thumb: Style::default()
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD),
+            row_unselected: Style::default(),
+            row_muted: Style::default(),
+            row_ended: Style::default(),
+            row_default: Style::default().add_modifier(Modifier::BOLD),
+            node_title: Style::default().fg(Color::White),
+            node_target: Style::default().fg(Color::White),
+            volume: Style::default()
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD),
+            volume_empty: Style::default().fg(Color::Gray),
+            volume_filled: Style::default()
+                .fg(Color::LightCyan)
+                .add_modifier(Modifier::BOLD),
+            meter_inactive: Style::default().fg(Color::Gray),
+            meter_active: Style::default()
+                .fg(Color::LightGreen)
+                .add_modifier(Modifier::BOLD),
+            meter_overload: Style::default()
+                .fg(Color::LightRed)
+                .add_modifier(Modifier::BOLD | Modifier::REVERSED),
+            meter_center_inactive: Style::default().fg(Color::Gray),
+            meter_center_active: Style::default()
+                .fg(Color::LightGreen)
+                .add_modifier(Modifier::BOLD),
+            config_device: Style::default().fg(Color::White),
+            config_profile: Style::default().fg(Color::White),
+            dropdown_icon: Style::default()
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD),
+            dropdown_border: Style::default().fg(Color::White),
+            dropdown_item: Style::default().fg(Color::White),
+            dropdown_selected: Style::default()
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD | Modifier::REVERSED),
+            dropdown_more: Style::default().fg(Color::White),
+        }
+    }
+
+    /// Attributes that render on monochrome and e-ink displays. Unlike
+    /// [`Theme::nocolor`], no states are distinguished by color or dimming.
+    fn monochrome() -> Self {
+        Self {
+            default_device: Style::default().add_modifier(Modifier::BOLD),
+            default_stream: Style::default().add_modifier(Modifier::BOLD),
+            loud_warning: Style::default().add_modifier(Modifier::BOLD),
+            resampled: Style::default(),
+            paused: Style::default(),
+            pinned: Style::default(),
+            recording: Style::default(),
+            selector: Style::default().add_modifier(Modifier::BOLD),
+            tab: Style::default(),
+            tab_selected: Style::default()
+                .add_modifier(Modifier::BOLD | Modifier::REVERSED),
+            tab_marker: Style::default().add_modifier(Modifier::BOLD),
+            flash: Style::default().add_modifier(Modifier::BOLD),
+            list_more: Style::default(),
+            scrollbar_track: Style::default(),
+            scrollbar_thumb: Style::default().add_modifier(Modifier::REVERSED),
+            row_unselected: Style::default(),
+            row_muted: Style::default(),
+            row_ended: Style::default().add_modifier(Modifier::DIM),
+            row_default: Style::default().add_modifier(Modifier::UNDERLINED),
+            node_title: Style::default(),
+            node_target: Style::default(),
+            volume: Style::default(),
+            volume_empty: Style::default(),
+            volume_filled: Style::default().add_modifier(Modifier::BOLD),
+            meter_inactive: Style::default(),
+            meter_active: Style::default().add_modifier(Modifier::BOLD),
+            meter_overload: Style::default()
+                .add_modifier(Modifier::BOLD | Modifier::REVERSED),
+            meter_center_inactive: Style::default(),
+            meter_center_active: Style::default().add_modifier(Modifier::BOLD),
+            config_device: Style::default(),
+            config_profile: Style::default(),
+            dropdown_icon: Style::default(),
+            dropdown_border: Style::default(),
+            dropdown_item: Style::default(),
+            dropdown_selected: Style::default()
+                .add_modifier(Modifier::BOLD | Modifier::REVERSED),
+            dropdown_more: Style::default(),
+        }
+    }
+
     /// Merge deserialized themes with defaults
     pub fn merge<'de, D>(
         deserializer: D,
@@ -242,6 +424,12 @@ impl Theme {
         if !merged.contains_key("plain") {
             merged.insert(String::from("plain"), Theme::plain());
         }
+        if !merged.contains_key("highcontrast") {
+            merged.insert(String::from("highcontrast"), Theme::highcontrast());
+        }
+        if !merged.contains_key("monochrome") {
+            merged.insert(String::from("monochrome"), Theme::monochrome());
+        }
         Ok(merged)
     }
 }
@@ -295,4 +483,15 @@ mod tests {
             assert_eq!(theme.selector, builtin.selector);
         }
     }
+
+    #[test]
+    fn state_not_encoded_by_color_alone() {
+        for theme in [Theme::highcontrast(), Theme::monochrome()] {
+            assert_ne!(
+                theme.meter_overload.add_modifier,
+                theme.meter_active.add_modifier
+            );
+            assert_ne!(theme.tab_selected.add_modifier, theme.tab.add_modifier);
+        }
+    }
 }
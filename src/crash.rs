@@ -0,0 +1,204 @@
+//! Restoring the terminal and writing a crash report when wiremix panics.
+//!
+//! The report holds the most recent events and a summary of the state at the
+//! last view rebuild, so that a crash can be reported with some idea of what
+//! led to it. It's saved in the XDG state directory alongside the session.
+
+use std::collections::VecDeque;
+use std::env;
+use std::fmt::{self, Write as _};
+use std::fs;
+use std::io::stdout;
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Context;
+use crossterm::{event::DisableMouseCapture, ExecutableCommand};
+
+use crate::event::{Event, MonitorEvent};
+use crate::signals;
+use crate::state::State;
+use crate::view::View;
+
+/// How many of the most recent events are kept for the report
+const EVENTS: usize = 100;
+
+/// Whether the hook is installed, so that nothing is recorded otherwise
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+static RECENT: Mutex<Recent> = Mutex::new(Recent::new());
+
+/// What's known about the moments before a crash.
+#[derive(Debug)]
+struct Recent {
+    events: VecDeque<String>,
+    state: String,
+}
+
+impl Recent {
+    const fn new() -> Self {
+        Self {
+            events: VecDeque::new(),
+            state: String::new(),
+        }
+    }
+
+    fn push(&mut self, event: String) {
+        if self.events.len() == EVENTS {
+            self.events.pop_front();
+        }
+        self.events.push_back(event);
+    }
+}
+
+/// Install a panic hook which restores the terminal, including disabling
+/// mouse capture if `mouse` is set, and writes a crash report. Call this after
+/// initializing the terminal so that it runs before ratatui's own hook.
+pub fn install(mouse: bool) {
+    ENABLED.store(true, Ordering::Relaxed);
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        if mouse {
+            let _ = stdout().execute(DisableMouseCapture);
+        }
+        signals::pop_keyboard_enhancement();
+        ratatui::restore();
+
+        let written = default_dir().map(|dir| write(&dir, info));
+        previous(info);
+        match written {
+            Some(Ok(path)) => {
+                eprintln!("A crash report was written to {}", path.display());
+            }
+            Some(Err(err)) => eprintln!("{:#}", err),
+            None => {}
+        }
+    }));
+}
+
+/// Keep an event for the report. Peaks are skipped because they would
+/// quickly push out everything else.
+pub fn record_event(event: &Event) {
+    if !ENABLED.load(Ordering::Relaxed)
+        || matches!(event, Event::Monitor(MonitorEvent::NodePeaks(..)))
+    {
+        return;
+    }
+    let event = format!("{:?}", event);
+    if let Ok(mut recent) = RECENT.lock() {
+        recent.push(event);
+    }
+}
+
+/// Keep a summary of the state and the view built from it for the report.
+pub fn record_state(state: &State, view: &View) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    let summary = summary(state, view);
+    if let Ok(mut recent) = RECENT.lock() {
+        recent.state = summary;
+    }
+}
+
+fn summary(state: &State, view: &View) -> String {
+    let mut summary = format!(
+        "{} nodes, {} devices, {} links, {} clients, {} metadata\n\
+         default sink: {:?}, default source: {:?}\n",
+        state.nodes.len(),
+        state.devices.len(),
+        state.links.len(),
+        state.clients.len(),
+        state.metadatas.len(),
+        view.default_sink,
+        view.default_source,
+    );
+    let mut nodes: Vec<_> = view.nodes.values().collect();
+    nodes.sort_by_key(|node| node.id);
+    for node in nodes {
+        let _ = writeln!(
+            summary,
+            "{} {} {:?}",
+            u32::from(node.id),
+            node.media_class.as_str(),
+            node.name
+        );
+    }
+    summary
+}
+
+/// Returns the directory crash reports are written to.
+pub fn default_dir() -> Option<PathBuf> {
+    if let Ok(xdg_state) = env::var("XDG_STATE_HOME") {
+        return Some(Path::new(&xdg_state).join("wiremix"));
+    }
+
+    if let Ok(home) = env::var("HOME") {
+        return Some(Path::new(&home).join(".local/state/wiremix"));
+    }
+
+    None
+}
+
+/// Write a report about the panic to a new file in `dir`, returning its path.
+fn write(
+    dir: &Path,
+    info: &dyn fmt::Display,
+) -> Result<PathBuf, anyhow::Error> {
+    let time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let path = dir.join(format!("crash-{}.txt", time));
+    let context =
+        || format!("Failed to write crash report to '{}'", path.display());
+
+    // The lock is poisoned if recording panicked, and held if this thread
+    // panicked while recording, so don't wait for it
+    let recent = match RECENT.try_lock() {
+        Ok(recent) => report(info, &recent),
+        Err(_) => report(info, &Recent::new()),
+    };
+
+    fs::create_dir_all(dir).with_context(context)?;
+    fs::write(&path, recent).with_context(context)?;
+    Ok(path)
+}
+
+fn report(info: &dyn fmt::Display, recent: &Recent) -> String {
+    let mut report = format!(
+        "wiremix {} crashed\n\n{}\n\n{}\nState:\n{}\n",
+        env!("CARGO_PKG_VERSION"),
+        info,
+        std::backtrace::Backtrace::force_capture(),
+        recent.state,
+    );
+    let _ = writeln!(report, "Last {} events:", recent.events.len());
+    for event in &recent.events {
+        let _ = writeln!(report, "{}", event);
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_recent_events() {
+        let mut recent = Recent::new();
+        for i in 0..EVENTS + 5 {
+            recent.push(i.to_string());
+        }
+
+        assert_eq!(recent.events.len(), EVENTS);
+        assert_eq!(recent.events.front().map(String::as_str), Some("5"));
+        assert_eq!(
+            recent.events.back().map(String::as_str),
+            Some((EVENTS + 4).to_string().as_str())
+        );
+    }
+}
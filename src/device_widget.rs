@@ -9,6 +9,7 @@ use ratatui::{
 
 use crossterm::event::{MouseButton, MouseEventKind};
 use smallvec::smallvec;
+use unicode_width::UnicodeWidthStr;
 
 use crate::app::{Action, MouseArea};
 use crate::config::Config;
@@ -18,6 +19,7 @@ use crate::view;
 pub struct DeviceWidget<'a> {
     device: &'a view::Device,
     selected: bool,
+    compact: bool,
     config: &'a Config,
 }
 
@@ -30,18 +32,33 @@ impl<'a> DeviceWidget<'a> {
         Self {
             device,
             selected,
+            compact: false,
             config,
         }
     }
 
+    /// Render the device on a single line.
+    pub fn compact(mut self, compact: bool) -> Self {
+        self.compact = compact;
+        self
+    }
+
     /// Height of a full device display.
-    pub fn height() -> u16 {
-        3
+    pub fn height(compact: bool) -> u16 {
+        if compact {
+            1
+        } else {
+            3
+        }
     }
 
     /// Spacing between objects
-    pub fn spacing() -> u16 {
-        2
+    pub fn spacing(compact: bool) -> u16 {
+        if compact {
+            1
+        } else {
+            2
+        }
     }
 
     /// Area for the target dropdown
@@ -62,7 +79,11 @@ impl<'a> DeviceWidget<'a> {
 
         // Position the dropdown so that the first item is over the displayed item
         let x = list_area.left().saturating_add(4);
-        let y = object_area.top().saturating_add(1);
+        let y = if object_list.compact {
+            object_area.top().saturating_sub(1)
+        } else {
+            object_area.top().saturating_add(1)
+        };
         // Add 2 for vertical borders and 2 for highlight symbol
         let width = max_target_length.saturating_add(4) as u16;
         let height = std::cmp::min(max_visible_items, object_list.targets.len())
@@ -94,6 +115,11 @@ impl StatefulWidget for DeviceWidget<'_> {
         let selected_area = layout[0];
         let node_area = layout[1];
 
+        if self.compact {
+            self.render_compact(selected_area, node_area, buf, mouse_areas);
+            return;
+        }
+
         if self.selected {
             let rows = Layout::default()
                 .direction(Direction::Vertical)
@@ -140,10 +166,81 @@ impl StatefulWidget for DeviceWidget<'_> {
 
         Line::from(vec![
             Span::from("    "),
+            self.dropdown_icon(),
+            Span::from(" "),
             Span::styled(
+                &self.device.target_title,
+                self.config.theme.config_profile,
+            ),
+        ])
+        .render(target_area, buf);
+
+        mouse_areas.push((
+            target_area,
+            smallvec![MouseEventKind::Down(MouseButton::Left)],
+            smallvec![
+                Action::SelectObject(self.device.id),
+                Action::ActivateDropdown
+            ],
+        ));
+    }
+}
+
+impl DeviceWidget<'_> {
+    /// The dropdown marker, or a spinner frame while the device is switching
+    /// profiles.
+    fn dropdown_icon(&self) -> Span<'_> {
+        let spinner = &self.config.char_set.spinner;
+        let frame = self.device.switching.and_then(|frame| {
+            let count = spinner.chars().count();
+            spinner.chars().nth(frame.checked_rem(count)?)
+        });
+        match frame {
+            Some(frame) => {
+                Span::styled(frame.to_string(), self.config.theme.dropdown_icon)
+            }
+            None => Span::styled(
                 &self.config.char_set.dropdown_icon,
                 self.config.theme.dropdown_icon,
             ),
+        }
+    }
+
+    fn render_compact(
+        self,
+        selected_area: Rect,
+        node_area: Rect,
+        buf: &mut Buffer,
+        mouse_areas: &mut Vec<MouseArea>,
+    ) {
+        if self.selected {
+            Line::from(Span::styled(
+                &self.config.char_set.selector_middle,
+                self.config.theme.selector,
+            ))
+            .render(selected_area, buf);
+        }
+
+        let title_width = self.device.title.width() as u16;
+        let layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Length(title_width.saturating_add(3)), // title_area
+                Constraint::Min(0), // target_area
+            ])
+            .split(node_area);
+        let title_area = layout[0];
+        let target_area = layout[1];
+
+        Line::from(vec![
+            Span::from("   "),
+            Span::styled(&self.device.title, self.config.theme.config_device),
+        ])
+        .render(title_area, buf);
+
+        Line::from(vec![
+            Span::from(" "),
+            self.dropdown_icon(),
             Span::from(" "),
             Span::styled(
                 &self.device.target_title,
@@ -0,0 +1,328 @@
+//! Comparing two snapshots saved from `wiremix list --json` or
+//! `wiremix graph --json`, for `wiremix diff`, to see what changed, such as
+//! after plugging in a dock.
+//!
+//! Objects are matched by their serial, which PipeWire never reuses, or by
+//! their ID in snapshots without serials. Every other field of a matched
+//! object is compared, so the same code handles both kinds of snapshot.
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context};
+use serde_json::{json, Map, Value};
+
+/// Compare the snapshots in files `a` and `b` and print the differences, or
+/// a JSON report if `json` is set.
+pub fn run(a: &Path, b: &Path, json: bool) -> Result<(), anyhow::Error> {
+    let read = |path: &Path| {
+        let context = || format!("Failed to read '{}'", path.display());
+        let text = fs::read_to_string(path).with_context(context)?;
+        serde_json::from_str::<Value>(&text).with_context(context)
+    };
+    let diff = diff(&read(a)?, &read(b)?)?;
+    if json {
+        println!("{:#}", diff.to_json());
+    } else if diff.is_empty() {
+        println!("No differences");
+    } else {
+        print!("{}", diff);
+    }
+    Ok(())
+}
+
+/// A field of an object which has a different value in the second snapshot.
+#[derive(Debug, PartialEq)]
+pub struct Change {
+    pub field: String,
+    pub from: Value,
+    pub to: Value,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Changed {
+    pub name: String,
+    pub changes: Vec<Change>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Diff {
+    pub added: Vec<Value>,
+    pub removed: Vec<Value>,
+    pub changed: Vec<Changed>,
+    /// Links between the named nodes only in the second snapshot
+    pub links_added: Vec<(String, String)>,
+    /// Links between the named nodes only in the first snapshot
+    pub links_removed: Vec<(String, String)>,
+}
+
+impl Diff {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn to_json(&self) -> Value {
+        let links = |links: &[(String, String)]| {
+            Value::from_iter(links.iter().map(
+                |(output, input)| json!({ "output": output, "input": input }),
+            ))
+        };
+        json!({
+            "added": self.added,
+            "removed": self.removed,
+            "changed": Value::from_iter(self.changed.iter().map(|changed| {
+                let changes: Map<String, Value> = changed
+                    .changes
+                    .iter()
+                    .map(|change| {
+                        let value = json!({
+                            "from": change.from,
+                            "to": change.to,
+                        });
+                        (change.field.clone(), value)
+                    })
+                    .collect();
+                json!({ "name": changed.name, "changes": changes })
+            })),
+            "links_added": links(&self.links_added),
+            "links_removed": links(&self.links_removed),
+        })
+    }
+}
+
+impl fmt::Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (sign, objects) in [("+", &self.added), ("-", &self.removed)] {
+            for object in objects {
+                write!(f, "{} {}", sign, name(object))?;
+                if let Some(Value::String(media_class)) =
+                    object.get("media_class")
+                {
+                    write!(f, " ({})", media_class)?;
+                }
+                writeln!(f)?;
+            }
+        }
+        for changed in &self.changed {
+            let changes: Vec<String> = changed
+                .changes
+                .iter()
+                .map(|change| {
+                    format!(
+                        "{} {} -> {}",
+                        change.field,
+                        display(&change.from),
+                        display(&change.to)
+                    )
+                })
+                .collect();
+            writeln!(f, "~ {}: {}", changed.name, changes.join(", "))?;
+        }
+        for (sign, links) in
+            [("+", &self.links_added), ("-", &self.links_removed)]
+        {
+            for (output, input) in links {
+                writeln!(f, "{} link {} -> {}", sign, output, input)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A snapshot's objects, and its links if it's a graph.
+struct Snapshot<'a> {
+    objects: &'a [Value],
+    links: Option<Vec<(String, String)>>,
+}
+
+impl<'a> Snapshot<'a> {
+    fn parse(value: &'a Value) -> Result<Self, anyhow::Error> {
+        match value {
+            // wiremix list --json
+            Value::Array(objects) => Ok(Self {
+                objects,
+                links: None,
+            }),
+            // wiremix graph --json
+            Value::Object(graph) => {
+                let Some(Value::Array(objects)) = graph.get("nodes") else {
+                    bail!("Not a snapshot from wiremix list or graph");
+                };
+                let name_of = |id: &Value| {
+                    objects
+                        .iter()
+                        .find(|object| object.get("id") == Some(id))
+                        .map_or_else(|| display(id), name)
+                };
+                let links = graph
+                    .get("links")
+                    .and_then(Value::as_array)
+                    .map(Vec::as_slice)
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|link| {
+                        (name_of(&link["output"]), name_of(&link["input"]))
+                    })
+                    .collect();
+                Ok(Self {
+                    objects,
+                    links: Some(links),
+                })
+            }
+            _ => bail!("Not a snapshot from wiremix list or graph"),
+        }
+    }
+}
+
+/// The differences between snapshots `a` and `b`.
+pub fn diff<'a>(a: &'a Value, b: &'a Value) -> Result<Diff, anyhow::Error> {
+    let (a, b) = (Snapshot::parse(a)?, Snapshot::parse(b)?);
+    if a.links.is_some() != b.links.is_some() {
+        bail!("Can't compare a snapshot from wiremix list with a graph");
+    }
+
+    let find = |objects: &'a [Value], object: &Value| -> Option<&'a Value> {
+        objects.iter().find(|other| key(other) == key(object))
+    };
+
+    let mut diff = Diff {
+        added: b
+            .objects
+            .iter()
+            .filter(|object| find(a.objects, object).is_none())
+            .cloned()
+            .collect(),
+        removed: a
+            .objects
+            .iter()
+            .filter(|object| find(b.objects, object).is_none())
+            .cloned()
+            .collect(),
+        ..Default::default()
+    };
+
+    for before in a.objects {
+        let Some(after) = find(b.objects, before) else {
+            continue;
+        };
+        let (Value::Object(before), Value::Object(after)) = (before, after)
+        else {
+            continue;
+        };
+        let changes: Vec<Change> = before
+            .keys()
+            .chain(after.keys().filter(|field| !before.contains_key(*field)))
+            .filter(|field| !matches!(field.as_str(), "id" | "serial"))
+            .filter_map(|field| {
+                let from = before.get(field).cloned().unwrap_or_default();
+                let to = after.get(field).cloned().unwrap_or_default();
+                (from != to).then(|| Change {
+                    field: field.clone(),
+                    from,
+                    to,
+                })
+            })
+            .collect();
+        if !changes.is_empty() {
+            diff.changed.push(Changed {
+                name: name(&Value::Object(after.clone())),
+                changes,
+            });
+        }
+    }
+
+    if let (Some(a_links), Some(b_links)) = (&a.links, &b.links) {
+        diff.links_added = b_links
+            .iter()
+            .filter(|link| !a_links.contains(link))
+            .cloned()
+            .collect();
+        diff.links_removed = a_links
+            .iter()
+            .filter(|link| !b_links.contains(link))
+            .cloned()
+            .collect();
+    }
+
+    Ok(diff)
+}
+
+/// What identifies an object in both snapshots.
+fn key(object: &Value) -> Option<&Value> {
+    object
+        .get("serial")
+        .filter(|serial| !serial.is_null())
+        .or_else(|| object.get("id"))
+}
+
+/// The name of an object, which is `title` in graphs.
+fn name(object: &Value) -> String {
+    object
+        .get("name")
+        .or_else(|| object.get("title"))
+        .map_or_else(|| String::from("?"), display)
+}
+
+fn display(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Null => String::from("none"),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compare_lists() {
+        let a = json!([
+            { "id": 40, "serial": 400, "name": "Speakers", "volume": 40,
+              "media_class": "Audio/Sink" },
+            { "id": 50, "serial": 500, "name": "Firefox", "volume": 40,
+              "target": "Speakers" },
+        ]);
+        let b = json!([
+            { "id": 50, "serial": 500, "name": "Firefox", "volume": 55,
+              "target": "Dock" },
+            { "id": 60, "serial": 600, "name": "Dock", "volume": 100,
+              "media_class": "Audio/Sink" },
+        ]);
+        let diff = diff(&a, &b).unwrap();
+        assert_eq!(
+            diff.to_string(),
+            "+ Dock (Audio/Sink)\n\
+             - Speakers (Audio/Sink)\n\
+             ~ Firefox: target Speakers -> Dock, volume 40 -> 55\n"
+        );
+        assert!(super::diff(&a, &a).unwrap().is_empty());
+    }
+
+    #[test]
+    fn compare_graph_links() {
+        let nodes = json!([
+            { "id": 40, "title": "Speakers" },
+            { "id": 50, "title": "Firefox" },
+            { "id": 60, "title": "Dock" },
+        ]);
+        let a = json!({
+            "nodes": nodes,
+            "links": [{ "output": 50, "input": 40, "count": 2 }],
+        });
+        let b = json!({
+            "nodes": nodes,
+            "links": [{ "output": 50, "input": 60, "count": 2 }],
+        });
+        let diff = diff(&a, &b).unwrap();
+        let link = |output: &str, input: &str| {
+            (String::from(output), String::from(input))
+        };
+        assert_eq!(diff.links_added, [link("Firefox", "Dock")]);
+        assert_eq!(diff.links_removed, [link("Firefox", "Speakers")]);
+        assert!(diff.changed.is_empty());
+
+        assert!(super::diff(&a, &json!([])).is_err());
+    }
+}
@@ -0,0 +1,144 @@
+//! Do-not-disturb mode, which mutes playback streams except those whose
+//! media.role is allowed by [`Dnd`](`crate::config::Dnd`).
+//!
+//! Only the streams muted by the mode are unmuted when it's turned off, so
+//! streams which were already muted stay that way. Unmuting a stream by hand
+//! while the mode is on keeps it unmuted.
+
+use std::collections::HashSet;
+
+use crate::config;
+use crate::object::ObjectId;
+use crate::state::State;
+use crate::view::View;
+
+/// A stream's ID, media.role and mute state.
+type Stream<'a> = (ObjectId, Option<&'a str>, bool);
+
+/// Tracks the streams muted while do-not-disturb is on.
+#[derive(Default, Debug)]
+pub struct Dnd {
+    active: bool,
+    /// Streams muted by do-not-disturb, to unmute when it's turned off
+    muted: HashSet<ObjectId>,
+    /// Streams which have been seen while active, so that a stream unmuted
+    /// by hand isn't muted again
+    seen: HashSet<ObjectId>,
+}
+
+impl Dnd {
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// The number of streams currently muted by do-not-disturb.
+    pub fn muted_count(&self) -> usize {
+        self.muted.len()
+    }
+
+    /// Turn do-not-disturb on. The streams are muted on the next
+    /// [`update`](`Dnd::update`).
+    pub fn start(&mut self) {
+        self.active = true;
+    }
+
+    /// Turn do-not-disturb off, returning the streams to unmute.
+    pub fn stop(&mut self) -> Vec<ObjectId> {
+        self.active = false;
+        self.seen.clear();
+        self.muted.drain().collect()
+    }
+
+    /// Returns the streams to mute after the streams present have changed.
+    pub fn update(
+        &mut self,
+        dnd: &config::Dnd,
+        state: &State,
+        view: &View,
+    ) -> Vec<ObjectId> {
+        if !self.active {
+            return Vec::new();
+        }
+
+        let streams: Vec<Stream> = view
+            .nodes
+            .values()
+            .filter(|node| node.media_class.is_sink_input())
+            .map(|node| {
+                let role = state
+                    .nodes
+                    .get(&node.id)
+                    .and_then(|node| node.media_role.as_deref());
+                (node.id, role, node.mute)
+            })
+            .collect();
+        self.plan(&dnd.allow, &streams)
+    }
+
+    fn plan(&mut self, allow: &[String], streams: &[Stream]) -> Vec<ObjectId> {
+        self.seen
+            .retain(|node_id| streams.iter().any(|(id, ..)| id == node_id));
+        // Forget streams which are gone or were unmuted by hand
+        self.muted.retain(|node_id| {
+            streams.iter().any(|(id, _, mute)| id == node_id && *mute)
+        });
+
+        let mut mute = Vec::new();
+        for &(node_id, role, muted) in streams {
+            if !self.seen.insert(node_id) || muted {
+                continue;
+            }
+            if role.is_some_and(|role| allow.iter().any(|r| r == role)) {
+                continue;
+            }
+            self.muted.insert(node_id);
+            mute.push(node_id);
+        }
+        mute
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mute_until_stopped() {
+        let allow = vec![String::from("Communication")];
+        let music = ObjectId::from_raw_id(1);
+        let call = ObjectId::from_raw_id(2);
+        let muted = ObjectId::from_raw_id(3);
+        let streams = [
+            (music, Some("Music"), false),
+            (call, Some("Communication"), false),
+            (muted, None, true),
+        ];
+
+        let mut dnd = Dnd::default();
+        dnd.start();
+        assert_eq!(dnd.plan(&allow, &streams), [music]);
+
+        // The mute has taken effect
+        let streams = [
+            (music, Some("Music"), true),
+            (call, Some("Communication"), false),
+            (muted, None, true),
+        ];
+        assert!(dnd.plan(&allow, &streams).is_empty());
+        assert_eq!(dnd.muted_count(), 1);
+        assert_eq!(dnd.stop(), [music]);
+        assert!(!dnd.is_active());
+    }
+
+    #[test]
+    fn keep_stream_unmuted_by_hand() {
+        let stream = ObjectId::from_raw_id(1);
+        let mut dnd = Dnd::default();
+        dnd.start();
+        assert_eq!(dnd.plan(&[], &[(stream, None, false)]), [stream]);
+        assert!(dnd.plan(&[], &[(stream, None, true)]).is_empty());
+        // Unmuted by hand
+        assert!(dnd.plan(&[], &[(stream, None, false)]).is_empty());
+        assert!(dnd.stop().is_empty());
+    }
+}
@@ -72,7 +72,7 @@ impl StatefulWidget for DropdownWidget<'_> {
                 Block::default()
                     .borders(Borders::ALL)
                     .border_style(self.config.theme.dropdown_border)
-                    .border_type(self.config.char_set.dropdown_border),
+                    .border_set(self.config.char_set.dropdown_border),
             )
             .style(self.config.theme.dropdown_item)
             .highlight_symbol(&highlight_symbol)
@@ -114,6 +114,7 @@ impl StatefulWidget for DropdownWidget<'_> {
         // Subtract 2 for vertical borders
         let dropdown_area_inner_height =
             (dropdown_area.height as usize).saturating_sub(2);
+        self.object_list.dropdown_visible = dropdown_area_inner_height;
         let last_index = first_index.saturating_add(dropdown_area_inner_height);
         // Add a clickable indicator to the bottom border if there or more
         // items if scrolled down
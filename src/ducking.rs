@@ -0,0 +1,163 @@
+//! Lowering some streams while others are present, configured by
+//! [`Ducking`](`crate::config::Ducking`).
+
+use std::collections::HashMap;
+
+use crate::config::Ducking;
+use crate::fade::{Curve, Fade};
+use crate::object::ObjectId;
+use crate::state::State;
+use crate::view::View;
+
+/// How long lowering or restoring a stream takes.
+const DUCK_DURATION_MS: u32 = 500;
+
+/// A stream's ID, media.role and volume.
+type Stream<'a> = (ObjectId, Option<&'a str>, Option<f32>);
+
+/// Tracks the streams which have been lowered.
+#[derive(Default, Debug)]
+pub struct Ducker {
+    /// Lowered streams and the volumes to restore them to
+    ducked: HashMap<ObjectId, f32>,
+}
+
+impl Ducker {
+    /// Fades for lowering or restoring streams after the streams present have
+    /// changed, with the volume each starts from.
+    pub fn update(
+        &mut self,
+        ducking: &Ducking,
+        state: &State,
+        view: &View,
+    ) -> Vec<(ObjectId, f32, Fade)> {
+        if !ducking.enabled {
+            return Vec::new();
+        }
+
+        let streams: Vec<Stream> = view
+            .nodes
+            .values()
+            .filter(|node| {
+                node.media_class.is_sink_input()
+                    || node.media_class.is_source_output()
+            })
+            .map(|node| {
+                let role = state
+                    .nodes
+                    .get(&node.id)
+                    .and_then(|node| node.media_role.as_deref());
+                (node.id, role, node.volume())
+            })
+            .collect();
+        self.plan(ducking, &streams)
+    }
+
+    fn plan(
+        &mut self,
+        ducking: &Ducking,
+        streams: &[Stream],
+    ) -> Vec<(ObjectId, f32, Fade)> {
+        let has_role = |roles: &[String], role: Option<&str>| {
+            role.is_some_and(|role| roles.iter().any(|r| r == role))
+        };
+        let fade = |volume| Fade {
+            volume,
+            duration_ms: DUCK_DURATION_MS,
+            curve: Curve::EaseInOut,
+        };
+
+        self.ducked
+            .retain(|node_id, _| streams.iter().any(|(id, ..)| id == node_id));
+
+        let triggered = streams
+            .iter()
+            .any(|(_, role, _)| has_role(&ducking.triggers, *role));
+        if !triggered {
+            return self
+                .ducked
+                .drain()
+                .filter_map(|(node_id, restore)| {
+                    let (_, _, volume) =
+                        streams.iter().find(|(id, ..)| *id == node_id)?;
+                    Some((node_id, (*volume)?, fade(restore)))
+                })
+                .collect();
+        }
+
+        let mut fades = Vec::new();
+        for &(node_id, role, volume) in streams {
+            if !has_role(&ducking.ducked, role)
+                || self.ducked.contains_key(&node_id)
+            {
+                continue;
+            }
+            let Some(volume) = volume else {
+                continue;
+            };
+            self.ducked.insert(node_id, volume);
+            fades.push((
+                node_id,
+                volume,
+                fade(volume * (1.0 - ducking.amount)),
+            ));
+        }
+        fades
+    }
+
+    /// The lowered streams and the volumes to restore them to, which are no
+    /// longer tracked, for restoring them before exiting.
+    pub fn restore(&mut self) -> Vec<(ObjectId, f32)> {
+        self.ducked.drain().collect()
+    }
+
+    /// Stop tracking a stream whose volume was set by hand, so that it keeps
+    /// the new volume.
+    pub fn forget(&mut self, node_id: ObjectId) {
+        self.ducked.remove(&node_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duck_during_call_and_restore() {
+        let ducking = Ducking {
+            enabled: true,
+            ..Default::default()
+        };
+        let music = ObjectId::from_raw_id(1);
+        let call = ObjectId::from_raw_id(2);
+        let other = ObjectId::from_raw_id(3);
+        let mut ducker = Ducker::default();
+
+        let before = [(music, Some("Music"), Some(0.8)), (other, None, None)];
+        assert!(ducker.plan(&ducking, &before).is_empty());
+
+        let during = [
+            (music, Some("Music"), Some(0.8)),
+            (call, Some("Communication"), Some(1.0)),
+        ];
+        let fades = ducker.plan(&ducking, &during);
+        assert_eq!(fades.len(), 1);
+        assert_eq!((fades[0].0, fades[0].1), (music, 0.8));
+        assert_eq!(fades[0].2.volume, 0.4);
+        // Already ducked
+        assert!(ducker.plan(&ducking, &during).is_empty());
+        // Exiting mid-call
+        let mut exiting = Ducker {
+            ducked: ducker.ducked.clone(),
+        };
+        assert_eq!(exiting.restore(), [(music, 0.8)]);
+        assert!(exiting.restore().is_empty());
+
+        let after = [(music, Some("Music"), Some(0.4))];
+        let fades = ducker.plan(&ducking, &after);
+        assert_eq!(fades.len(), 1);
+        assert_eq!((fades[0].0, fades[0].1), (music, 0.4));
+        assert_eq!(fades[0].2.volume, 0.8);
+        assert!(ducker.plan(&ducking, &after).is_empty());
+    }
+}
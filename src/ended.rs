@@ -0,0 +1,169 @@
+//! Keeping streams in the view for a while after they end, configured by
+//! `keep_ended_for`, so that short-lived streams such as event sounds can be
+//! seen long enough to tell which application played them.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::command::Command;
+use crate::notification_group::GROUP_ID;
+use crate::object::ObjectId;
+use crate::view::{self, View};
+
+/// Tracks the streams which have ended recently.
+#[derive(Default, Debug)]
+pub struct EndedStreams {
+    /// Each ended stream as it was last seen, and when it ended
+    ended: HashMap<ObjectId, (view::Node, Instant)>,
+}
+
+impl EndedStreams {
+    /// Note the streams in `previous` which are missing from a newly built
+    /// `view`, and add back those which ended less than `keep_for` ago,
+    /// marked as ended.
+    pub fn update(
+        &mut self,
+        previous: &View,
+        view: &mut View,
+        keep_for: Duration,
+        now: Instant,
+    ) {
+        for node in previous.nodes.values() {
+            let is_stream = node.media_class.is_sink_input()
+                || node.media_class.is_source_output();
+            // The notification group's row isn't a stream which can end
+            if !is_stream
+                || node.ended
+                || node.id == GROUP_ID
+                || view.nodes.contains_key(&node.id)
+            {
+                continue;
+            }
+            let mut node = node.clone();
+            node.ended = true;
+            node.paused = false;
+            node.recording = false;
+            node.peaks = None;
+            node.routes = None;
+            node.target = None;
+            node.target_title = String::from("Ended");
+            self.ended.insert(node.id, (node, now));
+        }
+        self.ended.retain(|node_id, (_, since)| {
+            !view.nodes.contains_key(node_id)
+                && now.saturating_duration_since(*since) < keep_for
+        });
+
+        for (node, _) in self.ended.values() {
+            let list = if node.media_class.is_sink_input() {
+                &mut view.nodes_playback
+            } else {
+                &mut view.nodes_recording
+            };
+            list.push(node.id);
+            view.nodes_all.push(node.id);
+            view.nodes.insert(node.id, node.clone());
+        }
+        let nodes = &view.nodes;
+        let serial = |node_id: &ObjectId| nodes[node_id].object_serial;
+        for list in [
+            &mut view.nodes_playback,
+            &mut view.nodes_recording,
+            &mut view.nodes_all,
+        ] {
+            list.sort_by_key(serial);
+        }
+    }
+
+    /// How long until an ended stream should be removed, which is zero if
+    /// one is overdue.
+    pub fn until_next_expiry(
+        &self,
+        keep_for: Duration,
+        now: Instant,
+    ) -> Option<Duration> {
+        self.ended
+            .values()
+            .map(|(_, since)| {
+                keep_for.saturating_sub(now.saturating_duration_since(*since))
+            })
+            .min()
+    }
+
+    /// Whether `command` is for a stream which has ended, so there's nothing
+    /// to send it to.
+    pub fn is_for_ended(&self, command: &Command) -> bool {
+        let node_id = match *command {
+            Command::NodeMute(node_id, _)
+            | Command::NodeVolumes(node_id, _)
+            | Command::NodeChannelMap(node_id, ..)
+            | Command::NodeUpmix(node_id, _) => node_id,
+            Command::MetadataSetProperty(_, subject, ..) => {
+                ObjectId::from_raw_id(subject)
+            }
+            _ => return false,
+        };
+        self.ended.contains_key(&node_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capture_manager::CaptureManager;
+    use crate::config::Names;
+    use crate::event::MonitorEvent;
+    use crate::media_class::MediaClass;
+    use crate::state::State;
+
+    fn add_stream(state: &mut State, id: u32) {
+        let id = ObjectId::from_raw_id(id);
+        for event in [
+            MonitorEvent::NodeName(id, format!("stream{}", u32::from(id))),
+            MonitorEvent::NodeDescription(id, String::from("Bell")),
+            MonitorEvent::NodeObjectSerial(id, u32::from(id) as i32),
+            MonitorEvent::NodeMediaClass(
+                id,
+                MediaClass::from("Stream/Output/Audio"),
+            ),
+            MonitorEvent::NodePositions(id, vec![0, 1]),
+            MonitorEvent::NodeVolumes(id, vec![1.0, 1.0]),
+            MonitorEvent::NodeMute(id, false),
+        ] {
+            state.update(&mut CaptureManager::default(), event);
+        }
+    }
+
+    #[test]
+    fn keep_ended_streams_for_a_while() {
+        let keep_for = Duration::from_secs(10);
+        let now = Instant::now();
+        let mut state = State::default();
+        add_stream(&mut state, 1);
+        add_stream(&mut state, 2);
+        let previous = View::from(&state, &Names::default());
+
+        let mut ended = EndedStreams::default();
+        state.update(
+            &mut CaptureManager::default(),
+            MonitorEvent::Removed(ObjectId::from_raw_id(1)),
+        );
+        let mut view = View::from(&state, &Names::default());
+        ended.update(&previous, &mut view, keep_for, now);
+        let first = ObjectId::from_raw_id(1);
+        assert_eq!(view.nodes_playback, [first, ObjectId::from_raw_id(2)]);
+        assert!(view.nodes[&first].ended);
+        assert!(ended.is_for_ended(&Command::NodeMute(first, true)));
+        assert_eq!(
+            ended.until_next_expiry(keep_for, now + Duration::from_secs(4)),
+            Some(Duration::from_secs(6))
+        );
+
+        let previous = view;
+        let mut view = View::from(&state, &Names::default());
+        let later = now + keep_for;
+        ended.update(&previous, &mut view, keep_for, later);
+        assert_eq!(view.nodes_playback, [ObjectId::from_raw_id(2)]);
+        assert_eq!(ended.until_next_expiry(keep_for, later), None);
+    }
+}
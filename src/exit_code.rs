@@ -0,0 +1,54 @@
+//! Exit codes, so that scripts can tell failures apart.
+//!
+//! Errors which should exit with something other than [`FAILURE`] are wrapped
+//! in [`Error`] and found again with [`of()`].
+
+use crate::error;
+
+/// Everything worked.
+pub const SUCCESS: i32 = 0;
+/// Any failure without a more specific code, including usage errors and
+/// problems with the configuration file.
+pub const FAILURE: i32 = 1;
+/// No object matches a selector given on the command line.
+pub const NOT_FOUND: i32 = 2;
+/// PipeWire, or the fallback backend, couldn't be reached.
+pub const UNREACHABLE: i32 = 3;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Nothing matches \"{0}\"")]
+    NotFound(String),
+    #[error("Failed to connect to the audio server: {0}")]
+    Unreachable(String),
+}
+
+/// The exit code for an error.
+pub fn of(err: &anyhow::Error) -> i32 {
+    if let Some(error::Error::Connect(_)) = err.downcast_ref() {
+        return UNREACHABLE;
+    }
+    match err.downcast_ref::<Error>() {
+        Some(Error::NotFound(_)) => NOT_FOUND,
+        Some(Error::Unreachable(_)) => UNREACHABLE,
+        None => FAILURE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_survive_context() {
+        use anyhow::Context;
+
+        let err = Err::<(), _>(Error::Unreachable(String::from("refused")))
+            .context("Listing nodes")
+            .unwrap_err();
+        assert_eq!(of(&err), UNREACHABLE);
+        assert_eq!(of(&anyhow::anyhow!("Bad config")), FAILURE);
+        let err = anyhow::Error::from(error::Error::Connect(String::new()));
+        assert_eq!(of(&err), UNREACHABLE);
+    }
+}
@@ -0,0 +1,143 @@
+//! Exporting the contents of a tab as plain text or Markdown, for sharing a
+//! setup in bug reports.
+
+use serde::{Deserialize, Serialize};
+use unicode_width::UnicodeWidthStr;
+
+use crate::view::{ListKind, View};
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// Columns aligned with spaces
+    Text,
+    /// A Markdown table
+    Markdown,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Text => "txt",
+            ExportFormat::Markdown => "md",
+        }
+    }
+}
+
+/// The objects in a list with their volumes and mute states, one row per
+/// object.
+pub fn export(
+    view: &View,
+    list_kind: ListKind,
+    format: ExportFormat,
+) -> String {
+    let yes = |yes: bool| String::from(if yes { "yes" } else { "" });
+
+    match list_kind {
+        ListKind::Node(node_kind) => {
+            let rows = view.full_nodes(node_kind).into_iter().map(|node| {
+                let volume = node
+                    .volume_percent()
+                    .map_or(String::new(), |percent| format!("{}%", percent));
+                vec![
+                    node.title.clone(),
+                    node.target_title.clone(),
+                    volume,
+                    yes(node.mute),
+                    yes(node.is_default_sink || node.is_default_source),
+                ]
+            });
+            table(
+                &["Name", "Target", "Volume", "Muted", "Default"],
+                rows.collect(),
+                format,
+            )
+        }
+        ListKind::Device => {
+            let rows = view.full_devices().into_iter().map(|device| {
+                vec![device.title.clone(), device.target_title.clone()]
+            });
+            table(&["Name", "Profile"], rows.collect(), format)
+        }
+    }
+}
+
+/// Format rows as a table with aligned columns.
+fn table(
+    header: &[&str],
+    rows: Vec<Vec<String>>,
+    format: ExportFormat,
+) -> String {
+    let mut rows: Vec<Vec<String>> = std::iter::once(
+        header.iter().map(|&cell| String::from(cell)).collect(),
+    )
+    .chain(rows)
+    .collect();
+    if format == ExportFormat::Markdown {
+        for cell in rows.iter_mut().flatten() {
+            *cell = cell.replace('|', "\\|");
+        }
+    }
+
+    let min_width = match format {
+        ExportFormat::Text => 0,
+        // The shortest delimiter row cell
+        ExportFormat::Markdown => 3,
+    };
+    let widths: Vec<usize> = (0..header.len())
+        .map(|column| {
+            rows.iter()
+                .map(|row| row[column].width())
+                .fold(min_width, usize::max)
+        })
+        .collect();
+    if format == ExportFormat::Markdown {
+        rows.insert(1, widths.iter().map(|&width| "-".repeat(width)).collect());
+    }
+
+    rows.iter()
+        .map(|row| {
+            let cells: Vec<String> = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, &width)| {
+                    format!("{}{}", cell, " ".repeat(width - cell.width()))
+                })
+                .collect();
+            let line = match format {
+                ExportFormat::Text => cells.join("  "),
+                ExportFormat::Markdown => format!("| {} |", cells.join(" | ")),
+            };
+            String::from(line.trim_end()) + "\n"
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aligned_tables() {
+        let rows = || {
+            vec![
+                vec![String::from("Firefox"), String::from("50%")],
+                vec![String::from("a|b"), String::from("100%")],
+            ]
+        };
+
+        assert_eq!(
+            table(&["Name", "Volume"], rows(), ExportFormat::Text),
+            "Name     Volume\n\
+             Firefox  50%\n\
+             a|b      100%\n"
+        );
+        assert_eq!(
+            table(&["Name", "Volume"], rows(), ExportFormat::Markdown),
+            "| Name    | Volume |\n\
+             | ------- | ------ |\n\
+             | Firefox | 50%    |\n\
+             | a\\|b    | 100%   |\n"
+        );
+    }
+}
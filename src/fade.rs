@@ -0,0 +1,170 @@
+//! Ramping volumes to a target over time for the
+//! [`Fade`](`crate::app::Action::Fade`) action.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::object::ObjectId;
+
+/// How often the volume of a fading node is updated.
+pub const FADE_STEP: Duration = Duration::from_millis(20);
+
+/// A fade of the selected nodes to `volume`, on the same scale as
+/// [`SetAbsoluteVolume`](`crate::app::Action::SetAbsoluteVolume`).
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Fade {
+    pub volume: f32,
+    #[serde(default = "Fade::default_duration_ms")]
+    pub duration_ms: u32,
+    #[serde(default)]
+    pub curve: Curve,
+}
+
+impl Fade {
+    fn default_duration_ms() -> u32 {
+        1000
+    }
+}
+
+/// The shape of a fade over time.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Curve {
+    /// Change at a constant rate
+    #[default]
+    Linear,
+    /// Start slowly and speed up
+    EaseIn,
+    /// Start quickly and slow down
+    EaseOut,
+    /// Start and end slowly
+    EaseInOut,
+}
+
+impl Curve {
+    /// The fraction of the change made at `t`, the fraction of the duration
+    /// which has passed.
+    fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Curve::Linear => t,
+            Curve::EaseIn => t * t,
+            Curve::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Curve::EaseInOut => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Ramp {
+    from: f32,
+    to: f32,
+    start: Instant,
+    duration: Duration,
+    curve: Curve,
+}
+
+/// The fades in progress, at most one per node.
+#[derive(Default, Debug)]
+pub struct Fades {
+    ramps: HashMap<ObjectId, Ramp>,
+}
+
+impl Fades {
+    /// Start fading a node from the volume `from`, replacing any fade already
+    /// in progress for it.
+    pub fn start(
+        &mut self,
+        node_id: ObjectId,
+        from: f32,
+        fade: Fade,
+        now: Instant,
+    ) {
+        let ramp = Ramp {
+            from,
+            to: fade.volume,
+            start: now,
+            duration: Duration::from_millis(fade.duration_ms.into()),
+            curve: fade.curve,
+        };
+        self.ramps.insert(node_id, ramp);
+    }
+
+    /// Stop fading a node, such as when its volume is changed by hand.
+    pub fn cancel(&mut self, node_id: ObjectId) {
+        self.ramps.remove(&node_id);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ramps.is_empty()
+    }
+
+    pub fn is_fading(&self, node_id: ObjectId) -> bool {
+        self.ramps.contains_key(&node_id)
+    }
+
+    /// The volume each fading node should have now. Fades which have reached
+    /// their target are finished.
+    pub fn step(&mut self, now: Instant) -> Vec<(ObjectId, f32)> {
+        let mut volumes = Vec::new();
+        self.ramps.retain(|&node_id, ramp| {
+            let elapsed = now.saturating_duration_since(ramp.start);
+            let t = if ramp.duration.is_zero() {
+                1.0
+            } else {
+                elapsed.as_secs_f32() / ramp.duration.as_secs_f32()
+            };
+            let volume =
+                ramp.from + (ramp.to - ramp.from) * ramp.curve.apply(t);
+            volumes.push((node_id, volume));
+            t < 1.0
+        });
+        volumes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ramp_to_target() {
+        let node_id = ObjectId::from_raw_id(1);
+        let fade = Fade {
+            volume: 0.0,
+            duration_ms: 1000,
+            curve: Curve::Linear,
+        };
+        let mut fades = Fades::default();
+        let now = Instant::now();
+        fades.start(node_id, 1.0, fade, now);
+
+        assert_eq!(fades.step(now), vec![(node_id, 1.0)]);
+        let half = now + Duration::from_millis(500);
+        assert_eq!(fades.step(half), vec![(node_id, 0.5)]);
+        assert!(!fades.is_empty());
+
+        let end = now + Duration::from_millis(1500);
+        assert_eq!(fades.step(end), vec![(node_id, 0.0)]);
+        assert!(fades.is_empty());
+    }
+
+    #[test]
+    fn curves() {
+        for curve in [
+            Curve::Linear,
+            Curve::EaseIn,
+            Curve::EaseOut,
+            Curve::EaseInOut,
+        ] {
+            assert_eq!(curve.apply(0.0), 0.0);
+            assert_eq!(curve.apply(1.0), 1.0);
+        }
+        assert!(Curve::EaseIn.apply(0.5) < 0.5);
+        assert!(Curve::EaseOut.apply(0.5) > 0.5);
+        assert_eq!(Curve::EaseInOut.apply(0.5), 0.5);
+    }
+}
@@ -0,0 +1,375 @@
+//! A Ratatui widget representing a single PipeWire node as a vertical fader in
+//! an object list.
+
+use ratatui::{
+    prelude::{Alignment, Buffer, Constraint, Direction, Layout, Rect},
+    text::{Line, Span},
+    widgets::{StatefulWidget, Widget},
+};
+
+use crossterm::event::{MouseButton, MouseEventKind};
+use smallvec::smallvec;
+
+use crate::app::{Action, MouseArea};
+use crate::braille::Fill;
+use crate::config::{Config, Peaks};
+use crate::device_kind::DeviceKind;
+use crate::meter;
+use crate::node_widget;
+use crate::object_list::ObjectList;
+use crate::view;
+
+pub struct FaderWidget<'a> {
+    node: &'a view::Node,
+    selected: bool,
+    device_kind: Option<DeviceKind>,
+    compact: bool,
+    marquee: Option<usize>,
+    config: &'a Config,
+}
+
+impl<'a> FaderWidget<'a> {
+    pub fn new(
+        node: &'a view::Node,
+        selected: bool,
+        device_kind: Option<DeviceKind>,
+        config: &'a Config,
+    ) -> Self {
+        Self {
+            node,
+            selected,
+            device_kind,
+            compact: false,
+            marquee: None,
+            config,
+        }
+    }
+
+    /// Render the fader without meters.
+    pub fn compact(mut self, compact: bool) -> Self {
+        self.compact = compact;
+        self
+    }
+
+    /// Scroll the title by this many characters if it doesn't fit.
+    pub fn marquee(mut self, offset: Option<usize>) -> Self {
+        self.marquee = offset;
+        self
+    }
+
+    /// Width of a full fader display.
+    pub fn width(compact: bool) -> u16 {
+        if compact {
+            7
+        } else {
+            11
+        }
+    }
+
+    /// Spacing between faders
+    pub fn spacing(_compact: bool) -> u16 {
+        1
+    }
+
+    /// Area for the target dropdown
+    pub fn dropdown_area(
+        object_list: &ObjectList,
+        list_area: &Rect,
+        object_area: &Rect,
+    ) -> Rect {
+        // Number of items to show at once
+        let max_visible_items = 5;
+
+        let max_target_length = object_list
+            .targets
+            .iter()
+            .map(|(_, title)| title.len())
+            .max()
+            .unwrap_or(0);
+
+        // Add 2 for vertical borders and 2 for highlight symbol
+        let width = max_target_length.saturating_add(4) as u16;
+        let height = std::cmp::min(max_visible_items, object_list.targets.len())
+            .saturating_add(2) as u16; // Plus 2 for horizontal borders
+
+        // Start at the fader but keep within the list area
+        let x = object_area
+            .left()
+            .min(list_area.right().saturating_sub(width));
+        // Just below the volume label
+        let y = object_area.top().saturating_add(1);
+
+        Rect::new(x, y, width, height)
+    }
+}
+
+/// How the volume faders of a node are laid out.
+#[derive(Debug, PartialEq)]
+struct Faders {
+    /// Number of faders, which is 1 for the mean volume
+    count: u16,
+    width: u16,
+    spacing: u16,
+}
+
+impl Faders {
+    /// A fader for each of `channels` channels if they fit in `width`
+    /// columns, spaced apart if there's room. Otherwise, or for a single
+    /// channel, one wider fader for the mean volume.
+    fn new(channels: usize, width: u16) -> Self {
+        let mean = Self {
+            count: u16::from(channels > 0),
+            width: 2,
+            spacing: 0,
+        };
+        let Ok(count) = u16::try_from(channels) else {
+            return mean;
+        };
+        if count <= 1 {
+            mean
+        } else if count.saturating_mul(2).saturating_sub(1) <= width {
+            Self {
+                count,
+                width: 1,
+                spacing: 1,
+            }
+        } else if count <= width {
+            Self {
+                count,
+                width: 1,
+                spacing: 0,
+            }
+        } else {
+            mean
+        }
+    }
+
+    /// Width of all of the faders together.
+    fn total_width(&self) -> u16 {
+        let spacing = self.count.saturating_sub(1) * self.spacing;
+        self.count * self.width + spacing
+    }
+}
+
+impl StatefulWidget for FaderWidget<'_> {
+    type State = Vec<MouseArea>;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let mouse_areas = state;
+
+        mouse_areas.push((
+            area,
+            smallvec![MouseEventKind::Down(MouseButton::Left)],
+            smallvec![Action::SelectObject(self.node.id)],
+        ));
+
+        mouse_areas.push((
+            area,
+            smallvec![MouseEventKind::Down(MouseButton::Right)],
+            smallvec![Action::SelectObject(self.node.id), Action::SetDefault],
+        ));
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1), // volume_label
+                Constraint::Min(0),    // bar_area
+                Constraint::Length(1), // title_area
+                Constraint::Length(1), // selected_area
+            ])
+            .split(area);
+        let volume_label = layout[0];
+        let bar_area = layout[1];
+        let title_area = layout[2];
+        let selected_area = layout[3];
+
+        if self.selected {
+            Line::from(Span::styled(
+                self.config
+                    .char_set
+                    .selector_middle
+                    .repeat(selected_area.width as usize),
+                self.config.theme.selector,
+            ))
+            .render(selected_area, buf);
+        }
+
+        let default_span =
+            if node_widget::is_default(self.node, self.device_kind) {
+                Span::styled(
+                    &self.config.char_set.default_device,
+                    self.config.theme.default_device,
+                )
+            } else if node_widget::is_loud(self.node, self.config) {
+                Span::styled(
+                    &self.config.char_set.loud_warning,
+                    self.config.theme.loud_warning,
+                )
+            } else {
+                Span::from("")
+            };
+        let node_title = node_widget::fit_title(
+            node_widget::node_title(self.node, self.device_kind),
+            (title_area.width.saturating_sub(default_span.width() as u16))
+                as usize,
+            self.marquee,
+        );
+        Line::from(vec![
+            default_span,
+            Span::styled(node_title, self.config.theme.node_title),
+        ])
+        .alignment(Alignment::Center)
+        .render(title_area, buf);
+
+        let show_meter = !self.compact && self.config.peaks != Peaks::Off;
+        // The meter and the column before it
+        let meter_width = if show_meter { 3 } else { 0 };
+        let faders = Faders::new(
+            self.node.volumes.len(),
+            bar_area.width.saturating_sub(meter_width),
+        );
+        let constraints = if show_meter {
+            vec![
+                Constraint::Fill(1),                      // _padding
+                Constraint::Length(faders.total_width()), // volume_bars
+                Constraint::Length(1),                    // _padding
+                Constraint::Length(2),                    // meter_area
+                Constraint::Fill(1),                      // _padding
+            ]
+        } else {
+            vec![
+                Constraint::Fill(1),                      // _padding
+                Constraint::Length(faders.total_width()), // volume_bars
+                Constraint::Fill(1),                      // _padding
+            ]
+        };
+        let layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(constraints)
+            .split(bar_area);
+        let volume_bars = layout[1];
+        let meter_area = show_meter.then(|| layout[3]);
+
+        if let Some(percent) = self.node.volume_percent() {
+            Line::from(Span::styled(
+                format!("{}%", percent),
+                self.config.theme.volume,
+            ))
+            .alignment(Alignment::Center)
+            .render(volume_label, buf);
+        }
+
+        // A fader for each channel, or for the mean volume if they don't fit
+        let volumes = match faders.count {
+            0 => Vec::new(),
+            1 => self.node.volume().into_iter().collect(),
+            _ => self
+                .node
+                .volumes
+                .iter()
+                .map(|volume| volume.cbrt())
+                .collect(),
+        };
+        let volume_bars = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(vec![Constraint::Length(faders.width); volumes.len()])
+            .spacing(faders.spacing)
+            .split(volume_bars);
+        for (volume, volume_bar) in volumes.into_iter().zip(volume_bars.iter())
+        {
+            let cells = node_widget::volume_cells(
+                volume,
+                volume_bar.height as usize,
+                Fill::BottomToTop,
+                &self.config.char_set.volume_vertical_filled,
+                &self.config.char_set.volume_vertical_empty,
+                self.config,
+            );
+
+            // Rows are rendered from the top
+            for ((symbol, style), row) in
+                cells.into_iter().rev().zip(volume_bar.rows())
+            {
+                Line::from(Span::styled(
+                    symbol.repeat(row.width as usize),
+                    style,
+                ))
+                .render(row, buf);
+            }
+        }
+        if self.node.mute {
+            Line::from("muted")
+                .alignment(Alignment::Center)
+                .render(volume_label, buf);
+        }
+
+        mouse_areas.push((
+            volume_label,
+            smallvec![MouseEventKind::Down(MouseButton::Left)],
+            smallvec![Action::SelectObject(self.node.id), Action::ToggleMute],
+        ));
+
+        // Add mouse areas for setting volume
+        for (i, row) in bar_area.rows().enumerate() {
+            let volume_step = 1.5 / bar_area.height as f32;
+            // The top row is the highest volume
+            let volume = volume_step * (bar_area.height as usize - i) as f32;
+            // Make the volume sticky around 100%. Otherwise it's often not
+            // possible to select by mouse.
+            let sticky_volume = if (1.0 - volume).abs() <= volume_step {
+                1.0
+            } else {
+                volume
+            };
+
+            mouse_areas.push((
+                row,
+                smallvec![
+                    MouseEventKind::Down(MouseButton::Left),
+                    MouseEventKind::Drag(MouseButton::Left),
+                ],
+                smallvec![
+                    Action::SelectObject(self.node.id),
+                    Action::SetAbsoluteVolume(sticky_volume),
+                ],
+            ));
+        }
+
+        // Render peaks
+        if let Some(meter_area) = meter_area {
+            let peaks: Vec<Option<f32>> = match self.node.peaks.as_deref() {
+                Some([left, right]) if self.config.peaks != Peaks::Mono => {
+                    vec![Some(*left), Some(*right)]
+                }
+                Some(peaks @ [_, ..]) => {
+                    vec![Some(peaks.iter().sum::<f32>() / peaks.len() as f32)]
+                }
+                _ => vec![None],
+            };
+            meter::render_vertical(meter_area, buf, &peaks, self.config);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn faders_per_channel() {
+        let faders = |count, width, spacing| Faders {
+            count,
+            width,
+            spacing,
+        };
+        assert_eq!(Faders::new(0, 8), faders(0, 2, 0));
+        assert_eq!(Faders::new(1, 8), faders(1, 2, 0));
+        assert_eq!(Faders::new(2, 8), faders(2, 1, 1));
+        assert_eq!(Faders::new(2, 8).total_width(), 3);
+        // 5.1 doesn't fit with spacing, 7.1 only just fits without
+        assert_eq!(Faders::new(6, 8), faders(6, 1, 0));
+        assert_eq!(Faders::new(8, 8), faders(8, 1, 0));
+        // Too many channels to show, so the mean is shown instead
+        assert_eq!(Faders::new(16, 8), faders(1, 2, 0));
+    }
+}
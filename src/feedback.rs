@@ -0,0 +1,39 @@
+//! A short tone played on an output device after its volume is changed, when
+//! `volume_feedback` is enabled.
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::tone;
+
+/// The least time between tones, so holding a key doesn't play a buzz.
+const FEEDBACK_INTERVAL: Duration = Duration::from_millis(150);
+
+/// Plays tones, limited to one per `FEEDBACK_INTERVAL`.
+#[derive(Default, Debug)]
+pub struct Feedback {
+    last: Option<Instant>,
+}
+
+impl Feedback {
+    /// Play the tone on the output device with `object_serial`, unless one
+    /// was played too recently.
+    pub fn play(
+        &mut self,
+        object_serial: i32,
+        now: Instant,
+    ) -> Result<(), anyhow::Error> {
+        if self
+            .last
+            .is_some_and(|last| now.duration_since(last) < FEEDBACK_INTERVAL)
+        {
+            return Ok(());
+        }
+        self.last = Some(now);
+
+        let wav = tone::wav(1, &tone::sine(880.0, 60, 0.3));
+        let mut child = tone::play(object_serial, None, wav)?;
+        thread::spawn(move || child.wait());
+        Ok(())
+    }
+}
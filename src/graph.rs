@@ -0,0 +1,261 @@
+//! Writing the nodes and the links between them as a Graphviz DOT digraph or
+//! as JSON, for `wiremix graph` and the `ExportGraph` action, so routing can
+//! be drawn outside the terminal.
+//!
+//! Links between the ports of the same two nodes are drawn as one edge with
+//! the number of links, since wiremix doesn't track ports.
+
+use std::collections::BTreeMap;
+use std::sync::mpsc;
+
+use anyhow::bail;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::config::Config;
+use crate::event::Event;
+use crate::list;
+use crate::object::ObjectId;
+use crate::state::State;
+use crate::view::View;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum GraphFormat {
+    /// A Graphviz digraph
+    Dot,
+    Json,
+}
+
+impl GraphFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            GraphFormat::Dot => "dot",
+            GraphFormat::Json => "json",
+        }
+    }
+}
+
+/// Wait for the initial objects from the monitor and print the graph as DOT,
+/// or as JSON if `json` is set.
+pub fn run(
+    event_rx: mpsc::Receiver<Event>,
+    config: &Config,
+    dot: bool,
+    json: bool,
+) -> Result<(), anyhow::Error> {
+    if dot && json {
+        bail!("--dot can't be used with --json");
+    }
+    let format = if json {
+        GraphFormat::Json
+    } else {
+        GraphFormat::Dot
+    };
+    let state = list::initial_state(&event_rx)?;
+    let view = View::from(&state, &config.names);
+    print!("{}", graph(&state, &view, format));
+    Ok(())
+}
+
+/// A node in the graph, with the title shown in the lists if it's shown.
+struct GraphNode<'a> {
+    id: ObjectId,
+    title: String,
+    media_class: &'a str,
+    device: Option<ObjectId>,
+}
+
+/// The graph of the nodes and links in `state`, titled as in `view`.
+pub fn graph(state: &State, view: &View, format: GraphFormat) -> String {
+    let mut nodes: Vec<GraphNode> = state
+        .nodes
+        .values()
+        .map(|node| GraphNode {
+            id: node.id,
+            title: view
+                .nodes
+                .get(&node.id)
+                .map(|view_node| view_node.title.clone())
+                .or_else(|| node.description.clone())
+                .or_else(|| node.nick.clone())
+                .or_else(|| node.name.clone())
+                .unwrap_or_else(|| node.id.to_string()),
+            media_class: node
+                .media_class
+                .as_ref()
+                .map_or("", |media_class| media_class.as_str()),
+            device: node.device_id,
+        })
+        .collect();
+    nodes.sort_by_key(|node| u32::from(node.id));
+
+    // The number of links between each pair of nodes
+    let mut links: BTreeMap<(u32, u32), usize> = BTreeMap::new();
+    for link in state.links.values() {
+        if state.nodes.contains_key(&link.output)
+            && state.nodes.contains_key(&link.input)
+        {
+            let key = (u32::from(link.output), u32::from(link.input));
+            *links.entry(key).or_default() += 1;
+        }
+    }
+
+    let device_title = |device_id: &ObjectId| {
+        view.devices
+            .get(device_id)
+            .map(|device| device.title.clone())
+            .or_else(|| {
+                let device = state.devices.get(device_id)?;
+                device.description.clone().or_else(|| device.name.clone())
+            })
+            .unwrap_or_else(|| device_id.to_string())
+    };
+
+    match format {
+        GraphFormat::Dot => dot(&nodes, &links, device_title),
+        GraphFormat::Json => {
+            let json_nodes = nodes.iter().map(|node| {
+                json!({
+                    "id": u32::from(node.id),
+                    "title": node.title,
+                    "media_class": node.media_class,
+                    "device": node.device.map(u32::from),
+                })
+            });
+            let mut device_ids: Vec<_> =
+                nodes.iter().filter_map(|node| node.device).collect();
+            device_ids.sort_by_key(|device_id| u32::from(*device_id));
+            device_ids.dedup();
+            let devices = device_ids.iter().map(|device_id| {
+                json!({
+                    "id": u32::from(*device_id),
+                    "title": device_title(device_id),
+                })
+            });
+            let links = links.iter().map(|((output, input), count)| {
+                json!({ "output": output, "input": input, "count": count })
+            });
+            format!(
+                "{:#}\n",
+                json!({
+                    "nodes": serde_json::Value::from_iter(json_nodes),
+                    "devices": serde_json::Value::from_iter(devices),
+                    "links": serde_json::Value::from_iter(links),
+                })
+            )
+        }
+    }
+}
+
+/// A digraph with the nodes of each device grouped in a cluster.
+fn dot(
+    nodes: &[GraphNode],
+    links: &BTreeMap<(u32, u32), usize>,
+    device_title: impl Fn(&ObjectId) -> String,
+) -> String {
+    let node_line = |node: &GraphNode| {
+        let label = if node.media_class.is_empty() {
+            quote(&node.title)
+        } else {
+            quote(&format!("{}\n{}", node.title, node.media_class))
+        };
+        format!("  n{} [label={}];\n", u32::from(node.id), label)
+    };
+
+    let mut dot = String::from("digraph wiremix {\n");
+    dot.push_str("  rankdir=LR;\n  node [shape=box];\n");
+
+    let mut clusters: BTreeMap<u32, Vec<&GraphNode>> = BTreeMap::new();
+    for node in nodes {
+        match node.device {
+            Some(device_id) => {
+                clusters.entry(u32::from(device_id)).or_default().push(node)
+            }
+            None => dot.push_str(&node_line(node)),
+        }
+    }
+    for (device_id, device_nodes) in clusters {
+        let title = device_title(&ObjectId::from_raw_id(device_id));
+        dot.push_str(&format!("  subgraph cluster_{} {{\n", device_id));
+        dot.push_str(&format!("    label={};\n", quote(&title)));
+        for node in device_nodes {
+            dot.push_str(&format!("  {}", node_line(node)));
+        }
+        dot.push_str("  }\n");
+    }
+
+    for ((output, input), count) in links {
+        if *count > 1 {
+            dot.push_str(&format!(
+                "  n{} -> n{} [label=\"{}\"];\n",
+                output, input, count
+            ));
+        } else {
+            dot.push_str(&format!("  n{} -> n{};\n", output, input));
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+/// A DOT string literal.
+fn quote(text: &str) -> String {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("\"{}\"", escaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state;
+
+    #[test]
+    fn dot_groups_devices_and_counts_links() {
+        let mut state = State::default();
+        let sink = ObjectId::from_raw_id(40);
+        let stream = ObjectId::from_raw_id(50);
+        let device = ObjectId::from_raw_id(30);
+        for (id, name, device_id) in [
+            (sink, "Speakers \"HDMI\"", Some(device)),
+            (stream, "Firefox", None),
+        ] {
+            state.nodes.insert(
+                id,
+                state::Node {
+                    id,
+                    description: Some(String::from(name)),
+                    device_id,
+                    ..Default::default()
+                },
+            );
+        }
+        for link_id in [60, 61] {
+            state.links.insert(
+                ObjectId::from_raw_id(link_id),
+                state::Link {
+                    output: stream,
+                    input: sink,
+                },
+            );
+        }
+
+        let dot = graph(&state, &View::default(), GraphFormat::Dot);
+        assert!(dot.contains("  n50 [label=\"Firefox\"];\n"));
+        assert!(dot.contains("subgraph cluster_30 {\n    label=\"30\";\n"));
+        assert!(dot.contains("[label=\"Speakers \\\"HDMI\\\"\"]"));
+        assert!(dot.contains("  n50 -> n40 [label=\"2\"];\n"));
+
+        let json: serde_json::Value = serde_json::from_str(&graph(
+            &state,
+            &View::default(),
+            GraphFormat::Json,
+        ))
+        .unwrap();
+        assert_eq!(json["links"][0]["count"], 2);
+        assert_eq!(json["nodes"][0]["device"], 30);
+    }
+}
@@ -0,0 +1,110 @@
+//! The configuration reference shown over the tabs by the `Help` action or
+//! `:help config` in vi mode. It's made from
+//! [`Config::reference()`](`crate::config::Config::reference`), so it
+//! documents the options this build of wiremix accepts.
+
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::config::{Config, Section};
+
+/// The reference being read.
+#[derive(Debug)]
+pub struct Help {
+    pub lines: Vec<String>,
+    /// The first line shown
+    pub offset: usize,
+    /// How many lines fit, set when the help is drawn
+    pub height: usize,
+}
+
+impl Default for Help {
+    fn default() -> Self {
+        Self {
+            lines: lines(&Config::reference()),
+            offset: 0,
+            height: 0,
+        }
+    }
+}
+
+impl Help {
+    /// Scroll for a key, returning false if it closes the help.
+    pub fn key(&mut self, key: KeyEvent) -> bool {
+        let page = self.height.max(1);
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => {
+                self.scroll_to(self.offset + 1)
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                self.scroll_to(self.offset.saturating_sub(1))
+            }
+            KeyCode::PageDown | KeyCode::Char(' ') => {
+                self.scroll_to(self.offset + page)
+            }
+            KeyCode::PageUp => self.scroll_to(self.offset.saturating_sub(page)),
+            KeyCode::Char('g') | KeyCode::Home => self.scroll_to(0),
+            KeyCode::Char('G') | KeyCode::End => self.scroll_to(usize::MAX),
+            KeyCode::Char('q' | '?') | KeyCode::Esc => return false,
+            _ => {}
+        }
+        true
+    }
+
+    fn scroll_to(&mut self, offset: usize) {
+        let last = self.lines.len().saturating_sub(self.height);
+        self.offset = offset.min(last);
+    }
+}
+
+/// The reference as lines of text.
+fn lines(sections: &[Section]) -> Vec<String> {
+    let mut lines = Vec::new();
+    for section in sections {
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+        lines.push(section.title.to_uppercase());
+        for entry in &section.entries {
+            let mut line = format!("  {}", entry.name);
+            if let Some(default) = &entry.default {
+                line.push_str(&format!(" = {}", default));
+            }
+            if !entry.kind.is_empty() {
+                line.push_str(&format!("  ({})", entry.kind));
+            }
+            lines.push(line);
+            lines.extend(
+                entry
+                    .description
+                    .iter()
+                    .map(|description| format!("      {}", description)),
+            );
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    #[test]
+    fn scroll_within_reference() {
+        let mut help = Help {
+            height: 10,
+            ..Default::default()
+        };
+        assert_eq!(help.lines[0], "OPTIONS");
+        assert!(help.lines.iter().any(|line| line == "ACTIONS"));
+
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        assert!(help.key(key(KeyCode::End)));
+        assert_eq!(help.offset, help.lines.len() - 10);
+        assert!(help.key(key(KeyCode::Char('j'))));
+        assert_eq!(help.offset, help.lines.len() - 10);
+        assert!(help.key(key(KeyCode::PageUp)));
+        assert_eq!(help.offset, help.lines.len() - 20);
+        assert!(!help.key(key(KeyCode::Esc)));
+    }
+}
@@ -0,0 +1,53 @@
+//! A Ratatui widget for the configuration reference, drawn as a box over
+//! most of the screen.
+
+use ratatui::{
+    layout::Margin,
+    prelude::{Buffer, Rect, StatefulWidget, Widget},
+    text::Line,
+    widgets::{Block, Borders, Clear},
+};
+
+use crate::config::Config;
+use crate::help::Help;
+
+pub struct HelpWidget<'a> {
+    config: &'a Config,
+}
+
+impl<'a> HelpWidget<'a> {
+    pub fn new(config: &'a Config) -> Self {
+        Self { config }
+    }
+}
+
+impl StatefulWidget for HelpWidget<'_> {
+    type State = Help;
+
+    fn render(self, area: Rect, buf: &mut Buffer, help: &mut Help) {
+        let box_area = area.inner(Margin {
+            horizontal: 2,
+            vertical: 1,
+        });
+
+        Clear.render(box_area, buf);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(self.config.theme.dropdown_border)
+            .border_set(self.config.char_set.dropdown_border)
+            .title(format!(" {} ", self.config.locale.get("help-title")))
+            .title_bottom(format!(" {} ", self.config.locale.get("help-hint")));
+        let inner = block.inner(box_area).inner(Margin {
+            horizontal: 1,
+            vertical: 0,
+        });
+        block.render(box_area, buf);
+
+        help.height = inner.height as usize;
+        let last = help.lines.len().saturating_sub(help.height);
+        help.offset = help.offset.min(last);
+        for (line, y) in help.lines[help.offset..].iter().zip(inner.rows()) {
+            Line::from(line.as_str()).render(y, buf);
+        }
+    }
+}
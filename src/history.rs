@@ -0,0 +1,156 @@
+//! A record of each node's recent peaks and volume, for spotting dropouts
+//! which are over before they can be seen on the meters.
+//!
+//! Samples are taken every [`HISTORY_STEP`] and kept for the configured
+//! number of seconds. When wiremix wasn't woken for a while, the view didn't
+//! change, so the missed samples are filled with the current values.
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use crate::object::ObjectId;
+use crate::view::View;
+
+/// How often samples are taken.
+pub const HISTORY_STEP: Duration = Duration::from_millis(100);
+
+/// A node's loudest peak and volume at one time.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct Sample {
+    /// Linear amplitude of the loudest channel
+    pub peak: f32,
+    /// Volume on the cubic scale shown by the volume bars
+    pub volume: f32,
+}
+
+#[derive(Debug)]
+pub struct History {
+    /// How many samples are kept for each node
+    capacity: usize,
+    nodes: HashMap<ObjectId, VecDeque<Sample>>,
+    /// When the next sample is due
+    next: Option<Instant>,
+}
+
+impl History {
+    /// A history covering the last `seconds`.
+    pub fn new(seconds: f32) -> Self {
+        let capacity = (seconds / HISTORY_STEP.as_secs_f32()).ceil() as usize;
+        Self {
+            capacity: capacity.max(1),
+            nodes: HashMap::new(),
+            next: None,
+        }
+    }
+
+    /// The length of time covered.
+    pub fn duration(&self) -> Duration {
+        HISTORY_STEP * self.capacity as u32
+    }
+
+    /// Take the samples of every node in `view` which are due.
+    pub fn sample(&mut self, view: &View, now: Instant) {
+        let next = *self.next.get_or_insert(now);
+        if now < next {
+            return;
+        }
+        let due = ((now - next).as_secs_f32() / HISTORY_STEP.as_secs_f32())
+            as usize
+            + 1;
+        self.next = Some(next + HISTORY_STEP * due as u32);
+
+        self.nodes
+            .retain(|node_id, _| view.nodes.contains_key(node_id));
+        for node in view.nodes.values() {
+            let sample = Sample {
+                peak: node
+                    .peaks
+                    .as_deref()
+                    .unwrap_or_default()
+                    .iter()
+                    .copied()
+                    .fold(0.0, f32::max),
+                volume: node.volume().unwrap_or_default(),
+            };
+            let samples = self.nodes.entry(node.id).or_default();
+            for _ in 0..due.min(self.capacity) {
+                if samples.len() == self.capacity {
+                    samples.pop_front();
+                }
+                samples.push_back(sample);
+            }
+        }
+    }
+
+    /// A node's history in `width` columns, oldest first. Each column has
+    /// the quietest peak of the samples it covers, so that short dropouts
+    /// aren't hidden, and the last volume. Columns from before the node
+    /// appeared are left out.
+    pub fn columns(&self, node_id: ObjectId, width: usize) -> Vec<Sample> {
+        let Some(samples) = self.nodes.get(&node_id) else {
+            return Vec::new();
+        };
+        if width == 0 {
+            return Vec::new();
+        }
+        let per_column = self.capacity.div_ceil(width);
+        // Line columns up with the end of the history so they don't shift
+        // between samples
+        let missing = self.capacity - samples.len();
+        let skip = missing % per_column;
+        let mut columns: Vec<Sample> = Vec::new();
+        for (i, sample) in samples.iter().enumerate() {
+            if (i + skip) % per_column == 0 || columns.is_empty() {
+                columns.push(*sample);
+            } else if let Some(column) = columns.last_mut() {
+                column.peak = column.peak.min(sample.peak);
+                column.volume = sample.volume;
+            }
+        }
+        columns
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history_with(samples: &[f32]) -> (History, ObjectId) {
+        let node_id = ObjectId::from_raw_id(1);
+        let mut history = History::new(1.0);
+        history.nodes.insert(
+            node_id,
+            samples
+                .iter()
+                .map(|&peak| Sample { peak, volume: 1.0 })
+                .collect(),
+        );
+        (history, node_id)
+    }
+
+    #[test]
+    fn keep_dropouts_in_columns() {
+        let peaks = [0.5, 0.5, 0.5, 0.0, 0.5, 0.5, 0.5, 0.5, 0.0, 0.5];
+        let (history, node_id) = history_with(&peaks);
+        let columns: Vec<_> = history
+            .columns(node_id, 5)
+            .iter()
+            .map(|sample| sample.peak)
+            .collect();
+        assert_eq!(columns, [0.5, 0.0, 0.5, 0.5, 0.0]);
+        // More columns than samples shows every sample
+        assert_eq!(history.columns(node_id, 80).len(), peaks.len());
+    }
+
+    #[test]
+    fn catch_up_missed_samples() {
+        let view = View::default();
+        let mut history = History::new(1.0);
+        let now = Instant::now();
+        history.sample(&view, now);
+        history.sample(&view, now + HISTORY_STEP / 2);
+        assert_eq!(history.next, Some(now + HISTORY_STEP));
+        history.sample(&view, now + HISTORY_STEP * 3);
+        assert_eq!(history.next, Some(now + HISTORY_STEP * 4));
+    }
+}
@@ -0,0 +1,96 @@
+//! A Ratatui widget for the peak and volume history of the selected node,
+//! drawn as sparklines along the bottom of the screen.
+
+use ratatui::{
+    layout::{Constraint, Layout},
+    prelude::{Buffer, Rect, Widget},
+    text::Line,
+    widgets::Sparkline,
+};
+
+use crate::config::Config;
+use crate::history::History;
+use crate::view;
+
+/// Rows taken by the widget.
+pub const HISTORY_HEIGHT: u16 = 3;
+
+pub struct HistoryWidget<'a> {
+    history: &'a History,
+    node: &'a view::Node,
+    config: &'a Config,
+}
+
+impl<'a> HistoryWidget<'a> {
+    pub fn new(
+        history: &'a History,
+        node: &'a view::Node,
+        config: &'a Config,
+    ) -> Self {
+        Self {
+            history,
+            node,
+            config,
+        }
+    }
+}
+
+impl Widget for HistoryWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let [title_area, peak_area, volume_area] =
+            Layout::vertical([Constraint::Length(1); 3]).areas(area);
+
+        Line::styled(
+            format!(
+                "History of {} over the last {} s",
+                self.node.title,
+                self.history.duration().as_secs()
+            ),
+            self.config.theme.flash,
+        )
+        .render(title_area, buf);
+
+        let [peak_label, peak_area] =
+            Layout::horizontal([Constraint::Length(7), Constraint::Fill(1)])
+                .areas(peak_area);
+        let [volume_label, volume_area] =
+            Layout::horizontal([Constraint::Length(7), Constraint::Fill(1)])
+                .areas(volume_area);
+        Line::from("Peak").render(peak_label, buf);
+        Line::from("Volume").render(volume_label, buf);
+
+        let columns =
+            self.history.columns(self.node.id, peak_area.width as usize);
+        // Newest at the right edge
+        let skip = peak_area.width.saturating_sub(columns.len() as u16);
+        let right = |area: Rect| Rect {
+            x: area.x + skip,
+            width: area.width - skip,
+            ..area
+        };
+
+        let peaks: Vec<u64> = columns
+            .iter()
+            .map(|sample| {
+                // The same -60 dB range as the meters
+                let db = 20.0 * (sample.peak + 1e-10).log10();
+                ((db + 60.0) / 60.0 * 100.0).clamp(0.0, 100.0) as u64
+            })
+            .collect();
+        Sparkline::default()
+            .data(&peaks)
+            .max(100)
+            .style(self.config.theme.meter_active)
+            .render(right(peak_area), buf);
+
+        let volumes: Vec<u64> = columns
+            .iter()
+            .map(|sample| (sample.volume * 100.0).clamp(0.0, 100.0) as u64)
+            .collect();
+        Sparkline::default()
+            .data(&volumes)
+            .max(100)
+            .style(self.config.theme.volume_filled)
+            .render(right(volume_area), buf);
+    }
+}
@@ -0,0 +1,28 @@
+//! Running user commands when something happens, such as
+//! [`Recording::on_start`](`crate::config::Recording::on_start`).
+
+use std::process::{self, Stdio};
+use std::thread;
+
+use anyhow::Context;
+
+/// Run a shell command with extra environment variables. The command isn't
+/// waited for so that it can't hold up the UI. `name` is the option the
+/// command came from, for errors.
+pub fn spawn(
+    name: &str,
+    command: &str,
+    env: &[(&str, &str)],
+) -> Result<(), anyhow::Error> {
+    let mut child = process::Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .envs(env.iter().copied())
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to run {}", name))?;
+    thread::spawn(move || child.wait());
+    Ok(())
+}
@@ -0,0 +1,129 @@
+//! Holding back view updates while a device which was plugged in or
+//! unplugged settles.
+//!
+//! A device appearing or disappearing brings a flood of changes to its
+//! profiles, routes, nodes and links. Rebuilding the view for each of them
+//! shows every intermediate state, and automation such as replacing lost
+//! defaults and port hooks would act on them. Instead the view is rebuilt
+//! once the changes stop for [`HOTPLUG_GRACE`].
+
+use std::time::{Duration, Instant};
+
+use crate::event::MonitorEvent;
+use crate::state::State;
+
+/// How long the changes must stop for before the view is rebuilt.
+pub const HOTPLUG_GRACE: Duration = Duration::from_millis(300);
+
+/// The longest the view is held back, in case the changes don't stop.
+const HOTPLUG_MAX: Duration = Duration::from_secs(2);
+
+/// Tracks whether a device is settling after being plugged in or unplugged.
+#[derive(Default, Debug)]
+pub struct Hotplug {
+    /// When the device appeared or disappeared
+    started: Option<Instant>,
+    /// When the last change arrived
+    last: Option<Instant>,
+}
+
+impl Hotplug {
+    /// Note an event before it's applied to `state`. A device appearing or
+    /// disappearing starts the grace period, and other changes extend it.
+    pub fn on_event(
+        &mut self,
+        state: &State,
+        event: &MonitorEvent,
+        now: Instant,
+    ) {
+        if matches!(event, MonitorEvent::NodePeaks(..)) {
+            return;
+        }
+        if self.is_settling(now) {
+            self.last = Some(now);
+        } else if is_hotplug(state, event) {
+            self.started = Some(now);
+            self.last = Some(now);
+        }
+    }
+
+    pub fn is_settling(&self, now: Instant) -> bool {
+        self.until_settled(now).is_some()
+    }
+
+    /// How long until the grace period ends, or `None` if it isn't running.
+    pub fn until_settled(&self, now: Instant) -> Option<Duration> {
+        let (started, last) = (self.started?, self.last?);
+        let end = (last + HOTPLUG_GRACE).min(started + HOTPLUG_MAX);
+        (now < end).then(|| end - now)
+    }
+}
+
+/// Whether an event is for a device which isn't known yet, or removes one
+/// which is.
+fn is_hotplug(state: &State, event: &MonitorEvent) -> bool {
+    match event {
+        MonitorEvent::DeviceDescription(id, ..)
+        | MonitorEvent::DeviceEnumRoute(id, ..)
+        | MonitorEvent::DeviceMediaClass(id, ..)
+        | MonitorEvent::DeviceName(id, ..)
+        | MonitorEvent::DeviceNick(id, ..)
+        | MonitorEvent::DeviceEnumProfile(id, ..)
+        | MonitorEvent::DeviceProfile(id, ..)
+        | MonitorEvent::DeviceRoute(id, ..)
+        | MonitorEvent::DeviceObjectSerial(id, ..)
+        | MonitorEvent::DeviceBattery(id, ..) => {
+            !state.devices.contains_key(id)
+        }
+        MonitorEvent::Removed(id) => state.devices.contains_key(id),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::ObjectId;
+
+    #[test]
+    fn settle_after_changes_stop() {
+        let state = State::default();
+        let device_id = ObjectId::from_raw_id(1);
+        let node_id = ObjectId::from_raw_id(2);
+        let mut hotplug = Hotplug::default();
+        let now = Instant::now();
+
+        // Changes to nodes alone don't start the grace period
+        let node = MonitorEvent::NodeMute(node_id, true);
+        hotplug.on_event(&state, &node, now);
+        assert!(!hotplug.is_settling(now));
+
+        let device = MonitorEvent::DeviceName(device_id, String::from("usb"));
+        hotplug.on_event(&state, &device, now);
+        assert_eq!(hotplug.until_settled(now), Some(HOTPLUG_GRACE));
+
+        let later = now + Duration::from_millis(200);
+        hotplug.on_event(&state, &node, later);
+        assert!(hotplug.is_settling(now + HOTPLUG_GRACE));
+        assert!(!hotplug.is_settling(later + HOTPLUG_GRACE));
+    }
+
+    #[test]
+    fn settle_eventually() {
+        let state = State::default();
+        let device_id = ObjectId::from_raw_id(1);
+        let mut hotplug = Hotplug::default();
+        let now = Instant::now();
+        let device = MonitorEvent::DeviceName(device_id, String::from("usb"));
+        hotplug.on_event(&state, &device, now);
+
+        // Changes keep arriving, but the view isn't held back forever
+        let mut time = now;
+        while time + HOTPLUG_GRACE < now + HOTPLUG_MAX {
+            time += Duration::from_millis(100);
+            hotplug.on_event(&state, &device, time);
+        }
+        assert!(hotplug.is_settling(time));
+        assert!(!hotplug.is_settling(now + HOTPLUG_MAX));
+    }
+}
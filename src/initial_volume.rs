@@ -0,0 +1,123 @@
+//! Setting the volume of streams the first time they appear while wiremix
+//! runs, configured by [`initial_volume`](`crate::config::Config::initial_volume`),
+//! such as starting game launchers at 30%.
+//!
+//! Streams are told apart by their node.name, so only the first stream of
+//! each application is set, and streams which are already present when
+//! wiremix starts are left alone. This is separate from the volumes the
+//! session manager restores, which apply every time a stream appears.
+
+use std::collections::HashSet;
+
+use crate::command::Command;
+use crate::config::InitialVolume;
+use crate::state::State;
+use crate::view::{View, VolumeAdjustment};
+
+/// Tracks the streams which have been seen.
+#[derive(Default, Debug)]
+pub struct InitialVolumes {
+    /// node.names of the streams seen, or None before the first update
+    seen: Option<HashSet<String>>,
+}
+
+impl InitialVolumes {
+    /// Commands setting the volumes of streams appearing for the first time
+    /// which match one of `rules`, using the first rule matching each.
+    pub fn update(
+        &mut self,
+        rules: &[InitialVolume],
+        state: &State,
+        view: &View,
+    ) -> Vec<Command> {
+        let streams = view.nodes.values().filter(|node| {
+            (node.media_class.is_sink_input()
+                || node.media_class.is_source_output())
+                // The volume can't be set until it's known
+                && !node.volumes.is_empty()
+        });
+
+        let Some(seen) = &mut self.seen else {
+            self.seen = Some(streams.map(|node| node.name.clone()).collect());
+            return Vec::new();
+        };
+        if rules.is_empty() {
+            return Vec::new();
+        }
+
+        let mut commands = Vec::new();
+        for node in streams {
+            if !seen.insert(node.name.clone()) {
+                continue;
+            }
+            let Some(rule) = rules
+                .iter()
+                .find(|rule| rule.select.nodes(state).contains(&node.id))
+            else {
+                continue;
+            };
+            let adjustment = VolumeAdjustment::Absolute(rule.volume);
+            commands.extend(view.volume(node.id, adjustment));
+        }
+        commands
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capture_manager::CaptureManager;
+    use crate::config::Names;
+    use crate::event::MonitorEvent;
+    use crate::media_class::MediaClass;
+    use crate::object::ObjectId;
+
+    fn add_stream(state: &mut State, id: u32, name: &str) {
+        let id = ObjectId::from_raw_id(id);
+        for event in [
+            MonitorEvent::NodeName(id, String::from(name)),
+            MonitorEvent::NodeDescription(id, String::from(name)),
+            MonitorEvent::NodeObjectSerial(id, u32::from(id) as i32),
+            MonitorEvent::NodeMediaClass(
+                id,
+                MediaClass::from("Stream/Output/Audio"),
+            ),
+            MonitorEvent::NodePositions(id, vec![0, 1]),
+            MonitorEvent::NodeVolumes(id, vec![1.0, 1.0]),
+            MonitorEvent::NodeMute(id, false),
+        ] {
+            state.update(&mut CaptureManager::default(), event);
+        }
+    }
+
+    #[test]
+    fn set_first_stream_of_each_application() {
+        let rules = [InitialVolume {
+            select: "node.name=steam*".parse().unwrap(),
+            volume: 0.5,
+        }];
+        let names = Names::default();
+        let mut state = State::default();
+        let mut initial_volumes = InitialVolumes::default();
+        let mut update = |state: &State| {
+            let view = View::from(state, &names);
+            initial_volumes.update(&rules, state, &view)
+        };
+
+        // Already playing when wiremix starts
+        add_stream(&mut state, 1, "steam.old");
+        assert!(update(&state).is_empty());
+
+        add_stream(&mut state, 2, "steam.launcher");
+        add_stream(&mut state, 3, "firefox");
+        let commands = update(&state);
+        assert!(matches!(
+            commands.as_slice(),
+            [Command::NodeVolumes(id, volumes)]
+                if *id == ObjectId::from_raw_id(2) && volumes == &[0.125; 2]
+        ));
+
+        add_stream(&mut state, 4, "steam.launcher");
+        assert!(update(&state).is_empty());
+    }
+}
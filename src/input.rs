@@ -1,17 +1,55 @@
 //! Setup and teardown of terminal input.
 //!
-//! [`spawn()`] starts the input thead.
+//! [`spawn()`] starts the input thead. [`ContextStack`] tracks which
+//! [`KeyContext`]s' keybindings apply to a key press.
 
 use std::sync::{mpsc, Arc};
 use std::thread;
 use std::time::Duration;
 
-use crossterm::event::EventStream;
+use crossterm::event::{Event as CrosstermEvent, EventStream};
+use crossterm::terminal;
 use futures::{channel::oneshot, FutureExt, StreamExt};
 use futures_timer::Delay;
+use serde::Deserialize;
 
 use crate::event::Event;
 
+/// How often the terminal size is checked, in case the terminal doesn't send
+/// SIGWINCH, as can happen over some multiplexers and SSH connections.
+const SIZE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A mode with its own keybindings, which take precedence over the global
+/// keybindings while it's active.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyContext {
+    /// Browsing the objects in a tab
+    List,
+    /// Choosing a target in an open dropdown
+    Dropdown,
+}
+
+/// The active [`KeyContext`]s, from outermost to innermost.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ContextStack(Vec<KeyContext>);
+
+impl ContextStack {
+    pub fn push(&mut self, context: KeyContext) {
+        self.0.push(context);
+    }
+
+    pub fn pop(&mut self) -> Option<KeyContext> {
+        self.0.pop()
+    }
+
+    /// Iterate over the contexts in the order their keybindings should be
+    /// looked up.
+    pub fn innermost_first(&self) -> impl Iterator<Item = KeyContext> + '_ {
+        self.0.iter().rev().copied()
+    }
+}
+
 /// Spawns a thread to listen for terminal input events.
 ///
 /// [`Event`](`crate::event::Event`)s are sent to tx.
@@ -57,17 +95,33 @@ async fn input_loop(
 ) {
     let mut reader = EventStream::new();
     let mut shutdown = shutdown_rx.fuse();
+    let mut size = terminal::size().ok();
+    // Started once rather than on every event, so that it still fires while
+    // there's a steady stream of input
+    let mut size_poll = Delay::new(SIZE_POLL_INTERVAL).fuse();
 
     loop {
-        let mut delay = Delay::new(Duration::from_millis(1_000)).fuse();
         let mut event = reader.next().fuse();
 
         futures::select! {
             _ = shutdown => break,
-            _ = delay => { },
+            _ = size_poll => {
+                size_poll = Delay::new(SIZE_POLL_INTERVAL).fuse();
+                let current = terminal::size().ok();
+                if let Some((width, height)) =
+                    current.filter(|_| current != size)
+                {
+                    size = current;
+                    let event = CrosstermEvent::Resize(width, height);
+                    let _ = tx.send(Event::from(event));
+                }
+            },
             maybe_event = event => {
                 match maybe_event {
                     Some(Ok(event)) => {
+                        if let CrosstermEvent::Resize(width, height) = event {
+                            size = Some((width, height));
+                        }
                         let _ = tx.send(Event::from(event));
                     }
                     None => break,
@@ -0,0 +1,291 @@
+//! Measuring the round-trip latency from an output device to an input
+//! device, such as from speakers to a microphone placed near them, by playing
+//! a chirp and finding it in a recording.
+//!
+//! The chirp is found twice: in a recording of the output device's monitor,
+//! which hears it as the device is sent it, and in a recording of the input
+//! device. Each recording is made with `pw-record`, and the time its first
+//! sample was captured is estimated from when its data arrives. The latency
+//! is the difference between the two times the chirp was heard, and includes
+//! both devices' buffering and the sound travelling through the air.
+
+use std::io::Read;
+use std::process::{self, Child, Stdio};
+use std::sync::mpsc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context};
+
+use crate::tone;
+
+/// How long recording runs before the chirp is played, for both recordings
+/// to be going.
+const LEAD_IN: Duration = Duration::from_millis(500);
+/// How long recording runs after the chirp, which bounds the latency which
+/// can be measured.
+const LISTEN: Duration = Duration::from_millis(1500);
+
+const CHIRP_MS: u32 = 100;
+const CHIRP_START_HZ: f32 = 500.0;
+const CHIRP_END_HZ: f32 = 5000.0;
+const CHIRP_AMPLITUDE: f32 = 0.5;
+
+/// How often to check whether a measurement has finished.
+pub const LATENCY_POLL: Duration = Duration::from_millis(100);
+
+/// How closely a recording must match the chirp to count as hearing it,
+/// from 0 to 1.
+const MIN_MATCH: f32 = 0.3;
+
+/// A measurement running in the background.
+#[derive(Default, Debug)]
+pub struct LatencyTest {
+    result: Option<mpsc::Receiver<Result<Duration, anyhow::Error>>>,
+}
+
+impl LatencyTest {
+    pub fn is_running(&self) -> bool {
+        self.result.is_some()
+    }
+
+    /// Start measuring from the output device with `sink_serial` to the input
+    /// device with `source_serial`.
+    pub fn start(&mut self, sink_serial: i32, source_serial: i32) {
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = tx.send(measure(sink_serial, source_serial));
+        });
+        self.result = Some(rx);
+    }
+
+    /// Returns the result once the measurement has finished.
+    pub fn poll(&mut self) -> Option<Result<Duration, anyhow::Error>> {
+        let result = match self.result.as_ref()?.try_recv() {
+            Ok(result) => result,
+            Err(mpsc::TryRecvError::Empty) => return None,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                Err(anyhow!("Latency measurement stopped"))
+            }
+        };
+        self.result = None;
+        Some(result)
+    }
+}
+
+fn measure(
+    sink_serial: i32,
+    source_serial: i32,
+) -> Result<Duration, anyhow::Error> {
+    let monitor = Recording::start(sink_serial, true)?;
+    let input = Recording::start(source_serial, false)?;
+    thread::sleep(LEAD_IN);
+
+    let template = chirp();
+    let mut player = tone::play(sink_serial, None, tone::wav(1, &template))?;
+    thread::sleep(LISTEN);
+    let _ = player.wait();
+
+    let (monitor_start, monitor) = monitor.finish()?;
+    let (input_start, input) = input.finish()?;
+
+    let heard = |samples: &[i16], start: Instant, device: &str| {
+        let offset = find(&template, samples).ok_or_else(|| {
+            anyhow!("The chirp wasn't heard by the {}", device)
+        })?;
+        Ok::<_, anyhow::Error>(start + samples_duration(offset))
+    };
+    let played = heard(&monitor, monitor_start, "output device")?;
+    let recorded = heard(&input, input_start, "input device")?;
+    recorded
+        .checked_duration_since(played)
+        .ok_or_else(|| anyhow!("The chirp was recorded before it was played"))
+}
+
+/// A `pw-record` process writing mono 16-bit samples, and the thread reading
+/// them.
+struct Recording {
+    child: Child,
+    reader: JoinHandle<Capture>,
+}
+
+impl Recording {
+    /// Record from the node with `object_serial`, or from its monitor if
+    /// `monitor` is set.
+    fn start(object_serial: i32, monitor: bool) -> Result<Self, anyhow::Error> {
+        let mut command = process::Command::new("pw-record");
+        command.args(["--target", &object_serial.to_string()]);
+        command.args(["--rate", &tone::RATE.to_string()]);
+        command.args(["--channels", "1", "--format", "s16"]);
+        if monitor {
+            command.args(["--properties", "{ stream.capture.sink = true }"]);
+        }
+        let mut child = command
+            .arg("-")
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .context("Failed to run pw-record")?;
+        let Some(mut stdout) = child.stdout.take() else {
+            return Err(anyhow!("Failed to read from pw-record"));
+        };
+        let reader = thread::spawn(move || {
+            let mut capture = Capture::default();
+            let mut buf = [0; 4096];
+            while let Ok(len @ 1..) = stdout.read(&mut buf) {
+                capture.push(&buf[..len], Instant::now());
+            }
+            capture
+        });
+        Ok(Self { child, reader })
+    }
+
+    /// Stop recording. Returns when the first sample was captured and the
+    /// samples.
+    fn finish(mut self) -> Result<(Instant, Vec<i16>), anyhow::Error> {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        let capture = self
+            .reader
+            .join()
+            .map_err(|_| anyhow!("Failed to read from pw-record"))?;
+        capture
+            .finish()
+            .ok_or_else(|| anyhow!("pw-record didn't record anything"))
+    }
+}
+
+/// Data read from `pw-record`, which may start with a WAV header.
+#[derive(Default, Debug)]
+struct Capture {
+    data: Vec<u8>,
+    /// The length of the data at each read and when the read returned
+    reads: Vec<(usize, Instant)>,
+}
+
+impl Capture {
+    fn push(&mut self, bytes: &[u8], now: Instant) {
+        self.data.extend_from_slice(bytes);
+        self.reads.push((self.data.len(), now));
+    }
+
+    /// Returns the samples and the earliest estimate of when the first one
+    /// was captured. Data only arrives after it's captured, so later reads
+    /// only overestimate.
+    fn finish(self) -> Option<(Instant, Vec<i16>)> {
+        let header_len = wav_header_len(&self.data);
+        let start = self
+            .reads
+            .iter()
+            .filter(|(len, _)| *len > header_len)
+            .map(|(len, time)| *time - samples_duration((len - header_len) / 2))
+            .min()?;
+        let samples = self.data[header_len..]
+            .chunks_exact(2)
+            .map(|bytes| i16::from_le_bytes([bytes[0], bytes[1]]))
+            .collect();
+        Some((start, samples))
+    }
+}
+
+/// The length of the WAV header at the start of `data`, or 0 if it's raw
+/// samples.
+fn wav_header_len(data: &[u8]) -> usize {
+    if !data.starts_with(b"RIFF") {
+        return 0;
+    }
+    let mut pos = 12;
+    while let Some(chunk) = data.get(pos..pos + 8) {
+        let len = u32::from_le_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]);
+        if &chunk[..4] == b"data" {
+            return pos + 8;
+        }
+        pos += 8 + len as usize;
+    }
+    data.len()
+}
+
+fn samples_duration(samples: usize) -> Duration {
+    Duration::from_secs_f64(samples as f64 / f64::from(tone::RATE))
+}
+
+/// A linear frequency sweep, which stands out clearly when correlated.
+fn chirp() -> Vec<i16> {
+    let count = tone::RATE * CHIRP_MS / 1000;
+    let duration = CHIRP_MS as f32 / 1000.0;
+    let rate = (CHIRP_END_HZ - CHIRP_START_HZ) / duration;
+    (0..count)
+        .map(|n| {
+            let t = n as f32 / tone::RATE as f32;
+            let phase = CHIRP_START_HZ * t + rate * t * t / 2.0;
+            let value = (2.0 * std::f32::consts::PI * phase).sin();
+            (value * CHIRP_AMPLITUDE * i16::MAX as f32) as i16
+        })
+        .collect()
+}
+
+/// Returns the offset in `recording` which best matches `template`, if it
+/// matches well enough.
+fn find(template: &[i16], recording: &[i16]) -> Option<usize> {
+    if template.is_empty() || recording.len() < template.len() {
+        return None;
+    }
+    let template: Vec<f32> = template.iter().map(|&s| f32::from(s)).collect();
+    let recording: Vec<f32> = recording.iter().map(|&s| f32::from(s)).collect();
+    let template_energy = template.iter().map(|s| s * s).sum::<f32>().sqrt();
+
+    // The energy of the window is kept as a running sum
+    let mut window_energy: f32 =
+        recording[..template.len()].iter().map(|s| s * s).sum();
+    let mut best = (0.0, 0);
+    for offset in 0..=recording.len() - template.len() {
+        if offset > 0 {
+            let removed = recording[offset - 1];
+            let added = recording[offset + template.len() - 1];
+            window_energy =
+                (window_energy - removed * removed + added * added).max(0.0);
+        }
+        let correlation: f32 = template
+            .iter()
+            .zip(&recording[offset..])
+            .map(|(a, b)| a * b)
+            .sum();
+        let norm = template_energy * window_energy.sqrt();
+        if norm > 0.0 && correlation / norm > best.0 {
+            best = (correlation / norm, offset);
+        }
+    }
+    (best.0 >= MIN_MATCH).then_some(best.1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_chirp_in_noise() {
+        let template = chirp();
+        let mut recording = tone::noise(200, 0.05);
+        let offset = 1234;
+        for (sample, chirp) in recording[offset..].iter_mut().zip(&template) {
+            *sample = sample.saturating_add(*chirp / 2);
+        }
+        assert_eq!(find(&template, &recording), Some(offset));
+        assert_eq!(find(&template, &tone::noise(200, 0.05)), None);
+    }
+
+    #[test]
+    fn skip_wav_header() {
+        let now = Instant::now();
+        let wav = tone::wav(1, &[1, 2, 3]);
+        let mut capture = Capture::default();
+        capture.push(&wav[..20], now);
+        capture.push(&wav[20..], now + Duration::from_millis(1));
+        let (start, samples) = capture.finish().unwrap();
+        assert_eq!(samples, [1, 2, 3]);
+        assert!(start > now);
+
+        assert_eq!(wav_header_len(&[0, 0, 1, 0]), 0);
+    }
+}
@@ -1,22 +1,76 @@
+// The PipeWire monitor and state model live in wiremix-core so they can be
+// used without the interface.
+pub use wiremix_core::{
+    capture_manager, channel_map, command, error, event, media_class, monitor,
+    object, state,
+};
+
 pub mod app;
-pub mod capture_manager;
-pub mod command;
+pub mod braille;
+pub mod clipboard;
+pub mod coalesce;
 pub mod config;
+pub mod crash;
 pub mod device_kind;
 pub mod device_widget;
+pub mod diff;
+pub mod dnd;
 pub mod dropdown_widget;
-pub mod event;
+pub mod ducking;
+pub mod ended;
+pub mod exit_code;
+pub mod export;
+pub mod fade;
+pub mod fader_widget;
+pub mod feedback;
+pub mod graph;
+pub mod help;
+pub mod help_widget;
+pub mod history;
+pub mod history_widget;
+pub mod hook;
+pub mod hotplug;
+pub mod initial_volume;
 pub mod input;
-pub mod media_class;
+pub mod latency;
+pub mod linked;
+pub mod list;
+pub mod loaded_modules;
+pub mod locale;
+pub mod macros;
 pub mod meter;
-pub mod monitor;
+pub mod metering;
+pub mod mic_check;
+pub mod mic_check_widget;
+pub mod mpris;
+pub mod mute_source;
 pub mod node_widget;
-pub mod object;
+pub mod notification_group;
 pub mod object_list;
 pub mod opt;
-pub mod state;
+pub mod optimistic;
+pub mod osc;
+pub mod paused;
+pub mod pins;
+pub mod ports;
+pub mod profile_switch;
+pub mod property_editor;
+pub mod pulse_module;
+pub mod recording;
+pub mod rule;
+pub mod schedule;
+pub mod session;
+pub mod signals;
+pub mod sleep;
+pub mod systemd;
+pub mod test_tone;
+pub mod tone;
 pub mod truncate;
+pub mod vi;
 pub mod view;
+pub mod wait;
+pub mod watchdog;
+pub mod wizard;
 
 #[cfg(feature = "trace")]
 pub mod trace;
@@ -0,0 +1,91 @@
+//! Endpoints linked by `linked` in the configuration, whose volume and mute
+//! changes are applied to each other.
+//!
+//! A volume change is applied to the other endpoints in proportion, so a
+//! subwoofer kept quieter than the speakers it's linked to stays that way.
+
+use crate::command::Command;
+use crate::view::{self, View};
+
+/// Returns the commands which apply a volume or mute command to the other
+/// endpoints linked with the one it's for.
+pub fn commands(
+    view: &View,
+    linked: &[Vec<String>],
+    command: &Command,
+) -> Vec<Command> {
+    let Some(node) = commanded_node(view, command) else {
+        return Vec::new();
+    };
+    let Some(group) = linked.iter().find(|group| group.contains(&node.name))
+    else {
+        return Vec::new();
+    };
+
+    group
+        .iter()
+        .filter(|name| **name != node.name)
+        .filter_map(|name| view.nodes.values().find(|node| node.name == *name))
+        .filter_map(|other| match command {
+            Command::NodeMute(_, mute) | Command::DeviceMute(.., mute) => {
+                view.set_mute(other.id, *mute)
+            }
+            Command::NodeVolumes(_, volumes)
+            | Command::DeviceVolumes(.., volumes) => {
+                let volumes = scaled(&other.volumes, &node.volumes, volumes);
+                view.set_volumes(other.id, volumes)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// The node a volume or mute command is for.
+fn commanded_node<'a>(
+    view: &'a View,
+    command: &Command,
+) -> Option<&'a view::Node> {
+    let device_info = match *command {
+        Command::NodeMute(node_id, _) | Command::NodeVolumes(node_id, _) => {
+            return view.nodes.get(&node_id);
+        }
+        Command::DeviceMute(device_id, route_index, route_device, _)
+        | Command::DeviceVolumes(device_id, route_index, route_device, _) => {
+            (device_id, route_index, route_device)
+        }
+        _ => return None,
+    };
+    view.nodes
+        .values()
+        .find(|node| node.device_info == Some(device_info))
+}
+
+/// Scales `volumes` by the change from `before` to `after`, or sets them to
+/// `after`'s average if `before` was silent.
+pub fn scaled(volumes: &[f32], before: &[f32], after: &[f32]) -> Vec<f32> {
+    let average =
+        |volumes: &[f32]| volumes.iter().sum::<f32>() / volumes.len() as f32;
+    if volumes.is_empty() || before.is_empty() || after.is_empty() {
+        return volumes.to_vec();
+    }
+    let (before, after) = (average(before), average(after));
+    if before == 0.0 {
+        return vec![after; volumes.len()];
+    }
+    volumes
+        .iter()
+        .map(|volume| volume * after / before)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scales_in_proportion() {
+        assert_eq!(scaled(&[0.25, 0.5], &[0.5, 0.5], &[1.0, 1.0]), [0.5, 1.0]);
+        assert_eq!(scaled(&[0.25], &[0.0, 0.0], &[0.5, 0.5]), [0.5]);
+        assert_eq!(scaled(&[], &[0.5], &[1.0]), [0.0; 0]);
+    }
+}
@@ -0,0 +1,131 @@
+//! Printing the objects PipeWire knows about for `wiremix list`.
+
+use std::sync::mpsc;
+
+use anyhow::{anyhow, bail};
+use serde_json::json;
+
+use crate::capture_manager::CaptureManager;
+use crate::config::{Config, ListFormat, Selector};
+use crate::event::Event;
+use crate::exit_code;
+use crate::object::ObjectId;
+use crate::opt::ListObjects;
+use crate::state::State;
+use crate::view::{NodeKind, View};
+
+/// Wait for the initial objects from the monitor and print a line for each
+/// node or device matching `select`, or a JSON array of them if `json` is set.
+pub fn run(
+    event_rx: mpsc::Receiver<Event>,
+    config: &Config,
+    objects: ListObjects,
+    format: Option<&str>,
+    select: Option<&str>,
+    json: bool,
+) -> Result<(), anyhow::Error> {
+    if json && format.is_some() {
+        bail!("--format can't be used with --json");
+    }
+    let format = match (format, objects) {
+        (Some(format), _) => format.parse()?,
+        (None, ListObjects::Nodes) => ListFormat::nodes(),
+        (None, ListObjects::Devices) => ListFormat::devices(),
+    };
+    let selector = select
+        .map(|select| select.parse::<Selector>().map_err(|err| anyhow!(err)))
+        .transpose()?;
+
+    let state = initial_state(&event_rx)?;
+    let view = View::from(&state, &config.names);
+    let selected = match (&selector, objects) {
+        (Some(selector), ListObjects::Nodes) => Some(selector.nodes(&state)),
+        (Some(selector), ListObjects::Devices) => {
+            Some(selector.devices(&state))
+        }
+        (None, _) => None,
+    };
+    if selected.as_ref().is_some_and(Vec::is_empty) {
+        let select = String::from(select.unwrap_or_default());
+        return Err(exit_code::Error::NotFound(select).into());
+    }
+    let is_selected = |id: &ObjectId| {
+        selected
+            .as_ref()
+            .map_or(true, |selected| selected.contains(id))
+    };
+    let nodes = view
+        .full_nodes(NodeKind::All)
+        .into_iter()
+        .filter(|node| is_selected(&node.id));
+    let devices = view
+        .full_devices()
+        .into_iter()
+        .filter(|device| is_selected(&device.id));
+
+    match objects {
+        ListObjects::Nodes if json => {
+            let nodes = nodes.map(|node| {
+                json!({
+                    "id": u32::from(node.id),
+                    "serial": node.object_serial,
+                    "name": node.title,
+                    "volume": node.volume_percent(),
+                    "mute": node.mute,
+                    "media_class": node.media_class.as_str(),
+                    "target": node.target_title,
+                    "default": node.is_default_sink || node.is_default_source,
+                })
+            });
+            println!("{:#}", serde_json::Value::from_iter(nodes));
+        }
+        ListObjects::Devices if json => {
+            let devices = devices.map(|device| {
+                json!({
+                    "id": u32::from(device.id),
+                    "serial": device.object_serial,
+                    "name": device.title,
+                    "target": device.target_title,
+                })
+            });
+            println!("{:#}", serde_json::Value::from_iter(devices));
+        }
+        ListObjects::Nodes => {
+            for node in nodes {
+                println!("{}", format.render_node(&state, node));
+            }
+        }
+        ListObjects::Devices => {
+            for device in devices {
+                println!("{}", format.render_device(&state, device));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Wait for the initial objects from the monitor.
+pub fn initial_state(
+    event_rx: &mpsc::Receiver<Event>,
+) -> Result<State, anyhow::Error> {
+    let mut state = State::default();
+    // Captures are never started, so this is only needed to update the state
+    let mut capture_manager = CaptureManager::default();
+    loop {
+        let Ok(received) = event_rx.recv() else {
+            let error = String::from("Monitor stopped");
+            return Err(exit_code::Error::Unreachable(error).into());
+        };
+        match received {
+            Event::Monitor(event) => state.update(&mut capture_manager, event),
+            // Nothing has been received yet, so this is a failure to connect
+            Event::Error(error) if error.is_fatal() => {
+                let error = error.to_string();
+                return Err(exit_code::Error::Unreachable(error).into());
+            }
+            Event::Ready => return Ok(state),
+            _ => {}
+        }
+    }
+}
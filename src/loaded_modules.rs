@@ -0,0 +1,135 @@
+//! A record of the PulseAudio modules wiremix has loaded, such as RTP
+//! streams, so that modules left behind by a session which crashed can be
+//! offered for unloading at the next startup.
+//!
+//! The record is kept in the XDG state directory with the process ID of the
+//! running wiremix, which is cleared on a clean exit. A process ID which is
+//! still set but no longer running means the last session didn't exit
+//! cleanly.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use nix::sys::signal;
+use nix::unistd::Pid;
+use serde::{Deserialize, Serialize};
+
+/// A module loaded with pactl.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LoadedModule {
+    pub index: u32,
+    pub name: String,
+    pub args: Vec<String>,
+}
+
+impl LoadedModule {
+    pub fn args(&self) -> Vec<&str> {
+        self.args.iter().map(String::as_str).collect()
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq)]
+#[serde(default)]
+pub struct LoadedModules {
+    /// The process ID of the wiremix which is running, if any
+    pub pid: Option<i32>,
+    pub modules: Vec<LoadedModule>,
+}
+
+impl LoadedModules {
+    /// Whether the session which wrote this record ended without exiting
+    /// cleanly.
+    pub fn crashed(&self) -> bool {
+        self.pid.is_some_and(|pid| {
+            signal::kill(Pid::from_raw(pid), None)
+                == Err(nix::errno::Errno::ESRCH)
+        })
+    }
+
+    pub fn add(&mut self, index: u32, name: &str, args: &[&str]) {
+        self.modules.push(LoadedModule {
+            index,
+            name: String::from(name),
+            args: args.iter().map(|arg| String::from(*arg)).collect(),
+        });
+    }
+
+    /// Forget the modules called name with args, after they were unloaded.
+    pub fn remove(&mut self, name: &str, args: &[&str]) {
+        self.modules
+            .retain(|module| module.name != name || module.args() != args);
+    }
+}
+
+/// Returns the path of the file recording the loaded modules.
+pub fn default_path() -> Option<PathBuf> {
+    if let Ok(xdg_state) = env::var("XDG_STATE_HOME") {
+        return Some(Path::new(&xdg_state).join("wiremix/modules.toml"));
+    }
+
+    if let Ok(home) = env::var("HOME") {
+        return Some(
+            Path::new(&home).join(".local/state/wiremix/modules.toml"),
+        );
+    }
+
+    None
+}
+
+/// Load the record. A missing file is an empty record.
+pub fn load(path: &Path) -> Result<LoadedModules, anyhow::Error> {
+    if !path.exists() {
+        return Ok(LoadedModules::default());
+    }
+
+    let context =
+        || format!("Failed to read loaded modules from '{}'", path.display());
+    let toml_str = fs::read_to_string(path).with_context(context)?;
+
+    toml::from_str(&toml_str).with_context(context)
+}
+
+/// Save the record, creating the parent directory if needed.
+pub fn save(
+    path: &Path,
+    loaded_modules: &LoadedModules,
+) -> Result<(), anyhow::Error> {
+    let context =
+        || format!("Failed to save loaded modules to '{}'", path.display());
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(context)?;
+    }
+    let toml_str = toml::to_string(loaded_modules).with_context(context)?;
+
+    fs::write(path, toml_str).with_context(context)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_and_remove() {
+        let mut loaded_modules = LoadedModules {
+            pid: Some(std::process::id() as i32),
+            modules: Vec::new(),
+        };
+        loaded_modules.add(1, "module-rtp-send", &["destination_ip=::1"]);
+        loaded_modules.add(2, "module-raop-discover", &[]);
+        loaded_modules.remove("module-rtp-send", &[]);
+        assert_eq!(loaded_modules.modules.len(), 2);
+        loaded_modules.remove("module-rtp-send", &["destination_ip=::1"]);
+        assert_eq!(loaded_modules.modules.len(), 1);
+
+        let toml_str = toml::to_string(&loaded_modules).unwrap();
+        assert_eq!(
+            toml::from_str::<LoadedModules>(&toml_str).unwrap(),
+            loaded_modules
+        );
+        // This process is still running
+        assert!(!loaded_modules.crashed());
+    }
+}
@@ -0,0 +1,195 @@
+//! Translatable interface text, such as tab names, dialogs and messages.
+//!
+//! Text is looked up by message ID in a catalog written in a subset of the
+//! [Fluent](https://projectfluent.org/) format: `id = text` lines with
+//! `{ $name }` placeholders and `#` comments. English is built in, and
+//! translations are read from `locales/<language>.ftl` next to the
+//! configuration file, falling back to English for any message they don't
+//! have.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Context};
+
+/// Catalogs built into wiremix, by language.
+const BUILT_IN: &[(&str, &str)] = &[("en", include_str!("../locales/en.ftl"))];
+
+/// Environment variables naming the locale, in the order gettext checks
+/// them.
+const VARIABLES: [&str; 3] = ["LC_ALL", "LC_MESSAGES", "LANG"];
+
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(PartialEq))]
+pub struct Locale {
+    messages: HashMap<String, String>,
+}
+
+impl Default for Locale {
+    /// English
+    fn default() -> Self {
+        Self {
+            messages: parse(BUILT_IN[0].1)
+                .expect("the built-in English catalog should be valid"),
+        }
+    }
+}
+
+/// Parse a catalog into its messages.
+fn parse(catalog: &str) -> Result<HashMap<String, String>, anyhow::Error> {
+    let mut messages = HashMap::new();
+    for (i, line) in catalog.lines().enumerate() {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let message = line.split_once('=').and_then(|(id, text)| {
+            let id = id.trim_end();
+            is_id(id).then(|| (String::from(id), String::from(text.trim())))
+        });
+        let Some((id, text)) = message else {
+            bail!("line {}: expected 'id = text'", i + 1);
+        };
+        messages.insert(id, text);
+    }
+    Ok(messages)
+}
+
+fn is_id(s: &str) -> bool {
+    s.starts_with(|c: char| c.is_ascii_lowercase())
+        && s.chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+/// The names to look for a catalog under for a locale such as
+/// `pt_BR.UTF-8`, most specific first: `pt_BR` and then `pt`. None for the
+/// `C` and `POSIX` locales, which mean untranslated.
+fn languages(locale: &str) -> Option<Vec<String>> {
+    let locale = locale.split(['.', '@']).next().unwrap_or_default();
+    if locale.is_empty() || locale == "C" || locale == "POSIX" {
+        return None;
+    }
+    let mut languages = vec![locale.replace('-', "_")];
+    if let Some((language, _)) = locale.split_once(['_', '-']) {
+        languages.push(String::from(language));
+    }
+    Some(languages)
+}
+
+impl Locale {
+    /// Load the catalog for `locale`, or for the locale named by the
+    /// environment if it's None. Catalogs in `dir` are preferred over the
+    /// built-in ones. It's an error for `locale` to have no catalog, but
+    /// English is used if the environment's locale has none.
+    pub fn load(
+        locale: Option<&str>,
+        dir: Option<&Path>,
+        var: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self, anyhow::Error> {
+        let explicit = locale.is_some();
+        let locale = locale.map(String::from).or_else(|| {
+            VARIABLES
+                .iter()
+                .filter_map(|name| var(name))
+                .find(|value| !value.is_empty())
+        });
+        let Some(languages) = locale.as_deref().and_then(languages) else {
+            return Ok(Self::default());
+        };
+
+        let mut english = Self::default();
+        for language in &languages {
+            if let Some(dir) = dir {
+                let path = dir.join(format!("{}.ftl", language));
+                let context = || {
+                    format!("Failed to read translation '{}'", path.display())
+                };
+                match fs::read_to_string(&path) {
+                    Ok(catalog) => {
+                        english
+                            .messages
+                            .extend(parse(&catalog).with_context(context)?);
+                        return Ok(english);
+                    }
+                    Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                    Err(err) => return Err(err).with_context(context),
+                }
+            }
+            let built_in = BUILT_IN.iter().find(|(name, _)| name == language);
+            if let Some((_, catalog)) = built_in {
+                english.messages.extend(parse(catalog)?);
+                return Ok(english);
+            }
+        }
+
+        if explicit {
+            Err(anyhow!("locale '{}' has no translation", languages[0]))
+        } else {
+            Ok(english)
+        }
+    }
+
+    /// The text of a message, or its ID if there's no such message.
+    pub fn get<'a>(&'a self, id: &'a str) -> &'a str {
+        self.messages.get(id).map_or(id, String::as_str)
+    }
+
+    /// The text of a message with its `{ $name }` placeholders filled in.
+    pub fn format(&self, id: &str, args: &[(&str, &dyn Display)]) -> String {
+        let mut text = String::from(self.get(id));
+        for (name, value) in args {
+            let value = value.to_string();
+            for placeholder in
+                [format!("{{ ${} }}", name), format!("{{${}}}", name)]
+            {
+                text = text.replace(&placeholder, &value);
+            }
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_translation() {
+        let dir = std::env::temp_dir()
+            .join(format!("wiremix-locale-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("de.ftl"),
+            "# German\ntab-playback = Wiedergabe\n\
+             flash-not-found = Nicht gefunden: { $query }\n",
+        )
+        .unwrap();
+        let env =
+            |name: &str| (name == "LANG").then(|| String::from("de_AT.UTF-8"));
+
+        let locale = Locale::load(None, Some(&dir), env).unwrap();
+        assert_eq!(locale.get("tab-playback"), "Wiedergabe");
+        assert_eq!(
+            locale.format("flash-not-found", &[("query", &"x")]),
+            "Nicht gefunden: x"
+        );
+        // Untranslated messages are in English
+        assert_eq!(locale.get("tab-recording"), "Recording");
+
+        let locale = Locale::load(Some("C"), Some(&dir), env).unwrap();
+        assert_eq!(locale.get("tab-playback"), "Playback");
+        let locale = Locale::load(None, Some(&dir), |_| None).unwrap();
+        assert_eq!(locale, Locale::default());
+        let locale = Locale::load(None, None, env).unwrap();
+        assert_eq!(locale, Locale::default());
+        assert!(Locale::load(Some("fr_FR"), Some(&dir), env).is_err());
+
+        fs::write(dir.join("fr.ftl"), "tab-playback Lecture\n").unwrap();
+        assert!(Locale::load(Some("fr"), Some(&dir), env).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
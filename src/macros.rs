@@ -0,0 +1,76 @@
+//! Sequences of actions which can be replayed with a single key.
+//!
+//! Macros can be defined in the configuration file or recorded while wiremix
+//! is running. Recorded macros are saved in the XDG state directory so that
+//! they persist between sessions.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+
+use crate::app::Action;
+
+/// Actions to perform, by the character identifying the macro.
+pub type Macros = BTreeMap<String, Vec<Action>>;
+
+/// Returns the path of the file containing recorded macros.
+pub fn default_path() -> Option<PathBuf> {
+    if let Ok(xdg_state) = env::var("XDG_STATE_HOME") {
+        return Some(Path::new(&xdg_state).join("wiremix/macros.toml"));
+    }
+
+    if let Ok(home) = env::var("HOME") {
+        return Some(Path::new(&home).join(".local/state/wiremix/macros.toml"));
+    }
+
+    None
+}
+
+/// Load recorded macros. A missing file has no macros.
+pub fn load(path: &Path) -> Result<Macros, anyhow::Error> {
+    if !path.exists() {
+        return Ok(Macros::new());
+    }
+
+    let context =
+        || format!("Failed to read macros from file '{}'", path.display());
+    let toml_str = fs::read_to_string(path).with_context(context)?;
+
+    toml::from_str(&toml_str).with_context(context)
+}
+
+/// Save recorded macros, creating the parent directory if needed.
+pub fn save(path: &Path, macros: &Macros) -> Result<(), anyhow::Error> {
+    let context =
+        || format!("Failed to save macros to file '{}'", path.display());
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(context)?;
+    }
+    let toml_str = toml::to_string(macros).with_context(context)?;
+
+    fs::write(path, toml_str).with_context(context)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let macros = Macros::from([(
+            String::from("m"),
+            vec![
+                Action::SelectTab(3),
+                Action::ToggleMute,
+                Action::SetAbsoluteVolume(0.2),
+            ],
+        )]);
+
+        let toml_str = toml::to_string(&macros).unwrap();
+        assert_eq!(toml::from_str::<Macros>(&toml_str).unwrap(), macros);
+    }
+}
@@ -3,44 +3,200 @@ use std::sync::{mpsc, Arc};
 
 use anyhow::Result;
 
-use crossterm::{
-    event::{DisableMouseCapture, EnableMouseCapture},
-    ExecutableCommand,
-};
-
 use wiremix::app;
-use wiremix::command::Command;
+use wiremix::command::MixCommand;
 use wiremix::config::Config;
+use wiremix::crash;
+use wiremix::diff;
+use wiremix::exit_code;
+use wiremix::graph;
 use wiremix::input;
+use wiremix::list;
 use wiremix::monitor;
-use wiremix::opt::Opt;
+use wiremix::mute_source;
+use wiremix::opt::{BackendKind, Opt, OptCommand};
+use wiremix::signals;
+use wiremix::sleep;
+use wiremix::wait;
+use wiremix::wizard;
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("Error: {:?}", err);
+        std::process::exit(exit_code::of(&err));
+    }
+}
 
-fn main() -> Result<()> {
+fn run() -> Result<()> {
     // Event channel for sending PipeWire and input events to the UI
     let (event_tx, event_rx) = mpsc::channel();
     let event_tx = Arc::new(event_tx);
 
     // Command channel for the UI to send commands to control PipeWire
-    let (command_tx, command_rx) = pipewire::channel::channel::<Command>();
+    let (command_tx, command_rx) = pipewire::channel::channel::<MixCommand>();
 
     // Parse command-line arguments
     let opt = Opt::parse();
 
+    if opt.command == Some(OptCommand::Schema) {
+        println!("{:#}", Config::schema());
+        return Ok(());
+    }
+
+    if opt.command == Some(OptCommand::Man) {
+        print!("{}", Config::man_page());
+        return Ok(());
+    }
+
+    if let Some(OptCommand::Completions { shell }) = opt.command {
+        Opt::completions(shell, &mut stdout())?;
+        return Ok(());
+    }
+
+    if let Some(OptCommand::Diff { a, b }) = &opt.command {
+        return diff::run(a, b, opt.json);
+    }
+
     let config_default_path = Config::default_path();
     let config_path = opt.config.as_deref().or(config_default_path.as_deref());
 
+    if let Some(OptCommand::InitConfig { force }) = opt.command {
+        let Some(path) = config_path else {
+            anyhow::bail!("No configuration path (set HOME or use --config)");
+        };
+        Config::init(path, force)?;
+        if !opt.quiet {
+            println!("Wrote {}", path.display());
+        }
+        return Ok(());
+    }
+
+    if opt.command == Some(OptCommand::CheckConfig) {
+        let path = config_path.filter(|path| path.exists());
+        let result = path.map_or(Ok(()), Config::check);
+        if opt.json {
+            let report = serde_json::json!({
+                "path": path,
+                "ok": result.is_ok(),
+                "error": result.as_ref().err().map(|err| format!("{:#}", err)),
+            });
+            println!("{:#}", report);
+        } else if let Err(err) = &result {
+            eprintln!("{:#}", err);
+        } else if !opt.quiet {
+            match path {
+                Some(path) => println!("{}: OK", path.display()),
+                None => println!("No configuration file, using defaults"),
+            }
+        }
+        if result.is_err() {
+            std::process::exit(exit_code::FAILURE);
+        }
+        return Ok(());
+    }
+
+    // Offer to set up a configuration file the first time wiremix runs
+    if let Some(path) = config_path.filter(|path| {
+        opt.command.is_none() && !opt.daemon && wizard::should_run(path)
+    }) {
+        // There's no file yet, but the environment, the command line and
+        // translations next to where the file will be can choose the language
+        let locale = Config::try_new(Some(path), &opt)?.locale;
+        let mut terminal = ratatui::init();
+        let result = wizard::run(&mut terminal, &locale);
+        ratatui::restore();
+        wizard::write(path, &result?)?;
+    }
+
     let config = Config::try_new(config_path, &opt)?;
 
+    // Fall back to another backend if there's no PipeWire to connect to
+    #[cfg(any(feature = "alsa", feature = "pulse"))]
+    let backend = opt
+        .backend
+        .unwrap_or_else(|| BackendKind::detect(config.remote.as_deref()));
+    #[cfg(not(any(feature = "alsa", feature = "pulse")))]
+    let backend = BackendKind::detect(config.remote.as_deref());
+
+    // Wait for PipeWire to start rather than failing to connect, unless a
+    // subcommand or the daemon is being run
+    if backend == BackendKind::Pipewire
+        && opt.command.is_none()
+        && !opt.daemon
+        && !monitor::socket_exists(config.remote.as_deref())
+    {
+        let mut terminal = ratatui::init();
+        let result = wait::for_socket(&mut terminal, &config);
+        ratatui::restore();
+        if !result? {
+            return Ok(());
+        }
+    }
+
     // Spawn the PipeWire monitor
-    let _monitor_handle = monitor::spawn(
-        config.remote.clone(),
-        Arc::clone(&event_tx),
-        command_rx,
-    )?;
-    let _input_handle = input::spawn(Arc::clone(&event_tx));
+    let _monitor_handle = match backend {
+        BackendKind::Pipewire => monitor::spawn::<monitor::Pipewire>(
+            config.remote.clone(),
+            Arc::clone(&event_tx),
+            command_rx,
+        )?,
+        #[cfg(feature = "alsa")]
+        BackendKind::Alsa => monitor::spawn::<monitor::alsa::Alsa>(
+            (),
+            Arc::clone(&event_tx),
+            command_rx,
+        )?,
+        #[cfg(feature = "pulse")]
+        BackendKind::Pulse => monitor::spawn::<monitor::pulse::Pulse>(
+            config.remote.clone(),
+            Arc::clone(&event_tx),
+            command_rx,
+        )?,
+    };
+
+    if let Some(OptCommand::List {
+        objects,
+        format,
+        select,
+    }) = &opt.command
+    {
+        return list::run(
+            event_rx,
+            &config,
+            *objects,
+            format.as_deref(),
+            select.as_deref(),
+            opt.json,
+        );
+    }
+
+    if let Some(OptCommand::Graph { dot }) = opt.command {
+        return graph::run(event_rx, &config, dot, opt.json);
+    }
+
+    if let Some(OptCommand::MuteSource { change }) = opt.command {
+        return mute_source::run(
+            event_rx,
+            &command_tx,
+            &config,
+            change,
+            opt.quiet,
+            opt.json,
+        );
+    }
+
+    if config.sleep.is_enabled() {
+        sleep::spawn(Arc::clone(&event_tx))?;
+    }
+
+    if opt.daemon {
+        signals::install_daemon(Arc::clone(&event_tx))?;
+        return app::App::new(command_tx, event_rx, config).run_daemon();
+    }
 
     #[cfg(debug_assertions)]
     if opt.dump_events {
+        let _input_handle = input::spawn(Arc::clone(&event_tx));
         // Event dumping mode for debugging the monitor code
         for received in event_rx {
             use wiremix::event::Event;
@@ -56,17 +212,16 @@ fn main() -> Result<()> {
     }
 
     // Normal UI mode
-    let support_mouse = config.mouse;
-    if support_mouse {
-        stdout().execute(EnableMouseCapture)?;
-    }
-    let mut terminal = ratatui::init();
+    let (mouse, inline) = (config.mouse, config.inline);
+    let mut terminal = signals::init(mouse, inline, config.kitty_keyboard)?;
+    crash::install(mouse);
+    signals::install(Arc::clone(&event_tx))?;
+    // Started after the terminal is set up because detecting keyboard
+    // enhancement reads from it
+    let _input_handle = input::spawn(Arc::clone(&event_tx));
     let app_result =
         app::App::new(command_tx, event_rx, config).run(&mut terminal);
-    ratatui::restore();
-    if support_mouse {
-        stdout().execute(DisableMouseCapture)?;
-    }
+    signals::restore(&mut terminal, mouse, inline);
 
     app_result
 }
@@ -2,27 +2,36 @@
 
 use ratatui::{
     prelude::{Alignment, Buffer, Constraint, Direction, Layout, Rect, Widget},
+    style::Style,
     text::{Line, Span},
 };
 
-use crate::config::Config;
+use crate::braille::Fill;
+use crate::config::{Config, Glyphs};
 
-fn render_peak(peak: f32, area: Rect) -> (usize, usize, usize) {
-    fn normalize(value: f32) -> f32 {
-        let amplitude = 10.0_f32.powf(value / 60.0);
-        let min = 10.0_f32.powf(-60.0 / 60.0);
-        let max = 10.0_f32.powf(6.0 / 60.0);
+fn normalize(value: f32) -> f32 {
+    let amplitude = 10.0_f32.powf(value / 60.0);
+    let min = 10.0_f32.powf(-60.0 / 60.0);
+    let max = 10.0_f32.powf(6.0 / 60.0);
 
-        (amplitude - min) / (max - min)
-    }
+    (amplitude - min) / (max - min)
+}
+
+/// Inverse of [`normalize`], converting a position on the meter back to dB.
+fn denormalize(meter: f32) -> f32 {
+    let min = 10.0_f32.powf(-60.0 / 60.0);
+    let max = 10.0_f32.powf(6.0 / 60.0);
+
+    60.0 * (meter * (max - min) + min).log10()
+}
 
-    // Convert to dB between -20 and +3
+fn render_peak(peak: f32, total_chars: usize) -> (usize, usize, usize) {
+    // Convert to dB between -60 and +6
     let db = 20.0 * (peak + 1e-10).log10();
     let vu_value = db.clamp(-60.0, 6.0);
 
     let meter = normalize(vu_value);
 
-    let total_chars = area.width as usize;
     let lit = ((meter * total_chars as f32).round() as usize).min(total_chars);
 
     // Values above 0.0 will be colored differently
@@ -38,6 +47,85 @@ fn render_peak(peak: f32, area: Rect) -> (usize, usize, usize) {
     (active_size, overload_size, inactive_size)
 }
 
+/// Inactive, active and overload symbols for one side of a meter.
+type MeterSymbols<'a> = (&'a str, &'a str, &'a str);
+
+/// Symbol and style for each cell of a meter, starting from the cell that
+/// lights up first.
+fn meter_cells<'a>(
+    peak: f32,
+    total_chars: usize,
+    fill: Fill,
+    (inactive, active, overload): MeterSymbols<'a>,
+    config: &Config,
+) -> Vec<(&'a str, Style)> {
+    let theme = &config.theme;
+    let (cells, lit_chars): (Vec<(&str, Style)>, usize) = match config
+        .char_set
+        .meter_glyphs
+    {
+        Glyphs::Symbols => {
+            let (active_peak, overload_peak, inactive_peak) =
+                render_peak(peak, total_chars);
+            let cells = std::iter::repeat((active, theme.meter_active))
+                .take(active_peak)
+                .chain(
+                    std::iter::repeat((overload, theme.meter_overload))
+                        .take(overload_peak),
+                )
+                .chain(
+                    std::iter::repeat((inactive, theme.meter_inactive))
+                        .take(inactive_peak),
+                )
+                .collect();
+            (cells, active_peak + overload_peak)
+        }
+        Glyphs::Braille => {
+            let steps = fill.steps();
+            let (active_peak, overload_peak, _) =
+                render_peak(peak, total_chars * steps);
+            let lit = active_peak + overload_peak;
+            let cells = fill
+                .cells(lit, total_chars)
+                .enumerate()
+                .map(|(i, symbol)| match symbol {
+                    // Cells containing any overload steps are overloaded
+                    Some(symbol) if lit.min((i + 1) * steps) > active_peak => {
+                        (symbol, theme.meter_overload)
+                    }
+                    Some(symbol) => (symbol, theme.meter_active),
+                    None => (inactive, theme.meter_inactive),
+                })
+                .collect();
+            (cells, lit.div_ceil(steps))
+        }
+    };
+
+    cells
+        .into_iter()
+        .enumerate()
+        .map(|(i, (symbol, style))| {
+            if i >= lit_chars {
+                return (symbol, style);
+            }
+            // Level at the end of this cell
+            let db = denormalize((i + 1) as f32 / total_chars as f32);
+            match config.gradient.meter_color(db) {
+                Some(color) => (symbol, style.fg(color)),
+                None => (symbol, style),
+            }
+        })
+        .collect()
+}
+
+fn cells_line<'a>(cells: impl Iterator<Item = (&'a str, Style)>) -> Line<'a> {
+    Line::from(
+        cells
+            .map(|(symbol, style)| Span::styled(symbol, style))
+            .collect::<Vec<_>>(),
+    )
+}
+
 pub fn render_stereo(
     meter_area: Rect,
     buf: &mut Buffer,
@@ -60,43 +148,26 @@ pub fn render_stereo(
     let (left_peak, right_peak) = peaks.unwrap_or_default();
 
     let area = meter_left;
-    let (active_peak, overload_peak, inactive_peak) =
-        render_peak(left_peak, area);
-    Line::from(vec![
-        Span::styled(
-            config.char_set.meter_left_inactive.repeat(inactive_peak),
-            config.theme.meter_inactive,
-        ),
-        Span::styled(
-            config.char_set.meter_left_overload.repeat(overload_peak),
-            config.theme.meter_overload,
-        ),
-        Span::styled(
-            config.char_set.meter_left_active.repeat(active_peak),
-            config.theme.meter_active,
-        ),
-    ])
-    .alignment(Alignment::Right)
-    .render(area, buf);
+    let cells = meter_cells(
+        left_peak,
+        area.width as usize,
+        Fill::RightToLeft,
+        left_symbols(config),
+        config,
+    );
+    cells_line(cells.into_iter().rev())
+        .alignment(Alignment::Right)
+        .render(area, buf);
 
     let area = meter_right;
-    let (active_peak, overload_peak, inactive_peak) =
-        render_peak(right_peak, area);
-    Line::from(vec![
-        Span::styled(
-            config.char_set.meter_right_active.repeat(active_peak),
-            config.theme.meter_active,
-        ),
-        Span::styled(
-            config.char_set.meter_right_overload.repeat(overload_peak),
-            config.theme.meter_overload,
-        ),
-        Span::styled(
-            config.char_set.meter_right_inactive.repeat(inactive_peak),
-            config.theme.meter_inactive,
-        ),
-    ])
-    .render(area, buf);
+    let cells = meter_cells(
+        right_peak,
+        area.width as usize,
+        Fill::LeftToRight,
+        right_symbols(config),
+        config,
+    );
+    cells_line(cells.into_iter()).render(area, buf);
 
     let live_line = if peaks.is_some() {
         Line::from(Span::styled(
@@ -140,23 +211,14 @@ pub fn render_mono(
     let meter_mono = layout[1];
 
     let area = meter_mono;
-    let (active_peak, overload_peak, inactive_peak) =
-        render_peak(mono_peak, area);
-    Line::from(vec![
-        Span::styled(
-            config.char_set.meter_right_active.repeat(active_peak),
-            config.theme.meter_active,
-        ),
-        Span::styled(
-            config.char_set.meter_right_overload.repeat(overload_peak),
-            config.theme.meter_overload,
-        ),
-        Span::styled(
-            config.char_set.meter_right_inactive.repeat(inactive_peak),
-            config.theme.meter_inactive,
-        ),
-    ])
-    .render(area, buf);
+    let cells = meter_cells(
+        mono_peak,
+        area.width as usize,
+        Fill::LeftToRight,
+        right_symbols(config),
+        config,
+    );
+    cells_line(cells.into_iter()).render(area, buf);
 
     let live_line = if peak.is_some() {
         Line::from(Span::styled(
@@ -171,3 +233,60 @@ pub fn render_mono(
     };
     live_line.render(meter_live, buf);
 }
+
+/// Render a meter for each channel as a column filling from the bottom.
+pub fn render_vertical(
+    meter_area: Rect,
+    buf: &mut Buffer,
+    peaks: &[Option<f32>],
+    config: &Config,
+) {
+    let constraints = vec![Constraint::Fill(1); peaks.len()];
+    let layout = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(constraints)
+        .split(meter_area);
+
+    for (&peak, &area) in peaks.iter().zip(layout.iter()) {
+        let cells = meter_cells(
+            peak.unwrap_or_default(),
+            area.height as usize,
+            Fill::BottomToTop,
+            right_symbols(config),
+            config,
+        );
+        // Rows are rendered from the top
+        for ((symbol, style), row) in cells.into_iter().rev().zip(area.rows()) {
+            Line::from(Span::styled(symbol.repeat(row.width as usize), style))
+                .render(row, buf);
+        }
+    }
+}
+
+fn left_symbols(config: &Config) -> MeterSymbols<'_> {
+    (
+        &config.char_set.meter_left_inactive,
+        &config.char_set.meter_left_active,
+        &config.char_set.meter_left_overload,
+    )
+}
+
+fn right_symbols(config: &Config) -> MeterSymbols<'_> {
+    (
+        &config.char_set.meter_right_inactive,
+        &config.char_set.meter_right_active,
+        &config.char_set.meter_right_overload,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn denormalize_inverts_normalize() {
+        for db in [-60.0, -24.0, -12.0, 0.0, 6.0] {
+            assert!((denormalize(normalize(db)) - db).abs() < 1e-3);
+        }
+    }
+}
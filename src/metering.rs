@@ -0,0 +1,155 @@
+//! Logging the peak levels of chosen nodes to a CSV or JSON Lines file while
+//! wiremix runs, configured by [`Metering`](`crate::config::Metering`), for
+//! looking over the levels of a recording afterwards.
+//!
+//! The levels logged are the values shown on the meters, which rise and fall
+//! with VU-meter ballistics, in dBFS. Times are seconds since the Unix epoch.
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::time::{Duration, Instant, SystemTime};
+
+use anyhow::Context;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::config;
+use crate::view::{self, View};
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum MeteringFormat {
+    /// A row of time, node name and loudest peak per node
+    #[default]
+    Csv,
+    /// An object per node with the peak of each channel
+    Jsonl,
+}
+
+/// A log file being written.
+#[derive(Debug)]
+pub struct MeteringLog {
+    file: BufWriter<File>,
+    format: MeteringFormat,
+    interval: Duration,
+    /// When the next rows are due
+    next: Option<Instant>,
+}
+
+impl MeteringLog {
+    /// Open the log file for appending, or `None` if no file is configured.
+    pub fn open(
+        metering: &config::Metering,
+    ) -> Result<Option<Self>, anyhow::Error> {
+        let Some(path) = &metering.path else {
+            return Ok(None);
+        };
+        let context =
+            || format!("Failed to open metering log '{}'", path.display());
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(context)?;
+        let is_empty = file.metadata().with_context(context)?.len() == 0;
+
+        let mut file = BufWriter::new(file);
+        if is_empty && metering.format == MeteringFormat::Csv {
+            writeln!(file, "time,node,peak_dbfs").with_context(context)?;
+        }
+        Ok(Some(Self {
+            file,
+            format: metering.format,
+            interval: Duration::from_secs_f32(metering.interval),
+            next: None,
+        }))
+    }
+
+    /// Write the levels of the nodes in `metering.nodes`, or of the default
+    /// source if it's empty, when they're due.
+    pub fn log(
+        &mut self,
+        metering: &config::Metering,
+        view: &View,
+        now: Instant,
+    ) -> Result<(), anyhow::Error> {
+        if self.next.is_some_and(|next| now < next) {
+            return Ok(());
+        }
+        self.next = Some(now + self.interval);
+
+        let time = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64();
+        let nodes: Vec<&view::Node> = if metering.nodes.is_empty() {
+            view.default_source_node().into_iter().collect()
+        } else {
+            view.nodes
+                .values()
+                .filter(|node| metering.nodes.contains(&node.name))
+                .collect()
+        };
+        for node in nodes {
+            let peaks = node.peaks.as_deref().unwrap_or_default();
+            writeln!(
+                self.file,
+                "{}",
+                row(self.format, time, &node.name, peaks)
+            )
+            .context("Failed to write metering log")?;
+        }
+        self.file.flush().context("Failed to write metering log")
+    }
+}
+
+fn dbfs(peak: f32) -> f64 {
+    let db = 20.0 * f64::from(peak + 1e-10).log10();
+    (db * 10.0).round() / 10.0
+}
+
+/// A line of the log for a node with `peaks`.
+fn row(format: MeteringFormat, time: f64, name: &str, peaks: &[f32]) -> String {
+    let loudest = dbfs(peaks.iter().copied().fold(0.0, f32::max));
+    match format {
+        MeteringFormat::Csv => {
+            // Quoted in case a name contains a comma
+            let name = name.replace('"', "\"\"");
+            format!("{:.3},\"{}\",{}", time, name, loudest)
+        }
+        MeteringFormat::Jsonl => {
+            let channels: Vec<f64> = peaks.iter().map(|&p| dbfs(p)).collect();
+            json!({
+                "time": (time * 1000.0).round() / 1000.0,
+                "node": name,
+                "peak_dbfs": loudest,
+                "channels_dbfs": channels,
+            })
+            .to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows() {
+        let peaks = [0.5, 0.25];
+        assert_eq!(
+            row(MeteringFormat::Csv, 1.5, "alsa_input.usb", &peaks),
+            "1.500,\"alsa_input.usb\",-6"
+        );
+        let value: serde_json::Value = serde_json::from_str(&row(
+            MeteringFormat::Jsonl,
+            1.5,
+            "alsa_input.usb",
+            &peaks,
+        ))
+        .unwrap();
+        assert_eq!(value["node"], "alsa_input.usb");
+        assert_eq!(value["peak_dbfs"], -6.0);
+        assert_eq!(value["channels_dbfs"][1], -12.0);
+    }
+}
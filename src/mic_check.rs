@@ -0,0 +1,163 @@
+//! A guided check of a microphone's level, which listens to the input for a
+//! few seconds and suggests a volume that leaves headroom below clipping.
+//!
+//! Peaks are measured after the source's own volume is applied, so the
+//! suggestion scales the current volume by how far the loudest peak is from
+//! [`TARGET_PEAK`]. Volumes are on the cubic scale shown by the volume bars,
+//! while peaks are linear amplitudes.
+
+use std::time::{Duration, Instant};
+
+use crate::object::ObjectId;
+
+/// How long to listen before suggesting a volume.
+pub const LISTEN: Duration = Duration::from_secs(3);
+
+/// The loudest peak to aim for, -6 dBFS.
+pub const TARGET_PEAK: f32 = 0.5;
+
+/// Meters are smoothed, so a peak this close to full scale means the input
+/// is clipping.
+const CLIP_PEAK: f32 = 0.95;
+
+/// Below -40 dBFS nothing was said into the microphone, or it's muted.
+const NOISE_FLOOR: f32 = 0.01;
+
+/// The highest volume suggested, avoiding software amplification.
+const MAX_SUGGESTION: f32 = 1.0;
+
+/// What the check recommends so far.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Suggestion {
+    /// Still listening for a while longer
+    Listening,
+    /// Nothing loud enough to judge was heard
+    TooQuiet,
+    /// The volume to set
+    Volume(f32),
+}
+
+/// A level check in progress on a source.
+#[derive(Debug)]
+pub struct MicCheck {
+    pub node_id: ObjectId,
+    started: Instant,
+    /// The loudest peak heard on any channel
+    peak: f32,
+    clipped: bool,
+}
+
+impl MicCheck {
+    pub fn new(node_id: ObjectId, now: Instant) -> Self {
+        Self {
+            node_id,
+            started: now,
+            peak: 0.0,
+            clipped: false,
+        }
+    }
+
+    /// Start listening again, such as after the volume was changed by hand.
+    pub fn restart(&mut self, now: Instant) {
+        *self = Self::new(self.node_id, now);
+    }
+
+    /// Note the source's current peaks.
+    pub fn update(&mut self, peaks: &[f32]) {
+        let loudest = peaks.iter().copied().fold(0.0, f32::max);
+        self.peak = self.peak.max(loudest);
+        self.clipped |= loudest >= CLIP_PEAK;
+    }
+
+    /// The loudest peak heard so far.
+    pub fn peak(&self) -> f32 {
+        self.peak
+    }
+
+    pub fn clipped(&self) -> bool {
+        self.clipped
+    }
+
+    /// Listening progress from 0 to 1.
+    pub fn progress(&self, now: Instant) -> f32 {
+        let elapsed = now.duration_since(self.started);
+        (elapsed.as_secs_f32() / LISTEN.as_secs_f32()).min(1.0)
+    }
+
+    /// The volume to set for the loudest peak to reach `TARGET_PEAK`, given
+    /// the source's current `volume`. A clipped input was louder than could
+    /// be measured, so the suggestion is at least that much lower and worth
+    /// checking again.
+    pub fn suggestion(&self, volume: f32, now: Instant) -> Suggestion {
+        if now.duration_since(self.started) < LISTEN {
+            return Suggestion::Listening;
+        }
+        if self.peak < NOISE_FLOOR || volume <= 0.0 {
+            return Suggestion::TooQuiet;
+        }
+        let peak = if self.clipped {
+            self.peak.max(1.0)
+        } else {
+            self.peak
+        };
+        let suggested = volume * (TARGET_PEAK / peak).cbrt();
+        Suggestion::Volume(suggested.clamp(0.0, MAX_SUGGESTION))
+    }
+}
+
+/// Converts a linear peak to dBFS.
+pub fn dbfs(peak: f32) -> f32 {
+    20.0 * (peak + 1e-10).log10()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(now: Instant) -> MicCheck {
+        MicCheck::new(ObjectId::from_raw_id(1), now)
+    }
+
+    #[test]
+    fn suggest_after_listening() {
+        let now = Instant::now();
+        let mut mic_check = check(now);
+        mic_check.update(&[0.1, 0.25]);
+        mic_check.update(&[0.2, 0.1]);
+        assert_eq!(mic_check.suggestion(0.5, now), Suggestion::Listening);
+
+        // Twice as loud is needed, a factor of the cube root of 2 in volume
+        let Suggestion::Volume(volume) =
+            mic_check.suggestion(0.5, now + LISTEN)
+        else {
+            panic!("No suggestion");
+        };
+        assert!((volume - 0.5 * 2.0_f32.cbrt()).abs() < 1e-4);
+        assert!(!mic_check.clipped());
+    }
+
+    #[test]
+    fn lower_clipping_input() {
+        let now = Instant::now();
+        let mut mic_check = check(now);
+        mic_check.update(&[0.99]);
+        assert!(mic_check.clipped());
+        let Suggestion::Volume(volume) =
+            mic_check.suggestion(0.8, now + LISTEN)
+        else {
+            panic!("No suggestion");
+        };
+        assert!(volume < 0.8);
+    }
+
+    #[test]
+    fn silence_is_too_quiet() {
+        let now = Instant::now();
+        let mut mic_check = check(now);
+        mic_check.update(&[0.001]);
+        assert_eq!(
+            mic_check.suggestion(1.0, now + LISTEN),
+            Suggestion::TooQuiet
+        );
+    }
+}
@@ -0,0 +1,116 @@
+//! A Ratatui widget for the microphone level check, drawn as a box over the
+//! middle of the screen.
+
+use std::time::Instant;
+
+use ratatui::{
+    layout::{Constraint, Flex, Layout},
+    prelude::{Buffer, Rect, Widget},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear},
+};
+
+use crate::config::Config;
+use crate::meter;
+use crate::mic_check::{self, MicCheck, Suggestion};
+use crate::view;
+
+pub struct MicCheckWidget<'a> {
+    mic_check: &'a MicCheck,
+    node: &'a view::Node,
+    config: &'a Config,
+    now: Instant,
+}
+
+impl<'a> MicCheckWidget<'a> {
+    pub fn new(
+        mic_check: &'a MicCheck,
+        node: &'a view::Node,
+        config: &'a Config,
+        now: Instant,
+    ) -> Self {
+        Self {
+            mic_check,
+            node,
+            config,
+            now,
+        }
+    }
+}
+
+impl Widget for MicCheckWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let [box_area] = Layout::vertical([Constraint::Length(9)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [box_area] = Layout::horizontal([Constraint::Max(60)])
+            .flex(Flex::Center)
+            .areas(box_area);
+
+        let locale = &self.config.locale;
+        Clear.render(box_area, buf);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(self.config.theme.dropdown_border)
+            .border_set(self.config.char_set.dropdown_border)
+            .title(format!(
+                " {} ",
+                locale.format("mic-check-title", &[("name", &self.node.title)])
+            ));
+        let inner = block.inner(box_area).inner(ratatui::layout::Margin {
+            horizontal: 1,
+            vertical: 0,
+        });
+        block.render(box_area, buf);
+
+        let [instructions, level, peak, _, suggestion, _, hint] =
+            Layout::vertical([Constraint::Length(1); 7]).areas(inner);
+
+        Line::from(locale.get("mic-check-instructions"))
+            .render(instructions, buf);
+
+        let current = self
+            .node
+            .peaks
+            .as_deref()
+            .map(|peaks| peaks.iter().copied().fold(0.0, f32::max));
+        meter::render_mono(level, buf, current, self.config);
+
+        let dbfs = format!("{:.1}", mic_check::dbfs(self.mic_check.peak()));
+        let mut peak_line =
+            Line::from(locale.format("mic-check-peak", &[("dbfs", &dbfs)]));
+        if self.mic_check.clipped() {
+            peak_line.push_span(Span::styled(
+                format!("  {}", locale.get("mic-check-clipping")),
+                self.config.theme.meter_overload,
+            ));
+        }
+        peak_line.render(peak, buf);
+
+        let volume = self.node.volume().unwrap_or_default();
+        let suggestion_line = match self.mic_check.suggestion(volume, self.now)
+        {
+            Suggestion::Listening => locale.format(
+                "mic-check-listening",
+                &[(
+                    "percent",
+                    &(self.mic_check.progress(self.now) * 100.0).round(),
+                )],
+            ),
+            Suggestion::TooQuiet => {
+                String::from(locale.get("mic-check-too-quiet"))
+            }
+            Suggestion::Volume(suggested) => locale.format(
+                "mic-check-suggestion",
+                &[
+                    ("suggested", &(suggested * 100.0).round()),
+                    ("volume", &(volume * 100.0).round()),
+                ],
+            ),
+        };
+        Line::from(suggestion_line).render(suggestion, buf);
+
+        Line::styled(locale.get("mic-check-hint"), self.config.theme.flash)
+            .render(hint, buf);
+    }
+}
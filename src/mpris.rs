@@ -0,0 +1,176 @@
+//! Pausing media players through MPRIS when the output device they were
+//! playing to disappears, with
+//! [`pause_on_disconnect`](`crate::config::Config::pause_on_disconnect`),
+//! instead of letting them carry on through the laptop speakers.
+//!
+//! Players are found on the session bus with `gdbus`. A player is paused if
+//! its process ID or its name matches the client of a stream which was
+//! playing to the device, since some applications, such as browsers, play
+//! audio from a different process than the one which owns the player.
+
+use std::process::{self, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context};
+
+const PREFIX: &str = "org.mpris.MediaPlayer2.";
+
+/// How often to check whether the players have been paused.
+pub const PAUSE_POLL: Duration = Duration::from_millis(100);
+
+/// The client of a stream whose player should be paused.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Client {
+    pub process_id: Option<u32>,
+    /// application.process.binary, such as "spotify"
+    pub binary: Option<String>,
+}
+
+/// Players being paused in the background.
+#[derive(Default, Debug)]
+pub struct PausePlayers {
+    result: Option<mpsc::Receiver<Result<Vec<String>, anyhow::Error>>>,
+}
+
+impl PausePlayers {
+    pub fn is_running(&self) -> bool {
+        self.result.is_some()
+    }
+
+    /// Start pausing the players of `clients`.
+    pub fn start(&mut self, clients: Vec<Client>) {
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = tx.send(pause(&clients));
+        });
+        self.result = Some(rx);
+    }
+
+    /// Returns the names of the players paused once they all have been.
+    pub fn poll(&mut self) -> Option<Result<Vec<String>, anyhow::Error>> {
+        let result = match self.result.as_ref()?.try_recv() {
+            Ok(result) => result,
+            Err(mpsc::TryRecvError::Empty) => return None,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                Err(anyhow!("Pausing players stopped"))
+            }
+        };
+        self.result = None;
+        Some(result)
+    }
+}
+
+/// Pause the players of `clients`, returning their names.
+fn pause(clients: &[Client]) -> Result<Vec<String>, anyhow::Error> {
+    let names = call(
+        "org.freedesktop.DBus",
+        "/org/freedesktop/DBus",
+        "org.freedesktop.DBus.ListNames",
+        &[],
+    )?;
+    let mut paused = Vec::new();
+    for bus_name in parse_names(&names) {
+        let player = player_name(&bus_name);
+        let process_id = call(
+            "org.freedesktop.DBus",
+            "/org/freedesktop/DBus",
+            "org.freedesktop.DBus.GetConnectionUnixProcessID",
+            &[&bus_name],
+        )
+        .ok()
+        .and_then(|output| parse_uint(&output));
+        let matches = clients.iter().any(|client| {
+            (process_id.is_some() && client.process_id == process_id)
+                || client.binary.as_deref() == Some(player)
+        });
+        if !matches {
+            continue;
+        }
+        call(
+            &bus_name,
+            "/org/mpris/MediaPlayer2",
+            "org.mpris.MediaPlayer2.Player.Pause",
+            &[],
+        )?;
+        paused.push(String::from(player));
+    }
+    Ok(paused)
+}
+
+/// Call a method on the session bus, returning what `gdbus` prints.
+fn call(
+    dest: &str,
+    object_path: &str,
+    method: &str,
+    args: &[&str],
+) -> Result<String, anyhow::Error> {
+    let output = process::Command::new("gdbus")
+        .args(["call", "--session", "--timeout", "1"])
+        .args(["--dest", dest, "--object-path", object_path])
+        .args(["--method", method])
+        .args(args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .context("Failed to run gdbus")?;
+    if !output.status.success() {
+        bail!("Failed to call {} on {}", method, dest);
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// The MPRIS players in the output of ListNames, which looks like
+/// `(['org.freedesktop.DBus', 'org.mpris.MediaPlayer2.spotify'],)`.
+fn parse_names(output: &str) -> Vec<String> {
+    output
+        .split('\'')
+        .skip(1)
+        .step_by(2)
+        .filter(|name| name.starts_with(PREFIX))
+        .map(String::from)
+        .collect()
+}
+
+/// The number in output such as `(uint32 1234,)`.
+fn parse_uint(output: &str) -> Option<u32> {
+    output
+        .trim()
+        .trim_start_matches("(uint32 ")
+        .trim_end_matches(",)")
+        .parse()
+        .ok()
+}
+
+/// The player in a bus name, such as "firefox" for
+/// `org.mpris.MediaPlayer2.firefox.instance_1_84`.
+fn player_name(bus_name: &str) -> &str {
+    let player = bus_name.strip_prefix(PREFIX).unwrap_or(bus_name);
+    player.split('.').next().unwrap_or(player)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_gdbus_output() {
+        let names = parse_names(
+            "(['org.freedesktop.DBus', ':1.4', \
+             'org.mpris.MediaPlayer2.spotify', \
+             'org.mpris.MediaPlayer2.firefox.instance_1_84'],)\n",
+        );
+        assert_eq!(
+            names,
+            [
+                "org.mpris.MediaPlayer2.spotify",
+                "org.mpris.MediaPlayer2.firefox.instance_1_84"
+            ]
+        );
+        assert_eq!(player_name(&names[0]), "spotify");
+        assert_eq!(player_name(&names[1]), "firefox");
+        assert_eq!(parse_uint("(uint32 1234,)\n"), Some(1234));
+        assert_eq!(parse_uint("()"), None);
+    }
+}
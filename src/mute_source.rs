@@ -0,0 +1,76 @@
+//! Muting or unmuting the default source for `wiremix mute-source`.
+
+use std::sync::mpsc;
+
+use anyhow::anyhow;
+use serde_json::json;
+
+use crate::command::MixCommand;
+use crate::config::Config;
+use crate::event::Event;
+use crate::exit_code;
+use crate::list;
+use crate::opt::MuteChange;
+use crate::view::View;
+
+/// Wait for the initial objects from the monitor, change the mute state of
+/// the default source and wait for the change to be made.
+pub fn run(
+    event_rx: mpsc::Receiver<Event>,
+    command_tx: &pipewire::channel::Sender<MixCommand>,
+    config: &Config,
+    change: MuteChange,
+    quiet: bool,
+    json: bool,
+) -> Result<(), anyhow::Error> {
+    let state = list::initial_state(&event_rx)?;
+    let view = View::from(&state, &config.names);
+    let Some(node) = view.default_source_node() else {
+        let error = String::from("No default source");
+        return Err(exit_code::Error::NotFound(error).into());
+    };
+
+    let mute = match change {
+        MuteChange::Toggle => !node.mute,
+        MuteChange::On => true,
+        MuteChange::Off => false,
+    };
+    let command = view
+        .set_mute(node.id, mute)
+        .ok_or_else(|| anyhow!("Can't mute {}", node.title))?;
+    let id = 0;
+    command_tx
+        .send(MixCommand { id, command })
+        .map_err(|_| anyhow!("Monitor stopped"))?;
+
+    loop {
+        let Ok(received) = event_rx.recv() else {
+            let error = String::from("Monitor stopped");
+            return Err(exit_code::Error::Unreachable(error).into());
+        };
+        match received {
+            Event::CommandResult(result_id, result) if result_id == id => {
+                result?;
+                break;
+            }
+            Event::Error(error) if error.is_fatal() => {
+                return Err(error.into());
+            }
+            _ => {}
+        }
+    }
+
+    if json {
+        let report = json!({
+            "id": u32::from(node.id),
+            "name": node.title,
+            "mute": mute,
+        });
+        println!("{:#}", report);
+    } else if !quiet {
+        let verb = if mute { "Muted" } else { "Unmuted" };
+        println!("{} {}", verb, node.title);
+    }
+
+    Ok(())
+}
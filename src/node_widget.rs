@@ -3,6 +3,7 @@
 use ratatui::{
     layout::Flex,
     prelude::{Alignment, Buffer, Constraint, Direction, Layout, Rect},
+    style::Style,
     text::{Line, Span},
     widgets::{StatefulWidget, Widget},
 };
@@ -11,14 +12,67 @@ use crossterm::event::{MouseButton, MouseEventKind};
 use smallvec::smallvec;
 
 use crate::app::{Action, MouseArea};
-use crate::config::{Config, Peaks};
+use crate::braille::Fill;
+use crate::config::{Column, ColumnKind, Config, Glyphs, Peaks};
 use crate::device_kind::DeviceKind;
 use crate::meter;
 use crate::object_list::ObjectList;
 use crate::truncate;
 use crate::view;
 
-fn is_default(node: &view::Node, device_kind: Option<DeviceKind>) -> bool {
+/// Symbol and style for each cell of a volume bar, starting from the cell
+/// that fills first.
+pub fn volume_cells<'a>(
+    volume: f32,
+    total_chars: usize,
+    fill: Fill,
+    filled: &'a str,
+    empty: &'a str,
+    config: &'a Config,
+) -> Vec<(&'a str, Style)> {
+    let fraction = volume.clamp(0.0, 1.5) / 1.5;
+    let theme = &config.theme;
+
+    let symbols: Vec<Option<&str>> = match config.char_set.volume_glyphs {
+        Glyphs::Symbols => {
+            let count = (fraction * total_chars as f32) as usize;
+            (0..total_chars)
+                .map(|i| (i < count).then_some(filled))
+                .collect()
+        }
+        Glyphs::Braille => {
+            let lit = (fraction * (total_chars * fill.steps()) as f32) as usize;
+            fill.cells(lit, total_chars).collect()
+        }
+    };
+
+    symbols
+        .into_iter()
+        .enumerate()
+        .map(|(i, symbol)| match symbol {
+            Some(symbol) => {
+                // Volume in percent at the end of this cell
+                let percent = (i + 1) as f32 / total_chars as f32 * 150.0;
+                let style = match config.gradient.volume_color(percent) {
+                    Some(color) => theme.volume_filled.fg(color),
+                    None => theme.volume_filled,
+                };
+                (symbol, style)
+            }
+            None => (empty, theme.volume_empty),
+        })
+        .collect()
+}
+
+/// Fit a title into `len` columns, either scrolled as a marquee or truncated.
+pub fn fit_title(title: &str, len: usize, marquee: Option<usize>) -> String {
+    match marquee {
+        Some(offset) => truncate::marquee(title, len, offset),
+        None => truncate::with_ellipses(title, len),
+    }
+}
+
+pub fn is_default(node: &view::Node, device_kind: Option<DeviceKind>) -> bool {
     match device_kind {
         Some(DeviceKind::Sink) => node.is_default_sink,
         Some(DeviceKind::Source) => node.is_default_source,
@@ -26,7 +80,22 @@ fn is_default(node: &view::Node, device_kind: Option<DeviceKind>) -> bool {
     }
 }
 
-fn node_title(node: &view::Node, device_kind: Option<DeviceKind>) -> &str {
+/// Whether a stream's effective volume exceeds the loudness_warning threshold.
+pub fn is_loud(node: &view::Node, config: &Config) -> bool {
+    match (config.loudness_warning, node.effective_volume) {
+        (Some(threshold), Some(volume)) => {
+            !node.mute && (volume * 100.0).round() > threshold
+        }
+        _ => false,
+    }
+}
+
+/// Whether a battery level is at or below `low_battery_warning`.
+pub fn is_low_battery(battery: u8, config: &Config) -> bool {
+    config.low_battery_warning > 0 && battery <= config.low_battery_warning
+}
+
+pub fn node_title(node: &view::Node, device_kind: Option<DeviceKind>) -> &str {
     match (device_kind, &node.title_source_sink) {
         (
             Some(DeviceKind::Source | DeviceKind::Sink),
@@ -40,6 +109,9 @@ pub struct NodeWidget<'a> {
     node: &'a view::Node,
     selected: bool,
     device_kind: Option<DeviceKind>,
+    compact: bool,
+    columns: &'a [Column],
+    marquee: Option<usize>,
     config: &'a Config,
 }
 
@@ -54,18 +126,47 @@ impl<'a> NodeWidget<'a> {
             node,
             selected,
             device_kind,
+            compact: false,
+            columns: &[],
+            marquee: None,
             config,
         }
     }
 
+    /// Render the node on a single line.
+    pub fn compact(mut self, compact: bool) -> Self {
+        self.compact = compact;
+        self
+    }
+
+    /// Render the node on a single line made up of these columns instead.
+    pub fn columns(mut self, columns: &'a [Column]) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    /// Scroll the title by this many characters if it doesn't fit.
+    pub fn marquee(mut self, offset: Option<usize>) -> Self {
+        self.marquee = offset;
+        self
+    }
+
     /// Height of a full node display.
-    pub fn height() -> u16 {
-        3
+    pub fn height(compact: bool) -> u16 {
+        if compact {
+            1
+        } else {
+            3
+        }
     }
 
     /// Spacing between nodes
-    pub fn spacing() -> u16 {
-        2
+    pub fn spacing(compact: bool) -> u16 {
+        if compact {
+            1
+        } else {
+            2
+        }
     }
 
     /// Area for the target dropdown
@@ -144,6 +245,16 @@ impl StatefulWidget for NodeWidget<'_> {
         let selected_area = layout[0];
         let node_area = layout[1];
 
+        if !self.columns.is_empty() {
+            self.render_columns(selected_area, node_area, buf, mouse_areas);
+            return;
+        }
+
+        if self.compact {
+            self.render_compact(selected_area, node_area, buf, mouse_areas);
+            return;
+        }
+
         if self.selected {
             // Render and indication that this is the selected node.
             let rows = Layout::default()
@@ -178,27 +289,7 @@ impl StatefulWidget for NodeWidget<'_> {
         let header_area = layout[0];
         let bar_area = layout[1];
 
-        let node_title = node_title(self.node, self.device_kind);
-        let target_line = match self.node.target {
-            Some(view::Target::Default) => {
-                // Add the default target indicator
-                Line::from(vec![
-                    Span::styled(
-                        &self.config.char_set.default_stream,
-                        self.config.theme.default_stream,
-                    ),
-                    Span::from(" "),
-                    Span::styled(
-                        &self.node.target_title,
-                        self.config.theme.node_target,
-                    ),
-                ])
-            }
-            _ => Line::from(Span::styled(
-                &self.node.target_title,
-                self.config.theme.node_target,
-            )),
-        };
+        let target_line = self.target_line();
 
         let layout = Layout::default()
             .direction(Direction::Horizontal)
@@ -224,24 +315,7 @@ impl StatefulWidget for NodeWidget<'_> {
             ],
         ));
 
-        let default_span = if is_default(self.node, self.device_kind) {
-            Span::styled(
-                &self.config.char_set.default_device,
-                self.config.theme.default_device,
-            )
-        } else {
-            Span::from(" ")
-        };
-        let node_title = truncate::with_ellipses(
-            node_title,
-            (header_left.width.saturating_sub(2)) as usize,
-        );
-        Line::from(vec![
-            default_span,
-            Span::from(" "),
-            Span::styled(node_title, self.config.theme.node_title),
-        ])
-        .render(header_left, buf);
+        self.title_line(header_left.width).render(header_left, buf);
 
         let constraints = if self.config.peaks != Peaks::Off {
             vec![
@@ -278,33 +352,149 @@ impl StatefulWidget for NodeWidget<'_> {
         let volume_label = layout[0];
         let volume_bar = layout[1];
 
-        let volumes = &self.node.volumes;
-        if !volumes.is_empty() {
-            let mean = volumes.iter().sum::<f32>() / volumes.len() as f32;
-            let volume = mean.cbrt();
-            let percent = (volume * 100.0).round() as u32;
+        self.render_volume(volume_label, volume_bar, buf, mouse_areas);
+
+        // Render peaks
+        if let Some(meter_area) = meter_area {
+            match self.node.peaks.as_deref() {
+                Some([left, right]) if self.config.peaks != Peaks::Mono => {
+                    meter::render_stereo(
+                        meter_area,
+                        buf,
+                        Some((*left, *right)),
+                        self.config,
+                    )
+                }
+                Some(peaks @ [..]) => meter::render_mono(
+                    meter_area,
+                    buf,
+                    (!peaks.is_empty()).then_some(
+                        peaks.iter().sum::<f32>() / peaks.len() as f32,
+                    ),
+                    self.config,
+                ),
+                _ => match self
+                    .node
+                    .positions
+                    .as_ref()
+                    .map(|positions| positions.len())
+                {
+                    Some(2) if self.config.peaks != Peaks::Mono => {
+                        meter::render_stereo(meter_area, buf, None, self.config)
+                    }
+                    _ => meter::render_mono(meter_area, buf, None, self.config),
+                },
+            }
+        }
+    }
+}
+
+impl NodeWidget<'_> {
+    /// The node's title preceded by the default endpoint or loudness
+    /// indicator, fit within width columns.
+    fn title_line(&self, width: u16) -> Line<'_> {
+        let default_span = if is_default(self.node, self.device_kind) {
+            Span::styled(
+                &self.config.char_set.default_device,
+                self.config.theme.default_device,
+            )
+        } else if is_loud(self.node, self.config) {
+            Span::styled(
+                &self.config.char_set.loud_warning,
+                self.config.theme.loud_warning,
+            )
+        } else {
+            Span::from(" ")
+        };
+        let node_title = fit_title(
+            node_title(self.node, self.device_kind),
+            (width.saturating_sub(2)) as usize,
+            self.marquee,
+        );
+        Line::from(vec![
+            default_span,
+            Span::from(" "),
+            Span::styled(node_title, self.config.theme.node_title),
+        ])
+    }
+
+    /// The node's target, with indicators if it's the default target or if
+    /// the stream is paused, pinned or being resampled, and the battery level
+    /// of Bluetooth devices.
+    fn target_line(&self) -> Line<'_> {
+        let mut spans = Vec::new();
+        if let Some(battery) = self.node.battery {
+            let style = if is_low_battery(battery, self.config) {
+                self.config.theme.loud_warning
+            } else {
+                self.config.theme.node_target
+            };
+            spans.push(Span::styled(format!("{}%", battery), style));
+            spans.push(Span::from(" "));
+        }
+        if self.node.paused {
+            spans.push(Span::styled(
+                &self.config.char_set.paused,
+                self.config.theme.paused,
+            ));
+            spans.push(Span::from(" "));
+        }
+        if self.node.pinned {
+            spans.push(Span::styled(
+                &self.config.char_set.pinned,
+                self.config.theme.pinned,
+            ));
+            spans.push(Span::from(" "));
+        }
+        if self.node.resample.is_some() {
+            spans.push(Span::styled(
+                &self.config.char_set.resampled,
+                self.config.theme.resampled,
+            ));
+            spans.push(Span::from(" "));
+        }
+        if let Some(view::Target::Default) = self.node.target {
+            // Add the default target indicator
+            spans.push(Span::styled(
+                &self.config.char_set.default_stream,
+                self.config.theme.default_stream,
+            ));
+            spans.push(Span::from(" "));
+        }
+        spans.push(Span::styled(
+            &self.node.target_title,
+            self.config.theme.node_target,
+        ));
+        Line::from(spans)
+    }
+
+    /// Render the volume label and bar along with their mouse areas.
+    fn render_volume(
+        &self,
+        volume_label: Rect,
+        volume_bar: Rect,
+        buf: &mut Buffer,
+        mouse_areas: &mut Vec<MouseArea>,
+    ) {
+        self.render_volume_label(volume_label, buf, mouse_areas);
+        self.render_volume_bar(volume_bar, buf, mouse_areas);
+    }
 
+    /// Render the volume percentage, or that the node is muted, along with
+    /// its mouse area.
+    fn render_volume_label(
+        &self,
+        volume_label: Rect,
+        buf: &mut Buffer,
+        mouse_areas: &mut Vec<MouseArea>,
+    ) {
+        if let Some(percent) = self.node.volume_percent() {
             Line::from(Span::styled(
                 format!("{}%", percent),
                 self.config.theme.volume,
             ))
             .alignment(Alignment::Right)
             .render(volume_label, buf);
-
-            let count = ((volume.clamp(0.0, 1.5) / 1.5)
-                * volume_bar.width as f32) as usize;
-
-            let filled = self.config.char_set.volume_filled.repeat(count);
-            let blank = self
-                .config
-                .char_set
-                .volume_empty
-                .repeat((volume_bar.width as usize).saturating_sub(count));
-            Line::from(vec![
-                Span::styled(filled, self.config.theme.volume_filled),
-                Span::styled(blank, self.config.theme.volume_empty),
-            ])
-            .render(volume_bar, buf);
         }
         if self.node.mute {
             Line::from("muted").render(volume_label, buf);
@@ -315,6 +505,36 @@ impl StatefulWidget for NodeWidget<'_> {
             smallvec![MouseEventKind::Down(MouseButton::Left)],
             smallvec![Action::SelectObject(self.node.id), Action::ToggleMute],
         ));
+    }
+
+    /// Render the volume bar along with its mouse areas.
+    fn render_volume_bar(
+        &self,
+        volume_bar: Rect,
+        buf: &mut Buffer,
+        mouse_areas: &mut Vec<MouseArea>,
+    ) {
+        let volumes = &self.node.volumes;
+        if !volumes.is_empty() {
+            let mean = volumes.iter().sum::<f32>() / volumes.len() as f32;
+            let volume = mean.cbrt();
+
+            let cells = volume_cells(
+                volume,
+                volume_bar.width as usize,
+                Fill::LeftToRight,
+                &self.config.char_set.volume_filled,
+                &self.config.char_set.volume_empty,
+                self.config,
+            );
+            Line::from(
+                cells
+                    .into_iter()
+                    .map(|(symbol, style)| Span::styled(symbol, style))
+                    .collect::<Vec<_>>(),
+            )
+            .render(volume_bar, buf);
+        }
 
         // Add mouse areas for setting volume
         for i in 0..=volume_bar.width {
@@ -347,37 +567,177 @@ impl StatefulWidget for NodeWidget<'_> {
                 ],
             ));
         }
+    }
 
-        // Render peaks
-        if let Some(meter_area) = meter_area {
-            match self.node.peaks.as_deref() {
-                Some([left, right]) if self.config.peaks != Peaks::Mono => {
-                    meter::render_stereo(
-                        meter_area,
-                        buf,
-                        Some((*left, *right)),
-                        self.config,
-                    )
+    fn render_compact(
+        self,
+        selected_area: Rect,
+        node_area: Rect,
+        buf: &mut Buffer,
+        mouse_areas: &mut Vec<MouseArea>,
+    ) {
+        if self.selected {
+            Span::styled(
+                &self.config.char_set.selector_middle,
+                self.config.theme.selector,
+            )
+            .render(selected_area, buf);
+        }
+
+        let layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Fill(1),   // title_area
+                Constraint::Length(1), // bracket_left
+                Constraint::Fill(1),   // volume_bar
+                Constraint::Length(1), // bracket_right
+                Constraint::Length(5), // volume_label
+            ])
+            .horizontal_margin(1)
+            .spacing(1)
+            .split(node_area);
+        let title_area = layout[0];
+        let volume_bar = layout[2];
+        let volume_label = layout[4];
+
+        self.title_line(title_area.width).render(title_area, buf);
+
+        // The brackets hug the bar, so render them in the spacing around it.
+        Span::from("[").render(
+            Rect::new(volume_bar.x.saturating_sub(1), volume_bar.y, 1, 1),
+            buf,
+        );
+        Span::from("]")
+            .render(Rect::new(volume_bar.right(), volume_bar.y, 1, 1), buf);
+
+        self.render_volume(volume_label, volume_bar, buf, mouse_areas);
+    }
+
+    fn render_columns(
+        self,
+        selected_area: Rect,
+        node_area: Rect,
+        buf: &mut Buffer,
+        mouse_areas: &mut Vec<MouseArea>,
+    ) {
+        if self.selected {
+            Span::styled(
+                &self.config.char_set.selector_middle,
+                self.config.theme.selector,
+            )
+            .render(selected_area, buf);
+        }
+
+        let constraints =
+            self.columns.iter().map(|column| match column.width {
+                Some(width) => Constraint::Length(width),
+                None => Constraint::Fill(1),
+            });
+        let layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(constraints)
+            .horizontal_margin(1)
+            .spacing(1)
+            .split(node_area);
+
+        let volumes = &self.node.volumes;
+        let mean = (!volumes.is_empty())
+            .then(|| volumes.iter().sum::<f32>() / volumes.len() as f32);
+
+        for (column, &area) in self.columns.iter().zip(layout.iter()) {
+            match column.kind {
+                ColumnKind::Name => {
+                    self.title_line(area.width).render(area, buf);
+                }
+                ColumnKind::Volume => {
+                    self.render_volume_bar(area, buf, mouse_areas);
+                }
+                ColumnKind::Percent => {
+                    self.render_volume_label(area, buf, mouse_areas);
+                }
+                ColumnKind::Db => {
+                    let db = match mean {
+                        Some(mean) if mean > 0.0 => {
+                            format!("{:.1} dB", 20.0 * mean.log10())
+                        }
+                        Some(_) => String::from("-inf dB"),
+                        None => String::new(),
+                    };
+                    Line::from(Span::styled(db, self.config.theme.volume))
+                        .alignment(Alignment::Right)
+                        .render(area, buf);
+                }
+                ColumnKind::MediaClass => {
+                    Line::from(self.node.media_class.as_str())
+                        .render(area, buf);
+                }
+                ColumnKind::Client => {
+                    let client =
+                        self.node.client.as_deref().unwrap_or_default();
+                    Line::from(truncate::with_ellipses(
+                        client,
+                        area.width as usize,
+                    ))
+                    .render(area, buf);
+                }
+                ColumnKind::Latency => {
+                    let latency = self
+                        .node
+                        .latency
+                        .map(|latency| format!("{:.1} ms", latency))
+                        .unwrap_or_default();
+                    Line::from(latency)
+                        .alignment(Alignment::Right)
+                        .render(area, buf);
+                }
+                ColumnKind::Format => {
+                    let format =
+                        self.node.format.as_deref().unwrap_or_default();
+                    Line::from(truncate::with_ellipses(
+                        format,
+                        area.width as usize,
+                    ))
+                    .render(area, buf);
+                }
+                ColumnKind::ChannelMap => {
+                    let channel_map =
+                        self.node.channel_map.as_deref().unwrap_or_default();
+                    Line::from(truncate::with_ellipses(
+                        channel_map,
+                        area.width as usize,
+                    ))
+                    .render(area, buf);
+                }
+                ColumnKind::Resample => {
+                    let resample = match self.node.resample {
+                        Some((rate, graph_rate)) => {
+                            let quality = self
+                                .node
+                                .resample_quality
+                                .as_ref()
+                                .map(|quality| format!(" (quality {quality})"))
+                                .unwrap_or_default();
+                            format!("{rate} to {graph_rate} Hz{quality}")
+                        }
+                        None => String::new(),
+                    };
+                    Line::from(truncate::with_ellipses(
+                        &resample,
+                        area.width as usize,
+                    ))
+                    .render(area, buf);
+                }
+                ColumnKind::Target => {
+                    self.target_line().render(area, buf);
+                    mouse_areas.push((
+                        area,
+                        smallvec![MouseEventKind::Down(MouseButton::Left)],
+                        smallvec![
+                            Action::SelectObject(self.node.id),
+                            Action::ActivateDropdown
+                        ],
+                    ));
                 }
-                Some(peaks @ [..]) => meter::render_mono(
-                    meter_area,
-                    buf,
-                    (!peaks.is_empty()).then_some(
-                        peaks.iter().sum::<f32>() / peaks.len() as f32,
-                    ),
-                    self.config,
-                ),
-                _ => match self
-                    .node
-                    .positions
-                    .as_ref()
-                    .map(|positions| positions.len())
-                {
-                    Some(2) if self.config.peaks != Peaks::Mono => {
-                        meter::render_stereo(meter_area, buf, None, self.config)
-                    }
-                    _ => meter::render_mono(meter_area, buf, None, self.config),
-                },
             }
         }
     }
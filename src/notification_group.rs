@@ -0,0 +1,245 @@
+//! A row in the Playback tab controlling the volume and mute of every
+//! notification and event sound stream at once, with
+//! [`notification_group`](`crate::config::Config::notification_group`),
+//! since those streams come and go too quickly to be adjusted one by one.
+//!
+//! The row is a node added to the [`View`] with an ID PipeWire never uses.
+//! Volume and mute commands for it are turned into commands for the streams
+//! in the group, scaling each stream's volume by the change in the group's.
+//! Streams which appear later are scaled by the group's volume and muted if
+//! it's muted.
+
+use std::collections::HashSet;
+
+use crate::command::Command;
+use crate::linked;
+use crate::media_class::MediaClass;
+use crate::object::ObjectId;
+use crate::view::{self, View};
+
+/// The ID of the group's row, which is SPA_ID_INVALID in PipeWire.
+pub const GROUP_ID: ObjectId = ObjectId::from_raw_id(u32::MAX);
+
+/// media.role values of the streams in the group.
+const ROLES: [&str; 2] = ["Notification", "Event"];
+
+/// The group's volume and mute, and the streams already adjusted for them.
+#[derive(Debug)]
+pub struct NotificationGroup {
+    /// Linear volume, where 1.0 leaves the streams' own volumes alone
+    volume: f32,
+    mute: bool,
+    /// Streams in the group which have been adjusted
+    seen: HashSet<ObjectId>,
+}
+
+impl Default for NotificationGroup {
+    fn default() -> Self {
+        Self {
+            volume: 1.0,
+            mute: false,
+            seen: HashSet::new(),
+        }
+    }
+}
+
+fn is_member(node: &view::Node) -> bool {
+    node.media_class.is_sink_input()
+        && !node.ended
+        && node.media_role.as_deref().is_some_and(|role| {
+            ROLES.iter().any(|member| member.eq_ignore_ascii_case(role))
+        })
+}
+
+impl NotificationGroup {
+    /// Commands applying the group's volume and mute to streams which have
+    /// joined it since the last update.
+    pub fn update(&mut self, view: &View) -> Vec<Command> {
+        let members: Vec<&view::Node> =
+            view.nodes.values().filter(|node| is_member(node)).collect();
+        self.seen
+            .retain(|node_id| members.iter().any(|node| node.id == *node_id));
+
+        let mut commands = Vec::new();
+        for node in members {
+            if !self.seen.insert(node.id) {
+                continue;
+            }
+            if self.volume != 1.0 {
+                let volumes =
+                    linked::scaled(&node.volumes, &[1.0], &[self.volume]);
+                commands.extend(view.set_volumes(node.id, volumes));
+            }
+            if self.mute && !node.mute {
+                commands.extend(view.set_mute(node.id, true));
+            }
+        }
+        commands
+    }
+
+    /// Add the group's row to the top of the Playback tab.
+    pub fn add_to(&self, view: &mut View) {
+        let count = self.seen.len();
+        let target_title = match count {
+            0 => String::from("No streams"),
+            1 => String::from("1 stream"),
+            count => format!("{} streams", count),
+        };
+        let node = view::Node {
+            id: GROUP_ID,
+            object_serial: i32::MAX,
+            name: String::from("wiremix.notifications"),
+            title: String::from("Notification sounds"),
+            title_source_sink: None,
+            media_class: MediaClass::from("Stream/Output/Audio"),
+            routes: None,
+            target_title,
+            target: None,
+            volumes: vec![self.volume; 2],
+            mute: self.mute,
+            peaks: None,
+            positions: None,
+            client: None,
+            media_role: None,
+            latency: None,
+            format: None,
+            channel_map: None,
+            resample: None,
+            resample_quality: None,
+            upmix: None,
+            network: false,
+            jack: false,
+            paused: false,
+            ended: false,
+            pinned: false,
+            battery: None,
+            recording: false,
+            effective_volume: None,
+            device_info: None,
+            is_default_sink: false,
+            is_default_source: false,
+        };
+        view.nodes.insert(GROUP_ID, node);
+        for list in [&mut view.nodes_playback, &mut view.nodes_all] {
+            list.retain(|node_id| *node_id != GROUP_ID);
+            list.insert(0, GROUP_ID);
+        }
+    }
+
+    /// The commands to send instead of `command` if it's for the group's
+    /// row, which also changes the group's volume or mute.
+    pub fn commands(
+        &mut self,
+        view: &View,
+        command: &Command,
+    ) -> Option<Vec<Command>> {
+        let members = || view.nodes.values().filter(|node| is_member(node));
+        match command {
+            Command::NodeVolumes(GROUP_ID, volumes) => {
+                let before = [self.volume];
+                self.volume = mean(volumes);
+                Some(
+                    members()
+                        .filter_map(|node| {
+                            let volumes =
+                                linked::scaled(&node.volumes, &before, volumes);
+                            view.set_volumes(node.id, volumes)
+                        })
+                        .collect(),
+                )
+            }
+            Command::NodeMute(GROUP_ID, mute) => {
+                self.mute = *mute;
+                Some(
+                    members()
+                        .filter_map(|node| view.set_mute(node.id, *mute))
+                        .collect(),
+                )
+            }
+            // Nothing else can be done to the row, such as moving it
+            Command::MetadataSetProperty(_, subject, ..)
+                if *subject == u32::from(GROUP_ID) =>
+            {
+                Some(Vec::new())
+            }
+            Command::NodeChannelMap(GROUP_ID, ..)
+            | Command::NodeUpmix(GROUP_ID, _) => Some(Vec::new()),
+            _ => None,
+        }
+    }
+}
+
+fn mean(volumes: &[f32]) -> f32 {
+    if volumes.is_empty() {
+        return 0.0;
+    }
+    volumes.iter().sum::<f32>() / volumes.len() as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capture_manager::CaptureManager;
+    use crate::config::Names;
+    use crate::event::MonitorEvent;
+    use crate::state::State;
+
+    fn add_stream(state: &mut State, id: u32, role: &str) {
+        let id = ObjectId::from_raw_id(id);
+        for event in [
+            MonitorEvent::NodeName(id, format!("stream{}", u32::from(id))),
+            MonitorEvent::NodeDescription(id, String::from("Sound")),
+            MonitorEvent::NodeObjectSerial(id, u32::from(id) as i32),
+            MonitorEvent::NodeMediaClass(
+                id,
+                MediaClass::from("Stream/Output/Audio"),
+            ),
+            MonitorEvent::NodeMediaRole(id, String::from(role)),
+            MonitorEvent::NodePositions(id, vec![0, 1]),
+            MonitorEvent::NodeVolumes(id, vec![0.5, 0.5]),
+            MonitorEvent::NodeMute(id, false),
+        ] {
+            state.update(&mut CaptureManager::default(), event);
+        }
+    }
+
+    fn volumes(commands: &[Command]) -> Vec<(u32, Vec<f32>)> {
+        commands
+            .iter()
+            .filter_map(|command| match command {
+                Command::NodeVolumes(id, volumes) => {
+                    Some((u32::from(*id), volumes.clone()))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn scale_members() {
+        let mut state = State::default();
+        add_stream(&mut state, 1, "Notification");
+        add_stream(&mut state, 2, "Music");
+        let view = View::from(&state, &Names::default());
+
+        let mut group = NotificationGroup::default();
+        assert!(group.update(&view).is_empty());
+
+        let command = Command::NodeVolumes(GROUP_ID, vec![0.5, 0.5]);
+        let commands = group.commands(&view, &command).unwrap();
+        assert_eq!(volumes(&commands), [(1, vec![0.25, 0.25])]);
+
+        // A new stream joins at the group's volume
+        add_stream(&mut state, 3, "event");
+        let view = View::from(&state, &Names::default());
+        assert_eq!(volumes(&group.update(&view)), [(3, vec![0.25, 0.25])]);
+
+        let mut view = view;
+        group.add_to(&mut view);
+        assert_eq!(view.nodes_playback[0], GROUP_ID);
+        assert_eq!(view.nodes[&GROUP_ID].target_title, "2 streams");
+
+        let other = Command::NodeVolumes(ObjectId::from_raw_id(2), vec![1.0]);
+        assert!(group.commands(&view, &other).is_none());
+    }
+}
@@ -1,9 +1,15 @@
 //! A Ratatui widget for an interactable list of PipeWire objects.
 
+use std::time::{Duration, Instant};
+
 use ratatui::{
     prelude::{Alignment, Buffer, Constraint, Direction, Layout, Rect},
+    style::Style,
     text::{Line, Span},
-    widgets::{ListState, StatefulWidget, Widget},
+    widgets::{
+        ListState, Scrollbar, ScrollbarOrientation, ScrollbarState,
+        StatefulWidget, Widget,
+    },
 };
 
 use crossterm::event::{MouseButton, MouseEventKind};
@@ -11,14 +17,20 @@ use smallvec::smallvec;
 
 use crate::app::{Action, MouseArea};
 use crate::command::Command;
-use crate::config::Config;
+use crate::config::{Column, Config, Orientation};
 use crate::device_kind::DeviceKind;
 use crate::device_widget::DeviceWidget;
 use crate::dropdown_widget::DropdownWidget;
-use crate::node_widget::NodeWidget;
+use crate::fader_widget::FaderWidget;
+use crate::node_widget::{self, NodeWidget};
 use crate::object::ObjectId;
 use crate::view::{self, ListKind, VolumeAdjustment};
 
+/// How often a scrolling title advances by one character.
+pub const MARQUEE_STEP: Duration = Duration::from_millis(250);
+/// How long a title is shown from the start before it begins scrolling.
+const MARQUEE_DELAY: Duration = Duration::from_secs(1);
+
 /// ObjectList stores information for filtering and displaying a subset of
 /// objects from a [`View`](`crate::view::View`).
 ///
@@ -27,8 +39,12 @@ use crate::view::{self, ListKind, VolumeAdjustment};
 pub struct ObjectList {
     /// Index of the first object in viewport
     top: usize,
+    /// Number of objects that fit entirely in the viewport
+    objects_visible: usize,
     /// ID of the currently selected object
     pub selected: Option<ObjectId>,
+    /// Where the selection started in vi visual mode
+    pub visual: Option<ObjectId>,
     /// Which set of objects to use from the View
     list_kind: ListKind,
     /// Default device type to use for defaults and node rendering
@@ -37,12 +53,25 @@ pub struct ObjectList {
     pub list_state: ListState,
     /// Targets
     pub targets: Vec<(view::Target, String)>,
+    /// Number of targets that fit in the dropdown
+    pub dropdown_visible: usize,
+    /// Render each object on a single line
+    pub compact: bool,
+    /// Render nodes as horizontal rows or vertical faders
+    pub orientation: Orientation,
+    /// Adjust stream volumes in the manner of PulseAudio's flat volumes
+    pub flat_volume: bool,
+    /// Render nodes on a single line made up of these columns
+    pub columns: Vec<Column>,
+    /// The object whose title is scrolling and when it started
+    marquee: Option<(ObjectId, Instant)>,
 }
 
 impl ObjectList {
     pub fn new(list_kind: ListKind, device_kind: Option<DeviceKind>) -> Self {
         Self {
             top: 0,
+            objects_visible: 0,
             selected: None,
             list_kind,
             device_kind,
@@ -72,6 +101,87 @@ impl ObjectList {
         }
     }
 
+    pub fn page_down(&mut self, view: &view::View) {
+        if let Some(index) = self.list_state.selected() {
+            let count = self.dropdown_visible.max(1);
+            let last = self.targets.len().saturating_sub(1);
+            self.list_state.select(Some((index + count).min(last)));
+        } else {
+            let count = self.objects_visible.max(1);
+            let new_selected =
+                view.next_id_by(self.list_kind, self.selected, count);
+            if new_selected.is_some() {
+                self.selected = new_selected;
+            }
+        }
+    }
+
+    pub fn page_up(&mut self, view: &view::View) {
+        if let Some(index) = self.list_state.selected() {
+            let count = self.dropdown_visible.max(1);
+            self.list_state.select(Some(index.saturating_sub(count)));
+        } else {
+            let count = self.objects_visible.max(1);
+            let new_selected =
+                view.previous_id_by(self.list_kind, self.selected, count);
+            if new_selected.is_some() {
+                self.selected = new_selected;
+            }
+        }
+    }
+
+    pub fn top(&mut self, view: &view::View) {
+        if self.list_state.selected().is_some() {
+            self.list_state.select(Some(0));
+        } else if let Some(first) = view.first_id(self.list_kind) {
+            self.selected = Some(first);
+        }
+    }
+
+    pub fn bottom(&mut self, view: &view::View) {
+        if self.list_state.selected().is_some() {
+            self.list_state
+                .select(Some(self.targets.len().saturating_sub(1)));
+        } else if let Some(last) = view.last_id(self.list_kind) {
+            self.selected = Some(last);
+        }
+    }
+
+    pub fn list_kind(&self) -> ListKind {
+        self.list_kind
+    }
+
+    /// The node.name or device.name of the selected object.
+    pub fn selected_name<'a>(&self, view: &'a view::View) -> Option<&'a str> {
+        self.selected
+            .and_then(|object_id| view.name(self.list_kind, object_id))
+    }
+
+    /// Select the object with a node.name or device.name. Returns whether
+    /// there was one.
+    pub fn select_name(&mut self, view: &view::View, name: &str) -> bool {
+        let found = view.find_name(self.list_kind, name);
+        if found.is_some() {
+            self.selected = found;
+        }
+        found.is_some()
+    }
+
+    /// Select the next object whose title contains a query, or the previous
+    /// one if not `forward`. Returns whether one was found.
+    pub fn search(
+        &mut self,
+        view: &view::View,
+        query: &str,
+        forward: bool,
+    ) -> bool {
+        let found = view.search(self.list_kind, self.selected, query, forward);
+        if found.is_some() {
+            self.selected = found;
+        }
+        found.is_some()
+    }
+
     fn dropdown_open(&mut self, view: &view::View) {
         let targets = match self.list_kind {
             ListKind::Node(_) => self
@@ -89,7 +199,32 @@ impl ObjectList {
         }
     }
 
-    fn selected_target(&self) -> Option<&view::Target> {
+    /// Open a dropdown of ways to rearrange the selected node's channel map.
+    pub fn channel_map_open(&mut self, view: &view::View) {
+        if !self.list_kind.is_node() {
+            return;
+        }
+        let maps = self
+            .selected
+            .and_then(|object_id| view.channel_maps(object_id));
+        if let Some((targets, index)) = maps {
+            self.dropdown_open_with(targets, index);
+        }
+    }
+
+    /// Open a dropdown of other options for the selected object.
+    pub fn dropdown_open_with(
+        &mut self,
+        targets: Vec<(view::Target, String)>,
+        index: usize,
+    ) {
+        if !targets.is_empty() {
+            self.targets = targets;
+            self.list_state.select(Some(index));
+        }
+    }
+
+    pub fn selected_target(&self) -> Option<&view::Target> {
         self.list_state
             .selected()
             .and_then(|index| self.targets.get(index))
@@ -114,6 +249,10 @@ impl ObjectList {
         commands
     }
 
+    pub fn is_dropdown_open(&self) -> bool {
+        self.list_state.selected().is_some()
+    }
+
     pub fn dropdown_close(&mut self) {
         self.list_state.select(None);
     }
@@ -131,13 +270,24 @@ impl ObjectList {
             .collect()
     }
 
+    /// The objects that actions apply to: the range selected in visual mode
+    /// or else the selected object.
+    pub fn marked(&self, view: &view::View) -> Vec<ObjectId> {
+        match (self.visual, self.selected) {
+            (Some(anchor), Some(selected)) => {
+                view.range(self.list_kind, anchor, selected)
+            }
+            _ => self.selected.into_iter().collect(),
+        }
+    }
+
     pub fn toggle_mute(&mut self, view: &view::View) -> Vec<Command> {
         if matches!(self.list_kind, ListKind::Device) {
             return Vec::new();
         }
-        self.selected
-            .and_then(|node_id| view.mute(node_id))
+        self.marked(view)
             .into_iter()
+            .filter_map(|node_id| view.mute(node_id))
             .collect()
     }
 
@@ -149,11 +299,11 @@ impl ObjectList {
         if matches!(self.list_kind, ListKind::Device) {
             return Vec::new();
         }
-        self.selected
-            .and_then(|node_id| {
-                view.volume(node_id, VolumeAdjustment::Absolute(volume))
-            })
+        self.marked(view)
             .into_iter()
+            .flat_map(|node_id| {
+                self.volume(view, node_id, VolumeAdjustment::Absolute(volume))
+            })
             .collect()
     }
 
@@ -165,14 +315,27 @@ impl ObjectList {
         if matches!(self.list_kind, ListKind::Device) {
             return Vec::new();
         }
-        self.selected
-            .and_then(|node_id| {
-                view.volume(node_id, VolumeAdjustment::Relative(volume))
-            })
+        self.marked(view)
             .into_iter()
+            .flat_map(|node_id| {
+                self.volume(view, node_id, VolumeAdjustment::Relative(volume))
+            })
             .collect()
     }
 
+    fn volume(
+        &self,
+        view: &view::View,
+        node_id: ObjectId,
+        adjustment: VolumeAdjustment,
+    ) -> Vec<Command> {
+        if self.flat_volume {
+            view.flat_volume(node_id, adjustment)
+        } else {
+            view.volume(node_id, adjustment).into_iter().collect()
+        }
+    }
+
     pub fn set_default(&mut self, view: &view::View) -> Vec<Command> {
         if matches!(self.list_kind, ListKind::Device) {
             return Vec::new();
@@ -199,18 +362,25 @@ impl ObjectList {
             self.selected_index(view)
         });
 
+        if self.visual.is_some_and(|anchor| {
+            view.position(self.list_kind, anchor).is_none()
+        }) {
+            self.visual = None;
+        }
+
+        // Restart the marquee when the selection changes
+        if self.marquee.map(|(id, _)| id) != self.selected {
+            self.marquee = self.selected.map(|id| (id, Instant::now()));
+        }
+
         let objects_len = view.len(self.list_kind);
 
         let (_, list_area, _) = self.areas(&area);
-        let full_height = match self.list_kind {
-            ListKind::Node(_) => {
-                NodeWidget::height().saturating_add(NodeWidget::spacing())
-            }
-            ListKind::Device => {
-                DeviceWidget::height().saturating_add(DeviceWidget::spacing())
-            }
-        };
-        let objects_visible = (list_area.height / full_height) as usize;
+        let (size, spacing) = self.object_size();
+        let full_size = size.saturating_add(spacing);
+        let objects_visible =
+            (self.list_length(&list_area) / full_size) as usize;
+        self.objects_visible = objects_visible;
 
         // If objects were removed and the viewport is now below the visible
         // objects, move the viewport up so that the bottom of the object list
@@ -244,6 +414,40 @@ impl ObjectList {
         }
     }
 
+    /// Whether objects are laid out left to right instead of top to bottom.
+    fn is_vertical(&self) -> bool {
+        self.list_kind.is_node() && self.orientation == Orientation::Vertical
+    }
+
+    /// Returns the length of the list area in the direction objects are laid
+    /// out.
+    fn list_length(&self, list_area: &Rect) -> u16 {
+        if self.is_vertical() {
+            list_area.width
+        } else {
+            list_area.height
+        }
+    }
+
+    /// Returns the size of an object in the direction objects are laid out
+    /// and the spacing between objects.
+    fn object_size(&self) -> (u16, u16) {
+        match self.list_kind {
+            ListKind::Node(_) if self.is_vertical() => (
+                FaderWidget::width(self.compact),
+                FaderWidget::spacing(self.compact),
+            ),
+            ListKind::Node(_) => {
+                let compact = self.compact || !self.columns.is_empty();
+                (NodeWidget::height(compact), NodeWidget::spacing(compact))
+            }
+            ListKind::Device => (
+                DeviceWidget::height(self.compact),
+                DeviceWidget::spacing(self.compact),
+            ),
+        }
+    }
+
     fn areas(&self, area: &Rect) -> (Rect, Rect, Rect) {
         let layout = Layout::default()
             .direction(Direction::Vertical)
@@ -271,6 +475,33 @@ struct ObjectListRenderContext<'a> {
 }
 
 impl ObjectListWidget<'_> {
+    /// How far the title of the object should be scrolled, if at all.
+    fn marquee_offset(&self, object_id: ObjectId) -> Option<usize> {
+        if !self.config.marquee || self.config.reduced_motion {
+            return None;
+        }
+
+        let (id, since) = self.object_list.marquee?;
+        (id == object_id).then(|| {
+            let elapsed = since.elapsed().saturating_sub(MARQUEE_DELAY);
+            (elapsed.as_millis() / MARQUEE_STEP.as_millis()) as usize
+        })
+    }
+
+    /// Indicator for objects outside of the viewport
+    /// A line saying how many objects are out of view, aligned with the
+    /// side they're on for vertical faders.
+    fn list_more_line(&self, count: usize, alignment: Alignment) -> Line<'_> {
+        let style = self.config.theme.list_more;
+        let list_more = &self.config.char_set.list_more;
+        Line::from(vec![
+            Span::styled(list_more, style),
+            Span::styled(format!(" {} more ", count), style),
+            Span::styled(list_more, style),
+        ])
+        .alignment(alignment)
+    }
+
     fn render_node_list(
         &mut self,
         node_kind: view::NodeKind,
@@ -280,6 +511,7 @@ impl ObjectListWidget<'_> {
         mouse_areas: &mut Vec<MouseArea>,
     ) {
         let all_objects = self.view.full_nodes(node_kind);
+        let marked = self.object_list.marked(self.view);
         let objects = all_objects
             .iter()
             .skip(self.object_list.top)
@@ -290,18 +522,45 @@ impl ObjectListWidget<'_> {
         let objects_and_areas: Vec<(&&view::Node, &Rect)> =
             objects.zip(context.objects_layout.iter()).collect();
         for (object, &object_area) in &objects_and_areas {
-            let selected = self
-                .object_list
-                .selected
-                .map(|id| id == object.id)
-                .unwrap_or_default();
-            NodeWidget::new(
-                object,
-                selected,
-                self.object_list.device_kind,
-                self.config,
-            )
-            .render(object_area, buf, mouse_areas);
+            let selected = marked.contains(&object.id);
+            if self.object_list.is_vertical() {
+                FaderWidget::new(
+                    object,
+                    selected,
+                    self.object_list.device_kind,
+                    self.config,
+                )
+                .compact(self.object_list.compact)
+                .marquee(self.marquee_offset(object.id))
+                .render(object_area, buf, mouse_areas);
+            } else {
+                NodeWidget::new(
+                    object,
+                    selected,
+                    self.object_list.device_kind,
+                    self.config,
+                )
+                .compact(self.object_list.compact)
+                .columns(&self.object_list.columns)
+                .marquee(self.marquee_offset(object.id))
+                .render(object_area, buf, mouse_areas);
+            }
+
+            let theme = &self.config.theme;
+            let mut row_style = Style::default();
+            if node_widget::is_default(object, self.object_list.device_kind) {
+                row_style = row_style.patch(theme.row_default);
+            }
+            if object.mute {
+                row_style = row_style.patch(theme.row_muted);
+            }
+            if object.ended {
+                row_style = row_style.patch(theme.row_ended);
+            }
+            if !selected {
+                row_style = row_style.patch(theme.row_unselected);
+            }
+            buf.set_style(object_area, row_style);
         }
 
         // Show the target dropdown?
@@ -315,13 +574,22 @@ impl ObjectListWidget<'_> {
                         .unwrap_or_default()
                 })
             {
-                DropdownWidget::new(
-                    self.object_list,
-                    &NodeWidget::dropdown_area(
+                let dropdown_area = if self.object_list.is_vertical() {
+                    FaderWidget::dropdown_area(
                         self.object_list,
                         &context.list_area,
                         object_area,
-                    ),
+                    )
+                } else {
+                    NodeWidget::dropdown_area(
+                        self.object_list,
+                        &context.list_area,
+                        object_area,
+                    )
+                };
+                DropdownWidget::new(
+                    self.object_list,
+                    &dropdown_area,
                     self.config,
                 )
                 .render(area, buf, mouse_areas);
@@ -337,6 +605,7 @@ impl ObjectListWidget<'_> {
         mouse_areas: &mut Vec<MouseArea>,
     ) {
         let all_objects = self.view.full_devices();
+        let marked = self.object_list.marked(self.view);
         let objects = all_objects
             .iter()
             .skip(self.object_list.top)
@@ -347,16 +616,14 @@ impl ObjectListWidget<'_> {
         let objects_and_areas: Vec<(&&view::Device, &Rect)> =
             objects.zip(context.objects_layout.iter()).collect();
         for (object, &object_area) in &objects_and_areas {
-            let selected = self
-                .object_list
-                .selected
-                .map(|id| id == object.id)
-                .unwrap_or_default();
-            DeviceWidget::new(object, selected, self.config).render(
-                object_area,
-                buf,
-                mouse_areas,
-            );
+            let selected = marked.contains(&object.id);
+            DeviceWidget::new(object, selected, self.config)
+                .compact(self.object_list.compact)
+                .render(object_area, buf, mouse_areas);
+
+            if !selected {
+                buf.set_style(object_area, self.config.theme.row_unselected);
+            }
         }
 
         // Show the target dropdown?
@@ -418,26 +685,25 @@ impl StatefulWidget for &mut ObjectListWidget<'_> {
             smallvec![Action::MoveDown],
         ));
 
-        let (spacing, height) = match self.object_list.list_kind {
-            ListKind::Node(_) => (NodeWidget::spacing(), NodeWidget::height()),
-            ListKind::Device => {
-                (DeviceWidget::spacing(), DeviceWidget::height())
-            }
-        };
+        let (size, spacing) = self.object_list.object_size();
+        let is_vertical = self.object_list.is_vertical();
+        let list_length = self.object_list.list_length(&list_area);
 
-        let full_object_height = height.saturating_add(spacing);
-        let objects_visible = (list_area.height / full_object_height) as usize;
+        let full_object_size = size.saturating_add(spacing);
+        let objects_visible = (list_length / full_object_size) as usize;
 
         let len = self.view.len(self.object_list.list_kind);
 
-        // Indicate we can scroll up if there are objects above the viewport.
+        // Indicate we can scroll up if there are objects above the viewport,
+        // or left of it for vertical faders.
         if self.object_list.top > 0 {
-            Line::from(Span::styled(
-                &self.config.char_set.list_more,
-                self.config.theme.list_more,
-            ))
-            .alignment(Alignment::Center)
-            .render(header_area, buf);
+            let alignment = if is_vertical {
+                Alignment::Left
+            } else {
+                Alignment::Center
+            };
+            self.list_more_line(self.object_list.top, alignment)
+                .render(header_area, buf);
         }
 
         // Indicate we can scroll down if there are objects below the
@@ -447,32 +713,83 @@ impl StatefulWidget for &mut ObjectListWidget<'_> {
         let is_bottom_last =
             self.object_list.top.saturating_add(objects_visible)
                 == len.saturating_sub(1);
-        let is_bottom_enough =
-            (list_area.height % full_object_height) >= height;
+        let is_bottom_enough = (list_length % full_object_size) >= size;
         if self.object_list.top.saturating_add(objects_visible) < len
             && !(is_bottom_last && is_bottom_enough)
         {
-            Line::from(Span::styled(
-                &self.config.char_set.list_more,
-                self.config.theme.list_more,
-            ))
-            .alignment(Alignment::Center)
-            .render(footer_area, buf);
+            let below = len
+                .saturating_sub(self.object_list.top)
+                .saturating_sub(objects_visible);
+            let alignment = if is_vertical {
+                Alignment::Right
+            } else {
+                Alignment::Center
+            };
+            self.list_more_line(below, alignment)
+                .render(footer_area, buf);
+        }
+
+        // Show a scrollbar if the objects don't all fit in the viewport and
+        // keep the objects clear of it. It's on the right, or below vertical
+        // faders.
+        let show_scrollbar = len > objects_visible && !list_area.is_empty();
+        let objects_area = match (show_scrollbar, is_vertical) {
+            (false, _) => list_area,
+            (true, false) => Rect {
+                width: list_area.width.saturating_sub(1),
+                ..list_area
+            },
+            (true, true) => Rect {
+                height: list_area.height.saturating_sub(1),
+                ..list_area
+            },
+        };
+        if show_scrollbar {
+            // Positions the viewport can be scrolled to
+            let positions = len.saturating_sub(objects_visible) + 1;
+            let mut scrollbar_state = ScrollbarState::new(positions)
+                .position(self.object_list.top)
+                .viewport_content_length(objects_visible);
+            let char_set = &self.config.char_set;
+            let (orientation, track, thumb) = if is_vertical {
+                (
+                    ScrollbarOrientation::HorizontalBottom,
+                    &char_set.scrollbar_horizontal_track,
+                    &char_set.scrollbar_horizontal_thumb,
+                )
+            } else {
+                (
+                    ScrollbarOrientation::VerticalRight,
+                    &char_set.scrollbar_track,
+                    &char_set.scrollbar_thumb,
+                )
+            };
+            Scrollbar::new(orientation)
+                .begin_symbol(None)
+                .end_symbol(None)
+                .track_symbol(Some(track))
+                .track_style(self.config.theme.scrollbar_track)
+                .thumb_symbol(thumb)
+                .thumb_style(self.config.theme.scrollbar_thumb)
+                .render(list_area, buf, &mut scrollbar_state);
         }
 
         let objects_layout = {
-            let object_height = height;
             let mut constraints =
-                vec![Constraint::Length(object_height); objects_visible];
+                vec![Constraint::Length(size); objects_visible];
             // A variable-length constraint for a partial last object
-            constraints.push(Constraint::Max(object_height));
+            constraints.push(Constraint::Max(size));
             let constraints = constraints;
 
             Layout::default()
-                .direction(Direction::Vertical)
+                .direction(if is_vertical {
+                    Direction::Horizontal
+                } else {
+                    Direction::Vertical
+                })
                 .constraints(constraints)
                 .spacing(spacing)
-                .split(list_area)
+                .split(objects_area)
         };
 
         match self.object_list.list_kind {
@@ -552,7 +869,7 @@ mod tests {
         let (state, _) = init();
         let view = View::from(&state, &config::Names::default());
 
-        let height = NodeWidget::height() + NodeWidget::spacing();
+        let height = NodeWidget::height(false) + NodeWidget::spacing(false);
         // + 2 for header and footer
         let rect = Rect::new(0, 0, 80, height * 3 + 2);
         let mut object_list =
@@ -573,7 +890,7 @@ mod tests {
         let (state, _) = init();
         let view = View::from(&state, &config::Names::default());
 
-        let height = NodeWidget::height() + NodeWidget::spacing();
+        let height = NodeWidget::height(false) + NodeWidget::spacing(false);
         // + 2 for header and footer
         let rect = Rect::new(0, 0, 80, height * 3 + 2);
         let mut object_list =
@@ -593,4 +910,330 @@ mod tests {
         assert_eq!(object_list.top, 7);
         assert_eq!(object_list.selected, Some(ObjectId::from_raw_id(9)));
     }
+
+    #[test]
+    fn object_list_page_navigation() {
+        use clap::Parser;
+
+        let (state, _) = init();
+        let view = View::from(&state, &config::Names::default());
+
+        let height = NodeWidget::height(false) + NodeWidget::spacing(false);
+        // + 2 for header and footer
+        let rect = Rect::new(0, 0, 80, height * 3 + 2);
+        let mut object_list =
+            ObjectList::new(ListKind::Node(NodeKind::All), None);
+        object_list.update(rect, &view);
+        assert_eq!(object_list.selected, Some(ObjectId::from_raw_id(0)));
+
+        object_list.page_down(&view);
+        object_list.update(rect, &view);
+        assert_eq!(object_list.selected, Some(ObjectId::from_raw_id(3)));
+
+        object_list.bottom(&view);
+        object_list.update(rect, &view);
+        assert_eq!(object_list.selected, Some(ObjectId::from_raw_id(9)));
+        assert_eq!(object_list.top, 7);
+
+        // Paging stops at the end of the list.
+        object_list.page_down(&view);
+        assert_eq!(object_list.selected, Some(ObjectId::from_raw_id(9)));
+
+        object_list.page_up(&view);
+        assert_eq!(object_list.selected, Some(ObjectId::from_raw_id(6)));
+
+        object_list.top(&view);
+        object_list.update(rect, &view);
+        assert_eq!(object_list.selected, Some(ObjectId::from_raw_id(0)));
+        assert_eq!(object_list.top, 0);
+
+        // In a dropdown, paging moves the dropdown selection by as many
+        // targets as the dropdown shows.
+        let targets = (0..10)
+            .map(|i| {
+                let target = view::Target::Node(ObjectId::from_raw_id(i));
+                (target, format!("Target {}", i))
+            })
+            .collect();
+        object_list.dropdown_open_with(targets, 1);
+        let opt = crate::opt::Opt::parse_from(["wiremix"]);
+        let config = Config::try_new(None, &opt).unwrap();
+        let mut widget = ObjectListWidget {
+            object_list: &mut object_list,
+            view: &view,
+            config: &config,
+        };
+        let mut buf = Buffer::empty(rect);
+        (&mut widget).render(rect, &mut buf, &mut Vec::new());
+        assert_eq!(object_list.dropdown_visible, 5);
+
+        object_list.page_down(&view);
+        assert_eq!(object_list.list_state.selected(), Some(6));
+        object_list.page_down(&view);
+        assert_eq!(object_list.list_state.selected(), Some(9));
+        object_list.page_up(&view);
+        assert_eq!(object_list.list_state.selected(), Some(4));
+        object_list.page_up(&view);
+        assert_eq!(object_list.list_state.selected(), Some(0));
+        assert_eq!(object_list.selected, Some(ObjectId::from_raw_id(0)));
+    }
+
+    #[test]
+    fn object_list_compact_fits_more() {
+        let (state, _) = init();
+        let view = View::from(&state, &config::Names::default());
+
+        let height = NodeWidget::height(true) + NodeWidget::spacing(true);
+        // + 2 for header and footer
+        let rect = Rect::new(0, 0, 80, height * 3 + 2);
+        let mut object_list =
+            ObjectList::new(ListKind::Node(NodeKind::All), None);
+        object_list.compact = true;
+        object_list.update(rect, &view);
+        assert_eq!(object_list.objects_visible, 3);
+
+        object_list.bottom(&view);
+        object_list.update(rect, &view);
+        assert_eq!(object_list.top, 7);
+    }
+
+    #[test]
+    fn object_list_vertical_uses_width() {
+        let (state, _) = init();
+        let view = View::from(&state, &config::Names::default());
+
+        let width = FaderWidget::width(false) + FaderWidget::spacing(false);
+        let rect = Rect::new(0, 0, width * 4, 20);
+        let mut object_list =
+            ObjectList::new(ListKind::Node(NodeKind::All), None);
+        object_list.orientation = Orientation::Vertical;
+        object_list.update(rect, &view);
+        assert_eq!(object_list.objects_visible, 4);
+    }
+
+    #[test]
+    fn object_list_flat_volume() {
+        let (mut state, mut capture_manager) = init();
+        let sink_id = ObjectId::from_raw_id(10);
+        let metadata_id = ObjectId::from_raw_id(11);
+        let events = vec![
+            MonitorEvent::NodeMediaClass(
+                sink_id,
+                MediaClass::from("Audio/Sink"),
+            ),
+            MonitorEvent::NodeName(sink_id, String::from("sink")),
+            MonitorEvent::NodeObjectSerial(sink_id, 10),
+            MonitorEvent::NodeVolumes(sink_id, vec![1.0, 1.0]),
+            MonitorEvent::NodeMute(sink_id, false),
+            MonitorEvent::NodeVolumes(ObjectId::from_raw_id(1), vec![1.0, 1.0]),
+            MonitorEvent::MetadataMetadataName(
+                metadata_id,
+                String::from("default"),
+            ),
+            MonitorEvent::MetadataProperty(
+                metadata_id,
+                0,
+                Some(String::from("default.audio.sink")),
+                Some(String::from(r#"{"name":"sink"}"#)),
+            ),
+        ];
+        for event in events {
+            state.update(&mut capture_manager, event);
+        }
+        let view = View::from(&state, &config::Names::default());
+
+        let volumes = |commands: &[Command], id| {
+            commands.iter().find_map(|command| match command {
+                Command::NodeVolumes(node_id, volumes) if *node_id == id => {
+                    Some(volumes[0])
+                }
+                _ => None,
+            })
+        };
+        let (stream, loudest) =
+            (ObjectId::from_raw_id(0), ObjectId::from_raw_id(1));
+
+        let mut object_list =
+            ObjectList::new(ListKind::Node(NodeKind::Playback), None);
+        object_list.flat_volume = true;
+        object_list.selected = Some(stream);
+
+        // Below the loudest stream, only the stream changes.
+        let commands = object_list.set_absolute_volume(&view, 0.5);
+        assert_eq!(commands.len(), 1);
+
+        // Above it, the sink is raised and the other streams compensate.
+        let commands = object_list.set_absolute_volume(&view, 1.2);
+        let factor = 1.2_f32.powi(3);
+        assert_eq!(commands.len(), 11);
+        assert_eq!(volumes(&commands, stream), Some(1.0));
+        assert_eq!(volumes(&commands, sink_id), Some(factor));
+        assert_eq!(volumes(&commands, loudest), Some(1.0 / factor));
+
+        // Lowering the loudest stream lowers the sink to the next loudest,
+        // which is then at 100%.
+        state.update(
+            &mut capture_manager,
+            MonitorEvent::NodeVolumes(stream, vec![0.125, 0.125]),
+        );
+        let view = View::from(&state, &config::Names::default());
+        object_list.selected = Some(loudest);
+        let commands = object_list.set_absolute_volume(&view, 0.0);
+        assert_eq!(commands.len(), 11);
+        assert_eq!(volumes(&commands, sink_id), Some(0.125));
+        assert_eq!(volumes(&commands, stream), Some(1.0));
+        assert_eq!(volumes(&commands, loudest), Some(0.0));
+
+        // Changing the sink rescales its streams so that the loudest is at
+        // 100%, keeping their loudness in proportion to the change.
+        state.update(
+            &mut capture_manager,
+            MonitorEvent::NodeVolumes(loudest, vec![0.5, 0.5]),
+        );
+        let view = View::from(&state, &config::Names::default());
+        let mut object_list =
+            ObjectList::new(ListKind::Node(NodeKind::Output), None);
+        object_list.flat_volume = true;
+        object_list.selected = Some(sink_id);
+        let commands = object_list.set_absolute_volume(&view, 0.5);
+        assert_eq!(commands.len(), 11);
+        assert_eq!(volumes(&commands, sink_id), Some(0.0625));
+        assert_eq!(volumes(&commands, stream), Some(0.25));
+        assert_eq!(volumes(&commands, loudest), Some(1.0));
+    }
+
+    #[test]
+    fn object_list_row_styles() {
+        use clap::Parser;
+        use ratatui::style::Modifier;
+
+        let (mut state, mut capture_manager) = init();
+        state.update(
+            &mut capture_manager,
+            MonitorEvent::NodeMute(ObjectId::from_raw_id(1), true),
+        );
+        let view = View::from(&state, &config::Names::default());
+
+        let opt = crate::opt::Opt::parse_from(["wiremix"]);
+        let mut config = Config::try_new(None, &opt).unwrap();
+        config.theme.row_unselected =
+            Style::default().add_modifier(Modifier::DIM);
+        config.theme.row_muted =
+            Style::default().add_modifier(Modifier::ITALIC);
+
+        let height = NodeWidget::height(false) + NodeWidget::spacing(false);
+        // + 2 for header and footer
+        let rect = Rect::new(0, 0, 80, height * 3 + 2);
+        let mut object_list =
+            ObjectList::new(ListKind::Node(NodeKind::All), None);
+        object_list.down(&view);
+        object_list.update(rect, &view);
+
+        let mut buf = Buffer::empty(rect);
+        let mut widget = ObjectListWidget {
+            object_list: &mut object_list,
+            view: &view,
+            config: &config,
+        };
+        (&mut widget).render(rect, &mut buf, &mut Vec::new());
+
+        // First row is selected, second is muted and unselected
+        let selected = buf[(1, 1)].modifier;
+        let muted = buf[(1, 1 + height)].modifier;
+        assert!(!selected.contains(Modifier::DIM));
+        assert!(muted.contains(Modifier::DIM | Modifier::ITALIC));
+    }
+
+    #[test]
+    fn object_list_vertical_scrollbar() {
+        use clap::Parser;
+
+        let (state, _) = init();
+        let view = View::from(&state, &config::Names::default());
+        let opt = crate::opt::Opt::parse_from(["wiremix"]);
+        let config = Config::try_new(None, &opt).unwrap();
+
+        let width = FaderWidget::width(false) + FaderWidget::spacing(false);
+        let rect = Rect::new(0, 0, width * 4, 20);
+        let mut object_list =
+            ObjectList::new(ListKind::Node(NodeKind::All), None);
+        object_list.orientation = Orientation::Vertical;
+        object_list.bottom(&view);
+        object_list.update(rect, &view);
+
+        let mut buf = Buffer::empty(rect);
+        let mut widget = ObjectListWidget {
+            object_list: &mut object_list,
+            view: &view,
+            config: &config,
+        };
+        (&mut widget).render(rect, &mut buf, &mut Vec::new());
+
+        let row = |y: u16| -> String {
+            (0..rect.width).map(|x| buf[(x, y)].symbol()).collect()
+        };
+        // Objects out of view to the left, and a scrollbar along the bottom
+        // of the list scrolled to the end
+        assert!(row(0).starts_with("••• 6 more •••"));
+        let scrollbar = row(rect.height - 2);
+        assert!(
+            scrollbar.starts_with(&config.char_set.scrollbar_horizontal_track)
+        );
+        assert!(
+            scrollbar.ends_with(&config.char_set.scrollbar_horizontal_thumb)
+        );
+    }
+
+    #[test]
+    fn object_list_columns() {
+        use crate::config::{Column, ColumnKind};
+        use clap::Parser;
+
+        let (mut state, mut capture_manager) = init();
+        state.update(
+            &mut capture_manager,
+            MonitorEvent::NodeVolumes(ObjectId::from_raw_id(1), vec![0.5, 0.5]),
+        );
+        let view = View::from(&state, &config::Names::default());
+        let opt = crate::opt::Opt::parse_from(["wiremix"]);
+        let config = Config::try_new(None, &opt).unwrap();
+
+        let rect = Rect::new(0, 0, 80, 10);
+        let mut object_list =
+            ObjectList::new(ListKind::Node(NodeKind::All), None);
+        object_list.columns = vec![
+            Column {
+                kind: ColumnKind::Name,
+                width: Some(20),
+            },
+            Column {
+                kind: ColumnKind::Db,
+                width: Some(9),
+            },
+            Column {
+                kind: ColumnKind::MediaClass,
+                width: None,
+            },
+        ];
+        object_list.down(&view);
+        object_list.update(rect, &view);
+
+        let mut buf = Buffer::empty(rect);
+        let mut widget = ObjectListWidget {
+            object_list: &mut object_list,
+            view: &view,
+            config: &config,
+        };
+        (&mut widget).render(rect, &mut buf, &mut Vec::new());
+
+        let row = |y: u16| -> String {
+            (0..rect.width).map(|x| buf[(x, y)].symbol()).collect()
+        };
+        // Each node is a single line of columns, with the name truncated to
+        // its width
+        assert!(row(1).contains("Node name: Medi...   -inf dB Stream/Output"));
+        assert!(!row(2).contains("Node name"));
+        assert!(row(3).contains("Node name: Medi...   -6.0 dB Stream/Output"));
+        assert!(row(rect.height - 1).contains("6 more"));
+    }
 }
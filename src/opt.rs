@@ -1,11 +1,60 @@
 //! Parse command-line arguments.
 
+use std::io;
 use std::path::PathBuf;
+use std::process;
 
 use clap::Parser;
 
 use crate::app::TabKind;
 use crate::config;
+use crate::exit_code;
+
+/// Lists the selectors to complete for `list --select`: the default
+/// endpoints and the names of the sinks and sources that exist when
+/// completing. It replaces `SELECTORS` in the shell code below.
+const SELECTORS_COMMAND: &str =
+    "printf '%s\\n' @DEFAULT_SINK@ @DEFAULT_SOURCE@; \
+    wiremix list nodes --select 'media.class=Audio/*' \
+    --format 'node.name={node:node.name}' 2>/dev/null";
+
+/// Completes `list --select` in bash, falling back to the generated
+/// completion for everything else.
+const BASH_SELECTORS: &str = r#"_wiremix_selectors() {
+    local line=${COMP_LINE:0:COMP_POINT}
+    local cur=${line##*[[:space:]]}
+    local prev=${line%"$cur"}
+    prev=${prev%"${prev##*[![:space:]]}"}
+    prev=${prev##*[[:space:]]}
+    if [[ $prev != --select ]]; then
+        _wiremix "$@"
+        return
+    fi
+    local IFS=$'\n'
+    COMPREPLY=($(compgen -W "$(SELECTORS)" -- "$cur"))
+    # Bash splits words at '=' and only replaces the text after it
+    if [[ $cur == *=* && $COMP_WORDBREAKS == *=* ]]; then
+        COMPREPLY=("${COMPREPLY[@]#"${cur%=*}="}")
+    fi
+}
+
+"#;
+
+/// Completes `list --select` in zsh.
+const ZSH_SELECTORS: &str = r#"(( $+functions[_wiremix_selectors] )) ||
+_wiremix_selectors() {
+    local -a selectors
+    selectors=(${(f)"$(SELECTORS)"})
+    compadd -a selectors
+}
+
+"#;
+
+/// Completes `list --select` in fish.
+const FISH_SELECTORS: &str = "complete -c wiremix \
+    -n \"__fish_wiremix_using_subcommand list\" -l select -f \
+    -a \"(SELECTORS)\"
+";
 
 #[derive(Parser)]
 #[clap(name = "wiremix", about = "PipeWire mixer")]
@@ -38,7 +87,7 @@ pub struct Opt {
         short = 's',
         long,
         value_name = "NAME",
-        help = "Character set to use [built-in sets: default, compat, extracompat]"
+        help = "Character set to use [built-in sets: default, compat, extracompat, ascii]"
     )]
     pub char_set: Option<String>,
 
@@ -46,7 +95,7 @@ pub struct Opt {
         short,
         long,
         value_name = "NAME",
-        help = "Theme to use [built-in themes: default, nocolor, plain]"
+        help = "Theme to use [built-in themes: default, nocolor, plain, highcontrast, monochrome]"
     )]
     pub theme: Option<String>,
 
@@ -64,6 +113,42 @@ pub struct Opt {
     #[clap(long, conflicts_with = "no_mouse", help = "Enable mouse support")]
     pub mouse: bool,
 
+    #[clap(
+        long,
+        conflicts_with = "compact",
+        help = "Show each item on multiple lines"
+    )]
+    pub no_compact: bool,
+
+    #[clap(
+        long,
+        conflicts_with = "no_compact",
+        help = "Show each item on a single line"
+    )]
+    pub compact: bool,
+
+    #[clap(
+        long,
+        value_name = "LINES",
+        value_parser = clap::value_parser!(u16).range(1..),
+        help = "Draw in this many lines below the prompt instead of the whole screen"
+    )]
+    pub inline: Option<u16>,
+
+    #[clap(
+        long,
+        conflicts_with = "reduced_motion",
+        help = "Allow smooth meters and other animation"
+    )]
+    pub no_reduced_motion: bool,
+
+    #[clap(
+        long,
+        conflicts_with = "no_reduced_motion",
+        help = "Minimize animation and update meters at a low rate"
+    )]
+    pub reduced_motion: bool,
+
     #[clap(
         short = 'v',
         long,
@@ -73,13 +158,250 @@ pub struct Opt {
     )]
     pub tab: Option<TabKind>,
 
+    #[clap(
+        long,
+        value_enum,
+        value_name = "TAB",
+        value_parser = clap::value_parser!(TabKind),
+        help = "Tab to show side by side with the main tab"
+    )]
+    pub split: Option<TabKind>,
+
+    #[clap(
+        long,
+        value_name = "KEY=VALUE",
+        value_parser = config::parse_override,
+        help = "Override a configuration option, such as theme=plain (repeatable)"
+    )]
+    pub set: Vec<toml::Table>,
+
+    #[clap(
+        short,
+        long,
+        global = true,
+        help = "Print nothing on success, only errors"
+    )]
+    pub quiet: bool,
+
+    #[clap(
+        long,
+        global = true,
+        help = "Print the output of list, graph, diff and check-config as JSON"
+    )]
+    pub json: bool,
+
+    #[clap(
+        long,
+        help = "Run without an interface, only applying automation such as ducking and hooks"
+    )]
+    pub daemon: bool,
+
+    #[cfg(any(feature = "alsa", feature = "pulse"))]
+    #[clap(
+        long,
+        value_enum,
+        help = "Backend to use [default: pipewire, or a fallback if PipeWire isn't running]"
+    )]
+    pub backend: Option<BackendKind>,
+
     #[cfg(debug_assertions)]
     #[clap(short, long, help = "Dump events without showing interface")]
     pub dump_events: bool,
+
+    #[command(subcommand)]
+    pub command: Option<OptCommand>,
+}
+
+#[derive(clap::Subcommand, Clone, Debug, PartialEq, Eq)]
+pub enum OptCommand {
+    #[command(about = "Check the configuration file for problems and exit")]
+    CheckConfig,
+    #[command(
+        about = "Write a documented default configuration file and exit"
+    )]
+    InitConfig {
+        #[clap(long, help = "Overwrite an existing configuration file")]
+        force: bool,
+    },
+    #[command(
+        about = "Print a JSON Schema for the configuration file and exit"
+    )]
+    Schema,
+    #[command(about = "Print a man page for the configuration file and exit")]
+    Man,
+    #[command(about = "Print a line for each node or device and exit")]
+    List {
+        #[clap(value_enum, default_value = "nodes")]
+        objects: ListObjects,
+        #[clap(
+            long,
+            value_name = "FORMAT",
+            help = "Line format with fields such as {id}, {name}, {volume}, {mute}, {media_class}, {target} and {default}, and name template tags such as {node:node.name}"
+        )]
+        format: Option<String>,
+        #[clap(
+            long,
+            value_name = "SELECTOR",
+            help = "Only list matching objects, such as @DEFAULT_SINK@ or node.name=alsa_output.*"
+        )]
+        select: Option<String>,
+    },
+    #[command(
+        about = "Print the nodes and links as a Graphviz graph and exit"
+    )]
+    Graph {
+        #[clap(long, help = "Print Graphviz DOT, the default without --json")]
+        dot: bool,
+    },
+    #[command(
+        about = "Compare two snapshots from list --json or graph --json and exit"
+    )]
+    Diff {
+        #[clap(value_name = "A")]
+        a: PathBuf,
+        #[clap(value_name = "B")]
+        b: PathBuf,
+    },
+    #[command(about = "Mute or unmute the default input device and exit")]
+    MuteSource {
+        #[clap(value_enum, default_value = "toggle")]
+        change: MuteChange,
+    },
+    #[command(about = "Print a shell completion script and exit")]
+    Completions {
+        #[clap(value_enum)]
+        shell: clap_complete::Shell,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum ListObjects {
+    Nodes,
+    Devices,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum MuteChange {
+    Toggle,
+    On,
+    Off,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum BackendKind {
+    Pipewire,
+    #[cfg(feature = "alsa")]
+    Alsa,
+    #[cfg(feature = "pulse")]
+    Pulse,
+}
+
+impl BackendKind {
+    /// PipeWire if there is a PipeWire socket for `remote`, or else
+    /// PulseAudio if there is a PulseAudio server, or else ALSA, depending on
+    /// which backends are enabled.
+    pub fn detect(remote: Option<&str>) -> Self {
+        #[cfg(any(feature = "alsa", feature = "pulse"))]
+        if !crate::monitor::socket_exists(remote) {
+            #[cfg(feature = "pulse")]
+            if crate::monitor::pulse::server_exists(None) {
+                return BackendKind::Pulse;
+            }
+            #[cfg(feature = "alsa")]
+            return BackendKind::Alsa;
+        }
+        let _ = remote;
+        BackendKind::Pipewire
+    }
 }
 
 impl Opt {
     pub fn parse() -> Self {
-        <Self as Parser>::parse()
+        <Self as Parser>::try_parse().unwrap_or_else(|err| {
+            // clap exits with 2 for usage errors, which means something else
+            // here
+            if err.use_stderr() {
+                let _ = err.print();
+                process::exit(exit_code::FAILURE);
+            }
+            err.exit()
+        })
+    }
+
+    /// Write a completion script for a shell. Bash, zsh and fish also
+    /// complete `list --select` with the sinks and sources that exist at the
+    /// time.
+    pub fn completions(
+        shell: clap_complete::Shell,
+        out: &mut dyn io::Write,
+    ) -> io::Result<()> {
+        let mut command = <Self as clap::CommandFactory>::command();
+        let mut script = Vec::new();
+        clap_complete::generate(shell, &mut command, "wiremix", &mut script);
+        let script = String::from_utf8_lossy(&script);
+        let hook =
+            |snippet: &str| snippet.replace("SELECTORS", SELECTORS_COMMAND);
+        let script = match shell {
+            clap_complete::Shell::Bash => {
+                let complete = "complete -F _wiremix ";
+                let start = script.find(complete).unwrap_or(script.len());
+                format!(
+                    "{}{}{}",
+                    &script[..start],
+                    hook(BASH_SELECTORS),
+                    script[start..]
+                        .replace(complete, "complete -F _wiremix_selectors ")
+                )
+            }
+            clap_complete::Shell::Zsh => {
+                let script = script.replacen(
+                    ":SELECTOR:_default",
+                    ":SELECTOR:_wiremix_selectors",
+                    1,
+                );
+                let start = script
+                    .find("if [ \"$funcstack[1]\"")
+                    .unwrap_or(script.len());
+                format!(
+                    "{}{}{}",
+                    &script[..start],
+                    hook(ZSH_SELECTORS),
+                    &script[start..]
+                )
+            }
+            clap_complete::Shell::Fish => {
+                format!("{}{}", script, hook(FISH_SELECTORS))
+            }
+            _ => script.into_owned(),
+        };
+        out.write_all(script.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn script(shell: clap_complete::Shell) -> String {
+        let mut out = Vec::new();
+        Opt::completions(shell, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn completions_complete_selectors() {
+        let bash = script(clap_complete::Shell::Bash);
+        assert!(bash.contains("_wiremix_selectors() {"));
+        assert!(bash.contains("complete -F _wiremix_selectors "));
+        assert!(!bash.contains("complete -F _wiremix "));
+
+        let zsh = script(clap_complete::Shell::Zsh);
+        assert!(zsh.contains(":SELECTOR:_wiremix_selectors"));
+        assert!(zsh.contains("_wiremix_selectors() {"));
+
+        for script in [bash, zsh, script(clap_complete::Shell::Fish)] {
+            assert!(script.contains(SELECTORS_COMMAND));
+            assert!(!script.contains("(SELECTORS)"));
+        }
     }
 }
@@ -0,0 +1,381 @@
+//! Optimistic volume and mute updates.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::command::Command;
+use crate::event::MonitorEvent;
+use crate::object::ObjectId;
+use crate::state::{State, StateDirty};
+
+/// How long a change can go unconfirmed after its command was sent before
+/// the monitor's value is restored.
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Volumes which differ by less than this on the cubic scale are considered
+/// equal, because backends can round them.
+const VOLUME_TOLERANCE: f32 = 0.01;
+
+/// A volume or mute setting of a node or of a device route.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Setting {
+    NodeVolumes(ObjectId),
+    NodeMute(ObjectId),
+    /// A device and route device
+    RouteVolumes(ObjectId, i32),
+    RouteMute(ObjectId, i32),
+}
+
+impl Setting {
+    /// The volume setting a command changes, if it changes one.
+    pub fn volumes(command: &Command) -> Option<Setting> {
+        match command {
+            Command::NodeVolumes(id, _) => Some(Setting::NodeVolumes(*id)),
+            Command::DeviceVolumes(id, _, route_device, _) => {
+                Some(Setting::RouteVolumes(*id, *route_device))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Volumes(Vec<f32>),
+    Mute(bool),
+}
+
+impl Value {
+    /// Whether a value from the monitor confirms this one.
+    fn confirmed_by(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::Volumes(a), Value::Volumes(b)) => {
+                a.len() == b.len()
+                    && a.iter().zip(b).all(|(a, b)| {
+                        (a.cbrt() - b.cbrt()).abs() < VOLUME_TOLERANCE
+                    })
+            }
+            (Value::Mute(a), Value::Mute(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Pending {
+    command_id: u64,
+    /// None while the command is held back by the
+    /// [`Coalescer`](`crate::coalesce::Coalescer`)
+    sent: Option<Instant>,
+    value: Value,
+    /// The latest value from the monitor, restored if the change fails
+    actual: Option<Value>,
+}
+
+/// Track volume and mute changes which haven't been confirmed yet.
+///
+/// Changes are applied to the [`State`] as soon as their commands are sent so
+/// that sliders move without waiting for the monitor. Until an event confirms
+/// a change, events with other values are overridden with it, and if the
+/// command fails or isn't confirmed in time, the monitor's latest value is
+/// restored.
+#[derive(Default, Debug)]
+pub struct Optimistic {
+    pending: HashMap<Setting, Pending>,
+}
+
+impl Optimistic {
+    /// Call when sending a command to apply its change to the state, and
+    /// [`Optimistic::on_sent()`] once it's actually sent to the monitor.
+    pub fn on_command(
+        &mut self,
+        state: &mut State,
+        command_id: u64,
+        command: &Command,
+    ) {
+        let (setting, value) = match command {
+            Command::NodeVolumes(id, volumes) => {
+                (Setting::NodeVolumes(*id), Value::Volumes(volumes.clone()))
+            }
+            Command::NodeMute(id, mute) => {
+                (Setting::NodeMute(*id), Value::Mute(*mute))
+            }
+            Command::DeviceVolumes(id, _, route_device, volumes) => (
+                Setting::RouteVolumes(*id, *route_device),
+                Value::Volumes(volumes.clone()),
+            ),
+            Command::DeviceMute(id, _, route_device, mute) => {
+                (Setting::RouteMute(*id, *route_device), Value::Mute(*mute))
+            }
+            _ => return,
+        };
+
+        let actual = match self.pending.remove(&setting) {
+            Some(pending) => pending.actual,
+            None => get(state, setting),
+        };
+        if actual.is_none() {
+            // Nothing to update
+            return;
+        }
+        set(state, setting, &value);
+        self.pending.insert(
+            setting,
+            Pending {
+                command_id,
+                sent: None,
+                value,
+                actual,
+            },
+        );
+    }
+
+    /// The settings an event from the monitor can confirm. Call this before
+    /// passing the event to [`State::update()`] and pass the result to
+    /// [`Optimistic::reconcile()`] afterwards.
+    pub fn settings(event: &MonitorEvent) -> Vec<Setting> {
+        match event {
+            MonitorEvent::NodeVolumes(id, _) => vec![Setting::NodeVolumes(*id)],
+            MonitorEvent::NodeMute(id, _) => vec![Setting::NodeMute(*id)],
+            MonitorEvent::DeviceRoute(id, _, route_device, ..) => vec![
+                Setting::RouteVolumes(*id, *route_device),
+                Setting::RouteMute(*id, *route_device),
+            ],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Confirm pending changes to settings which the state now has, and
+    /// override the monitor's values for the others.
+    pub fn reconcile(&mut self, state: &mut State, settings: &[Setting]) {
+        for &setting in settings {
+            let Some(pending) = self.pending.get_mut(&setting) else {
+                continue;
+            };
+            let actual = get(state, setting);
+            if actual
+                .as_ref()
+                .is_some_and(|actual| pending.value.confirmed_by(actual))
+            {
+                self.pending.remove(&setting);
+            } else {
+                set(state, setting, &pending.value);
+                pending.actual = actual;
+            }
+        }
+    }
+
+    /// Call when a command is sent to the monitor to start waiting for it to
+    /// be confirmed.
+    pub fn on_sent(&mut self, command_id: u64, now: Instant) {
+        if let Some(pending) = self
+            .pending
+            .values_mut()
+            .find(|pending| pending.command_id == command_id)
+        {
+            pending.sent = Some(now);
+        }
+    }
+
+    /// Call when a command fails to restore the monitor's value.
+    pub fn on_failure(&mut self, state: &mut State, command_id: u64) {
+        let setting = self.pending.iter().find_map(|(setting, pending)| {
+            (pending.command_id == command_id).then_some(*setting)
+        });
+        if let Some(setting) = setting {
+            self.restore(state, setting);
+        }
+    }
+
+    /// Restore the monitor's values for changes sent before `now` minus the
+    /// timeout. Returns whether any were restored.
+    pub fn expire(&mut self, state: &mut State, now: Instant) -> bool {
+        let expired: Vec<_> = self
+            .pending
+            .iter()
+            .filter(|(_, pending)| {
+                pending.sent.is_some_and(|sent| {
+                    now.saturating_duration_since(sent) >= CONFIRM_TIMEOUT
+                })
+            })
+            .map(|(setting, _)| *setting)
+            .collect();
+        for &setting in &expired {
+            self.restore(state, setting);
+        }
+        !expired.is_empty()
+    }
+
+    /// How long until the next change expires.
+    pub fn until_next_expiry(&self, now: Instant) -> Option<Duration> {
+        self.pending
+            .values()
+            .filter_map(|pending| pending.sent)
+            .map(|sent| {
+                CONFIRM_TIMEOUT
+                    .saturating_sub(now.saturating_duration_since(sent))
+            })
+            .min()
+    }
+
+    fn restore(&mut self, state: &mut State, setting: Setting) {
+        if let Some(Pending {
+            actual: Some(actual),
+            ..
+        }) = self.pending.remove(&setting)
+        {
+            set(state, setting, &actual);
+        }
+    }
+}
+
+fn get(state: &State, setting: Setting) -> Option<Value> {
+    match setting {
+        Setting::NodeVolumes(id) => {
+            Some(Value::Volumes(state.nodes.get(&id)?.volumes.clone()?))
+        }
+        Setting::NodeMute(id) => Some(Value::Mute(state.nodes.get(&id)?.mute?)),
+        Setting::RouteVolumes(id, route_device) => {
+            let device = state.devices.get(&id)?;
+            let route = device.routes.get(&route_device)?;
+            Some(Value::Volumes(route.volumes.clone()))
+        }
+        Setting::RouteMute(id, route_device) => {
+            let device = state.devices.get(&id)?;
+            Some(Value::Mute(device.routes.get(&route_device)?.mute))
+        }
+    }
+}
+
+fn set(state: &mut State, setting: Setting, value: &Value) {
+    match (setting, value) {
+        (Setting::NodeVolumes(id), Value::Volumes(volumes)) => {
+            if let Some(node) = state.nodes.get_mut(&id) {
+                node.volumes = Some(volumes.clone());
+            }
+        }
+        (Setting::NodeMute(id), Value::Mute(mute)) => {
+            if let Some(node) = state.nodes.get_mut(&id) {
+                node.mute = Some(*mute);
+            }
+        }
+        (Setting::RouteVolumes(id, route_device), Value::Volumes(volumes)) => {
+            if let Some(route) = state
+                .devices
+                .get_mut(&id)
+                .and_then(|device| device.routes.get_mut(&route_device))
+            {
+                route.volumes = volumes.clone();
+            }
+        }
+        (Setting::RouteMute(id, route_device), Value::Mute(mute)) => {
+            if let Some(route) = state
+                .devices
+                .get_mut(&id)
+                .and_then(|device| device.routes.get_mut(&route_device))
+            {
+                route.mute = *mute;
+            }
+        }
+        _ => return,
+    }
+    state.dirty = StateDirty::Everything;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capture_manager::CaptureManager;
+
+    fn state_with_node(id: ObjectId, volumes: Vec<f32>) -> State {
+        let mut state = State::default();
+        let mut capture_manager = CaptureManager::default();
+        state.update(
+            &mut capture_manager,
+            MonitorEvent::NodeVolumes(id, volumes),
+        );
+        state
+    }
+
+    fn update(
+        optimistic: &mut Optimistic,
+        state: &mut State,
+        event: MonitorEvent,
+    ) {
+        let settings = Optimistic::settings(&event);
+        state.update(&mut CaptureManager::default(), event);
+        optimistic.reconcile(state, &settings);
+    }
+
+    fn volumes(state: &State, id: ObjectId) -> Vec<f32> {
+        state.nodes.get(&id).unwrap().volumes.clone().unwrap()
+    }
+
+    #[test]
+    fn stale_events_are_overridden_until_confirmed() {
+        let id = ObjectId::from_raw_id(1);
+        let mut state = state_with_node(id, vec![0.125]);
+        let mut optimistic = Optimistic::default();
+
+        optimistic.on_command(
+            &mut state,
+            0,
+            &Command::NodeVolumes(id, vec![1.0]),
+        );
+        assert_eq!(volumes(&state, id), vec![1.0]);
+
+        // An event from before the command doesn't move the slider back
+        update(
+            &mut optimistic,
+            &mut state,
+            MonitorEvent::NodeVolumes(id, vec![0.125]),
+        );
+        assert_eq!(volumes(&state, id), vec![1.0]);
+
+        update(
+            &mut optimistic,
+            &mut state,
+            MonitorEvent::NodeVolumes(id, vec![1.0]),
+        );
+        assert!(optimistic.pending.is_empty());
+    }
+
+    #[test]
+    fn failure_restores_monitor_value() {
+        let id = ObjectId::from_raw_id(1);
+        let mut state = state_with_node(id, vec![0.125]);
+        let mut optimistic = Optimistic::default();
+
+        optimistic.on_command(
+            &mut state,
+            0,
+            &Command::NodeVolumes(id, vec![1.0]),
+        );
+        optimistic.on_failure(&mut state, 0);
+        assert_eq!(volumes(&state, id), vec![0.125]);
+    }
+
+    #[test]
+    fn unconfirmed_changes_expire() {
+        let id = ObjectId::from_raw_id(1);
+        let mut state = state_with_node(id, vec![0.125]);
+        let mut optimistic = Optimistic::default();
+
+        optimistic.on_command(
+            &mut state,
+            0,
+            &Command::NodeVolumes(id, vec![1.0]),
+        );
+        // Held back commands don't expire
+        let now = Instant::now();
+        assert!(!optimistic.expire(&mut state, now + CONFIRM_TIMEOUT * 2));
+        assert_eq!(optimistic.until_next_expiry(now), None);
+
+        optimistic.on_sent(0, now);
+        assert_eq!(optimistic.until_next_expiry(now), Some(CONFIRM_TIMEOUT));
+        assert!(!optimistic.expire(&mut state, now));
+        assert!(optimistic.expire(&mut state, now + CONFIRM_TIMEOUT));
+        assert_eq!(volumes(&state, id), vec![0.125]);
+        assert_eq!(optimistic.until_next_expiry(now), None);
+    }
+}
@@ -0,0 +1,101 @@
+//! Setting the terminal title and sending desktop notifications with OSC
+//! escape sequences, configured by `[osc]`.
+//!
+//! The title is also what terminal multiplexers show for the pane, such as
+//! `#T` in tmux's status line. Notifications are passed through tmux to the
+//! outer terminal, which needs tmux's `allow-passthrough` option.
+
+use std::env;
+use std::io::{stdout, Write};
+
+use serde::Deserialize;
+
+/// Escape sequences for desktop notifications.
+#[derive(Deserialize, Default, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Protocol {
+    /// `OSC 9 ; body`, supported by iTerm2, kitty, WezTerm, Windows Terminal
+    /// and Ghostty
+    #[default]
+    Osc9,
+    /// `OSC 777 ; notify ; title ; body`, supported by foot, urxvt, WezTerm
+    /// and Ghostty
+    Osc777,
+}
+
+/// Save the current title so that [`pop_title()`] can restore it.
+pub fn push_title() {
+    write("\x1b[22;0t");
+}
+
+/// Restore the title saved by [`push_title()`].
+pub fn pop_title() {
+    write("\x1b[23;0t");
+}
+
+pub fn set_title(title: &str) {
+    write(&format!("\x1b]2;{}\x07", sanitize(title)));
+}
+
+pub fn notify(protocol: Protocol, body: &str) {
+    let sequence = notification(protocol, body);
+    if env::var_os("TMUX").is_some() {
+        write(&tmux_passthrough(&sequence));
+    } else {
+        write(&sequence);
+    }
+}
+
+fn notification(protocol: Protocol, body: &str) -> String {
+    match protocol {
+        Protocol::Osc9 => format!("\x1b]9;{}\x07", sanitize(body)),
+        Protocol::Osc777 => {
+            // The title and body are separated by semicolons
+            let body = sanitize(body).replace(';', ",");
+            format!("\x1b]777;notify;wiremix;{}\x07", body)
+        }
+    }
+}
+
+/// Wrap a sequence so that tmux sends it to the outer terminal.
+fn tmux_passthrough(sequence: &str) -> String {
+    format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"))
+}
+
+/// Remove control characters, which could end the sequence early.
+fn sanitize(text: &str) -> String {
+    text.chars().filter(|ch| !ch.is_control()).collect()
+}
+
+/// Write directly to the terminal, ignoring errors since these are only
+/// cosmetic.
+fn write(sequence: &str) {
+    let mut stdout = stdout();
+    let _ = stdout.write_all(sequence.as_bytes());
+    let _ = stdout.flush();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notifications() {
+        assert_eq!(
+            notification(Protocol::Osc9, "Headphones\x07 plugged in"),
+            "\x1b]9;Headphones plugged in\x07"
+        );
+        assert_eq!(
+            notification(Protocol::Osc777, "Low battery: Buds; 10%"),
+            "\x1b]777;notify;wiremix;Low battery: Buds, 10%\x07"
+        );
+    }
+
+    #[test]
+    fn passthrough() {
+        assert_eq!(
+            tmux_passthrough("\x1b]9;hi\x07"),
+            "\x1bPtmux;\x1b\x1b]9;hi\x07\x1b\\"
+        );
+    }
+}
@@ -0,0 +1,58 @@
+//! Hiding streams which have been paused for a while, configured by
+//! `hide_paused_after`.
+
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use crate::object::ObjectId;
+use crate::view::View;
+
+/// Tracks when streams were paused.
+#[derive(Default, Debug)]
+pub struct PausedStreams {
+    /// When each paused stream was first seen paused
+    since: HashMap<ObjectId, Instant>,
+    /// The paused streams which have been removed from the view's lists
+    hidden: HashSet<ObjectId>,
+}
+
+impl PausedStreams {
+    /// Track which streams are paused in a newly built view, and remove those
+    /// paused for at least `timeout` from its lists.
+    pub fn update(&mut self, view: &mut View, timeout: Duration, now: Instant) {
+        self.since.retain(|node_id, _| {
+            view.nodes.get(node_id).is_some_and(|node| node.paused)
+        });
+        for node in view.nodes.values().filter(|node| node.paused) {
+            self.since.entry(node.id).or_insert(now);
+        }
+
+        self.hidden = self
+            .since
+            .iter()
+            .filter(|(_, since)| {
+                now.saturating_duration_since(**since) >= timeout
+            })
+            .map(|(&node_id, _)| node_id)
+            .collect();
+        for list in [&mut view.nodes_playback, &mut view.nodes_recording] {
+            list.retain(|node_id| !self.hidden.contains(node_id));
+        }
+    }
+
+    /// How long until another stream should be hidden, which is zero if one
+    /// is overdue.
+    pub fn until_next_hide(
+        &self,
+        timeout: Duration,
+        now: Instant,
+    ) -> Option<Duration> {
+        self.since
+            .iter()
+            .filter(|(node_id, _)| !self.hidden.contains(node_id))
+            .map(|(_, since)| {
+                timeout.saturating_sub(now.saturating_duration_since(*since))
+            })
+            .min()
+    }
+}
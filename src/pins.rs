@@ -0,0 +1,118 @@
+//! Pinning streams to an output or input device so that they stay there.
+//!
+//! Pinning a stream writes its device's object.serial to the stream's
+//! `target.object` metadata, which the session manager honors when it next
+//! links the stream. If the session manager moves the stream anyway, such as
+//! back to HDMI when a monitor wakes up, the target is written again.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::command::Command;
+use crate::object::ObjectId;
+use crate::view::{Target, View};
+
+/// The streams which are pinned and the devices they're pinned to.
+#[derive(Default, Debug)]
+pub struct Pins {
+    pinned: HashMap<ObjectId, ObjectId>,
+    /// Pinned streams which were moved back and haven't arrived yet, so the
+    /// target isn't written again on every view
+    moved: HashSet<ObjectId>,
+}
+
+impl Pins {
+    /// Pin a stream to the device it's using, returning the device.
+    pub fn pin(
+        &mut self,
+        view: &View,
+        stream_id: ObjectId,
+    ) -> Option<ObjectId> {
+        let target_id = current_target(view, stream_id)?;
+        self.pinned.insert(stream_id, target_id);
+        Some(target_id)
+    }
+
+    /// Unpin a stream, returning whether it was pinned.
+    pub fn unpin(&mut self, stream_id: ObjectId) -> bool {
+        self.moved.remove(&stream_id);
+        self.pinned.remove(&stream_id).is_some()
+    }
+
+    /// Mark the pinned streams in a newly built view and forget streams
+    /// which are gone. Returns the streams which have been moved off the
+    /// device they're pinned to, while that device is still present, with
+    /// the device.
+    pub fn update(&mut self, view: &mut View) -> Vec<(ObjectId, ObjectId)> {
+        self.pinned
+            .retain(|stream_id, _| view.nodes.contains_key(stream_id));
+        self.moved
+            .retain(|stream_id| self.pinned.contains_key(stream_id));
+
+        let mut moved = Vec::new();
+        for (&stream_id, &target_id) in &self.pinned {
+            if let Some(node) = view.nodes.get_mut(&stream_id) {
+                node.pinned = true;
+            }
+            let current = current_target(view, stream_id);
+            if current == Some(target_id) {
+                self.moved.remove(&stream_id);
+            } else if current.is_some()
+                && view.nodes.contains_key(&target_id)
+                && self.moved.insert(stream_id)
+            {
+                moved.push((stream_id, target_id));
+            }
+        }
+        moved
+    }
+}
+
+/// The device a stream is linked to, following the default if it doesn't
+/// have a target of its own.
+fn current_target(view: &View, stream_id: ObjectId) -> Option<ObjectId> {
+    let node = view.nodes.get(&stream_id)?;
+    let default = if node.media_class.is_sink_input() {
+        view.default_sink
+    } else if node.media_class.is_source_output() {
+        view.default_source
+    } else {
+        return None;
+    };
+    match node.target? {
+        Target::Node(target_id) => Some(target_id),
+        Target::Default => match default? {
+            Target::Node(target_id) => Some(target_id),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Returns the commands which point a stream's `target.object` at a device.
+pub fn commands(
+    view: &View,
+    stream_id: ObjectId,
+    target_id: ObjectId,
+) -> Vec<Command> {
+    let (Some(metadata_id), Some(target)) =
+        (view.metadata_id, view.nodes.get(&target_id))
+    else {
+        return Vec::new();
+    };
+    vec![
+        Command::MetadataSetProperty(
+            metadata_id,
+            stream_id.into(),
+            String::from("target.node"),
+            None,
+            None,
+        ),
+        Command::MetadataSetProperty(
+            metadata_id,
+            stream_id.into(),
+            String::from("target.object"),
+            Some(String::from("Spa:Id")),
+            Some(target.object_serial.to_string()),
+        ),
+    ]
+}
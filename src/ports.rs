@@ -0,0 +1,107 @@
+//! Noticing when ports become available or unavailable, such as when
+//! headphones are plugged into or unplugged from a jack.
+
+use std::collections::HashMap;
+
+use crate::object::ObjectId;
+use crate::state::State;
+
+/// A port whose availability changed.
+#[derive(Debug, PartialEq)]
+pub struct PortChange {
+    pub device_id: ObjectId,
+    pub description: String,
+    pub available: bool,
+}
+
+/// Tracks the availability of every device's ports.
+#[derive(Default, Debug)]
+pub struct Ports {
+    /// Availability by device and route index, or None before the first
+    /// update
+    available: Option<HashMap<(ObjectId, i32), bool>>,
+}
+
+impl Ports {
+    /// The ports whose availability changed since the last update. Nothing
+    /// has changed on the first update.
+    pub fn update(&mut self, state: &State) -> Vec<PortChange> {
+        let available: HashMap<_, _> = state
+            .devices
+            .values()
+            .flat_map(|device| {
+                device
+                    .enum_routes
+                    .values()
+                    .map(|route| ((device.id, route.index), route.available))
+            })
+            .collect();
+
+        let mut changes = Vec::new();
+        if let Some(previous) = &self.available {
+            for (&(device_id, index), &available) in &available {
+                if previous.get(&(device_id, index)) != Some(&!available) {
+                    continue;
+                }
+                let Some(route) = state
+                    .devices
+                    .get(&device_id)
+                    .and_then(|device| device.enum_routes.get(&index))
+                else {
+                    continue;
+                };
+                changes.push(PortChange {
+                    device_id,
+                    description: route.description.clone(),
+                    available,
+                });
+            }
+        }
+        self.available = Some(available);
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capture_manager::CaptureManager;
+    use crate::event::MonitorEvent;
+
+    fn enum_route(state: &mut State, available: bool) {
+        let event = MonitorEvent::DeviceEnumRoute(
+            ObjectId::from_raw_id(1),
+            0,
+            String::from("Headphones"),
+            available,
+            vec![1],
+            vec![0],
+        );
+        state.update(&mut CaptureManager::default(), event);
+    }
+
+    #[test]
+    fn plug_and_unplug() {
+        let mut state = State::default();
+        let mut ports = Ports::default();
+        enum_route(&mut state, false);
+        assert!(ports.update(&state).is_empty());
+        assert!(ports.update(&state).is_empty());
+
+        enum_route(&mut state, true);
+        let changes = ports.update(&state);
+        assert_eq!(
+            changes,
+            vec![PortChange {
+                device_id: ObjectId::from_raw_id(1),
+                description: String::from("Headphones"),
+                available: true,
+            }]
+        );
+
+        enum_route(&mut state, false);
+        let changes = ports.update(&state);
+        assert_eq!(changes.len(), 1);
+        assert!(!changes[0].available);
+    }
+}
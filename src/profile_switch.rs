@@ -0,0 +1,134 @@
+//! Tracking device profile switches until they take effect or fail.
+//!
+//! Switching profiles, such as a Bluetooth headset's codec, is asynchronous
+//! and can fail, in which case the device keeps its old profile.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::object::ObjectId;
+use crate::state::State;
+
+/// How long a switch can take before it's considered to have failed.
+const SWITCH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How often the spinner of a device being switched advances.
+pub const SPINNER_STEP: Duration = Duration::from_millis(100);
+
+#[derive(Debug)]
+struct Switch {
+    command_id: u64,
+    profile_index: i32,
+    sent: Instant,
+}
+
+/// Profile switches which haven't taken effect yet, at most one per device.
+#[derive(Default, Debug)]
+pub struct ProfileSwitches {
+    pending: HashMap<ObjectId, Switch>,
+}
+
+impl ProfileSwitches {
+    /// Call when sending a command to switch a device's profile.
+    pub fn start(
+        &mut self,
+        device_id: ObjectId,
+        profile_index: i32,
+        command_id: u64,
+        now: Instant,
+    ) {
+        let switch = Switch {
+            command_id,
+            profile_index,
+            sent: now,
+        };
+        self.pending.insert(device_id, switch);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// The spinner frame for a device being switched.
+    pub fn frame(&self, device_id: ObjectId, now: Instant) -> Option<usize> {
+        let switch = self.pending.get(&device_id)?;
+        let elapsed = now.saturating_duration_since(switch.sent);
+        Some((elapsed.as_millis() / SPINNER_STEP.as_millis()) as usize)
+    }
+
+    /// Stop tracking switches which have taken effect, and return the devices
+    /// whose switches have timed out.
+    pub fn update(&mut self, state: &State, now: Instant) -> Vec<ObjectId> {
+        let mut timed_out = Vec::new();
+        self.pending.retain(|device_id, switch| {
+            let Some(device) = state.devices.get(device_id) else {
+                return false;
+            };
+            if device.profile_index == Some(switch.profile_index) {
+                return false;
+            }
+            if now.saturating_duration_since(switch.sent) >= SWITCH_TIMEOUT {
+                timed_out.push(*device_id);
+                return false;
+            }
+            true
+        });
+        timed_out
+    }
+
+    /// The device whose switch failed because its command did, if any.
+    pub fn on_failure(&mut self, command_id: u64) -> Option<ObjectId> {
+        let device_id = self
+            .pending
+            .iter()
+            .find(|(_, switch)| switch.command_id == command_id)
+            .map(|(&device_id, _)| device_id)?;
+        self.pending.remove(&device_id);
+        Some(device_id)
+    }
+
+    /// Call when a device reports an error, returning whether it was being
+    /// switched.
+    pub fn on_error(&mut self, device_id: ObjectId) -> bool {
+        self.pending.remove(&device_id).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capture_manager::CaptureManager;
+    use crate::event::MonitorEvent;
+
+    #[test]
+    fn confirm_or_time_out() {
+        let device_id = ObjectId::from_raw_id(1);
+        let mut state = State::default();
+        let mut capture_manager = CaptureManager::default();
+        state.update(
+            &mut capture_manager,
+            MonitorEvent::DeviceProfile(device_id, 1),
+        );
+        let now = Instant::now();
+
+        let mut switches = ProfileSwitches::default();
+        switches.start(device_id, 2, 7, now);
+        assert!(switches.update(&state, now).is_empty());
+        assert_eq!(switches.frame(device_id, now), Some(0));
+        state.update(
+            &mut capture_manager,
+            MonitorEvent::DeviceProfile(device_id, 2),
+        );
+        assert!(switches.update(&state, now).is_empty());
+        assert!(switches.is_empty());
+
+        switches.start(device_id, 3, 8, now);
+        let later = now + SWITCH_TIMEOUT;
+        assert_eq!(switches.update(&state, later), vec![device_id]);
+        assert!(switches.is_empty());
+
+        switches.start(device_id, 3, 9, now);
+        assert_eq!(switches.on_failure(9), Some(device_id));
+        assert!(!switches.on_error(device_id));
+    }
+}
@@ -0,0 +1,182 @@
+//! Editing a node's properties in the tab bar.
+//!
+//! A node's own properties can only be changed by the client which owns it,
+//! so edits are written to the default metadata for the node, where the
+//! session manager reads settings such as `target.object`. Each edit keeps
+//! the value it replaced so that it can be reverted.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::object::ObjectId;
+
+/// A property which can be edited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Property {
+    PrioritySession,
+    PauseOnIdle,
+    TargetObject,
+}
+
+impl Property {
+    pub const ALL: [Property; 3] = [
+        Property::PrioritySession,
+        Property::PauseOnIdle,
+        Property::TargetObject,
+    ];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            Property::PrioritySession => "priority.session",
+            Property::PauseOnIdle => "node.pause-on-idle",
+            Property::TargetObject => "target.object",
+        }
+    }
+
+    /// The metadata type the value is written with.
+    pub fn type_(&self) -> Option<&'static str> {
+        match self {
+            Property::PrioritySession => Some("Spa:Int"),
+            Property::PauseOnIdle => Some("Spa:Bool"),
+            Property::TargetObject => None,
+        }
+    }
+
+    /// Returns the value in the form it's written, or why it isn't valid.
+    pub fn validate(&self, value: &str) -> Result<String, &'static str> {
+        let value = value.trim();
+        match self {
+            Property::PrioritySession => value
+                .parse::<i32>()
+                .map(|value| value.to_string())
+                .map_err(|_| "expected an integer"),
+            Property::PauseOnIdle => value
+                .parse::<bool>()
+                .map(|value| value.to_string())
+                .map_err(|_| "expected true or false"),
+            Property::TargetObject if value.is_empty() => {
+                Err("expected a node name or serial")
+            }
+            Property::TargetObject => Ok(String::from(value)),
+        }
+    }
+}
+
+/// What a key press did to the value being edited.
+#[derive(Debug, PartialEq)]
+pub enum Outcome {
+    Editing,
+    Cancelled,
+    /// The valid value to write
+    Submitted(String),
+}
+
+/// A property value being typed.
+#[derive(Debug)]
+pub struct PropertyEditor {
+    pub node_id: ObjectId,
+    pub property: Property,
+    /// The value before editing, if any
+    pub previous: Option<String>,
+    input: String,
+    /// Why the last submitted value was rejected
+    error: Option<&'static str>,
+}
+
+impl PropertyEditor {
+    pub fn new(
+        node_id: ObjectId,
+        property: Property,
+        previous: Option<String>,
+    ) -> Self {
+        Self {
+            node_id,
+            property,
+            input: previous.clone().unwrap_or_default(),
+            previous,
+            error: None,
+        }
+    }
+
+    pub fn key(&mut self, key: KeyEvent) -> Outcome {
+        match key.code {
+            KeyCode::Char('u')
+                if key.modifiers.contains(KeyModifiers::CONTROL) =>
+            {
+                self.input.clear();
+            }
+            KeyCode::Char(c)
+                if !key.modifiers.contains(KeyModifiers::CONTROL) =>
+            {
+                self.input.push(c);
+            }
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            KeyCode::Enter => match self.property.validate(&self.input) {
+                Ok(value) => return Outcome::Submitted(value),
+                Err(error) => {
+                    self.error = Some(error);
+                    return Outcome::Editing;
+                }
+            },
+            KeyCode::Esc => return Outcome::Cancelled,
+            _ => {}
+        }
+        self.error = None;
+        Outcome::Editing
+    }
+
+    /// The property and the value being typed, for showing in the tab bar.
+    pub fn prompt(&self) -> String {
+        match self.error {
+            Some(error) => {
+                format!("{}={} ({})", self.property.key(), self.input, error)
+            }
+            None => format!("{}={}", self.property.key(), self.input),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn typed(editor: &mut PropertyEditor, text: &str) -> Outcome {
+        for c in text.chars() {
+            editor.key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        editor.key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn validates_types() {
+        let id = ObjectId::from_raw_id(1);
+        let mut editor =
+            PropertyEditor::new(id, Property::PrioritySession, None);
+        assert_eq!(typed(&mut editor, "high"), Outcome::Editing);
+        assert_eq!(
+            editor.prompt(),
+            "priority.session=high (expected an integer)"
+        );
+
+        let mut editor = PropertyEditor::new(
+            id,
+            Property::PrioritySession,
+            Some(String::from("1000")),
+        );
+        assert_eq!(
+            typed(&mut editor, "0"),
+            Outcome::Submitted(String::from("10000"))
+        );
+
+        let mut editor = PropertyEditor::new(id, Property::PauseOnIdle, None);
+        assert_eq!(
+            typed(&mut editor, "false"),
+            Outcome::Submitted(String::from("false"))
+        );
+        assert_eq!(
+            Property::TargetObject.validate(" "),
+            Err("expected a node name or serial")
+        );
+    }
+}
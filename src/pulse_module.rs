@@ -0,0 +1,110 @@
+//! Loading and unloading PulseAudio modules with pactl.
+//!
+//! pipewire-pulse implements many PulseAudio modules, such as network
+//! discovery, on top of PipeWire. Modules loaded this way stay loaded after
+//! wiremix exits.
+
+use std::process;
+
+use anyhow::{anyhow, Context};
+
+/// Run pactl with args, returning its output.
+fn pactl(args: &[&str]) -> Result<String, anyhow::Error> {
+    let output = process::Command::new("pactl")
+        .args(args)
+        .output()
+        .context("Failed to run pactl")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("pactl {}: {}", args.join(" "), stderr.trim()));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Indices of the loaded modules called name. If args isn't empty, only
+/// modules loaded with the same arguments are included.
+pub fn loaded(name: &str, args: &[&str]) -> Result<Vec<u32>, anyhow::Error> {
+    let list = pactl(&["list", "short", "modules"])?;
+    Ok(parse_modules(&list, name, &args.join(" ")))
+}
+
+/// Load a module, returning its index.
+pub fn load(name: &str, args: &[&str]) -> Result<u32, anyhow::Error> {
+    let load_args = [&["load-module", name], args].concat();
+    let output = pactl(&load_args)?;
+    output
+        .trim()
+        .parse()
+        .with_context(|| format!("Unexpected output from pactl: {}", output))
+}
+
+pub fn unload(index: u32) -> Result<(), anyhow::Error> {
+    pactl(&["unload-module", &index.to_string()]).map(|_| ())
+}
+
+/// Unload the modules called name, with args if there are any, if any are
+/// loaded, or load one otherwise. Returns the index of the module if it was
+/// loaded.
+pub fn toggle(name: &str, args: &[&str]) -> Result<Option<u32>, anyhow::Error> {
+    let indices = loaded(name, args)?;
+    if indices.is_empty() {
+        return load(name, args).map(Some);
+    }
+
+    for index in indices {
+        unload(index)?;
+    }
+    Ok(None)
+}
+
+/// Parse the output of `pactl list short modules` for modules called name,
+/// and with args unless it's empty.
+fn parse_modules(list: &str, name: &str, args: &str) -> Vec<u32> {
+    list.lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            let index = fields.next()?;
+            let matches = fields.next()? == name
+                && (args.is_empty() || fields.next()? == args);
+            matches.then(|| index.parse().ok())?
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_modules_by_name() {
+        let list = "\
+536870912\tlibpipewire-module-protocol-pulse\t\t
+536870913\tmodule-raop-discover\t\t
+536870914\tmodule-null-sink\tsink_name=test\t
+536870915\tmodule-raop-discover\t\t
+";
+        assert_eq!(
+            parse_modules(list, "module-raop-discover", ""),
+            vec![536870913, 536870915]
+        );
+        assert!(parse_modules(list, "module-zeroconf-publish", "").is_empty());
+    }
+
+    #[test]
+    fn parse_modules_by_args() {
+        let list = "\
+536870913\tmodule-rtp-send\tdestination_ip=239.0.0.1\t
+536870914\tmodule-rtp-send\tdestination_ip=239.0.0.2 port=46000\t
+";
+        assert_eq!(
+            parse_modules(
+                list,
+                "module-rtp-send",
+                "destination_ip=239.0.0.2 port=46000"
+            ),
+            vec![536870914]
+        );
+        assert!(parse_modules(list, "module-rtp-send", "port=46000").is_empty());
+    }
+}
@@ -0,0 +1,57 @@
+//! Noticing applications starting to record, configured by
+//! [`Recording`](`crate::config::Recording`).
+
+use crate::hook;
+
+/// Tracks the applications which are recording.
+#[derive(Default, Debug)]
+pub struct RecordingClients {
+    clients: Vec<String>,
+}
+
+impl RecordingClients {
+    /// Replace the applications which are recording, returning those which
+    /// weren't before.
+    pub fn update(&mut self, clients: &[impl AsRef<str>]) -> Vec<String> {
+        let clients: Vec<String> = clients
+            .iter()
+            .map(|client| String::from(client.as_ref()))
+            .collect();
+        let started = clients
+            .iter()
+            .filter(|client| !self.clients.contains(client))
+            .cloned()
+            .collect();
+        self.clients = clients;
+        started
+    }
+}
+
+/// Run the `on_start` command for an application which started recording
+/// from a `kind` of device, "microphone" or "camera".
+pub fn on_start(
+    command: &str,
+    kind: &str,
+    client: &str,
+) -> Result<(), anyhow::Error> {
+    let env = [
+        ("WIREMIX_RECORDING_CLIENT", client),
+        ("WIREMIX_RECORDING_KIND", kind),
+    ];
+    hook::spawn("recording.on_start", command, &env)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn started_clients() {
+        let mut clients = RecordingClients::default();
+        assert_eq!(clients.update(&["Firefox"]), vec!["Firefox"]);
+        assert!(clients.update(&["Firefox"]).is_empty());
+        assert_eq!(clients.update(&["Discord", "Firefox"]), vec!["Discord"]);
+        assert!(clients.update(&[] as &[&str]).is_empty());
+        assert_eq!(clients.update(&["Firefox"]), vec!["Firefox"]);
+    }
+}
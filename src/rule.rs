@@ -0,0 +1,192 @@
+//! Configuration snippets pre-filled from an object, for the `CopyRule` and
+//! `AppendRule` actions, so that rules don't have to be written by hand from
+//! the object's properties.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Context};
+use serde::{Deserialize, Serialize};
+
+use crate::object::ObjectId;
+use crate::view::View;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleKind {
+    /// A `[[names.overrides]]` entry for the object
+    NameOverride,
+    /// An `[[initial_volume]]` rule at a stream's current volume
+    InitialVolume,
+    /// A `[priorities]` list ranking a sink or source first
+    Priority,
+    /// A `[[role_tab]]` listing the streams with a stream's media.role
+    RoleTab,
+}
+
+/// A TOML string, quoted and escaped.
+fn quote(s: &str) -> String {
+    toml::Value::from(s).to_string()
+}
+
+/// A selector condition value, quoted and escaped.
+fn quote_selector(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// A snippet of configuration for a `kind` rule about `object_id`.
+pub fn snippet(
+    view: &View,
+    object_id: ObjectId,
+    kind: RuleKind,
+) -> Result<String, anyhow::Error> {
+    if let Some(device) = view.devices.get(&object_id) {
+        return match kind {
+            RuleKind::NameOverride => Ok(format!(
+                "[[names.overrides]]\n\
+                 types = [ \"device\" ]\n\
+                 property = \"device:device.name\"\n\
+                 value = {}\n\
+                 templates = [ \"{{device:device.description}}\" ]\n",
+                quote(&device.name),
+            )),
+            _ => bail!("Only name overrides can be created for devices"),
+        };
+    }
+    let node = view
+        .nodes
+        .get(&object_id)
+        .ok_or_else(|| anyhow!("Nothing selected"))?;
+    let is_stream =
+        node.media_class.is_sink_input() || node.media_class.is_source_output();
+
+    match kind {
+        RuleKind::NameOverride if is_stream => Ok(format!(
+            "[[names.overrides]]\n\
+             types = [ \"stream\" ]\n\
+             property = \"node:node.name\"\n\
+             value = {}\n\
+             templates = [ \"{{node:node.name}}: {{node:media.name}}\" ]\n",
+            quote(&node.name),
+        )),
+        RuleKind::NameOverride => Ok(format!(
+            "[[names.overrides]]\n\
+             types = [ \"endpoint\" ]\n\
+             property = \"node:node.name\"\n\
+             value = {}\n\
+             templates = [ \"{{node:node.description}}\" ]\n",
+            quote(&node.name),
+        )),
+        RuleKind::InitialVolume => {
+            if !is_stream {
+                bail!("Initial volumes can only be set for streams");
+            }
+            let volume = node.volume().unwrap_or(1.0);
+            Ok(format!(
+                "[[initial_volume]]\n\
+                 select = {}\n\
+                 volume = {:.2}\n",
+                quote(&format!("node.name={}", quote_selector(&node.name))),
+                volume,
+            ))
+        }
+        RuleKind::Priority => {
+            let list = if node.media_class.is_sink() {
+                "sinks"
+            } else if node.media_class.is_source() {
+                "sources"
+            } else {
+                bail!("Priorities can only be set for sinks and sources");
+            };
+            Ok(format!(
+                "[priorities]\n{} = [ {} ]\n",
+                list,
+                quote(&node.name)
+            ))
+        }
+        RuleKind::RoleTab => {
+            let Some(role) = node.media_role.as_deref().filter(|_| is_stream)
+            else {
+                bail!("Role tabs can only be made from streams with a role");
+            };
+            Ok(format!(
+                "[[role_tab]]\n\
+                 title = {}\n\
+                 roles = [ {} ]\n",
+                quote(role),
+                quote(role),
+            ))
+        }
+    }
+}
+
+/// Append `snippet` to the configuration file at `path`, creating it if
+/// needed. Nothing is written if the result wouldn't be valid TOML, such as
+/// when the file already has a `[priorities]` table.
+pub fn append(path: &Path, snippet: &str) -> Result<(), anyhow::Error> {
+    let context =
+        || format!("Failed to add the rule to file '{}'", path.display());
+    let mut contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err).with_context(context),
+    };
+    if !contents.is_empty() {
+        if !contents.ends_with('\n') {
+            contents.push('\n');
+        }
+        contents.push('\n');
+    }
+    contents.push_str(snippet);
+    contents
+        .parse::<toml::Table>()
+        .map_err(|err| anyhow!("{}", err.message()))
+        .with_context(context)?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(context)?;
+    }
+    fs::write(path, contents).with_context(context)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capture_manager::CaptureManager;
+    use crate::config::{InitialVolume, Names};
+    use crate::event::MonitorEvent;
+    use crate::media_class::MediaClass;
+    use crate::state::State;
+
+    #[test]
+    fn initial_volume_rule_selects_stream() {
+        let id = ObjectId::from_raw_id(1);
+        let mut state = State::default();
+        for event in [
+            MonitorEvent::NodeName(id, String::from("Game \"Launcher\"")),
+            MonitorEvent::NodeDescription(id, String::from("Launcher")),
+            MonitorEvent::NodeObjectSerial(id, 1),
+            MonitorEvent::NodeMediaClass(
+                id,
+                MediaClass::from("Stream/Output/Audio"),
+            ),
+            MonitorEvent::NodePositions(id, vec![0, 1]),
+            MonitorEvent::NodeVolumes(id, vec![0.125, 0.125]),
+            MonitorEvent::NodeMute(id, false),
+        ] {
+            state.update(&mut CaptureManager::default(), event);
+        }
+        let view = View::from(&state, &Names::default());
+
+        let text = snippet(&view, id, RuleKind::InitialVolume).unwrap();
+        let table: toml::Table = text.parse().unwrap();
+        let rule: InitialVolume =
+            table["initial_volume"][0].clone().try_into().unwrap();
+        assert_eq!(rule.volume, 0.5);
+        assert_eq!(rule.select.nodes(&state), [id]);
+
+        assert!(snippet(&view, id, RuleKind::Priority).is_err());
+        assert!(snippet(&view, id, RuleKind::RoleTab).is_err());
+    }
+}
@@ -0,0 +1,149 @@
+//! Applying the entries of [`schedule`](`crate::config::Config::schedule`) at
+//! their times of day, such as capping the volume at night.
+//!
+//! The entry in effect is the last one whose time has passed today, or the
+//! last one of the day before if none has.
+
+use std::fmt;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::config::ScheduleEntry;
+
+/// How often the time is checked for an entry taking effect.
+pub const SCHEDULE_STEP: Duration = Duration::from_secs(10);
+
+/// A local time of day, written as "HH:MM".
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(try_from = "String")]
+pub struct TimeOfDay {
+    /// Minutes since midnight
+    minutes: u16,
+}
+
+impl TimeOfDay {
+    /// The current local time.
+    pub fn now() -> Self {
+        use nix::libc;
+
+        // SAFETY: time and localtime_r only write to the values passed to
+        // them, and a zeroed tm is a valid value.
+        let minutes = unsafe {
+            let now = libc::time(std::ptr::null_mut());
+            let mut tm: libc::tm = std::mem::zeroed();
+            if libc::localtime_r(&now, &mut tm).is_null() {
+                0
+            } else {
+                tm.tm_hour * 60 + tm.tm_min
+            }
+        };
+        Self {
+            minutes: minutes as u16,
+        }
+    }
+}
+
+impl TryFrom<String> for TimeOfDay {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let error = || format!("expected a time as HH:MM, got '{}'", value);
+        let (hours, minutes) = value.split_once(':').ok_or_else(error)?;
+        let hours: u16 = hours.parse().map_err(|_| error())?;
+        let minutes: u16 = minutes.parse().map_err(|_| error())?;
+        if hours > 23 || minutes > 59 {
+            return Err(error());
+        }
+        Ok(Self {
+            minutes: hours * 60 + minutes,
+        })
+    }
+}
+
+impl fmt::Display for TimeOfDay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02}", self.minutes / 60, self.minutes % 60)
+    }
+}
+
+/// Tracks the schedule entry in effect.
+#[derive(Default, Debug)]
+pub struct Scheduler {
+    current: Option<usize>,
+}
+
+impl Scheduler {
+    /// The index of the entry in effect, if there are any entries.
+    pub fn current(&self) -> Option<usize> {
+        self.current
+    }
+
+    /// Returns the index of the entry which takes effect at `now`, if it's
+    /// a different one from before. The first call returns the entry in
+    /// effect.
+    pub fn update(
+        &mut self,
+        schedule: &[ScheduleEntry],
+        now: TimeOfDay,
+    ) -> Option<usize> {
+        let current = in_effect(schedule, now);
+        if current == self.current {
+            return None;
+        }
+        self.current = current;
+        current
+    }
+}
+
+/// The index of the entry in effect at `now`.
+fn in_effect(schedule: &[ScheduleEntry], now: TimeOfDay) -> Option<usize> {
+    let latest = |until: TimeOfDay| {
+        schedule
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.at <= until)
+            .max_by_key(|(_, entry)| entry.at)
+            .map(|(index, _)| index)
+    };
+    let end_of_day = TimeOfDay { minutes: u16::MAX };
+    latest(now).or_else(|| latest(end_of_day))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(value: &str) -> TimeOfDay {
+        TimeOfDay::try_from(String::from(value)).unwrap()
+    }
+
+    fn entry(at: &str) -> ScheduleEntry {
+        ScheduleEntry {
+            name: None,
+            at: time(at),
+            max_volume: None,
+            fade_ms: 0,
+        }
+    }
+
+    #[test]
+    fn parse_times() {
+        assert_eq!(time("23:00").to_string(), "23:00");
+        assert_eq!(time("7:05").to_string(), "07:05");
+        assert!(TimeOfDay::try_from(String::from("24:00")).is_err());
+        assert!(TimeOfDay::try_from(String::from("noon")).is_err());
+    }
+
+    #[test]
+    fn entry_in_effect_wraps_past_midnight() {
+        let schedule = [entry("08:00"), entry("23:00")];
+        let mut scheduler = Scheduler::default();
+        assert_eq!(scheduler.update(&schedule, time("02:00")), Some(1));
+        assert_eq!(scheduler.update(&schedule, time("07:59")), None);
+        assert_eq!(scheduler.update(&schedule, time("08:00")), Some(0));
+        assert_eq!(scheduler.update(&schedule, time("22:00")), None);
+        assert_eq!(scheduler.update(&schedule, time("23:30")), Some(1));
+        assert_eq!(scheduler.current(), Some(1));
+    }
+}
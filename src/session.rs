@@ -0,0 +1,91 @@
+//! UI state saved on exit so that it can be restored on the next launch.
+//!
+//! The session is saved in the XDG state directory alongside recorded macros.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use crate::app::TabKind;
+
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq)]
+#[serde(default)]
+pub struct Session {
+    /// The tab which had focus
+    pub tab: Option<TabKind>,
+    /// The node.name or device.name of the object selected in each tab
+    pub selected: BTreeMap<TabKind, String>,
+    /// Whether the split tab had focus
+    pub split_focused: bool,
+    /// Whether compact mode was toggled on
+    pub compact: Option<bool>,
+}
+
+/// Returns the path of the file containing the saved session.
+pub fn default_path() -> Option<PathBuf> {
+    if let Ok(xdg_state) = env::var("XDG_STATE_HOME") {
+        return Some(Path::new(&xdg_state).join("wiremix/session.toml"));
+    }
+
+    if let Ok(home) = env::var("HOME") {
+        return Some(
+            Path::new(&home).join(".local/state/wiremix/session.toml"),
+        );
+    }
+
+    None
+}
+
+/// Load the saved session. A missing file is an empty session.
+pub fn load(path: &Path) -> Result<Session, anyhow::Error> {
+    if !path.exists() {
+        return Ok(Session::default());
+    }
+
+    let context =
+        || format!("Failed to read session from file '{}'", path.display());
+    let toml_str = fs::read_to_string(path).with_context(context)?;
+
+    toml::from_str(&toml_str).with_context(context)
+}
+
+/// Save the session, creating the parent directory if needed.
+pub fn save(path: &Path, session: &Session) -> Result<(), anyhow::Error> {
+    let context =
+        || format!("Failed to save session to file '{}'", path.display());
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(context)?;
+    }
+    let toml_str = toml::to_string(session).with_context(context)?;
+
+    fs::write(path, toml_str).with_context(context)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let session = Session {
+            tab: Some(TabKind::Output),
+            selected: BTreeMap::from([
+                (TabKind::Playback, String::from("Firefox")),
+                (
+                    TabKind::Output,
+                    String::from("alsa_output.pci-0000_00_1f.3"),
+                ),
+            ]),
+            split_focused: true,
+            compact: Some(false),
+        };
+
+        let toml_str = toml::to_string(&session).unwrap();
+        assert_eq!(toml::from_str::<Session>(&toml_str).unwrap(), session);
+    }
+}
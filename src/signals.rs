@@ -0,0 +1,247 @@
+//! Setting up the terminal, and suspending and resuming the interface.
+//!
+//! The terminal is in raw mode, so Ctrl-Z arrives as a key bound to
+//! [`Action::Suspend`](`crate::app::Action::Suspend`) rather than as SIGTSTP.
+//! [`install()`] catches SIGTSTP sent some other way, and SIGCONT after the
+//! process was stopped by something else, so that the main loop can restore
+//! the terminal before stopping and set it up again afterwards. SIGTERM and
+//! SIGINT make the main loop exit normally, so that what wiremix changed
+//! while running is put back, which matters most for `--daemon` under
+//! systemd.
+
+use std::io::stdout;
+use std::os::fd::{AsRawFd, BorrowedFd, IntoRawFd};
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+
+use anyhow::Result;
+use crossterm::{
+    event::{
+        DisableMouseCapture, EnableMouseCapture, KeyboardEnhancementFlags,
+        PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+    },
+    terminal::{self, EnterAlternateScreen},
+    ExecutableCommand,
+};
+use nix::errno::Errno;
+use nix::libc::c_int;
+use nix::sys::signal::{self, SaFlags, SigAction, SigHandler, SigSet, Signal};
+use nix::unistd;
+use ratatui::{
+    backend::CrosstermBackend, DefaultTerminal, Terminal, TerminalOptions,
+    Viewport,
+};
+
+use crate::config::Config;
+use crate::event::Event;
+
+/// Only disambiguating escape codes, so keys are still reported as presses
+/// with the text they type
+const KEYBOARD_FLAGS: KeyboardEnhancementFlags =
+    KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES;
+
+/// Set by SIGTSTP
+static SUSPEND: AtomicBool = AtomicBool::new(false);
+/// Set by SIGCONT
+static RESUMED: AtomicBool = AtomicBool::new(false);
+/// Set by SIGTERM and SIGINT
+static TERMINATE: AtomicBool = AtomicBool::new(false);
+/// The write end of a pipe for waking the main loop from the signal handler
+static WAKE_FD: AtomicI32 = AtomicI32::new(-1);
+/// Whether keyboard enhancement flags were pushed by [`init()`]
+static KEYBOARD_ENHANCED: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn on_signal(signal: c_int) {
+    if signal == Signal::SIGTSTP as c_int {
+        SUSPEND.store(true, Ordering::SeqCst);
+    } else if signal == Signal::SIGCONT as c_int {
+        RESUMED.store(true, Ordering::SeqCst);
+    } else {
+        TERMINATE.store(true, Ordering::SeqCst);
+    }
+
+    let fd = WAKE_FD.load(Ordering::SeqCst);
+    if fd >= 0 {
+        // The pipe is never closed, and write() is safe in a signal handler
+        let _ = unistd::write(unsafe { BorrowedFd::borrow_raw(fd) }, &[0]);
+    }
+}
+
+/// Catch SIGTSTP, SIGCONT, SIGTERM and SIGINT. The main loop is woken by
+/// sending a resize event to `tx`, which also makes it draw again.
+pub fn install(tx: Arc<mpsc::Sender<Event>>) -> Result<()> {
+    catch(
+        tx,
+        &[
+            Signal::SIGTSTP,
+            Signal::SIGCONT,
+            Signal::SIGTERM,
+            Signal::SIGINT,
+        ],
+    )
+}
+
+/// Catch SIGTERM and SIGINT for `--daemon`, which has no terminal to
+/// restore, so it's stopped and continued as usual.
+pub fn install_daemon(tx: Arc<mpsc::Sender<Event>>) -> Result<()> {
+    catch(tx, &[Signal::SIGTERM, Signal::SIGINT])
+}
+
+fn catch(tx: Arc<mpsc::Sender<Event>>, signals: &[Signal]) -> Result<()> {
+    let (read_fd, write_fd) = unistd::pipe()?;
+    WAKE_FD.store(write_fd.into_raw_fd(), Ordering::SeqCst);
+
+    thread::spawn(move || {
+        let mut buf = [0; 16];
+        loop {
+            match unistd::read(read_fd.as_raw_fd(), &mut buf) {
+                Ok(0) => break,
+                Ok(_) | Err(Errno::EINTR) => {}
+                Err(_) => break,
+            }
+            let (width, height) = terminal::size().unwrap_or_default();
+            let event = crossterm::event::Event::Resize(width, height);
+            if tx.send(Event::from(event)).is_err() {
+                break;
+            }
+        }
+    });
+
+    let action = SigAction::new(
+        SigHandler::Handler(on_signal),
+        SaFlags::SA_RESTART,
+        SigSet::empty(),
+    );
+    for &signal in signals {
+        // The handler only touches atomics and writes to the pipe
+        unsafe { signal::sigaction(signal, &action) }?;
+    }
+
+    Ok(())
+}
+
+/// Whether SIGTSTP was received since this was last called.
+pub fn take_suspend() -> bool {
+    SUSPEND.swap(false, Ordering::SeqCst)
+}
+
+/// Whether the process was asked to exit.
+pub fn take_terminate() -> bool {
+    TERMINATE.swap(false, Ordering::SeqCst)
+}
+
+/// Ask the main loop to exit as SIGTERM would, without raising it.
+#[cfg(test)]
+pub fn request_terminate() {
+    TERMINATE.store(true, Ordering::SeqCst);
+}
+
+/// Whether the process was continued since this was last called.
+pub fn take_resumed() -> bool {
+    RESUMED.swap(false, Ordering::SeqCst)
+}
+
+/// Restore the terminal and stop the process as Ctrl-Z normally would, then
+/// set the terminal up again once it's continued.
+pub fn suspend(terminal: &mut DefaultTerminal, config: &Config) -> Result<()> {
+    restore(terminal, config.mouse, config.inline);
+
+    // Stop with the default action rather than the handler
+    let default =
+        SigAction::new(SigHandler::SigDfl, SaFlags::empty(), SigSet::empty());
+    let previous = unsafe { signal::sigaction(Signal::SIGTSTP, &default) }?;
+    let stopped = signal::raise(Signal::SIGTSTP);
+    unsafe { signal::sigaction(Signal::SIGTSTP, &previous) }?;
+    stopped?;
+
+    RESUMED.store(false, Ordering::SeqCst);
+    resume(terminal, config)
+}
+
+/// Set the terminal up again after the process was continued. The shell may
+/// have reset it and drawn over the screen, so everything is drawn again.
+pub fn resume(terminal: &mut DefaultTerminal, config: &Config) -> Result<()> {
+    terminal::enable_raw_mode()?;
+    if KEYBOARD_ENHANCED.load(Ordering::SeqCst) {
+        stdout().execute(PushKeyboardEnhancementFlags(KEYBOARD_FLAGS))?;
+    }
+    match config.inline {
+        // Start a new region below whatever the shell printed
+        Some(height) => *terminal = inline_terminal(height)?,
+        None => {
+            stdout().execute(EnterAlternateScreen)?;
+            terminal.clear()?;
+        }
+    }
+    if config.mouse {
+        stdout().execute(EnableMouseCapture)?;
+    }
+
+    Ok(())
+}
+
+/// Set up the terminal for the interface, drawing in the whole screen or in
+/// `inline` lines below the cursor, and capturing the mouse if `mouse` is set.
+///
+/// If `kitty_keyboard` is set and the terminal supports the kitty keyboard
+/// protocol, it's enabled so that keys such as Ctrl-I and Tab, or Esc and
+/// Alt combinations, can be told apart.
+pub fn init(
+    mouse: bool,
+    inline: Option<u16>,
+    kitty_keyboard: bool,
+) -> Result<DefaultTerminal> {
+    if mouse {
+        stdout().execute(EnableMouseCapture)?;
+    }
+    let terminal = match inline {
+        Some(height) => {
+            terminal::enable_raw_mode()?;
+            inline_terminal(height)?
+        }
+        None => ratatui::init(),
+    };
+    // Terminals without support answer the query with only their device
+    // attributes, so they're left as they are
+    if kitty_keyboard
+        && terminal::supports_keyboard_enhancement().unwrap_or(false)
+    {
+        stdout().execute(PushKeyboardEnhancementFlags(KEYBOARD_FLAGS))?;
+        KEYBOARD_ENHANCED.store(true, Ordering::SeqCst);
+    }
+    Ok(terminal)
+}
+
+/// Undo [`init()`]'s keyboard enhancement, if any.
+pub fn pop_keyboard_enhancement() {
+    if KEYBOARD_ENHANCED.load(Ordering::SeqCst) {
+        let _ = stdout().execute(PopKeyboardEnhancementFlags);
+    }
+}
+
+/// Restore the terminal after [`init()`]. Inline mode's lines are cleared so
+/// nothing is left behind.
+pub fn restore(
+    terminal: &mut DefaultTerminal,
+    mouse: bool,
+    inline: Option<u16>,
+) {
+    if mouse {
+        let _ = stdout().execute(DisableMouseCapture);
+    }
+    pop_keyboard_enhancement();
+    if inline.is_some() {
+        let _ = terminal.clear();
+        let _ = terminal::disable_raw_mode();
+    } else {
+        ratatui::restore();
+    }
+}
+
+fn inline_terminal(height: u16) -> std::io::Result<DefaultTerminal> {
+    let options = TerminalOptions {
+        viewport: Viewport::Inline(height),
+    };
+    Terminal::with_options(CrosstermBackend::new(stdout()), options)
+}
@@ -0,0 +1,158 @@
+//! Noticing the system going to sleep and waking up, configured by
+//! [`Sleep`](`crate::config::Sleep`).
+//!
+//! logind's PrepareForSleep signal is watched with `gdbus monitor`. To mute
+//! outputs before the system actually sleeps, a delay inhibitor is held with
+//! `systemd-inhibit` and released once the outputs are muted.
+
+use std::io::{BufRead, BufReader};
+use std::process::{self, Child, Stdio};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::Context;
+
+use crate::device_kind::DeviceKind;
+use crate::event::Event;
+
+/// How long after waking to keep restoring defaults and mutes, while
+/// devices such as USB audio interfaces reappear.
+pub const WAKE_GRACE: Duration = Duration::from_secs(15);
+
+/// How long to wait for outputs to be muted before letting the system sleep.
+pub const MUTE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Watch for logind's PrepareForSleep signal, sending
+/// [`Event::Sleep`](`crate::event::Event::Sleep`) to `tx` each time it's
+/// received.
+pub fn spawn(tx: Arc<mpsc::Sender<Event>>) -> Result<(), anyhow::Error> {
+    let mut child = process::Command::new("gdbus")
+        .args([
+            "monitor",
+            "--system",
+            "--dest",
+            "org.freedesktop.login1",
+            "--object-path",
+            "/org/freedesktop/login1",
+        ])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .context("Failed to run gdbus to watch for sleep")?;
+    let Some(stdout) = child.stdout.take() else {
+        return Ok(());
+    };
+
+    thread::spawn(move || {
+        for line in BufReader::new(stdout).lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if let Some(sleeping) = parse_prepare_for_sleep(&line) {
+                if tx.send(Event::Sleep(sleeping)).is_err() {
+                    break;
+                }
+            }
+        }
+        let _ = child.kill();
+        let _ = child.wait();
+    });
+
+    Ok(())
+}
+
+/// Whether a line of `gdbus monitor` output is a PrepareForSleep signal for
+/// going to sleep (true) or waking (false).
+fn parse_prepare_for_sleep(line: &str) -> Option<bool> {
+    let (_, args) = line.split_once(".PrepareForSleep ")?;
+    match args.trim() {
+        "(true,)" => Some(true),
+        "(false,)" => Some(false),
+        _ => None,
+    }
+}
+
+/// What was changed or in use before sleeping, to restore after waking.
+#[derive(Default, Debug)]
+pub struct Resume {
+    /// Names of the outputs muted before sleeping
+    pub muted: Vec<String>,
+    /// Names of the default devices before sleeping
+    pub defaults: Vec<(DeviceKind, String)>,
+    /// When the system woke, while there's still something to restore
+    pub woke: Option<Instant>,
+}
+
+/// A logind delay inhibitor for sleep, held while `systemd-inhibit` runs
+/// `cat`, which exits when its input is closed.
+#[derive(Default, Debug)]
+pub struct Inhibitor {
+    child: Option<Child>,
+}
+
+impl Inhibitor {
+    /// Take the inhibitor if it isn't held already.
+    pub fn take(&mut self) -> Result<(), anyhow::Error> {
+        if self.child.is_some() {
+            return Ok(());
+        }
+        let child = process::Command::new("systemd-inhibit")
+            .args([
+                "--what=sleep",
+                "--mode=delay",
+                "--who=wiremix",
+                "--why=Muting outputs",
+                "cat",
+            ])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .context("Failed to run systemd-inhibit")?;
+        self.child = Some(child);
+        Ok(())
+    }
+
+    /// Release the inhibitor, letting the system sleep.
+    pub fn release(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            drop(child.stdin.take());
+            let _ = child.wait();
+        }
+    }
+}
+
+impl Drop for Inhibitor {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_signals() {
+        let signal = "/org/freedesktop/login1: \
+            org.freedesktop.login1.Manager.PrepareForSleep";
+        assert_eq!(
+            parse_prepare_for_sleep(&format!("{} (true,)", signal)),
+            Some(true)
+        );
+        assert_eq!(
+            parse_prepare_for_sleep(&format!("{} (false,)", signal)),
+            Some(false)
+        );
+        assert_eq!(
+            parse_prepare_for_sleep(
+                "/org/freedesktop/login1: \
+                org.freedesktop.login1.Manager.SessionNew ('3', objectpath \
+                '/org/freedesktop/login1/session/_33')"
+            ),
+            None
+        );
+    }
+}
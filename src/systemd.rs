@@ -0,0 +1,35 @@
+//! Telling systemd about the status of `wiremix --daemon` with the
+//! `sd_notify` protocol.
+
+use std::env;
+use std::io;
+use std::os::linux::net::SocketAddrExt;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::net::{SocketAddr, UnixDatagram};
+use std::path::Path;
+
+/// Whether the system was booted with systemd, like `sd_booted()`.
+pub fn booted() -> bool {
+    Path::new("/run/systemd/system").is_dir()
+}
+
+/// Send a status such as `READY=1` to the service manager. Does nothing
+/// unless running as a systemd service with `NOTIFY_SOCKET` set.
+pub fn notify(status: &str) -> io::Result<()> {
+    let Some(path) = env::var_os("NOTIFY_SOCKET").filter(|_| booted()) else {
+        return Ok(());
+    };
+
+    let socket = UnixDatagram::unbound()?;
+    // A leading @ means a socket in the abstract namespace
+    match path.as_bytes().strip_prefix(b"@") {
+        Some(name) => {
+            let address = SocketAddr::from_abstract_name(name)?;
+            socket.send_to_addr(status.as_bytes(), &address)?;
+        }
+        None => {
+            socket.send_to(status.as_bytes(), &path)?;
+        }
+    }
+    Ok(())
+}
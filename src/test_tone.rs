@@ -0,0 +1,100 @@
+//! Playing a burst of noise on each channel of an output device in turn, for
+//! checking that its speakers are connected and mapped correctly, like
+//! `speaker-test`.
+
+use std::process::Child;
+
+use crate::channel_map;
+use crate::tone;
+
+/// How long each channel plays for.
+const BURST_MS: u32 = 1000;
+/// The silence between channels.
+const GAP_MS: u32 = 400;
+const AMPLITUDE: f32 = 0.25;
+
+/// The test being played, if any.
+#[derive(Default, Debug)]
+pub struct TestTone {
+    child: Option<Child>,
+}
+
+impl TestTone {
+    /// Whether a test is still playing.
+    pub fn is_playing(&mut self) -> bool {
+        self.child
+            .as_mut()
+            .is_some_and(|child| matches!(child.try_wait(), Ok(None)))
+    }
+
+    /// Play noise on each of `positions` in turn on the output device with
+    /// `object_serial`, stopping any test already playing.
+    pub fn play(
+        &mut self,
+        object_serial: i32,
+        positions: &[u32],
+    ) -> Result<(), anyhow::Error> {
+        self.stop();
+        let channel_map = positions
+            .iter()
+            .map(|&position| channel_map::name(position))
+            .collect::<Vec<_>>()
+            .join(",");
+        let wav = tone::wav(positions.len() as u16, &samples(positions.len()));
+        self.child = Some(tone::play(object_serial, Some(&channel_map), wav)?);
+        Ok(())
+    }
+
+    pub fn stop(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+impl Drop for TestTone {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Interleaved frames with a burst of noise on each of `channels` channels
+/// in turn, followed by a gap.
+fn samples(channels: usize) -> Vec<i16> {
+    let burst = tone::noise(BURST_MS, AMPLITUDE);
+    let gap = (tone::RATE * GAP_MS / 1000) as usize;
+    let frames_per_channel = burst.len() + gap;
+
+    let mut samples = vec![0; frames_per_channel * channels * channels];
+    for channel in 0..channels {
+        let start = channel * frames_per_channel;
+        for (i, &sample) in burst.iter().enumerate() {
+            samples[(start + i) * channels + channel] = sample;
+        }
+    }
+    samples
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_channel_at_a_time() {
+        let channels = 3;
+        let samples = samples(channels);
+        let frames: Vec<_> = samples.chunks(channels).collect();
+        for frame in &frames {
+            assert!(frame.iter().filter(|&&sample| sample != 0).count() <= 1);
+        }
+        // Each channel plays in its own turn
+        let burst_frame = |channel: usize| {
+            let frames_per_channel = frames.len() / channels;
+            frames[channel * frames_per_channel + 1000]
+        };
+        for channel in 0..channels {
+            assert_ne!(burst_frame(channel)[channel], 0);
+        }
+    }
+}
@@ -0,0 +1,133 @@
+//! Generating short sounds and playing them on an output device with
+//! `pw-play`, for [`feedback`](`crate::feedback`) and
+//! [`test_tone`](`crate::test_tone`).
+//!
+//! Sounds are written to `pw-play` as 16-bit WAV data on its standard input,
+//! so it plays them as a playback stream targeting the device.
+
+use std::io::Write;
+use std::process::{self, Child, Stdio};
+use std::thread;
+
+use anyhow::Context;
+
+/// Sample rate of generated sounds.
+pub const RATE: u32 = 48000;
+
+/// Length of the fade in and out of generated sounds, avoiding clicks.
+const RAMP_MS: u32 = 5;
+
+/// A WAV file of interleaved 16-bit frames with `channels` channels.
+pub fn wav(channels: u16, samples: &[i16]) -> Vec<u8> {
+    let data_len = (samples.len() * 2) as u32;
+    let frame_len = channels * 2;
+
+    let mut wav = Vec::with_capacity(44 + data_len as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&channels.to_le_bytes());
+    wav.extend_from_slice(&RATE.to_le_bytes());
+    wav.extend_from_slice(&(RATE * u32::from(frame_len)).to_le_bytes());
+    wav.extend_from_slice(&frame_len.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes()); // Bits per sample
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    for sample in samples {
+        wav.extend_from_slice(&sample.to_le_bytes());
+    }
+    wav
+}
+
+/// A sine wave lasting `duration_ms` at `amplitude` from 0 to 1, faded in
+/// and out.
+pub fn sine(frequency: f32, duration_ms: u32, amplitude: f32) -> Vec<i16> {
+    let count = RATE * duration_ms / 1000;
+    (0..count)
+        .map(|n| {
+            let t = n as f32 / RATE as f32;
+            let value = (2.0 * std::f32::consts::PI * frequency * t).sin();
+            to_sample(value * amplitude * envelope(n, count))
+        })
+        .collect()
+}
+
+/// White noise lasting `duration_ms` at `amplitude` from 0 to 1, faded in
+/// and out.
+pub fn noise(duration_ms: u32, amplitude: f32) -> Vec<i16> {
+    let count = RATE * duration_ms / 1000;
+    // A linear congruential generator is random enough to hear as noise
+    let mut state: u32 = 0x2545_f491;
+    (0..count)
+        .map(|n| {
+            state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            let value = (state >> 8) as f32 / (1 << 23) as f32 - 1.0;
+            to_sample(value * amplitude * envelope(n, count))
+        })
+        .collect()
+}
+
+/// The gain at sample `n` of `count` for fading in and out.
+fn envelope(n: u32, count: u32) -> f32 {
+    let ramp = (RATE * RAMP_MS / 1000) as f32;
+    (n as f32 / ramp).min((count - n) as f32 / ramp).min(1.0)
+}
+
+fn to_sample(value: f32) -> i16 {
+    (value.clamp(-1.0, 1.0) * i16::MAX as f32) as i16
+}
+
+/// Play WAV data on the output device with `object_serial`, with the
+/// channels assigned to `channel_map`, such as "FL,FR", if given. Returns
+/// the `pw-play` process, which exits when the sound ends.
+pub fn play(
+    object_serial: i32,
+    channel_map: Option<&str>,
+    wav: Vec<u8>,
+) -> Result<Child, anyhow::Error> {
+    let mut command = process::Command::new("pw-play");
+    command.args(["--target", &object_serial.to_string()]);
+    command.args(["--media-role", "Event"]);
+    if let Some(channel_map) = channel_map {
+        command.args(["--channel-map", channel_map]);
+    }
+    let mut child = command
+        .arg("-")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .context("Failed to run pw-play")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // Longer sounds don't fit in the pipe
+        thread::spawn(move || stdin.write_all(&wav));
+    }
+    Ok(child)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wav_header_matches_data() {
+        let wav = wav(2, &sine(440.0, 10, 0.5));
+        assert_eq!(&wav[0..4], b"RIFF");
+        let riff_len = u32::from_le_bytes(wav[4..8].try_into().unwrap());
+        assert_eq!(riff_len as usize, wav.len() - 8);
+        let data_len = u32::from_le_bytes(wav[40..44].try_into().unwrap());
+        assert_eq!(data_len as usize, wav.len() - 44);
+        // Starts silent to avoid a click
+        assert_eq!(&wav[44..46], &[0, 0]);
+    }
+
+    #[test]
+    fn noise_stays_in_range() {
+        let noise = noise(100, 0.5);
+        assert_eq!(noise.len(), 4800);
+        assert!(noise.iter().all(|&sample| sample.unsigned_abs() <= 16384));
+        assert!(noise.iter().any(|&sample| sample.unsigned_abs() > 8192));
+    }
+}
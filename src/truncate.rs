@@ -26,6 +26,32 @@ pub fn with_ellipses(text: &str, len: usize) -> String {
     result + &ellipses[0..len.min(ellipses.len())]
 }
 
+/// Window of `len` columns into `text`, scrolled left by `offset` characters
+/// and wrapping around with a gap. Text that fits is returned unchanged.
+pub fn marquee(text: &str, len: usize, offset: usize) -> String {
+    if UnicodeWidthStr::width(text) <= len {
+        return String::from(text);
+    }
+
+    let gap = "   ";
+    let chars: Vec<char> = text.chars().chain(gap.chars()).collect();
+
+    let mut result = String::new();
+    let mut current_width = 0;
+
+    for &c in chars.iter().cycle().skip(offset % chars.len()) {
+        let char_width = unicode_width::UnicodeWidthChar::width(c).unwrap_or(0);
+        if current_width + char_width > len {
+            break;
+        }
+
+        result.push(c);
+        current_width += char_width;
+    }
+
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -59,4 +85,32 @@ mod tests {
     fn empty() {
         assert_eq!(with_ellipses("hello", 0), "");
     }
+
+    #[test]
+    fn marquee_fits() {
+        assert_eq!(marquee("hello", 5, 3), "hello");
+    }
+
+    #[test]
+    fn marquee_scrolls() {
+        assert_eq!(marquee("hello", 4, 0), "hell");
+        assert_eq!(marquee("hello", 4, 2), "llo ");
+    }
+
+    #[test]
+    fn marquee_wraps() {
+        assert_eq!(marquee("hello", 4, 6), "  he");
+        // One full cycle of "hello" plus the gap
+        assert_eq!(marquee("hello", 4, 8), "hell");
+    }
+
+    #[test]
+    fn marquee_wide_chars() {
+        assert_eq!(marquee("日本語です", 5, 0), "日本");
+    }
+
+    #[test]
+    fn marquee_empty() {
+        assert_eq!(marquee("hello", 0, 1), "");
+    }
 }
@@ -0,0 +1,261 @@
+//! Vi-style input with counts, multi-key commands, search, `:` commands and
+//! visual mode.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::app::Action;
+
+/// Counts larger than this are clamped so a typo can't hang the UI.
+const MAX_COUNT: usize = 999;
+
+/// What to do in response to a key in vi mode.
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum ViCommand {
+    /// Handle an action
+    Action(Action),
+    /// Handle the action bound to a key
+    Key(KeyEvent),
+    /// Select the next object, or the previous if not `forward`, whose title
+    /// contains a query
+    Search { query: String, forward: bool },
+    /// Run a command typed after `:`, such as `help config`
+    Command(String),
+    /// Start or stop selecting a range of objects
+    ToggleVisual,
+    /// Stop selecting a range of objects
+    ExitVisual,
+}
+
+/// The state of a vi command being typed.
+#[derive(Debug, Default)]
+pub struct Vi {
+    /// Count typed before a command
+    count: Option<usize>,
+    /// First key of a two-key command such as `gg` or `dd`
+    pending: Option<char>,
+    /// `/` or `:` and the query or command being typed after it
+    line: Option<(char, String)>,
+    /// The last submitted query, for `n` and `N`
+    last_query: Option<String>,
+}
+
+impl Vi {
+    /// Handle a key press, returning the commands it completes. `visual` is
+    /// whether a range is being selected.
+    pub fn key(&mut self, key: KeyEvent, visual: bool) -> Vec<ViCommand> {
+        if let Some((_, text)) = &mut self.line {
+            return match key.code {
+                KeyCode::Char(c) if !has_control(key) => {
+                    text.push(c);
+                    Vec::new()
+                }
+                KeyCode::Backspace => {
+                    if text.pop().is_none() {
+                        self.line = None;
+                    }
+                    Vec::new()
+                }
+                KeyCode::Enter => {
+                    let (start, text) = self.line.take().unwrap_or_default();
+                    if text.is_empty() {
+                        return Vec::new();
+                    }
+                    if start == ':' {
+                        return vec![ViCommand::Command(text)];
+                    }
+                    self.last_query = Some(text.clone());
+                    vec![ViCommand::Search {
+                        query: text,
+                        forward: true,
+                    }]
+                }
+                KeyCode::Esc => {
+                    self.line = None;
+                    Vec::new()
+                }
+                _ => Vec::new(),
+            };
+        }
+
+        let KeyCode::Char(c) = key.code else {
+            self.reset();
+            return match key.code {
+                KeyCode::Esc if visual => vec![ViCommand::ExitVisual],
+                _ => vec![ViCommand::Key(key)],
+            };
+        };
+        if has_control(key) {
+            self.reset();
+            return vec![ViCommand::Key(key)];
+        }
+
+        if let Some(digit) = c.to_digit(10) {
+            // A leading 0 isn't a count, so it keeps its keybinding
+            if self.pending.is_none() && (digit != 0 || self.count.is_some()) {
+                let count = self.count.unwrap_or_default();
+                self.count = Some(
+                    count
+                        .saturating_mul(10)
+                        .saturating_add(digit as usize)
+                        .min(MAX_COUNT),
+                );
+                return Vec::new();
+            }
+        }
+
+        let count = self.count.take();
+        let repeat = count.unwrap_or(1);
+        // Select the count-th object or else the object at one end
+        let go_to = |end| match count {
+            Some(count) => {
+                std::iter::once(ViCommand::Action(Action::MoveToTop))
+                    .chain(
+                        std::iter::repeat(ViCommand::Action(Action::MoveDown))
+                            .take(count - 1),
+                    )
+                    .collect()
+            }
+            None => vec![ViCommand::Action(end)],
+        };
+
+        match (self.pending.take(), c) {
+            (Some('g'), 'g') => go_to(Action::MoveToTop),
+            (Some('g'), 'd') => vec![ViCommand::Action(Action::SetDefault)],
+            (Some('d'), 'd') => {
+                vec![ViCommand::Action(Action::ToggleMute)]
+            }
+            // Unknown two-key commands are cancelled
+            (Some(_), _) => Vec::new(),
+            (None, 'd') if visual => vec![
+                ViCommand::Action(Action::ToggleMute),
+                ViCommand::ExitVisual,
+            ],
+            (None, 'g' | 'd') => {
+                self.count = count;
+                self.pending = Some(c);
+                Vec::new()
+            }
+            (None, 'G') => go_to(Action::MoveToBottom),
+            (None, '/' | ':') => {
+                self.line = Some((c, String::new()));
+                Vec::new()
+            }
+            (None, 'n' | 'N') => {
+                let Some(query) = &self.last_query else {
+                    return Vec::new();
+                };
+                let search = ViCommand::Search {
+                    query: query.clone(),
+                    forward: c == 'n',
+                };
+                vec![search; repeat]
+            }
+            (None, 'v' | 'V') => vec![ViCommand::ToggleVisual],
+            (None, _) => vec![ViCommand::Key(key); repeat],
+        }
+    }
+
+    /// The partially typed command, for showing in the tab bar.
+    pub fn prompt(&self) -> Option<String> {
+        if let Some((start, text)) = &self.line {
+            return Some(format!("{}{}", start, text));
+        }
+        let prompt = self
+            .count
+            .map(|count| count.to_string())
+            .into_iter()
+            .chain(self.pending.map(String::from))
+            .collect::<String>();
+        (!prompt.is_empty()).then_some(prompt)
+    }
+
+    fn reset(&mut self) {
+        self.count = None;
+        self.pending = None;
+    }
+}
+
+fn has_control(key: KeyEvent) -> bool {
+    key.modifiers
+        .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn char(c: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE)
+    }
+
+    fn keys(vi: &mut Vi, keys: &str, visual: bool) -> Vec<ViCommand> {
+        keys.chars().flat_map(|c| vi.key(char(c), visual)).collect()
+    }
+
+    #[test]
+    fn counts_repeat_keys() {
+        let mut vi = Vi::default();
+        assert_eq!(keys(&mut vi, "1", false), vec![]);
+        assert_eq!(vi.prompt(), Some(String::from("1")));
+        assert_eq!(
+            keys(&mut vi, "0j", false),
+            vec![ViCommand::Key(char('j')); 10]
+        );
+        assert_eq!(vi.prompt(), None);
+
+        // A leading 0 is passed through
+        assert_eq!(keys(&mut vi, "0", false), vec![ViCommand::Key(char('0'))]);
+    }
+
+    #[test]
+    fn two_key_commands() {
+        let mut vi = Vi::default();
+        assert_eq!(
+            keys(&mut vi, "dd", false),
+            vec![ViCommand::Action(Action::ToggleMute)]
+        );
+        assert_eq!(
+            keys(&mut vi, "3gg", false),
+            vec![
+                ViCommand::Action(Action::MoveToTop),
+                ViCommand::Action(Action::MoveDown),
+                ViCommand::Action(Action::MoveDown),
+            ]
+        );
+        assert_eq!(keys(&mut vi, "dx", false), vec![]);
+        assert_eq!(
+            keys(&mut vi, "d", true),
+            vec![ViCommand::Action(Action::ToggleMute), ViCommand::ExitVisual]
+        );
+    }
+
+    #[test]
+    fn search() {
+        let mut vi = Vi::default();
+        assert_eq!(keys(&mut vi, "n", false), vec![]);
+        assert_eq!(keys(&mut vi, "/fir", false), vec![]);
+        assert_eq!(vi.prompt(), Some(String::from("/fir")));
+        let search = |forward| ViCommand::Search {
+            query: String::from("fir"),
+            forward,
+        };
+        assert_eq!(
+            vi.key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE), false),
+            vec![search(true)]
+        );
+        assert_eq!(keys(&mut vi, "2N", false), vec![search(false); 2]);
+    }
+
+    #[test]
+    fn commands() {
+        let mut vi = Vi::default();
+        assert_eq!(keys(&mut vi, ":help config", false), vec![]);
+        assert_eq!(vi.prompt(), Some(String::from(":help config")));
+        assert_eq!(
+            vi.key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE), false),
+            vec![ViCommand::Command(String::from("help config"))]
+        );
+        assert_eq!(vi.prompt(), None);
+    }
+}
@@ -5,11 +5,13 @@ use std::collections::HashMap;
 
 use serde_json::json;
 
+use crate::channel_map::{self, Remap};
 use crate::command::Command;
 use crate::config;
 use crate::device_kind::DeviceKind;
 use crate::media_class::MediaClass;
 use crate::object::ObjectId;
+use crate::property_editor::Property;
 use crate::state;
 
 /// A view for transforming [`State`](`crate::state::State`) into a better
@@ -39,9 +41,16 @@ pub struct View {
     pub nodes_recording: Vec<ObjectId>,
     pub nodes_output: Vec<ObjectId>,
     pub nodes_input: Vec<ObjectId>,
+    pub nodes_network: Vec<ObjectId>,
+    pub nodes_jack: Vec<ObjectId>,
+    /// The streams of each role tab, set by [`Self::set_role_tabs()`]
+    pub nodes_role: Vec<Vec<ObjectId>>,
 
     pub devices_all: Vec<ObjectId>,
 
+    /// The names of the applications using a camera, in alphabetical order
+    pub camera_clients: Vec<String>,
+
     pub sinks: Vec<(Target, String)>,
     pub sources: Vec<(Target, String)>,
 
@@ -56,10 +65,12 @@ pub enum Target {
     Node(ObjectId),
     Route(ObjectId, i32, i32),
     Profile(ObjectId, i32),
+    ChannelMap(Remap),
+    Property(Property),
     Default,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Node {
     pub id: ObjectId,
     pub object_serial: i32,
@@ -79,6 +90,46 @@ pub struct Node {
     pub peaks: Option<Vec<f32>>,
     pub positions: Option<Vec<u32>>,
 
+    /// The application.name of the node's client
+    pub client: Option<String>,
+    /// The stream's media.role, such as "Music" or "Communication"
+    pub media_role: Option<String>,
+    /// Requested latency in milliseconds
+    pub latency: Option<f32>,
+    /// Negotiated format, e.g. "F32LE 48000 Hz 2ch"
+    pub format: Option<String>,
+    /// Negotiated channel positions, e.g. "FL FR"
+    pub channel_map: Option<String>,
+    /// For streams being resampled, the stream's rate and the graph rate
+    pub resample: Option<(u32, u32)>,
+    /// The node's resample.quality, if set
+    pub resample_quality: Option<String>,
+    /// For sinks which report channelmix.upmix, whether stereo is spread
+    /// over all of the sink's channels
+    pub upmix: Option<bool>,
+    /// Whether this is a network sink or source
+    pub network: bool,
+    /// Whether this belongs to a JACK client
+    pub jack: bool,
+    /// Whether this is a stream which isn't running, such as one which has
+    /// been paused (corked)
+    pub paused: bool,
+    /// Whether this is a stream which has ended but is kept in the view by
+    /// [`EndedStreams`](`crate::ended::EndedStreams`)
+    pub ended: bool,
+    /// Whether this is a stream pinned to its device, set by
+    /// [`Pins`](`crate::pins::Pins`)
+    pub pinned: bool,
+    /// Battery percentage of the Bluetooth device this node belongs to
+    pub battery: Option<u8>,
+    /// Whether this is a running stream capturing from a microphone or other
+    /// source, rather than a monitor
+    pub recording: bool,
+
+    /// For playback streams, the volume combined with the volume of the sink
+    /// being played to, on the same cubic scale as the volume bars.
+    pub effective_volume: Option<f32>,
+
     /// If this is a device/endpoint node, store the (device_id, route_index,
     /// card_device) here because they are needed for the
     /// [`DeviceVolume`](`crate::command::Command::DeviceVolumes`) and
@@ -93,12 +144,18 @@ pub struct Node {
 pub struct Device {
     pub id: ObjectId,
     pub object_serial: i32,
+    pub name: String,
     pub title: String,
 
     pub profiles: Vec<(Target, String)>,
+    /// Index of the device's "pro-audio" profile, if it has one
+    pub pro_audio_profile: Option<i32>,
 
     pub target_title: String,
     pub target: Option<Target>,
+
+    /// The spinner frame while the device is switching profiles
+    pub switching: Option<usize>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -113,6 +170,12 @@ pub enum NodeKind {
     Recording,
     Output,
     Input,
+    /// Network sinks and sources
+    Network,
+    /// Nodes of JACK clients
+    Jack,
+    /// Streams shown in the [`RoleTab`](`config::RoleTab`) with this index
+    Role(usize),
     #[default]
     All,
 }
@@ -190,6 +253,21 @@ fn active_route(
 }
 
 impl Node {
+    /// The mean volume on the cubic scale of the volume bars, where 1.0 is
+    /// 100%.
+    pub fn volume(&self) -> Option<f32> {
+        if self.volumes.is_empty() {
+            return None;
+        }
+        let mean = self.volumes.iter().sum::<f32>() / self.volumes.len() as f32;
+        Some(mean.cbrt())
+    }
+
+    /// The mean volume as a percentage on the cubic scale of the volume bars.
+    pub fn volume_percent(&self) -> Option<u32> {
+        self.volume().map(|volume| (volume * 100.0).round() as u32)
+    }
+
     fn from(
         state: &state::State,
         names: &config::Names,
@@ -202,6 +280,9 @@ impl Node {
         let id = node.id;
 
         let media_class = node.media_class.as_ref()?.clone();
+        if media_class.is_video_source() || media_class.is_video_stream() {
+            return None;
+        }
         let title = names.resolve(state, node)?;
 
         // Nodes can represent either streams or devices.
@@ -222,6 +303,13 @@ impl Node {
                 } else {
                     (node.volumes.as_ref()?.clone(), node.mute?, None)
                 }
+            } else if node.is_jack() {
+                // JACK clients may not have volume controls.
+                (
+                    node.volumes.clone().unwrap_or_default(),
+                    node.mute.unwrap_or_default(),
+                    None,
+                )
             } else {
                 // We can interact with a stream node's volume and mute status
                 // directly.
@@ -302,10 +390,32 @@ impl Node {
                 )
             };
             (None, target, target_title)
+        } else if node.network == Some(true) {
+            // Network endpoints show their connection status instead.
+            (None, None, connection_status(node))
         } else {
             (None, None, String::from("No route selected"))
         };
 
+        let resample = resample(state, node, &media_class);
+        let paused = (media_class.is_sink_input()
+            || media_class.is_source_output())
+            && node.state.as_ref().is_some_and(|(state, _)| {
+                state == "idle" || state == "suspended"
+            });
+
+        let recording = media_class.is_source_output()
+            && !paused
+            && state.inputs(id).iter().any(|input| {
+                state
+                    .nodes
+                    .get(input)
+                    .and_then(|input| input.media_class.as_ref())
+                    .is_some_and(MediaClass::is_source)
+            });
+
+        let upmix = node.upmix.filter(|_| media_class.is_sink());
+
         Some(Self {
             id,
             object_serial: node.object_serial?,
@@ -320,6 +430,32 @@ impl Node {
             mute,
             peaks: node.peaks.clone(),
             positions: node.positions.clone(),
+            client: node
+                .client_id
+                .and_then(|client_id| state.clients.get(&client_id))
+                .and_then(|client| client.application_name.clone()),
+            media_role: node.media_role.clone(),
+            latency: node.latency.as_deref().and_then(latency_ms),
+            format: node.format.as_ref().map(format_summary),
+            channel_map: node
+                .format
+                .as_ref()
+                .filter(|format| !format.channel_map.is_empty())
+                .map(|format| format.channel_map.join(" ")),
+            resample,
+            resample_quality: node.resample_quality.clone(),
+            upmix,
+            network: node.network == Some(true),
+            jack: node.is_jack(),
+            paused,
+            ended: false,
+            pinned: false,
+            battery: node
+                .device_id
+                .and_then(|device_id| state.devices.get(&device_id))
+                .and_then(|device| device.battery),
+            recording,
+            effective_volume: None,
             device_info,
             is_default_sink: *default_sink_name == node.name,
             is_default_source: *default_source_name == node.name,
@@ -355,6 +491,12 @@ impl Device {
             .map(|(index, title)| (Target::Profile(id, index), title))
             .collect();
 
+        let pro_audio_profile = device
+            .profiles
+            .values()
+            .find(|profile| profile.name == "pro-audio")
+            .map(|profile| profile.index);
+
         let target_profile = device.profiles.get(&device.profile_index?)?;
         let target_title = if target_profile.available {
             target_profile.description.clone()
@@ -367,15 +509,174 @@ impl Device {
         Some(Device {
             id,
             object_serial: device.object_serial?,
+            name: device.name.clone().unwrap_or_default(),
             title,
             profiles,
+            pro_audio_profile,
             target_title,
             target,
+            switching: None,
         })
     }
 }
 
-fn default_for(state: &state::State, which: &str) -> Option<String> {
+/// The sink a playback stream plays to, which is the default sink for streams
+/// without a target.
+fn playback_sink(
+    node: &Node,
+    default_sink: Option<Target>,
+) -> Option<ObjectId> {
+    if !node.media_class.is_sink_input() {
+        return None;
+    }
+    match (node.target?, default_sink) {
+        (Target::Node(sink_id), _) => Some(sink_id),
+        (Target::Default, Some(Target::Node(sink_id))) => Some(sink_id),
+        _ => None,
+    }
+}
+
+/// The factor to scale a sink at `sink_volume` by for flat volumes, so that
+/// it's as loud as its loudest stream at `loudest` relative to the sink, or
+/// None if it doesn't need to change. The sink is kept within the range of
+/// the volume bars.
+fn flat_factor(loudest: f32, sink_volume: f32) -> Option<f32> {
+    if loudest <= 0.0 || sink_volume <= 0.0 {
+        return None;
+    }
+    let factor = loudest.min(1.5_f32.powi(3) / sink_volume);
+    ((factor - 1.0).abs() > 1e-4).then_some(factor)
+}
+
+/// A node's linear channel volumes after an adjustment on the cubic scale.
+fn adjusted_volumes(
+    node: &Node,
+    adjustment: VolumeAdjustment,
+) -> Option<Vec<f32>> {
+    let mut volumes = node.volumes.clone();
+    if volumes.is_empty() {
+        return None;
+    }
+    match adjustment {
+        VolumeAdjustment::Relative(delta) => {
+            let avg = volumes.iter().sum::<f32>() / volumes.len() as f32;
+            volumes.fill((avg.cbrt() + delta).max(0.0).powi(3));
+        }
+        VolumeAdjustment::Absolute(volume) => {
+            volumes.fill(volume.max(0.0).powi(3));
+        }
+    }
+    Some(volumes)
+}
+
+/// Returns a command for setting a node's linear channel volumes.
+fn volumes_command(node: &Node, volumes: Vec<f32>) -> Command {
+    if let Some((device_id, route_index, route_device)) = node.device_info {
+        Command::DeviceVolumes(device_id, route_index, route_device, volumes)
+    } else {
+        Command::NodeVolumes(node.id, volumes)
+    }
+}
+
+/// Converts a latency fraction like 1024/48000 to milliseconds.
+fn latency_ms(latency: &str) -> Option<f32> {
+    let (quantum, rate) = latency.split_once('/')?;
+    let quantum: f32 = quantum.trim().parse().ok()?;
+    let rate: f32 = rate.trim().parse().ok()?;
+
+    (rate > 0.0).then(|| quantum * 1000.0 / rate)
+}
+
+/// Describes the connection of a network endpoint based on its node state.
+/// The names of the applications with a running video stream from a camera,
+/// in alphabetical order.
+fn camera_clients(state: &state::State) -> Vec<String> {
+    let is_camera = |node_id: &ObjectId| {
+        state
+            .nodes
+            .get(node_id)
+            .and_then(|node| node.media_class.as_ref())
+            .is_some_and(MediaClass::is_video_source)
+    };
+    state
+        .nodes
+        .values()
+        .filter(|node| {
+            node.media_class
+                .as_ref()
+                .is_some_and(MediaClass::is_video_stream)
+                && node
+                    .state
+                    .as_ref()
+                    .is_some_and(|(state, _)| state == "running")
+                && state.inputs(node.id).iter().any(is_camera)
+        })
+        .filter_map(|node| {
+            let client = node
+                .client_id
+                .and_then(|client_id| state.clients.get(&client_id))
+                .and_then(|client| client.application_name.clone());
+            client.or_else(|| node.name.clone())
+        })
+        .sorted()
+        .dedup()
+        .collect()
+}
+
+fn connection_status(node: &state::Node) -> String {
+    match &node.state {
+        Some((state, _)) if state == "running" => String::from("Streaming"),
+        Some((state, _)) if state == "idle" => String::from("Connected"),
+        Some((state, _)) if state == "suspended" => String::from("Suspended"),
+        Some((state, _)) if state == "creating" => String::from("Connecting"),
+        Some((_, Some(error))) => format!("Error: {}", error),
+        _ => String::from("Unknown"),
+    }
+}
+
+/// Describes a negotiated format like "F32LE 48000 Hz 2ch".
+fn format_summary(format: &state::Format) -> String {
+    format!(
+        "{} {} Hz {}ch",
+        format.sample_format, format.rate, format.channels
+    )
+}
+
+/// Mean of a node's linear channel volumes.
+fn mean_volume(node: &Node) -> Option<f32> {
+    (!node.volumes.is_empty())
+        .then(|| node.volumes.iter().sum::<f32>() / node.volumes.len() as f32)
+}
+
+/// The rate the graph is running at, taking clock.force-rate into account.
+fn graph_rate(state: &state::State) -> Option<u32> {
+    let metadata = state.get_metadata_by_name("settings")?;
+    let settings = metadata.properties.get(&0)?;
+    let rate = |key| settings.get(key)?.parse::<u32>().ok();
+
+    rate("clock.force-rate")
+        .filter(|&rate| rate > 0)
+        .or_else(|| rate("clock.rate"))
+}
+
+/// Returns a stream's rate and the graph rate if they differ.
+fn resample(
+    state: &state::State,
+    node: &state::Node,
+    media_class: &MediaClass,
+) -> Option<(u32, u32)> {
+    if !(media_class.is_sink_input() || media_class.is_source_output()) {
+        return None;
+    }
+
+    let rate = node.format.as_ref()?.rate;
+    let graph_rate = graph_rate(state)?;
+    (rate != graph_rate).then_some((rate, graph_rate))
+}
+
+/// The name of a default node, such as `default.audio.sink`, from the
+/// "default" metadata.
+pub fn default_for(state: &state::State, which: &str) -> Option<String> {
     let metadata = state.get_metadata_by_name("default")?;
     let json = metadata.properties.get(&0)?.get(which)?;
     let obj = serde_json::from_str::<serde_json::Value>(json).ok()?;
@@ -488,6 +789,25 @@ impl View {
             .map(|node| (node.id, node))
             .collect();
 
+        let mut nodes = nodes;
+        let effective_volumes: Vec<_> = nodes
+            .values()
+            .filter_map(|node| {
+                let sink_id = playback_sink(node, default_sink)?;
+                let sink = nodes.get(&sink_id)?;
+                let sink_volume =
+                    if sink.mute { 0.0 } else { mean_volume(sink)? };
+                let volume = mean_volume(node)? * sink_volume;
+                Some((node.id, volume.cbrt()))
+            })
+            .collect();
+        for (node_id, effective_volume) in effective_volumes {
+            if let Some(node) = nodes.get_mut(&node_id) {
+                node.effective_volume = Some(effective_volume);
+            }
+        }
+        let nodes = nodes;
+
         let devices: HashMap<ObjectId, Device> = state
             .devices
             .values()
@@ -500,10 +820,21 @@ impl View {
         let mut nodes_recording = Vec::new();
         let mut nodes_output = Vec::new();
         let mut nodes_input = Vec::new();
+        let mut nodes_network = Vec::new();
+        let mut nodes_jack = Vec::new();
         for (id, node) in
             nodes.iter().sorted_by_key(|(_, node)| node.object_serial)
         {
             nodes_all.push(*id);
+            if node.jack
+                && !node.media_class.is_sink()
+                && !node.media_class.is_source()
+            {
+                // JACK clients get their own list rather than being shown
+                // as streams.
+                nodes_jack.push(*id);
+                continue;
+            }
             if node.media_class.is_sink_input() {
                 nodes_playback.push(*id);
             }
@@ -516,12 +847,19 @@ impl View {
             if node.media_class.is_source() {
                 nodes_input.push(*id);
             }
+            if node.network
+                && (node.media_class.is_sink() || node.media_class.is_source())
+            {
+                nodes_network.push(*id);
+            }
         }
         let nodes_all = nodes_all;
         let nodes_playback = nodes_playback;
         let nodes_recording = nodes_recording;
         let nodes_output = nodes_output;
         let nodes_input = nodes_input;
+        let nodes_network = nodes_network;
+        let nodes_jack = nodes_jack;
 
         let devices_all = devices
             .iter()
@@ -530,6 +868,7 @@ impl View {
             .collect();
 
         Self {
+            camera_clients: camera_clients(state),
             nodes,
             devices,
             nodes_all,
@@ -537,6 +876,9 @@ impl View {
             nodes_recording,
             nodes_output,
             nodes_input,
+            nodes_network,
+            nodes_jack,
+            nodes_role: Vec::new(),
             devices_all,
             sinks,
             sources,
@@ -546,6 +888,47 @@ impl View {
         }
     }
 
+    /// Stop offering monitors of sinks as targets for recording streams.
+    pub fn hide_monitors(&mut self) {
+        let nodes = &self.nodes;
+        self.sources.retain(|(target, _)| match target {
+            Target::Node(node_id) => nodes
+                .get(node_id)
+                .map_or(true, |node| !node.media_class.is_sink()),
+            _ => true,
+        });
+    }
+
+    /// Fill the lists of the role tabs with the playback and recording
+    /// streams whose media.role is one of each tab's roles.
+    pub fn set_role_tabs(&mut self, role_tabs: &[config::RoleTab]) {
+        self.nodes_role = role_tabs
+            .iter()
+            .map(|role_tab| {
+                let mut nodes: Vec<&Node> = self
+                    .nodes_playback
+                    .iter()
+                    .chain(&self.nodes_recording)
+                    .filter_map(|node_id| self.nodes.get(node_id))
+                    .filter(|node| {
+                        node.media_role
+                            .as_ref()
+                            .is_some_and(|role| role_tab.roles.contains(role))
+                    })
+                    .collect();
+                match role_tab.sort {
+                    config::TabSort::Serial => {
+                        nodes.sort_by_key(|node| node.object_serial)
+                    }
+                    config::TabSort::Title => {
+                        nodes.sort_by(|a, b| a.title.cmp(&b.title))
+                    }
+                }
+                nodes.into_iter().map(|node| node.id).collect()
+            })
+            .collect();
+    }
+
     /// Update just the peaks of an existing State.
     pub fn update_peaks(&mut self, state: &state::State) {
         for state_node in state.nodes.values() {
@@ -586,6 +969,61 @@ impl View {
         ))
     }
 
+    /// Returns the sink or source following the current default, ordering
+    /// them by their position in priorities and then by title. Wraps around
+    /// to the first one.
+    pub fn next_default(
+        &self,
+        device_kind: DeviceKind,
+        priorities: &[String],
+    ) -> Option<ObjectId> {
+        let (node_kind, default) = match device_kind {
+            DeviceKind::Sink => (NodeKind::Output, self.default_sink),
+            DeviceKind::Source => (NodeKind::Input, self.default_source),
+        };
+        let rank = |node: &Node| {
+            priorities
+                .iter()
+                .position(|name| *name == node.name)
+                .unwrap_or(priorities.len())
+        };
+
+        let nodes: Vec<&Node> = self
+            .full_nodes(node_kind)
+            .into_iter()
+            .sorted_by(|a, b| {
+                rank(a).cmp(&rank(b)).then_with(|| a.title.cmp(&b.title))
+            })
+            .collect();
+        let next_index = nodes
+            .iter()
+            .position(|node| Some(Target::Node(node.id)) == default)
+            .map_or(0, |index| (index + 1) % nodes.len());
+
+        nodes.get(next_index).map(|node| node.id)
+    }
+
+    /// Returns the highest-ranked sink or source in priorities which is
+    /// present, if any.
+    pub fn preferred_default(
+        &self,
+        device_kind: DeviceKind,
+        priorities: &[String],
+    ) -> Option<ObjectId> {
+        let node_kind = match device_kind {
+            DeviceKind::Sink => NodeKind::Output,
+            DeviceKind::Source => NodeKind::Input,
+        };
+        let nodes = self.full_nodes(node_kind);
+
+        priorities.iter().find_map(|name| {
+            nodes
+                .iter()
+                .find(|node| node.name == *name)
+                .map(|node| node.id)
+        })
+    }
+
     /// Returns a command for setting the provided node's target to the
     /// provided target.
     pub fn set_target(
@@ -644,13 +1082,35 @@ impl View {
             Target::Profile(device_id, profile_index) => {
                 vec![Command::DeviceSetProfile(device_id, profile_index)]
             }
+            Target::ChannelMap(remap) => {
+                let Some(node) = self.nodes.get(&node_id) else {
+                    return Vec::new();
+                };
+                let input = node.media_class.is_sink()
+                    || node.media_class.is_source_output();
+                node.positions
+                    .as_deref()
+                    .and_then(|positions| remap.apply(positions))
+                    .map(|positions| {
+                        Command::NodeChannelMap(node_id, input, positions)
+                    })
+                    .into_iter()
+                    .collect()
+            }
+            // Properties are typed in before they're written
+            Target::Property(_) => Vec::new(),
         }
     }
 
     /// Returns a command for muting the provided node.
     pub fn mute(&self, node_id: ObjectId) -> Option<Command> {
         let node = self.nodes.get(&node_id)?;
-        let mute = !node.mute;
+        self.set_mute(node_id, !node.mute)
+    }
+
+    /// Returns a command for muting or unmuting the provided node.
+    pub fn set_mute(&self, node_id: ObjectId, mute: bool) -> Option<Command> {
+        let node = self.nodes.get(&node_id)?;
 
         if let Some((device_id, route_index, route_device)) = node.device_info {
             Some(Command::DeviceMute(
@@ -664,6 +1124,24 @@ impl View {
         }
     }
 
+    /// Returns a command for turning the provided sink's upmixing on or off,
+    /// and whether it will be on.
+    pub fn toggle_upmix(&self, node_id: ObjectId) -> Option<(Command, bool)> {
+        let upmix = !self.nodes.get(&node_id)?.upmix?;
+        Some((Command::NodeUpmix(node_id, upmix), upmix))
+    }
+
+    /// Returns a command for setting the provided node's linear channel
+    /// volumes.
+    pub fn set_volumes(
+        &self,
+        node_id: ObjectId,
+        volumes: Vec<f32>,
+    ) -> Option<Command> {
+        let node = self.nodes.get(&node_id)?;
+        Some(volumes_command(node, volumes))
+    }
+
     /// Returns a command for changing the volume of the provided node.
     pub fn volume(
         &self,
@@ -671,32 +1149,91 @@ impl View {
         adjustment: VolumeAdjustment,
     ) -> Option<Command> {
         let node = self.nodes.get(&node_id)?;
+        let volumes = adjusted_volumes(node, adjustment)?;
 
-        let mut volumes = node.volumes.clone();
-        if volumes.is_empty() {
-            return None;
-        }
-        match adjustment {
-            VolumeAdjustment::Relative(delta) => {
-                let avg = volumes.iter().sum::<f32>() / volumes.len() as f32;
-                volumes.fill((avg.cbrt() + delta).max(0.0).powi(3));
-            }
-            VolumeAdjustment::Absolute(volume) => {
-                volumes.fill(volume.max(0.0).powi(3));
-            }
-        }
-        let volumes = volumes;
+        Some(volumes_command(node, volumes))
+    }
 
-        if let Some((device_id, route_index, route_device)) = node.device_info {
-            Some(Command::DeviceVolumes(
-                device_id,
-                route_index,
-                route_device,
-                volumes,
-            ))
-        } else {
-            Some(Command::NodeVolumes(node_id, volumes))
+    /// Returns commands for changing the volume of the provided node in the
+    /// manner of PulseAudio's flat volumes, where a sink is as loud as its
+    /// loudest playback stream. Changing a stream raises or lowers its sink
+    /// to match the loudest stream, and the sink's other streams are
+    /// rescaled so that their loudness doesn't change. Changing a sink
+    /// rescales its streams the other way, so that the loudest is at 100%.
+    pub fn flat_volume(
+        &self,
+        node_id: ObjectId,
+        adjustment: VolumeAdjustment,
+    ) -> Vec<Command> {
+        let Some(node) = self.nodes.get(&node_id) else {
+            return Vec::new();
+        };
+        let Some(volumes) = adjusted_volumes(node, adjustment) else {
+            return Vec::new();
+        };
+        let scale = |node: &Node, volumes: &[f32], factor: f32| {
+            volumes_command(node, volumes.iter().map(|v| v * factor).collect())
+        };
+
+        if node.media_class.is_sink() {
+            let streams = self.sink_streams(node.id);
+            let loudest = streams
+                .iter()
+                .filter_map(|stream| mean_volume(stream))
+                .fold(0.0, f32::max);
+            let volume = volumes.iter().sum::<f32>() / volumes.len() as f32;
+            let Some(factor) = flat_factor(loudest, volume) else {
+                return vec![volumes_command(node, volumes)];
+            };
+            let mut commands = vec![scale(node, &volumes, factor)];
+            commands.extend(
+                streams
+                    .into_iter()
+                    .map(|stream| scale(stream, &stream.volumes, 1.0 / factor)),
+            );
+            return commands;
         }
+
+        let sink = playback_sink(node, self.default_sink)
+            .and_then(|sink_id| self.nodes.get(&sink_id));
+        let Some((sink, sink_volume)) =
+            sink.and_then(|sink| Some((sink, mean_volume(sink)?)))
+        else {
+            return vec![volumes_command(node, volumes)];
+        };
+        let others: Vec<_> = self
+            .sink_streams(sink.id)
+            .into_iter()
+            .filter(|other| other.id != node.id)
+            .collect();
+        let loudest = others
+            .iter()
+            .filter_map(|other| mean_volume(other))
+            .fold(volumes.iter().sum::<f32>() / volumes.len() as f32, f32::max);
+        let Some(factor) = flat_factor(loudest, sink_volume) else {
+            return vec![volumes_command(node, volumes)];
+        };
+
+        let mut commands = vec![
+            scale(node, &volumes, 1.0 / factor),
+            scale(sink, &sink.volumes, factor),
+        ];
+        commands.extend(
+            others
+                .into_iter()
+                .map(|other| scale(other, &other.volumes, 1.0 / factor)),
+        );
+        commands
+    }
+
+    /// The playback streams on a sink.
+    fn sink_streams(&self, sink_id: ObjectId) -> Vec<&Node> {
+        self.full_nodes(NodeKind::Playback)
+            .into_iter()
+            .filter(|stream| {
+                playback_sink(stream, self.default_sink) == Some(sink_id)
+            })
+            .collect()
     }
 
     fn ids(&self, node_kind: ListKind) -> &[ObjectId] {
@@ -705,6 +1242,11 @@ impl View {
             ListKind::Node(NodeKind::Recording) => &self.nodes_recording,
             ListKind::Node(NodeKind::Output) => &self.nodes_output,
             ListKind::Node(NodeKind::Input) => &self.nodes_input,
+            ListKind::Node(NodeKind::Network) => &self.nodes_network,
+            ListKind::Node(NodeKind::Jack) => &self.nodes_jack,
+            ListKind::Node(NodeKind::Role(index)) => {
+                self.nodes_role.get(index).map_or(&[], Vec::as_slice)
+            }
             ListKind::Node(NodeKind::All) => &self.nodes_all,
             ListKind::Device => &self.devices_all,
         }
@@ -728,6 +1270,32 @@ impl View {
             .collect()
     }
 
+    /// The node of the default sink.
+    pub fn default_sink_node(&self) -> Option<&Node> {
+        self.nodes
+            .values()
+            .find(|node| node.is_default_sink && node.media_class.is_sink())
+    }
+
+    /// The node of the default source.
+    pub fn default_source_node(&self) -> Option<&Node> {
+        self.nodes
+            .values()
+            .find(|node| node.is_default_source && node.media_class.is_source())
+    }
+
+    /// The names of the applications recording from a microphone or other
+    /// source, in alphabetical order.
+    pub fn recording_clients(&self) -> Vec<&str> {
+        self.nodes
+            .values()
+            .filter(|node| node.recording)
+            .map(|node| node.client.as_deref().unwrap_or(&node.title))
+            .sorted()
+            .dedup()
+            .collect()
+    }
+
     /// Returns the next node in the list_kind after a provided node.
     pub fn next_id(
         &self,
@@ -762,6 +1330,56 @@ impl View {
         objects.get(next_index).copied()
     }
 
+    /// Returns the node in the list_kind that is `count` positions after a
+    /// provided node, stopping at the end of the list.
+    pub fn next_id_by(
+        &self,
+        list_kind: ListKind,
+        object_id: Option<ObjectId>,
+        count: usize,
+    ) -> Option<ObjectId> {
+        let objects = self.ids(list_kind);
+        let index = match object_id {
+            Some(object_id) => objects
+                .iter()
+                .position(|&id| id == object_id)?
+                .saturating_add(count),
+            None => 0,
+        };
+        objects
+            .get(index.min(objects.len().saturating_sub(1)))
+            .copied()
+    }
+
+    /// Returns the node in the list_kind that is `count` positions before a
+    /// provided node, stopping at the start of the list.
+    pub fn previous_id_by(
+        &self,
+        list_kind: ListKind,
+        object_id: Option<ObjectId>,
+        count: usize,
+    ) -> Option<ObjectId> {
+        let objects = self.ids(list_kind);
+        let index = match object_id {
+            Some(object_id) => objects
+                .iter()
+                .position(|&id| id == object_id)?
+                .saturating_sub(count),
+            None => 0,
+        };
+        objects.get(index).copied()
+    }
+
+    /// Returns the first node in the list_kind.
+    pub fn first_id(&self, list_kind: ListKind) -> Option<ObjectId> {
+        self.ids(list_kind).first().copied()
+    }
+
+    /// Returns the last node in the list_kind.
+    pub fn last_id(&self, list_kind: ListKind) -> Option<ObjectId> {
+        self.ids(list_kind).last().copied()
+    }
+
     /// Returns the index in the list_kind for the provided object.
     pub fn position(
         &self,
@@ -771,6 +1389,90 @@ impl View {
         self.ids(list_kind).iter().position(|&id| id == object_id)
     }
 
+    /// Returns the objects in the list_kind from one object to another,
+    /// inclusive, in list order.
+    pub fn range(
+        &self,
+        list_kind: ListKind,
+        from: ObjectId,
+        to: ObjectId,
+    ) -> Vec<ObjectId> {
+        let (Some(from), Some(to)) =
+            (self.position(list_kind, from), self.position(list_kind, to))
+        else {
+            return Vec::new();
+        };
+        self.ids(list_kind)[from.min(to)..=from.max(to)].to_vec()
+    }
+
+    /// Returns the next object in the list_kind after a provided object,
+    /// or before it if not `forward`, whose title contains a query. The
+    /// search wraps around and ignores case.
+    pub fn search(
+        &self,
+        list_kind: ListKind,
+        object_id: Option<ObjectId>,
+        query: &str,
+        forward: bool,
+    ) -> Option<ObjectId> {
+        let query = query.to_lowercase();
+        let objects = self.ids(list_kind);
+        let len = objects.len();
+        // Without a starting object, search from the top of the list
+        let start = object_id
+            .and_then(|object_id| self.position(list_kind, object_id))
+            .unwrap_or(if forward { len.saturating_sub(1) } else { 0 });
+        (1..=len)
+            .map(|offset| {
+                if forward {
+                    (start + offset) % len
+                } else {
+                    (start + len - offset) % len
+                }
+            })
+            .map(|index| objects[index])
+            .find(|object_id| {
+                let title = match list_kind {
+                    ListKind::Node(_) => {
+                        self.nodes.get(object_id).map(|node| &node.title)
+                    }
+                    ListKind::Device => {
+                        self.devices.get(object_id).map(|device| &device.title)
+                    }
+                };
+                title.is_some_and(|title| title.to_lowercase().contains(&query))
+            })
+    }
+
+    /// Returns the node.name or device.name of an object in the list_kind.
+    pub fn name(
+        &self,
+        list_kind: ListKind,
+        object_id: ObjectId,
+    ) -> Option<&str> {
+        match list_kind {
+            ListKind::Node(_) => {
+                self.nodes.get(&object_id).map(|node| node.name.as_str())
+            }
+            ListKind::Device => self
+                .devices
+                .get(&object_id)
+                .map(|device| device.name.as_str()),
+        }
+    }
+
+    /// Returns the object in the list_kind with a node.name or device.name.
+    pub fn find_name(
+        &self,
+        list_kind: ListKind,
+        name: &str,
+    ) -> Option<ObjectId> {
+        self.ids(list_kind)
+            .iter()
+            .copied()
+            .find(|&object_id| self.name(list_kind, object_id) == Some(name))
+    }
+
     /// Returns length of the list_kind.
     pub fn len(&self, list_kind: ListKind) -> usize {
         self.ids(list_kind).len()
@@ -826,6 +1528,30 @@ impl View {
         Some((targets, selected_position))
     }
 
+    /// Returns the ways a node's channel map can be rearranged, titled with
+    /// the positions they would result in.
+    pub fn channel_maps(
+        &self,
+        node_id: ObjectId,
+    ) -> Option<(Vec<(Target, String)>, usize)> {
+        let positions = self.nodes.get(&node_id)?.positions.as_deref()?;
+
+        let targets = Remap::ALL
+            .iter()
+            .filter_map(|remap| {
+                let remapped = remap.apply(positions)?;
+                let title = format!(
+                    "{}: {}",
+                    remap.title(),
+                    channel_map::names(&remapped)
+                );
+                Some((Target::ChannelMap(*remap), title))
+            })
+            .collect();
+
+        Some((targets, 0))
+    }
+
     /// Returns the possible targets for a device.
     pub fn device_targets(
         &self,
@@ -846,3 +1572,178 @@ impl View {
         Some((targets, selected_position))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capture_manager::CaptureManager;
+    use crate::event::MonitorEvent;
+
+    /// Events for a node with a volume for each of two channels.
+    fn node(id: u32, media_class: &str, name: &str) -> Vec<MonitorEvent> {
+        let id = ObjectId::from_raw_id(id);
+        vec![
+            MonitorEvent::NodeMediaClass(id, MediaClass::from(media_class)),
+            MonitorEvent::NodeName(id, String::from(name)),
+            MonitorEvent::NodeDescription(id, String::from(name)),
+            MonitorEvent::NodeObjectSerial(id, u32::from(id) as i32),
+            MonitorEvent::NodeVolumes(id, vec![1.0, 1.0]),
+            MonitorEvent::NodeMute(id, false),
+        ]
+    }
+
+    /// Events making the node named `name` the default sink.
+    fn default_sink(name: &str) -> Vec<MonitorEvent> {
+        let metadata_id = ObjectId::from_raw_id(100);
+        vec![
+            MonitorEvent::MetadataMetadataName(
+                metadata_id,
+                String::from("default"),
+            ),
+            MonitorEvent::MetadataProperty(
+                metadata_id,
+                0,
+                Some(String::from("default.audio.sink")),
+                Some(json!({ "name": name }).to_string()),
+            ),
+        ]
+    }
+
+    fn view(events: impl IntoIterator<Item = MonitorEvent>) -> View {
+        let mut state = state::State::default();
+        let mut capture_manager = CaptureManager::default();
+        for event in events {
+            state.update(&mut capture_manager, event);
+        }
+        View::from(&state, &config::Names::default())
+    }
+
+    /// Sinks named b, a and c, a source and a stream.
+    fn sinks() -> Vec<MonitorEvent> {
+        [
+            node(1, "Audio/Sink", "b"),
+            node(2, "Audio/Sink", "a"),
+            node(3, "Audio/Sink", "c"),
+            node(4, "Audio/Source", "mic"),
+            node(5, "Stream/Output/Audio", "player"),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    #[test]
+    fn next_default_follows_priorities_then_titles() {
+        let id = ObjectId::from_raw_id;
+        let priorities = [String::from("c")];
+        let next = |default: &str| {
+            view(sinks().into_iter().chain(default_sink(default)))
+                .next_default(DeviceKind::Sink, &priorities)
+        };
+        // c is ranked first, then a and b by title, wrapping around
+        assert_eq!(next("c"), Some(id(2)));
+        assert_eq!(next("a"), Some(id(1)));
+        assert_eq!(next("b"), Some(id(3)));
+    }
+
+    #[test]
+    fn next_default_without_default() {
+        let id = ObjectId::from_raw_id;
+        // The first sink when there's no default or it's gone
+        let view_without = view(sinks());
+        assert_eq!(
+            view_without.next_default(DeviceKind::Sink, &[]),
+            Some(id(2))
+        );
+        let gone = view(sinks().into_iter().chain(default_sink("gone")));
+        assert_eq!(gone.next_default(DeviceKind::Sink, &[]), Some(id(2)));
+        // Sources are cycled separately
+        assert_eq!(gone.next_default(DeviceKind::Source, &[]), Some(id(4)));
+        assert_eq!(view([]).next_default(DeviceKind::Sink, &[]), None);
+    }
+
+    #[test]
+    fn preferred_default_is_highest_present() {
+        let id = ObjectId::from_raw_id;
+        let view = view(sinks());
+        let priorities = |names: &[&str]| -> Vec<String> {
+            names.iter().copied().map(String::from).collect()
+        };
+
+        let preferred = |names: &[&str], device_kind| {
+            view.preferred_default(device_kind, &priorities(names))
+        };
+        assert_eq!(preferred(&["c", "a"], DeviceKind::Sink), Some(id(3)));
+        // Absent devices are skipped
+        assert_eq!(preferred(&["usb", "a"], DeviceKind::Sink), Some(id(2)));
+        assert_eq!(preferred(&["usb"], DeviceKind::Sink), None);
+        assert_eq!(preferred(&[], DeviceKind::Sink), None);
+        // Only devices of the right kind count
+        assert_eq!(preferred(&["mic", "b"], DeviceKind::Sink), Some(id(1)));
+        assert_eq!(preferred(&["b", "mic"], DeviceKind::Source), Some(id(4)));
+    }
+
+    #[test]
+    fn effective_volume_and_loudness() {
+        use clap::Parser;
+
+        let id = ObjectId::from_raw_id;
+        let (sink, stream) = (id(1), id(5));
+        // The stream plays on the default sink, b
+        let events = |sink_volume: f32, sink_mute: bool| {
+            sinks().into_iter().chain(default_sink("b")).chain([
+                MonitorEvent::NodeVolumes(sink, vec![sink_volume; 2]),
+                MonitorEvent::NodeMute(sink, sink_mute),
+                MonitorEvent::NodeVolumes(stream, vec![0.5, 0.5]),
+            ])
+        };
+        let opt = crate::opt::Opt::parse_from(["wiremix"]);
+        let mut config = config::Config::try_new(None, &opt).unwrap();
+        config.loudness_warning = Some(100.0);
+
+        // The stream's volume times its sink's, on the cubic scale
+        let quiet = view(events(1.0, false));
+        let effective = quiet.nodes[&stream].effective_volume.unwrap();
+        assert!((effective - 0.5_f32.cbrt()).abs() < 1e-6);
+        assert!(!crate::node_widget::is_loud(&quiet.nodes[&stream], &config));
+        // Only streams played on a sink have one
+        assert_eq!(quiet.nodes[&sink].effective_volume, None);
+        let no_default = view(sinks());
+        assert_eq!(no_default.nodes[&stream].effective_volume, None);
+
+        let loud = view(events(3.375, false));
+        let effective = loud.nodes[&stream].effective_volume.unwrap();
+        assert!((effective - (0.5_f32 * 3.375).cbrt()).abs() < 1e-6);
+        assert!(crate::node_widget::is_loud(&loud.nodes[&stream], &config));
+        config.loudness_warning = None;
+        assert!(!crate::node_widget::is_loud(&loud.nodes[&stream], &config));
+
+        // A muted sink is silent
+        let muted = view(events(3.375, true));
+        assert_eq!(muted.nodes[&stream].effective_volume, Some(0.0));
+    }
+
+    #[test]
+    fn latency_from_property() {
+        assert_eq!(latency_ms("1024/48000").map(f32::round), Some(21.0));
+        assert_eq!(latency_ms(" 480 / 48000 "), Some(10.0));
+        // Malformed fractions
+        assert_eq!(latency_ms("1024"), None);
+        assert_eq!(latency_ms("1024/"), None);
+        assert_eq!(latency_ms("/48000"), None);
+        assert_eq!(latency_ms("1024/48kHz"), None);
+        assert_eq!(latency_ms("1024/0"), None);
+        assert_eq!(latency_ms("1024/48000/2"), None);
+
+        // A node without node.latency has none
+        let id = ObjectId::from_raw_id;
+        let view =
+            view(sinks().into_iter().chain([MonitorEvent::NodeLatency(
+                id(5),
+                String::from("256/48000"),
+            )]));
+        let latency = view.nodes[&id(5)].latency.map(|ms| (ms * 100.0).round());
+        assert_eq!(latency, Some(533.0));
+        assert_eq!(view.nodes[&id(1)].latency, None);
+    }
+}
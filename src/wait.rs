@@ -0,0 +1,69 @@
+//! Waiting for PipeWire to start, for when wiremix is started first, such as
+//! by a session manager.
+
+use std::time::Duration;
+
+use anyhow::Result;
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::{
+    layout::{Alignment, Constraint, Layout},
+    text::Line,
+    DefaultTerminal,
+};
+
+use crate::config::Config;
+use crate::monitor;
+
+/// How often to check for the socket.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Show a waiting screen until there is a PipeWire socket for `remote`.
+/// Returns false if the user quit instead.
+pub fn for_socket(
+    terminal: &mut DefaultTerminal,
+    config: &Config,
+) -> Result<bool> {
+    let remote = config.remote.as_deref();
+    let message = match remote {
+        Some(remote) => {
+            config.locale.format("wait-remote", &[("remote", &remote)])
+        }
+        None => String::from(config.locale.get("wait")),
+    };
+
+    while !monitor::socket_exists(remote) {
+        terminal.draw(|frame| {
+            let [area] = Layout::vertical([Constraint::Length(2)])
+                .flex(ratatui::layout::Flex::Center)
+                .areas(frame.area());
+            let [message_area, hint_area] =
+                Layout::vertical([Constraint::Length(1); 2]).areas(area);
+            frame.render_widget(
+                Line::from(message.as_str()).alignment(Alignment::Center),
+                message_area,
+            );
+            frame.render_widget(
+                Line::styled(
+                    config.locale.get("wait-hint"),
+                    config.theme.flash,
+                )
+                .alignment(Alignment::Center),
+                hint_area,
+            );
+        })?;
+
+        if !event::poll(POLL_INTERVAL)? {
+            continue;
+        }
+        if let Event::Key(key) = event::read()? {
+            let is_ctrl_c = key.code == KeyCode::Char('c')
+                && key.modifiers.contains(KeyModifiers::CONTROL);
+            let is_quit = matches!(key.code, KeyCode::Char('q') | KeyCode::Esc);
+            if key.kind == KeyEventKind::Press && (is_quit || is_ctrl_c) {
+                return Ok(false);
+            }
+        }
+    }
+
+    Ok(true)
+}
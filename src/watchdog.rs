@@ -0,0 +1,140 @@
+//! Warning when the default sink stays silent while streams are playing
+//! sound to it, configured by [`Watchdog`](`crate::config::Watchdog`), such
+//! as when an HDMI output has stopped taking audio.
+
+use std::time::{Duration, Instant};
+
+use crate::object::ObjectId;
+use crate::view::{Node, Target, View};
+
+/// Peaks at or below this, -80 dBFS, count as silence.
+const SILENCE: f32 = 1e-4;
+
+/// Tracks how long the default sink has been silent.
+#[derive(Default, Debug)]
+pub struct Watchdog {
+    /// The sink and when it was first seen silent while streams played to it
+    silent_since: Option<(ObjectId, Instant)>,
+    /// Whether the current silence has been warned about
+    warned: bool,
+}
+
+impl Watchdog {
+    /// Returns the default sink once it has been silent for `after` while
+    /// streams play sound to it, once for each time it goes silent.
+    pub fn update(
+        &mut self,
+        view: &View,
+        after: Duration,
+        now: Instant,
+    ) -> Option<ObjectId> {
+        self.track(silent_sink(view), after, now)
+    }
+
+    fn track(
+        &mut self,
+        silent_sink: Option<ObjectId>,
+        after: Duration,
+        now: Instant,
+    ) -> Option<ObjectId> {
+        let Some(sink_id) = silent_sink else {
+            self.silent_since = None;
+            self.warned = false;
+            return None;
+        };
+        let since = match self.silent_since {
+            Some((node_id, since)) if node_id == sink_id => since,
+            _ => {
+                self.silent_since = Some((sink_id, now));
+                self.warned = false;
+                now
+            }
+        };
+        if self.warned || now.saturating_duration_since(since) < after {
+            return None;
+        }
+        self.warned = true;
+        Some(sink_id)
+    }
+
+    /// How long until a warning is due, if the default sink is silent.
+    pub fn until_warning(
+        &self,
+        after: Duration,
+        now: Instant,
+    ) -> Option<Duration> {
+        let (_, since) = self.silent_since?;
+        (!self.warned)
+            .then(|| after.saturating_sub(now.saturating_duration_since(since)))
+    }
+}
+
+fn is_silent(node: &Node) -> bool {
+    node.peaks
+        .as_deref()
+        .is_some_and(|peaks| peaks.iter().all(|&peak| peak <= SILENCE))
+}
+
+/// The default sink if it's unmuted and silent while an unmuted stream
+/// plays sound to it.
+fn silent_sink(view: &View) -> Option<ObjectId> {
+    let Some(Target::Node(sink_id)) = view.default_sink else {
+        return None;
+    };
+    let sink = view.nodes.get(&sink_id)?;
+    if sink.mute || sink.volume() == Some(0.0) || !is_silent(sink) {
+        return None;
+    }
+    let playing = view.nodes.values().any(|node| {
+        node.media_class.is_sink_input()
+            && node.target == Some(Target::Node(sink_id))
+            && !node.paused
+            && !node.mute
+            && node.peaks.is_some()
+            && !is_silent(node)
+    });
+    playing.then_some(sink_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn warn_once_per_silence() {
+        let after = Duration::from_secs(5);
+        let sink = Some(ObjectId::from_raw_id(40));
+        let mut watchdog = Watchdog::default();
+        let now = Instant::now();
+        let at = |secs| now + Duration::from_secs(secs);
+
+        assert_eq!(watchdog.track(sink, after, now), None);
+        assert_eq!(
+            watchdog.until_warning(after, at(2)),
+            Some(Duration::from_secs(3))
+        );
+        assert_eq!(watchdog.track(sink, after, at(5)), sink);
+        assert_eq!(watchdog.track(sink, after, at(6)), None);
+        assert_eq!(watchdog.until_warning(after, at(6)), None);
+
+        // Sound coming back starts over
+        assert_eq!(watchdog.track(None, after, at(7)), None);
+        assert_eq!(watchdog.track(sink, after, at(8)), None);
+        assert_eq!(watchdog.track(sink, after, at(13)), sink);
+    }
+
+    #[test]
+    fn start_over_when_default_sink_changes() {
+        let after = Duration::from_secs(5);
+        let mut watchdog = Watchdog::default();
+        let now = Instant::now();
+        let at = |secs| now + Duration::from_secs(secs);
+        let hdmi = Some(ObjectId::from_raw_id(40));
+        let speakers = Some(ObjectId::from_raw_id(41));
+
+        watchdog.track(hdmi, after, now);
+        assert_eq!(watchdog.track(speakers, after, at(4)), None);
+        assert_eq!(watchdog.track(speakers, after, at(6)), None);
+        assert_eq!(watchdog.track(speakers, after, at(9)), speakers);
+    }
+}
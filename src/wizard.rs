@@ -0,0 +1,262 @@
+//! A short setup shown the first time wiremix runs, when there's no
+//! configuration file, for choosing the options people most often change.
+//! The configuration file is then written as `wiremix init-config` would
+//! write it, with the choices set. Quitting the setup writes it with nothing
+//! set, so it's only shown once.
+
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::{
+    layout::{Constraint, Layout},
+    style::{Modifier, Style},
+    text::Line,
+    DefaultTerminal,
+};
+
+use crate::config::Config;
+use crate::locale::Locale;
+
+/// A question, with the option it sets and the values to choose from, the
+/// first of which is the default. The question and descriptions are message
+/// IDs.
+struct Step {
+    question: &'static str,
+    option: &'static str,
+    /// Each value and a description of it
+    choices: &'static [(&'static str, &'static str)],
+}
+
+const STEPS: [Step; 5] = [
+    Step {
+        question: "wizard-theme",
+        option: "theme",
+        choices: &[
+            ("default", "wizard-theme-default"),
+            ("nocolor", "wizard-theme-nocolor"),
+            ("plain", "wizard-theme-plain"),
+            ("highcontrast", "wizard-theme-highcontrast"),
+            ("monochrome", "wizard-theme-monochrome"),
+        ],
+    },
+    Step {
+        question: "wizard-char-set",
+        option: "char_set",
+        choices: &[
+            ("default", "wizard-char-set-default"),
+            ("compat", "wizard-char-set-compat"),
+            ("extracompat", "wizard-char-set-extracompat"),
+            ("ascii", "wizard-char-set-ascii"),
+        ],
+    },
+    Step {
+        question: "wizard-input-mode",
+        option: "input_mode",
+        choices: &[
+            ("default", "wizard-input-mode-default"),
+            ("vi", "wizard-input-mode-vi"),
+        ],
+    },
+    Step {
+        question: "wizard-monitors",
+        option: "monitors",
+        choices: &[
+            ("true", "wizard-monitors-true"),
+            ("false", "wizard-monitors-false"),
+        ],
+    },
+    Step {
+        question: "wizard-peaks",
+        option: "peaks",
+        choices: &[
+            ("auto", "wizard-peaks-auto"),
+            ("mono", "wizard-peaks-mono"),
+            ("off", "wizard-peaks-off"),
+        ],
+    },
+];
+
+/// Whether the wizard should be shown instead of starting with the defaults,
+/// which is when there's no configuration file and someone is there to
+/// answer.
+pub fn should_run(path: &Path) -> bool {
+    !path.exists() && io::stdin().is_terminal() && io::stdout().is_terminal()
+}
+
+/// Ask the questions, returning each option and the value chosen for it.
+/// Esc keeps the defaults for the remaining questions, and quitting leaves
+/// every option unset.
+pub fn run(
+    terminal: &mut DefaultTerminal,
+    locale: &Locale,
+) -> Result<Vec<(&'static str, &'static str)>> {
+    let mut chosen = Vec::new();
+    let mut selected = 0;
+    while let Some(step) = STEPS.get(chosen.len()) {
+        terminal.draw(|frame| {
+            let height = step.choices.len() as u16 + 6;
+            let [area] = Layout::vertical([Constraint::Length(height)])
+                .flex(ratatui::layout::Flex::Center)
+                .areas(frame.area());
+            let [area] = Layout::horizontal([Constraint::Max(72)])
+                .flex(ratatui::layout::Flex::Center)
+                .areas(area);
+            let mut lines = vec![
+                Line::styled(
+                    locale.format(
+                        "wizard-title",
+                        &[
+                            ("step", &(chosen.len() + 1)),
+                            ("steps", &STEPS.len()),
+                        ],
+                    ),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Line::default(),
+                Line::from(locale.get(step.question)),
+            ];
+            for (i, (value, description)) in step.choices.iter().enumerate() {
+                let line = format!("{:<12} {}", value, locale.get(description));
+                lines.push(if i == selected {
+                    Line::styled(
+                        format!("> {}", line),
+                        Style::default().add_modifier(Modifier::REVERSED),
+                    )
+                } else {
+                    Line::from(format!("  {}", line))
+                });
+            }
+            lines.push(Line::default());
+            lines.push(Line::styled(
+                locale.get("wizard-hint"),
+                Style::default().add_modifier(Modifier::DIM),
+            ));
+            frame.render_widget(ratatui::text::Text::from(lines), area);
+        })?;
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        let is_ctrl_c = key.code == KeyCode::Char('c')
+            && key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            _ if is_ctrl_c => return Ok(Vec::new()),
+            KeyCode::Char('q') => return Ok(Vec::new()),
+            KeyCode::Char('j') | KeyCode::Down => {
+                selected = (selected + 1).min(step.choices.len() - 1);
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                selected = selected.saturating_sub(1);
+            }
+            KeyCode::Enter => {
+                chosen.push((step.option, step.choices[selected].0));
+                selected = 0;
+            }
+            KeyCode::Esc => {
+                chosen.extend(
+                    STEPS[chosen.len()..]
+                        .iter()
+                        .map(|step| (step.option, step.choices[0].0)),
+                );
+            }
+            _ => {}
+        }
+    }
+    Ok(chosen)
+}
+
+/// A choice as a TOML value, which is a string unless it's a boolean.
+fn value(choice: &str) -> toml::Value {
+    match choice {
+        "true" => toml::Value::Boolean(true),
+        "false" => toml::Value::Boolean(false),
+        choice => toml::Value::from(choice),
+    }
+}
+
+/// [`Config::example_commented()`] with the chosen options set.
+pub fn config_text(chosen: &[(&str, &str)]) -> String {
+    let mut text = Config::example_commented();
+    for (option, value) in chosen {
+        let commented = format!("\n#{} = ", option);
+        let Some(start) = text.find(&commented) else {
+            continue;
+        };
+        let end = text[start + 1..]
+            .find('\n')
+            .map_or(text.len(), |end| start + 1 + end);
+        let line = format!("\n{} = {}", option, self::value(value));
+        text.replace_range(start..end, &line);
+    }
+    text
+}
+
+/// Write the configuration file with the chosen options set.
+pub fn write(path: &Path, chosen: &[(&str, &str)]) -> Result<()> {
+    let context = || {
+        format!("Failed to write configuration to file '{}'", path.display())
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(context)?;
+    }
+    fs::write(path, config_text(chosen)).with_context(context)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_chosen_options() {
+        let text = config_text(&[
+            ("theme", "plain"),
+            ("input_mode", "vi"),
+            ("monitors", "false"),
+        ]);
+        let table: toml::Table = text.parse().unwrap();
+        assert_eq!(table["theme"].as_str(), Some("plain"));
+        assert_eq!(table["input_mode"].as_str(), Some("vi"));
+        assert_eq!(table["monitors"].as_bool(), Some(false));
+        assert!(!table.contains_key("char_set"));
+    }
+
+    /// Quitting writes the file with nothing set, so the setup isn't shown
+    /// again.
+    #[test]
+    fn skipped_setup_is_written() {
+        let dir = std::env::temp_dir()
+            .join(format!("wiremix-wizard-test-{}", std::process::id()));
+        let path = dir.join("wiremix.toml");
+        write(&path, &[]).unwrap();
+        assert!(path.exists());
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(text, Config::example_commented());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn every_message_is_in_the_catalog() {
+        let locale = Locale::default();
+        for step in &STEPS {
+            let descriptions = step.choices.iter().map(|(_, id)| id);
+            for id in std::iter::once(&step.question).chain(descriptions) {
+                assert_ne!(locale.get(id), *id);
+            }
+        }
+    }
+
+    #[test]
+    fn every_option_is_in_the_example() {
+        let text = Config::example_commented();
+        for step in &STEPS {
+            let commented = format!("\n#{} = ", step.option);
+            assert!(text.contains(&commented), "{}", step.option);
+        }
+    }
+}
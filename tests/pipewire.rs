@@ -0,0 +1,266 @@
+//! End-to-end tests against a private PipeWire daemon.
+//!
+//! These start `pipewire` (or `$WIREMIX_TEST_PIPEWIRE`) with its own runtime
+//! directory and a configuration creating two null sinks, a stream and the
+//! default metadata, then send commands built by the view through the monitor
+//! and check the state the monitor reports back. There's no session manager,
+//! so defaults and targets are checked in the metadata rather than by what
+//! gets linked.
+//!
+//! They need PipeWire installed, so they only run with
+//! `cargo test --test pipewire -- --ignored`.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command as Process, Stdio};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use wiremix::capture_manager::CaptureManager;
+use wiremix::command::{Command, MixCommand};
+use wiremix::config::Names;
+use wiremix::device_kind::DeviceKind;
+use wiremix::event::Event;
+use wiremix::monitor;
+use wiremix::object::ObjectId;
+use wiremix::state::State;
+use wiremix::view::{Target, View, VolumeAdjustment};
+
+/// How long to wait for PipeWire to start or to report a change.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+const CONFIG: &str = r#"
+context.properties = {
+    core.daemon = true
+    core.name = pipewire-0
+    support.dbus = false
+}
+context.spa-libs = {
+    audio.convert.* = audioconvert/libspa-audioconvert
+    support.* = support/libspa-support
+}
+context.modules = [
+    { name = libpipewire-module-protocol-native }
+    { name = libpipewire-module-metadata }
+    { name = libpipewire-module-adapter }
+    { name = libpipewire-module-client-node }
+]
+context.objects = [
+    { factory = metadata args = { metadata.name = default } }
+    { factory = adapter
+      args = {
+          factory.name = support.null-audio-sink
+          node.name = wiremix-test-sink-a
+          node.description = "Test Sink A"
+          media.class = Audio/Sink
+          audio.position = [ FL FR ]
+      }
+    }
+    { factory = adapter
+      args = {
+          factory.name = support.null-audio-sink
+          node.name = wiremix-test-sink-b
+          node.description = "Test Sink B"
+          media.class = Audio/Sink
+          audio.position = [ FL FR ]
+      }
+    }
+    { factory = adapter
+      args = {
+          factory.name = support.null-audio-sink
+          node.name = wiremix-test-stream
+          node.description = "Test Stream"
+          media.class = Stream/Output/Audio
+          audio.position = [ FL FR ]
+      }
+    }
+]
+"#;
+
+/// A PipeWire daemon which is stopped and cleaned up when dropped.
+struct Daemon {
+    child: Child,
+    dir: PathBuf,
+}
+
+impl Daemon {
+    fn start() -> Self {
+        let dir = env::temp_dir()
+            .join(format!("wiremix-test-pipewire-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let config = dir.join("pipewire.conf");
+        fs::write(&config, CONFIG).unwrap();
+
+        let program = env::var_os("WIREMIX_TEST_PIPEWIRE")
+            .unwrap_or_else(|| "pipewire".into());
+        let child = Process::new(program)
+            .arg("-c")
+            .arg(&config)
+            .env("PIPEWIRE_RUNTIME_DIR", &dir)
+            .env("XDG_RUNTIME_DIR", &dir)
+            .stdout(Stdio::null())
+            .spawn()
+            .expect("Couldn't start pipewire");
+        let daemon = Daemon { child, dir };
+
+        let socket = daemon.dir.join("pipewire-0");
+        let start = Instant::now();
+        while !socket.exists() {
+            assert!(start.elapsed() < TIMEOUT, "PipeWire didn't start");
+            thread::sleep(Duration::from_millis(50));
+        }
+        daemon
+    }
+
+    fn runtime_dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+impl Drop for Daemon {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// The monitor's view of the daemon, updated as events arrive.
+struct Graph {
+    events: mpsc::Receiver<Event>,
+    commands: pipewire::channel::Sender<MixCommand>,
+    next_id: u64,
+    state: State,
+    capture_manager: CaptureManager,
+}
+
+impl Graph {
+    fn connect() -> (Self, monitor::MonitorHandle) {
+        let (event_tx, events) = mpsc::channel();
+        let (commands, command_rx) = pipewire::channel::channel();
+        let handle = monitor::spawn::<monitor::Pipewire>(
+            None,
+            Arc::new(event_tx),
+            command_rx,
+        )
+        .unwrap();
+        let mut graph = Graph {
+            events,
+            commands,
+            next_id: 0,
+            state: State::default(),
+            capture_manager: CaptureManager::default(),
+        };
+        graph.wait_until("the initial objects", |state| {
+            ["wiremix-test-sink-a", "wiremix-test-sink-b"]
+                .iter()
+                .all(|name| find(state, name).is_some())
+                && state.get_metadata_by_name("default").is_some()
+        });
+        (graph, handle)
+    }
+
+    fn view(&self) -> View {
+        View::from(&self.state, &Names::default())
+    }
+
+    fn send(&mut self, command: Command) {
+        let id = self.next_id;
+        self.next_id += 1;
+        assert!(self.commands.send(MixCommand { id, command }).is_ok());
+    }
+
+    /// Apply events until `done` is true of the state.
+    fn wait_until(&mut self, what: &str, done: impl Fn(&State) -> bool) {
+        let start = Instant::now();
+        while !done(&self.state) {
+            let remaining = TIMEOUT.saturating_sub(start.elapsed());
+            match self.events.recv_timeout(remaining) {
+                Ok(Event::Monitor(event)) => {
+                    self.state.update(&mut self.capture_manager, event);
+                }
+                Ok(Event::CommandResult(_, Err(err))) => {
+                    panic!("Command failed waiting for {}: {}", what, err)
+                }
+                Ok(Event::Error(err)) if err.is_fatal() => {
+                    panic!("Monitor failed waiting for {}: {}", what, err)
+                }
+                Ok(_) => {}
+                Err(_) => panic!("Timed out waiting for {}", what),
+            }
+        }
+    }
+}
+
+fn find(state: &State, name: &str) -> Option<ObjectId> {
+    state
+        .nodes
+        .values()
+        .find(|node| node.name.as_deref() == Some(name))
+        .map(|node| node.id)
+}
+
+fn metadata_value(state: &State, subject: u32, key: &str) -> Option<String> {
+    state
+        .get_metadata_by_name("default")?
+        .properties
+        .get(&subject)?
+        .get(key)
+        .cloned()
+}
+
+#[test]
+#[ignore = "needs PipeWire, run with --ignored"]
+fn control_plane() {
+    let daemon = Daemon::start();
+    env::set_var("PIPEWIRE_RUNTIME_DIR", daemon.runtime_dir());
+    env::set_var("XDG_RUNTIME_DIR", daemon.runtime_dir());
+    let (mut graph, _monitor) = Graph::connect();
+
+    let sink_a = find(&graph.state, "wiremix-test-sink-a").unwrap();
+    let sink_b = find(&graph.state, "wiremix-test-sink-b").unwrap();
+    graph.wait_until("the stream", |state| {
+        find(state, "wiremix-test-stream").is_some()
+    });
+    let stream = find(&graph.state, "wiremix-test-stream").unwrap();
+
+    // Set volume
+    graph.wait_until("volumes", |state| state.nodes[&sink_a].volumes.is_some());
+    let command = graph
+        .view()
+        .volume(sink_a, VolumeAdjustment::Absolute(0.5))
+        .unwrap();
+    graph.send(command);
+    graph.wait_until("the new volume", |state| {
+        state.nodes[&sink_a]
+            .volumes
+            .as_ref()
+            .is_some_and(|volumes| {
+                volumes.iter().all(|volume| (volume - 0.125).abs() < 0.001)
+            })
+    });
+
+    // Mute
+    let command = graph.view().set_mute(sink_a, true).unwrap();
+    graph.send(command);
+    graph.wait_until("mute", |state| state.nodes[&sink_a].mute == Some(true));
+
+    // Set default
+    let command = graph.view().set_default(sink_b, DeviceKind::Sink).unwrap();
+    graph.send(command);
+    graph.wait_until("the configured default", |state| {
+        metadata_value(state, 0, "default.configured.audio.sink")
+            .is_some_and(|value| value.contains("wiremix-test-sink-b"))
+    });
+
+    // Move stream
+    for command in graph.view().set_target(stream, Target::Node(sink_b)) {
+        graph.send(command);
+    }
+    graph.wait_until("the stream's target", |state| {
+        metadata_value(state, stream.into(), "target.node")
+            == Some(u32::from(sink_b).to_string())
+    });
+}
@@ -0,0 +1,244 @@
+//! Golden snapshots of the interface.
+//!
+//! Each test feeds a recorded stream of monitor events into the app, draws a
+//! frame at a fixed size and compares the buffer, including styles, with a
+//! file in `tests/snapshots`. Run with `UPDATE_SNAPSHOTS=1` to write the files
+//! after an intended change, and review the diff.
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::mpsc;
+
+use clap::Parser;
+use ratatui::{backend::TestBackend, Terminal};
+
+use wiremix::app::{App, TabKind};
+use wiremix::command::MixCommand;
+use wiremix::config::Config;
+use wiremix::event::{Event, MonitorEvent};
+use wiremix::media_class::MediaClass;
+use wiremix::object::ObjectId;
+use wiremix::opt::Opt;
+
+const WIDTH: u16 = 80;
+const HEIGHT: u16 = 24;
+
+/// Events from a laptop with one sound card, playing music in one
+/// application while another is muted.
+fn laptop() -> Vec<Event> {
+    let id = ObjectId::from_raw_id;
+    let (card, sink, source) = (id(40), id(50), id(51));
+    let (music, video, client) = (id(60), id(61), id(70));
+    let metadata = id(90);
+
+    let mut events = vec![
+        MonitorEvent::DeviceName(
+            card,
+            String::from("alsa_card.pci-0000_00_1f.3"),
+        ),
+        MonitorEvent::DeviceDescription(card, String::from("Built-in Audio")),
+        MonitorEvent::DeviceMediaClass(card, MediaClass::from("Audio/Device")),
+        MonitorEvent::DeviceEnumProfile(
+            card,
+            0,
+            String::from("off"),
+            String::from("Off"),
+            true,
+            Vec::new(),
+        ),
+        MonitorEvent::DeviceEnumProfile(
+            card,
+            1,
+            String::from("output:analog-stereo+input:analog-stereo"),
+            String::from("Analog Stereo Duplex"),
+            true,
+            vec![
+                (MediaClass::from("Audio/Sink"), vec![0]),
+                (MediaClass::from("Audio/Source"), vec![1]),
+            ],
+        ),
+        MonitorEvent::DeviceProfile(card, 1),
+        MonitorEvent::DeviceObjectSerial(card, 40),
+    ];
+
+    let devices = [
+        (
+            sink,
+            "Audio/Sink",
+            "alsa_output.pci-0000_00_1f.3.analog-stereo",
+        ),
+        (
+            source,
+            "Audio/Source",
+            "alsa_input.pci-0000_00_1f.3.analog-stereo",
+        ),
+    ];
+    for (node, media_class, name) in devices {
+        events.extend([
+            MonitorEvent::NodeName(node, String::from(name)),
+            MonitorEvent::NodeDescription(
+                node,
+                String::from("Built-in Audio Analog Stereo"),
+            ),
+            MonitorEvent::NodeMediaClass(node, MediaClass::from(media_class)),
+            MonitorEvent::NodeDeviceId(node, card),
+            MonitorEvent::NodeObjectSerial(node, u32::from(node) as i32),
+            MonitorEvent::NodePositions(node, vec![3, 4]),
+            MonitorEvent::NodeVolumes(node, vec![0.512, 0.512]),
+            MonitorEvent::NodeMute(node, false),
+            MonitorEvent::NodeState(node, String::from("running"), None),
+        ]);
+    }
+
+    events.extend([
+        MonitorEvent::ClientApplicationName(client, String::from("Firefox")),
+        MonitorEvent::MetadataMetadataName(metadata, String::from("default")),
+        MonitorEvent::MetadataProperty(
+            metadata,
+            0,
+            Some(String::from("default.audio.sink")),
+            Some(String::from(
+                r#"{"name":"alsa_output.pci-0000_00_1f.3.analog-stereo"}"#,
+            )),
+        ),
+        MonitorEvent::MetadataProperty(
+            metadata,
+            0,
+            Some(String::from("default.audio.source")),
+            Some(String::from(
+                r#"{"name":"alsa_input.pci-0000_00_1f.3.analog-stereo"}"#,
+            )),
+        ),
+    ]);
+
+    let streams = [
+        (music, "Music Player", "Song (Remastered 2011)", 0.8, false),
+        (video, "Firefox", "Video", 1.0, true),
+    ];
+    for (node, name, media_name, volume, mute) in streams {
+        events.extend([
+            MonitorEvent::NodeName(node, String::from(name)),
+            MonitorEvent::NodeMediaName(node, String::from(media_name)),
+            MonitorEvent::NodeMediaClass(
+                node,
+                MediaClass::from("Stream/Output/Audio"),
+            ),
+            MonitorEvent::NodeObjectSerial(node, u32::from(node) as i32),
+            MonitorEvent::NodePositions(node, vec![3, 4]),
+            MonitorEvent::NodeVolumes(node, vec![volume, volume]),
+            MonitorEvent::NodeMute(node, mute),
+            MonitorEvent::NodeState(node, String::from("running"), None),
+        ]);
+    }
+    events.extend([
+        MonitorEvent::NodeClientId(video, client),
+        MonitorEvent::Link(id(80), music, sink),
+        MonitorEvent::Link(id(81), video, sink),
+    ]);
+
+    events
+        .into_iter()
+        .map(Event::Monitor)
+        .chain([Event::Ready])
+        .collect()
+}
+
+/// Configuration from command-line arguments, ignoring any files or
+/// environment which could differ between machines.
+fn config(args: &[&str]) -> Config {
+    let opt =
+        Opt::parse_from(std::iter::once("wiremix").chain(args.iter().copied()));
+    let mut config = Config::try_new(None, &opt).unwrap();
+    config.macros_path = None;
+    config.session_path = None;
+    config.marquee = false;
+    config
+}
+
+/// Draw the events with the configuration and compare the result with the
+/// snapshot called `name`.
+fn assert_snapshot(name: &str, config: Config, events: Vec<Event>) {
+    let (command_tx, _command_rx) = pipewire::channel::channel::<MixCommand>();
+    let (event_tx, event_rx) = mpsc::channel();
+    for event in events {
+        event_tx.send(event).unwrap();
+    }
+
+    let mut app = App::new(command_tx, event_rx, config);
+    let mut terminal = Terminal::new(TestBackend::new(WIDTH, HEIGHT)).unwrap();
+    app.draw_received(&mut terminal).unwrap();
+    let actual = format!("{:#?}\n", terminal.backend().buffer());
+
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(format!("{}.txt", name));
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::write(&path, &actual).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&path).unwrap_or_else(|err| {
+        panic!("{}: {} (run with UPDATE_SNAPSHOTS=1)", path.display(), err)
+    });
+    if actual != expected {
+        panic!(
+            "{} doesn't match the snapshot {}, run with UPDATE_SNAPSHOTS=1 \
+             to update it if the change is intended:\n{}",
+            name,
+            path.display(),
+            actual
+        );
+    }
+}
+
+#[test]
+fn playback() {
+    assert_snapshot("playback", config(&[]), laptop());
+}
+
+#[test]
+fn output_devices() {
+    let mut config = config(&[]);
+    config.tab = TabKind::Output;
+    assert_snapshot("output_devices", config, laptop());
+}
+
+#[test]
+fn configuration() {
+    let mut config = config(&[]);
+    config.tab = TabKind::Configuration;
+    assert_snapshot("configuration", config, laptop());
+}
+
+#[test]
+fn compact() {
+    assert_snapshot("compact", config(&["--compact"]), laptop());
+}
+
+#[test]
+fn split() {
+    assert_snapshot("split", config(&["--split", "output"]), laptop());
+}
+
+#[test]
+fn ascii_nocolor() {
+    let config = config(&["--char-set", "ascii", "--theme", "nocolor"]);
+    assert_snapshot("ascii_nocolor", config, laptop());
+}
+
+#[test]
+fn highcontrast() {
+    assert_snapshot(
+        "highcontrast",
+        config(&["--theme", "highcontrast"]),
+        laptop(),
+    );
+}
+
+#[test]
+fn vertical() {
+    assert_snapshot(
+        "vertical",
+        config(&["--set", "orientation=\"vertical\""]),
+        laptop(),
+    );
+}
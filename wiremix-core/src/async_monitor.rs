@@ -0,0 +1,173 @@
+//! An async interface to the monitor for applications using tokio, enabled by
+//! the `tokio` feature.
+//!
+//! [`spawn()`] starts a monitor like [`monitor::spawn()`] and returns an
+//! [`Events`] stream and a [`Commands`] handle whose
+//! [`send()`](`Commands::send()`) resolves once the backend has executed the
+//! command.
+
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::task::{Context, Poll};
+use std::thread;
+
+use anyhow::Result;
+use futures_core::Stream;
+use tokio::sync::{mpsc as tokio_mpsc, oneshot};
+
+use crate::command::{Command, CommandError, MixCommand};
+use crate::event::Event;
+use crate::monitor::{self, Backend, MonitorHandle};
+
+/// Commands waiting for their results, by ID.
+type Pending =
+    Arc<Mutex<HashMap<u64, oneshot::Sender<Result<(), CommandError>>>>>;
+
+/// Start monitoring a backend, like [`monitor::spawn()`].
+///
+/// Dropping the [`Events`] stops the monitor, which blocks until its thread
+/// has finished.
+pub fn spawn<B: Backend>(options: B::Options) -> Result<(Events, Commands)> {
+    let (event_tx, event_rx) = mpsc::channel();
+    let (command_tx, command_rx) = pipewire::channel::channel::<MixCommand>();
+    let handle = monitor::spawn::<B>(options, Arc::new(event_tx), command_rx)?;
+
+    let (stream_tx, stream_rx) = tokio_mpsc::unbounded_channel();
+    let pending = Pending::default();
+    thread::spawn({
+        let pending = Arc::clone(&pending);
+        move || forward(event_rx, stream_tx, pending)
+    });
+
+    let events = Events {
+        rx: stream_rx,
+        _handle: handle,
+    };
+    let commands = Commands {
+        tx: command_tx,
+        next_id: Arc::new(AtomicU64::new(0)),
+        pending,
+    };
+    Ok((events, commands))
+}
+
+/// Pass events from the monitor to the stream, and results of commands sent
+/// through [`Commands`] to the commands waiting for them.
+fn forward(
+    rx: mpsc::Receiver<Event>,
+    tx: tokio_mpsc::UnboundedSender<Event>,
+    pending: Pending,
+) {
+    for event in rx {
+        let event = match event {
+            Event::CommandResult(id, result) => {
+                let waiting = pending
+                    .lock()
+                    .ok()
+                    .and_then(|mut pending| pending.remove(&id));
+                match waiting {
+                    Some(waiting) => {
+                        let _ = waiting.send(result);
+                        continue;
+                    }
+                    None => Event::CommandResult(id, result),
+                }
+            }
+            event => event,
+        };
+        if tx.send(event).is_err() {
+            break;
+        }
+    }
+    // The monitor has stopped, so fail the commands still waiting
+    if let Ok(mut pending) = pending.lock() {
+        pending.clear();
+    }
+}
+
+/// A stream of the monitor's [`Event`]s, which ends when the monitor stops.
+///
+/// Results of commands sent through [`Commands`] aren't included.
+pub struct Events {
+    rx: tokio_mpsc::UnboundedReceiver<Event>,
+    _handle: MonitorHandle,
+}
+
+impl Events {
+    /// The next event, or `None` once the monitor has stopped.
+    pub async fn recv(&mut self) -> Option<Event> {
+        self.rx.recv().await
+    }
+}
+
+impl Stream for Events {
+    type Item = Event;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Event>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+/// Sends [`Command`]s to the monitor. It can be cloned to send commands from
+/// several tasks.
+#[derive(Clone)]
+pub struct Commands {
+    tx: pipewire::channel::Sender<MixCommand>,
+    next_id: Arc<AtomicU64>,
+    pending: Pending,
+}
+
+impl Commands {
+    /// Execute a command, resolving once the backend reports whether it
+    /// succeeded.
+    pub async fn send(&self, command: Command) -> Result<(), CommandError> {
+        let stopped = || CommandError::Failed(String::from("Monitor stopped"));
+
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (result_tx, result_rx) = oneshot::channel();
+        self.pending
+            .lock()
+            .map_err(|_| stopped())?
+            .insert(id, result_tx);
+        if self.tx.send(MixCommand { id, command }).is_err() {
+            if let Ok(mut pending) = self.pending.lock() {
+                pending.remove(&id);
+            }
+            return Err(stopped());
+        }
+        result_rx.await.unwrap_or_else(|_| Err(stopped()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn results_go_to_waiting_commands() {
+        let (event_tx, event_rx) = mpsc::channel();
+        let (stream_tx, mut stream_rx) = tokio_mpsc::unbounded_channel();
+        let pending = Pending::default();
+        let (result_tx, mut result_rx) = oneshot::channel();
+        pending.lock().unwrap().insert(1, result_tx);
+
+        event_tx.send(Event::CommandResult(1, Ok(()))).unwrap();
+        event_tx.send(Event::CommandResult(2, Ok(()))).unwrap();
+        event_tx.send(Event::Ready).unwrap();
+        drop(event_tx);
+        forward(event_rx, stream_tx, Arc::clone(&pending));
+
+        assert_eq!(result_rx.try_recv(), Ok(Ok(())));
+        assert!(matches!(
+            stream_rx.try_recv(),
+            Ok(Event::CommandResult(2, Ok(())))
+        ));
+        assert!(matches!(stream_rx.try_recv(), Ok(Event::Ready)));
+        assert!(stream_rx.try_recv().is_err());
+    }
+}
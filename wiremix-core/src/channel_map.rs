@@ -0,0 +1,216 @@
+//! Channel positions and the ways a node's channel map can be rearranged.
+//!
+//! Positions are SPA channel IDs as reported by
+//! [`MonitorEvent::NodePositions`](`crate::event::MonitorEvent::NodePositions`)
+//! and written by
+//! [`Command::NodeChannelMap`](`crate::command::Command::NodeChannelMap`).
+
+use libspa_sys::{
+    SPA_AUDIO_CHANNEL_LAST_Aux, SPA_AUDIO_CHANNEL_START_Aux,
+    SPA_AUDIO_CHANNEL_FC, SPA_AUDIO_CHANNEL_FL, SPA_AUDIO_CHANNEL_FLC,
+    SPA_AUDIO_CHANNEL_FLW, SPA_AUDIO_CHANNEL_FR, SPA_AUDIO_CHANNEL_FRC,
+    SPA_AUDIO_CHANNEL_FRW, SPA_AUDIO_CHANNEL_LFE, SPA_AUDIO_CHANNEL_LFE2,
+    SPA_AUDIO_CHANNEL_MONO, SPA_AUDIO_CHANNEL_RC, SPA_AUDIO_CHANNEL_RL,
+    SPA_AUDIO_CHANNEL_RLC, SPA_AUDIO_CHANNEL_RR, SPA_AUDIO_CHANNEL_RRC,
+    SPA_AUDIO_CHANNEL_SL, SPA_AUDIO_CHANNEL_SR, SPA_AUDIO_CHANNEL_TC,
+    SPA_AUDIO_CHANNEL_TFC, SPA_AUDIO_CHANNEL_TFL, SPA_AUDIO_CHANNEL_TFR,
+    SPA_AUDIO_CHANNEL_TRC, SPA_AUDIO_CHANNEL_TRL, SPA_AUDIO_CHANNEL_TRR,
+    SPA_AUDIO_CHANNEL_TSL, SPA_AUDIO_CHANNEL_TSR,
+};
+
+/// Left and right counterparts
+const PAIRS: &[(u32, u32)] = &[
+    (SPA_AUDIO_CHANNEL_FL, SPA_AUDIO_CHANNEL_FR),
+    (SPA_AUDIO_CHANNEL_SL, SPA_AUDIO_CHANNEL_SR),
+    (SPA_AUDIO_CHANNEL_FLC, SPA_AUDIO_CHANNEL_FRC),
+    (SPA_AUDIO_CHANNEL_RL, SPA_AUDIO_CHANNEL_RR),
+    (SPA_AUDIO_CHANNEL_TFL, SPA_AUDIO_CHANNEL_TFR),
+    (SPA_AUDIO_CHANNEL_TRL, SPA_AUDIO_CHANNEL_TRR),
+    (SPA_AUDIO_CHANNEL_RLC, SPA_AUDIO_CHANNEL_RRC),
+    (SPA_AUDIO_CHANNEL_FLW, SPA_AUDIO_CHANNEL_FRW),
+    (SPA_AUDIO_CHANNEL_TSL, SPA_AUDIO_CHANNEL_TSR),
+];
+
+/// A rearrangement of a node's channel positions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Remap {
+    /// Swap every left channel with its right counterpart
+    SwapLeftRight,
+    /// Swap the front and rear pairs
+    SwapFrontRear,
+    /// Swap the center and LFE channels
+    SwapCenterLfe,
+    /// Assign every left channel to FL and every right channel to FR,
+    /// leaving the others alone
+    Stereo,
+}
+
+impl Remap {
+    pub const ALL: [Remap; 4] = [
+        Remap::SwapLeftRight,
+        Remap::SwapFrontRear,
+        Remap::SwapCenterLfe,
+        Remap::Stereo,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Remap::SwapLeftRight => "Swap left and right",
+            Remap::SwapFrontRear => "Swap front and rear",
+            Remap::SwapCenterLfe => "Swap center and LFE",
+            Remap::Stereo => "Downmix to stereo",
+        }
+    }
+
+    /// Returns the positions rearranged, or `None` if this wouldn't change
+    /// them.
+    pub fn apply(&self, positions: &[u32]) -> Option<Vec<u32>> {
+        let remapped: Vec<u32> = positions
+            .iter()
+            .map(|&position| self.position(positions, position))
+            .collect();
+        (remapped != positions).then_some(remapped)
+    }
+
+    fn position(&self, positions: &[u32], position: u32) -> u32 {
+        match self {
+            Remap::SwapLeftRight => swap(positions, PAIRS, position),
+            Remap::SwapFrontRear => swap(
+                positions,
+                &[
+                    (SPA_AUDIO_CHANNEL_FL, SPA_AUDIO_CHANNEL_RL),
+                    (SPA_AUDIO_CHANNEL_FR, SPA_AUDIO_CHANNEL_RR),
+                ],
+                position,
+            ),
+            Remap::SwapCenterLfe => swap(
+                positions,
+                &[(SPA_AUDIO_CHANNEL_FC, SPA_AUDIO_CHANNEL_LFE)],
+                position,
+            ),
+            Remap::Stereo => {
+                if PAIRS.iter().any(|&(left, _)| left == position) {
+                    SPA_AUDIO_CHANNEL_FL
+                } else if PAIRS.iter().any(|&(_, right)| right == position) {
+                    SPA_AUDIO_CHANNEL_FR
+                } else {
+                    position
+                }
+            }
+        }
+    }
+}
+
+/// Returns the other position of the pair `position` is in, if `positions`
+/// has both.
+fn swap(positions: &[u32], pairs: &[(u32, u32)], position: u32) -> u32 {
+    pairs
+        .iter()
+        .filter(|(a, b)| positions.contains(a) && positions.contains(b))
+        .find_map(|&(a, b)| {
+            if position == a {
+                Some(b)
+            } else if position == b {
+                Some(a)
+            } else {
+                None
+            }
+        })
+        .unwrap_or(position)
+}
+
+/// Short name of a channel position, as used by pw-dump.
+pub fn name(position: u32) -> String {
+    let name = match position {
+        SPA_AUDIO_CHANNEL_MONO => "MONO",
+        SPA_AUDIO_CHANNEL_FL => "FL",
+        SPA_AUDIO_CHANNEL_FR => "FR",
+        SPA_AUDIO_CHANNEL_FC => "FC",
+        SPA_AUDIO_CHANNEL_LFE => "LFE",
+        SPA_AUDIO_CHANNEL_SL => "SL",
+        SPA_AUDIO_CHANNEL_SR => "SR",
+        SPA_AUDIO_CHANNEL_FLC => "FLC",
+        SPA_AUDIO_CHANNEL_FRC => "FRC",
+        SPA_AUDIO_CHANNEL_RC => "RC",
+        SPA_AUDIO_CHANNEL_RL => "RL",
+        SPA_AUDIO_CHANNEL_RR => "RR",
+        SPA_AUDIO_CHANNEL_TC => "TC",
+        SPA_AUDIO_CHANNEL_TFL => "TFL",
+        SPA_AUDIO_CHANNEL_TFC => "TFC",
+        SPA_AUDIO_CHANNEL_TFR => "TFR",
+        SPA_AUDIO_CHANNEL_TRL => "TRL",
+        SPA_AUDIO_CHANNEL_TRC => "TRC",
+        SPA_AUDIO_CHANNEL_TRR => "TRR",
+        SPA_AUDIO_CHANNEL_RLC => "RLC",
+        SPA_AUDIO_CHANNEL_RRC => "RRC",
+        SPA_AUDIO_CHANNEL_FLW => "FLW",
+        SPA_AUDIO_CHANNEL_FRW => "FRW",
+        SPA_AUDIO_CHANNEL_LFE2 => "LFE2",
+        SPA_AUDIO_CHANNEL_TSL => "TSL",
+        SPA_AUDIO_CHANNEL_TSR => "TSR",
+        SPA_AUDIO_CHANNEL_START_Aux..=SPA_AUDIO_CHANNEL_LAST_Aux => {
+            return format!("AUX{}", position - SPA_AUDIO_CHANNEL_START_Aux);
+        }
+        _ => "UNK",
+    };
+    String::from(name)
+}
+
+/// Names of positions separated by spaces, e.g. "FL FR".
+pub fn names(positions: &[u32]) -> String {
+    positions
+        .iter()
+        .map(|&position| name(position))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SURROUND: [u32; 6] = [
+        SPA_AUDIO_CHANNEL_FL,
+        SPA_AUDIO_CHANNEL_FR,
+        SPA_AUDIO_CHANNEL_FC,
+        SPA_AUDIO_CHANNEL_LFE,
+        SPA_AUDIO_CHANNEL_RL,
+        SPA_AUDIO_CHANNEL_RR,
+    ];
+
+    fn remap(remap: Remap, positions: &[u32]) -> Option<String> {
+        remap.apply(positions).map(|positions| names(&positions))
+    }
+
+    #[test]
+    fn remaps() {
+        assert_eq!(
+            remap(Remap::SwapLeftRight, &SURROUND).as_deref(),
+            Some("FR FL FC LFE RR RL")
+        );
+        assert_eq!(
+            remap(Remap::SwapFrontRear, &SURROUND).as_deref(),
+            Some("RL RR FC LFE FL FR")
+        );
+        assert_eq!(
+            remap(Remap::SwapCenterLfe, &SURROUND).as_deref(),
+            Some("FL FR LFE FC RL RR")
+        );
+        assert_eq!(
+            remap(Remap::Stereo, &SURROUND).as_deref(),
+            Some("FL FR FC LFE FL FR")
+        );
+    }
+
+    #[test]
+    fn unchanged_remaps() {
+        let stereo = [SPA_AUDIO_CHANNEL_FL, SPA_AUDIO_CHANNEL_FR];
+        assert_eq!(remap(Remap::SwapFrontRear, &stereo), None);
+        assert_eq!(remap(Remap::SwapCenterLfe, &stereo), None);
+        assert_eq!(remap(Remap::Stereo, &stereo), None);
+        assert_eq!(
+            remap(Remap::SwapLeftRight, &[SPA_AUDIO_CHANNEL_MONO]),
+            None
+        );
+    }
+}
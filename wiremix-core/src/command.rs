@@ -0,0 +1,49 @@
+//! PipeWire controls which can be executed by the monitor module.
+
+use crate::object::ObjectId;
+
+#[derive(Debug, Clone)]
+pub enum Command {
+    NodeMute(ObjectId, bool),
+    DeviceMute(ObjectId, i32, i32, bool),
+    NodeVolumes(ObjectId, Vec<f32>),
+    /// Set the channel positions of a node's ports, which are its input
+    /// ports if the flag is set or else its output ports.
+    NodeChannelMap(ObjectId, bool, Vec<u32>),
+    /// Set the node's channelmix.upmix param.
+    NodeUpmix(ObjectId, bool),
+    DeviceVolumes(ObjectId, i32, i32, Vec<f32>),
+    DeviceSetRoute(ObjectId, i32, i32),
+    DeviceSetProfile(ObjectId, i32),
+    NodeCaptureStart(ObjectId, i32, bool),
+    NodeCaptureStop(ObjectId),
+    MetadataSetProperty(ObjectId, u32, String, Option<String>, Option<String>),
+}
+
+/// A [`Command`] with an ID for matching it with the
+/// [`Event::CommandResult`](`crate::event::Event::CommandResult`) reporting
+/// whether it succeeded.
+#[derive(Debug, Clone)]
+pub struct MixCommand {
+    pub id: u64,
+    pub command: Command,
+}
+
+/// Why a backend couldn't execute a [`Command`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum CommandError {
+    /// The object isn't known to the backend. This can be transient, such as
+    /// when a command is sent for an object the backend is still binding.
+    #[error("Object {} not found", .0.to_string())]
+    NotFound(ObjectId),
+    /// The backend tried and failed to execute the command.
+    #[error("{0}")]
+    Failed(String),
+}
+
+impl CommandError {
+    /// Whether trying again later might succeed.
+    pub fn is_transient(&self) -> bool {
+        matches!(self, CommandError::NotFound(_))
+    }
+}
@@ -0,0 +1,56 @@
+//! Errors reported by the monitor in [`Event::Error`](`crate::event::Event`).
+
+use crate::command::CommandError;
+use crate::event;
+use crate::object::ObjectId;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum Error {
+    /// The backend couldn't be connected to or set up.
+    #[error("{0}")]
+    Connect(String),
+    /// The server reported an error, or the connection to it was lost.
+    #[error("{0}")]
+    Protocol(String),
+    /// A command failed outside of a
+    /// [`CommandResult`](`crate::event::Event::CommandResult`).
+    #[error(transparent)]
+    Command(#[from] CommandError),
+    /// Peaks couldn't be captured for a node.
+    #[error("Couldn't capture peaks for node {}: {}", .0.to_string(), .1)]
+    Capture(ObjectId, String),
+}
+
+impl Error {
+    /// Whether the error is harmless and shouldn't stop wiremix.
+    pub fn is_ignored(&self) -> bool {
+        match self {
+            Error::Protocol(message) => event::is_ignored_error(message),
+            _ => false,
+        }
+    }
+
+    /// Whether the error means the monitor has stopped, rather than that
+    /// something failed along the way.
+    pub fn is_fatal(&self) -> bool {
+        matches!(self, Error::Connect(_) | Error::Protocol(_))
+            && !self.is_ignored()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_lost_connections_are_fatal() {
+        let connect = Error::Connect(String::from("Connection refused"));
+        assert!(connect.is_fatal());
+        let ignored = Error::Protocol(String::from("no global 42"));
+        assert!(ignored.is_ignored() && !ignored.is_fatal());
+        let capture = Error::Capture(ObjectId::from_raw_id(1), String::new());
+        assert!(!capture.is_fatal());
+        let command = Error::from(CommandError::Failed(String::from("Nope")));
+        assert_eq!(command.to_string(), "Nope");
+    }
+}
@@ -1,10 +1,12 @@
 //! Input events for the application.
 //!
-//! These come from [`monitor`](`crate::monitor`) (PipeWire events) and from
-//! [`input`](`crate::input`) (terminal input events).
+//! These come from [`monitor`](`crate::monitor`) (PipeWire events) and, with
+//! the `crossterm` feature, from the terminal.
 
 use pipewire::link::LinkInfoRef;
 
+use crate::command::CommandError;
+use crate::error::Error;
 use crate::media_class::MediaClass;
 use crate::object::ObjectId;
 
@@ -15,29 +17,53 @@ pub enum MonitorEvent {
     DeviceMediaClass(ObjectId, MediaClass),
     DeviceName(ObjectId, String),
     DeviceNick(ObjectId, String),
-    DeviceEnumProfile(ObjectId, i32, String, bool, Vec<(MediaClass, Vec<i32>)>),
+    DeviceEnumProfile(
+        ObjectId,
+        i32,
+        String,
+        String,
+        bool,
+        Vec<(MediaClass, Vec<i32>)>,
+    ),
     DeviceProfile(ObjectId, i32),
     DeviceRoute(ObjectId, i32, i32, Vec<i32>, String, bool, Vec<f32>, bool),
     DeviceObjectSerial(ObjectId, i32),
+    DeviceBattery(ObjectId, u8),
 
     MetadataMetadataName(ObjectId, String),
     MetadataProperty(ObjectId, u32, Option<String>, Option<String>),
 
     ClientApplicationName(ObjectId, String),
     ClientApplicationProcessBinary(ObjectId, String),
+    ClientApplicationProcessId(ObjectId, String),
+    ClientFlatpakAppId(ObjectId, String),
+    ClientPipewireAccess(ObjectId, String),
+    ClientPortalAppId(ObjectId, String),
+    ClientSnapAppId(ObjectId, String),
+    ClientProcessCgroup(ObjectId, String),
+    ClientProcessCmdline(ObjectId, String),
 
     NodeCardProfileDevice(ObjectId, i32),
+    NodeClientApi(ObjectId, String),
     NodeClientId(ObjectId, ObjectId),
     NodeDescription(ObjectId, String),
     NodeDeviceId(ObjectId, ObjectId),
+    NodeFormat(ObjectId, String, u32, u32, Vec<String>),
+    NodeLatency(ObjectId, String),
     NodeMediaClass(ObjectId, MediaClass),
     NodeMediaName(ObjectId, String),
+    NodeMediaRole(ObjectId, String),
     NodeName(ObjectId, String),
+    NodeNetwork(ObjectId, bool),
     NodeNick(ObjectId, String),
     NodeObjectSerial(ObjectId, i32),
     NodePeaks(ObjectId, Vec<f32>, u32),
     NodePositions(ObjectId, Vec<u32>),
     NodeRate(ObjectId, u32),
+    NodeResampleQuality(ObjectId, String),
+    NodeState(ObjectId, String, Option<String>),
+    /// Whether the node's channelmix.upmix param is set
+    NodeUpmix(ObjectId, bool),
     NodeVolumes(ObjectId, Vec<f32>),
     NodeMute(ObjectId, bool),
 
@@ -46,6 +72,10 @@ pub enum MonitorEvent {
     StreamStopped(ObjectId),
 
     Removed(ObjectId),
+
+    /// An error reported by an object, such as a device failing to switch
+    /// profiles
+    ObjectError(ObjectId, String),
 }
 
 impl From<&LinkInfoRef> for MonitorEvent {
@@ -60,12 +90,32 @@ impl From<&LinkInfoRef> for MonitorEvent {
 
 #[derive(Debug)]
 pub enum Event {
+    #[cfg(feature = "crossterm")]
     Input(crossterm::event::Event),
     Monitor(MonitorEvent),
-    Error(String),
+    Error(Error),
+    /// Whether the [`MixCommand`](`crate::command::MixCommand`) with the
+    /// given ID succeeded.
+    CommandResult(u64, Result<(), CommandError>),
     Ready,
+    /// The system is about to sleep (true) or has woken up (false).
+    Sleep(bool),
+}
+
+/// Whether an error message from PipeWire is harmless and shouldn't stop
+/// wiremix.
+pub fn is_ignored_error(error: &str) -> bool {
+    // These happen when objects are removed while the monitor is still in the
+    // process of setting up listeners
+    error.starts_with("no global ")
+        || error.starts_with("unknown resource ")
+        // I see this one when disconnecting a Bluetooth sink
+        || error == "Received error event"
+        // Not sure where this originates
+        || error == "Error: Buffer allocation failed"
 }
 
+#[cfg(feature = "crossterm")]
 impl From<crossterm::event::Event> for Event {
     fn from(event: crossterm::event::Event) -> Self {
         Event::Input(event)
@@ -0,0 +1,60 @@
+//! The PipeWire monitor and state model behind wiremix, for programs which
+//! want to follow PipeWire's objects without wiremix's interface.
+//!
+//! [`monitor::spawn()`] starts a thread which sends
+//! [`Event`](`event::Event`)s describing PipeWire's objects and executes
+//! [`MixCommand`](`command::MixCommand`)s. Applying the events to a
+//! [`State`](`state::State`) keeps an up-to-date model of the nodes, devices,
+//! links and default devices:
+//!
+//! ```no_run
+//! use std::sync::{mpsc, Arc};
+//!
+//! use wiremix_core::capture_manager::CaptureManager;
+//! use wiremix_core::command::MixCommand;
+//! use wiremix_core::event::Event;
+//! use wiremix_core::monitor;
+//! use wiremix_core::state::State;
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let (event_tx, event_rx) = mpsc::channel();
+//! let (_command_tx, command_rx) = pipewire::channel::channel::<MixCommand>();
+//! let _monitor = monitor::spawn::<monitor::Pipewire>(
+//!     None,
+//!     Arc::new(event_tx),
+//!     command_rx,
+//! )?;
+//!
+//! let mut state = State::default();
+//! let mut capture_manager = CaptureManager::default();
+//! for event in event_rx {
+//!     match event {
+//!         Event::Monitor(event) => state.update(&mut capture_manager, event),
+//!         Event::Ready => break,
+//!         _ => {}
+//!     }
+//! }
+//! for node in state.nodes.values() {
+//!     println!("{:?}", node.name);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The `alsa` and `pulse` features add fallback backends, and the
+//! `crossterm` feature adds [`Event::Input`](`event::Event::Input`) so that
+//! terminal input can share the event channel. The `tokio` feature adds
+//! `async_monitor`, a `Stream` of events and commands which can be awaited,
+//! for async applications.
+
+#[cfg(feature = "tokio")]
+pub mod async_monitor;
+pub mod capture_manager;
+pub mod channel_map;
+pub mod command;
+pub mod error;
+pub mod event;
+pub mod media_class;
+pub mod monitor;
+pub mod object;
+pub mod state;
@@ -10,6 +10,10 @@ impl From<&str> for MediaClass {
 }
 
 impl MediaClass {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
     pub fn is_sink(&self) -> bool {
         matches!(self.0.as_str(), "Audio/Sink" | "Audio/Duplex")
     }
@@ -33,6 +37,14 @@ impl MediaClass {
         self.0 == "Audio/Sink"
     }
 
+    pub fn is_video_source(&self) -> bool {
+        self.0 == "Video/Source"
+    }
+
+    pub fn is_video_stream(&self) -> bool {
+        self.0 == "Stream/Input/Video"
+    }
+
     pub fn is_recordable(&self) -> bool {
         self.is_source() || self.is_sink() || self.is_sink_input()
     }
@@ -0,0 +1,669 @@
+//! Setup and teardown of monitoring.
+//!
+//! [`spawn()`] starts a thread monitoring a [`Backend`], which is PipeWire
+//! unless another backend is enabled and selected.
+
+#[cfg(feature = "alsa")]
+pub mod alsa;
+mod client;
+mod deserialize;
+mod device;
+mod event_sender;
+mod execute;
+mod link;
+mod metadata;
+mod node;
+mod process;
+mod proxy_registry;
+#[cfg(feature = "pulse")]
+pub mod pulse;
+mod stream;
+mod stream_registry;
+mod sync_registry;
+#[cfg(any(feature = "alsa", feature = "pulse"))]
+mod worker;
+
+use anyhow::Result;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Duration;
+
+use nix::sys::eventfd::{EfdFlags, EventFd};
+use nix::sys::time::TimeSpec;
+use nix::sys::timerfd::{
+    ClockId, Expiration, TimerFd, TimerFlags, TimerSetTimeFlags,
+};
+use std::os::fd::{AsFd, AsRawFd, RawFd};
+
+use pipewire::{
+    loop_::{IoSource, TimerSource},
+    main_loop::MainLoop,
+    properties::properties,
+    proxy::ProxyT,
+    types::ObjectType,
+};
+
+use crate::command::{Command, CommandError, MixCommand};
+use crate::error::Error;
+use crate::event::{Event, MonitorEvent};
+pub use crate::monitor::event_sender::EventSender;
+use crate::monitor::{
+    proxy_registry::ProxyRegistry, stream_registry::StreamRegistry,
+    sync_registry::SyncRegistry,
+};
+use crate::object::ObjectId;
+
+/// How many times a command failing transiently is tried.
+const COMMAND_ATTEMPTS: u32 = 3;
+/// How long to wait before trying a command again.
+const COMMAND_RETRY_INTERVAL: Duration = Duration::from_millis(200);
+
+/// A source of [`MonitorEvent`]s which executes [`Command`]s.
+///
+/// Backends are created and run on the thread started by [`spawn()`], which
+/// owns the main loop, quits it on shutdown, and passes received commands to
+/// the backend.
+pub trait Backend: Sized + 'static {
+    /// What's needed to create the backend, such as the remote to connect to.
+    type Options: Send + 'static;
+
+    /// Connects and starts sending events for the backend's objects to
+    /// `sender`, followed by [`Event::Ready`] once the initial objects have
+    /// been sent.
+    ///
+    /// Returns the backend along with any main loop sources it needs to keep
+    /// alive while the main loop runs.
+    fn subscribe<'l>(
+        options: Self::Options,
+        main_loop: &'l MainLoop,
+        sender: &Rc<EventSender>,
+    ) -> Result<(Rc<Self>, Sources<'l>)>;
+
+    /// Executes a command other than
+    /// [`NodeCaptureStart`](`Command::NodeCaptureStart`) and
+    /// [`NodeCaptureStop`](`Command::NodeCaptureStop`). Commands failing with
+    /// a transient [`CommandError`] are tried again a few times.
+    fn command(&self, command: Command) -> Result<(), CommandError>;
+
+    /// Starts sending [`MonitorEvent::NodePeaks`] for a node. Backends
+    /// without peak meters can leave this as a no-op.
+    fn capture_start(
+        &self,
+        _node_id: ObjectId,
+        _object_serial: i32,
+        _capture_sink: bool,
+    ) {
+    }
+
+    /// Stops sending peaks for a node.
+    fn capture_stop(&self, _node_id: ObjectId) {}
+}
+
+/// Main loop sources which a [`Backend`] needs to keep alive.
+#[derive(Default)]
+pub struct Sources<'l> {
+    pub io: Vec<IoSource<'l, RawFd>>,
+    pub timers: Vec<TimerSource<'l>>,
+}
+
+/// Spawns a thread to monitor a [`Backend`], created from `options`.
+///
+/// [`Event`](`crate::event::Event`)s from the backend are sent to `tx`.
+/// [`Command`](`crate::command::Command`)s sent to `rx` will be executed.
+///
+/// Returns a [`MonitorHandle`] to automatically clean up the thread.
+pub fn spawn<B: Backend>(
+    options: B::Options,
+    tx: Arc<mpsc::Sender<Event>>,
+    rx: pipewire::channel::Receiver<MixCommand>,
+) -> Result<MonitorHandle> {
+    let shutdown_fd =
+        Arc::new(EventFd::from_value_and_flags(0, EfdFlags::EFD_NONBLOCK)?);
+
+    let handle = thread::spawn({
+        let shutdown_fd = Arc::clone(&shutdown_fd);
+        move || {
+            let _ = run::<B>(options, tx, rx, shutdown_fd);
+        }
+    });
+
+    Ok(MonitorHandle {
+        fd: Some(shutdown_fd),
+        handle: Some(handle),
+    })
+}
+
+/// Whether there is a socket for connecting to the remote, or to the default
+/// remote if `remote` is `None`.
+pub fn socket_exists(remote: Option<&str>) -> bool {
+    use std::env;
+    use std::path::PathBuf;
+
+    let remote = remote
+        .map(String::from)
+        .or_else(|| env::var("PIPEWIRE_REMOTE").ok())
+        .unwrap_or_else(|| String::from("pipewire-0"));
+    let path = PathBuf::from(&remote);
+    if path.is_absolute() {
+        return path.exists();
+    }
+
+    ["PIPEWIRE_RUNTIME_DIR", "XDG_RUNTIME_DIR"]
+        .iter()
+        .filter_map(env::var_os)
+        .any(|dir| PathBuf::from(dir).join(&remote).exists())
+}
+
+/// Wrapper for handling PipeWire initialization/deinitialization.
+///
+/// Every backend uses a PipeWire main loop, even those which don't connect to
+/// PipeWire.
+fn run<B: Backend>(
+    options: B::Options,
+    tx: Arc<mpsc::Sender<Event>>,
+    rx: pipewire::channel::Receiver<MixCommand>,
+    shutdown_fd: Arc<EventFd>,
+) -> Result<()> {
+    pipewire::init();
+
+    let _guard = scopeguard::guard((), |_| unsafe {
+        pipewire::deinit();
+    });
+
+    let main_loop = MainLoop::new(None)?;
+    let sender = Rc::new(EventSender::new(tx, main_loop.downgrade()));
+
+    monitor::<B>(options, &main_loop, &sender, rx, shutdown_fd).unwrap_or_else(
+        |e| {
+            sender.send_error(Error::Connect(format!("{:#}", e)));
+        },
+    );
+
+    Ok(())
+}
+
+/// Handle for a monitoring thread.
+///
+/// On cleanup, the PipeWire [`MainLoop`](`pipewire::main_loop::MainLoop`) will
+/// be notified to [`quit()`](`pipewire::main_loop::MainLoop::quit()`), and the
+/// thread will be joined.
+pub struct MonitorHandle {
+    fd: Option<Arc<EventFd>>,
+    handle: Option<thread::JoinHandle<()>>,
+}
+
+impl Drop for MonitorHandle {
+    fn drop(&mut self) {
+        if let Some(fd) = self.fd.take() {
+            let _ = fd.arm();
+        }
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Subscribes to a backend and runs the main loop until shutdown.
+fn monitor<B: Backend>(
+    options: B::Options,
+    main_loop: &MainLoop,
+    sender: &Rc<EventSender>,
+    rx: pipewire::channel::Receiver<MixCommand>,
+    shutdown_fd: Arc<EventFd>,
+) -> Result<()> {
+    let fd = shutdown_fd.as_raw_fd();
+    let _shutdown_watch =
+        main_loop
+            .loop_()
+            .add_io(fd, libspa::support::system::IoFlags::IN, {
+                let main_loop_weak = main_loop.downgrade();
+                move |_status| {
+                    if let Some(main_loop) = main_loop_weak.upgrade() {
+                        main_loop.quit();
+                    }
+                }
+            });
+
+    let (backend, _sources) = B::subscribe(options, main_loop, sender)?;
+
+    let retries = Rc::new(Retries::try_new()?);
+
+    let _receiver = rx.attach(main_loop.loop_(), {
+        let backend = Rc::clone(&backend);
+        let sender = Rc::clone(sender);
+        let retries = Rc::clone(&retries);
+        move |command| execute(&*backend, &sender, &retries, command, 1)
+    });
+
+    let _retry_watch = main_loop.loop_().add_io(
+        retries.timer.as_fd().as_raw_fd(),
+        libspa::support::system::IoFlags::IN,
+        {
+            let backend = Rc::clone(&backend);
+            let sender = Rc::clone(sender);
+            let retries = Rc::clone(&retries);
+            move |_status| {
+                for (command, attempts) in retries.take() {
+                    execute(
+                        &*backend,
+                        &sender,
+                        &retries,
+                        command,
+                        attempts + 1,
+                    );
+                }
+            }
+        },
+    );
+
+    main_loop.run();
+
+    Ok(())
+}
+
+/// Commands which failed transiently and how many times they were tried,
+/// with a timer which is only armed while there are any.
+struct Retries {
+    pending: RefCell<Vec<(MixCommand, u32)>>,
+    timer: TimerFd,
+}
+
+impl Retries {
+    fn try_new() -> Result<Self> {
+        Ok(Self {
+            pending: Default::default(),
+            timer: TimerFd::new(
+                ClockId::CLOCK_MONOTONIC,
+                TimerFlags::TFD_NONBLOCK,
+            )?,
+        })
+    }
+
+    /// Queues a command to be tried again, arming the timer if it's the
+    /// first.
+    fn push(&self, command: MixCommand, attempts: u32) {
+        let mut pending = self.pending.borrow_mut();
+        if pending.is_empty() {
+            let expiration = Expiration::OneShot(TimeSpec::from_duration(
+                COMMAND_RETRY_INTERVAL,
+            ));
+            let _ = self.timer.set(expiration, TimerSetTimeFlags::empty());
+        }
+        pending.push((command, attempts));
+    }
+
+    /// Takes the queued commands once the timer has fired. The timer is
+    /// one-shot, so it stays disarmed unless one of them is queued again.
+    fn take(&self) -> Vec<(MixCommand, u32)> {
+        let _ = self.timer.wait();
+        std::mem::take(&mut *self.pending.borrow_mut())
+    }
+}
+
+/// Executes a command, queueing it to be tried again if it failed
+/// transiently, or otherwise reporting its result.
+fn execute<B: Backend>(
+    backend: &B,
+    sender: &EventSender,
+    retries: &Retries,
+    mix_command: MixCommand,
+    attempt: u32,
+) {
+    let result = match mix_command.command.clone() {
+        Command::NodeCaptureStart(node_id, object_serial, capture_sink) => {
+            backend.capture_start(node_id, object_serial, capture_sink);
+            return;
+        }
+        Command::NodeCaptureStop(node_id) => {
+            backend.capture_stop(node_id);
+            return;
+        }
+        command => backend.command(command),
+    };
+
+    match result {
+        Err(e) if e.is_transient() && attempt < COMMAND_ATTEMPTS => {
+            retries.push(mix_command, attempt);
+        }
+        result => sender.send_command_result(mix_command.id, result),
+    }
+}
+
+/// The PipeWire backend.
+pub struct Pipewire {
+    sender: Rc<EventSender>,
+    // Fields are dropped in order, so listeners come before what they listen
+    // to and the core comes last.
+    _registry_listener: pipewire::registry::Listener,
+    streams: Rc<RefCell<StreamRegistry<stream::StreamData>>>,
+    proxies: Rc<RefCell<ProxyRegistry>>,
+    _registry: Rc<pipewire::registry::Registry>,
+    _core_listener: pipewire::core::Listener,
+    _syncs: Rc<RefCell<SyncRegistry>>,
+    core: Rc<pipewire::core::Core>,
+    _context: pipewire::context::Context,
+}
+
+impl Backend for Pipewire {
+    /// The name of the remote to connect to.
+    type Options = Option<String>;
+
+    /// Sets up core and registry listeners.
+    fn subscribe<'l>(
+        remote: Option<String>,
+        main_loop: &'l MainLoop,
+        sender: &Rc<EventSender>,
+    ) -> Result<(Rc<Self>, Sources<'l>)> {
+        let context = pipewire::context::Context::new(main_loop)?;
+        let props = remote.map(|remote| {
+            properties! {
+                *pipewire::keys::REMOTE_NAME => remote
+            }
+        });
+        let core = Rc::new(context.connect(props)?);
+
+        let syncs = Rc::new(RefCell::new(SyncRegistry::default()));
+
+        let core_listener = core
+            .add_listener_local()
+            .done({
+                let sender_weak = Rc::downgrade(sender);
+                let syncs_weak = Rc::downgrade(&syncs);
+                move |_id, seq| {
+                    let Some(sender) = sender_weak.upgrade() else {
+                        return;
+                    };
+                    let Some(syncs) = syncs_weak.upgrade() else {
+                        return;
+                    };
+                    if syncs.borrow_mut().done(seq) {
+                        sender.send_ready();
+                    }
+                }
+            })
+            .error({
+                let sender_weak = Rc::downgrade(sender);
+                move |id, _seq, _res, message| {
+                    // Errors about objects are sent by their proxies'
+                    // listeners as MonitorEvent::Error.
+                    if id != pipewire::core::PW_ID_CORE {
+                        return;
+                    }
+                    if let Some(sender) = sender_weak.upgrade() {
+                        sender.send_error(Error::Protocol(message.to_string()));
+                    };
+                }
+            })
+            .register();
+
+        let registry = Rc::new(core.get_registry()?);
+        let registry_weak = Rc::downgrade(&registry);
+
+        // Proxies and their listeners need to stay alive so store them here
+        let proxies = Rc::new(RefCell::new(ProxyRegistry::try_new()?));
+        // It's not safe to delete proxies and listeners during PipeWire callbacks,
+        // so registries defer cleanup and use an EventFd to signal that objects
+        // are pending deletion.
+        let proxy_gc_watch = main_loop.loop_().add_io(
+            proxies.borrow().gc_fd.as_raw_fd(),
+            libspa::support::system::IoFlags::IN,
+            {
+                let proxies = Rc::clone(&proxies);
+                move |_status| {
+                    proxies.borrow_mut().collect_garbage();
+                }
+            },
+        );
+
+        // Proxies and their listeners need to stay alive so store them here
+        let streams = Rc::new(RefCell::new(StreamRegistry::try_new()?));
+        // It's not safe to delete proxies and listeners during PipeWire callbacks,
+        // so registries defer cleanup and use an EventFd to signal that objects
+        // are pending deletion.
+        let streams_gc_watch = main_loop.loop_().add_io(
+            streams.borrow().gc_fd.as_raw_fd(),
+            libspa::support::system::IoFlags::IN,
+            {
+                let streams = Rc::clone(&streams);
+                let sender_weak = Rc::downgrade(sender);
+                move |_status| {
+                    let collected = streams.borrow_mut().collect_garbage();
+                    if let Some(sender) = sender_weak.upgrade() {
+                        for id in collected {
+                            sender.send(MonitorEvent::StreamStopped(id));
+                        }
+                    }
+                }
+            },
+        );
+
+        let registry_listener = registry
+            .add_listener_local()
+            .global({
+                let core_weak = Rc::downgrade(&core);
+                let proxies = Rc::clone(&proxies);
+                let sender_weak = Rc::downgrade(sender);
+                let streams_weak = Rc::downgrade(&streams);
+                let syncs_weak = Rc::downgrade(&syncs);
+                move |obj| {
+                    let obj_id = ObjectId::from(obj);
+                    let Some(registry) = registry_weak.upgrade() else {
+                        return;
+                    };
+
+                    let Some(sender) = sender_weak.upgrade() else {
+                        return;
+                    };
+
+                    let Some(streams) = streams_weak.upgrade() else {
+                        return;
+                    };
+
+                    let Some(core) = core_weak.upgrade() else {
+                        return;
+                    };
+
+                    let Some(syncs) = syncs_weak.upgrade() else {
+                        return;
+                    };
+
+                    let proxy_spe = match obj.type_ {
+                        ObjectType::Client => {
+                            let result =
+                                client::monitor_client(&registry, obj, &sender);
+                            if let Some((node, listener)) = result {
+                                proxies.borrow_mut().add_client(
+                                    obj_id,
+                                    Rc::clone(&node),
+                                    listener,
+                                );
+                                Some(node as Rc<dyn ProxyT>)
+                            } else {
+                                None
+                            }
+                        }
+                        ObjectType::Node => {
+                            let result =
+                                node::monitor_node(&registry, obj, &sender);
+                            if let Some((node, listener)) = result {
+                                proxies.borrow_mut().add_node(
+                                    obj_id,
+                                    Rc::clone(&node),
+                                    listener,
+                                );
+                                Some(node as Rc<dyn ProxyT>)
+                            } else {
+                                None
+                            }
+                        }
+                        ObjectType::Device => {
+                            let result =
+                                device::monitor_device(&registry, obj, &sender);
+                            match result {
+                                Some((device, listener)) => {
+                                    proxies.borrow_mut().add_device(
+                                        obj_id,
+                                        Rc::clone(&device),
+                                        listener,
+                                    );
+                                    Some(device as Rc<dyn ProxyT>)
+                                }
+                                None => None,
+                            }
+                        }
+                        ObjectType::Link => {
+                            let result =
+                                link::monitor_link(&registry, obj, &sender);
+                            match result {
+                                Some((link, listener)) => {
+                                    proxies.borrow_mut().add_link(
+                                        obj_id,
+                                        Rc::clone(&link),
+                                        listener,
+                                    );
+                                    Some(link as Rc<dyn ProxyT>)
+                                }
+                                None => None,
+                            }
+                        }
+                        ObjectType::Metadata => {
+                            let result = metadata::monitor_metadata(
+                                &registry, obj, &sender,
+                            );
+                            match result {
+                                Some((metadata, listener)) => {
+                                    proxies.borrow_mut().add_metadata(
+                                        obj_id,
+                                        Rc::clone(&metadata),
+                                        listener,
+                                    );
+                                    Some(metadata as Rc<dyn ProxyT>)
+                                }
+                                None => None,
+                            }
+                        }
+                        _ => None,
+                    };
+                    let Some(proxy_spe) = proxy_spe else {
+                        return;
+                    };
+
+                    let proxy = proxy_spe.upcast_ref();
+
+                    // Use a weak ref to prevent references cycle between Proxy and proxies:
+                    // - ref on proxies in the closure, bound to the Proxy lifetime
+                    // - proxies owning a ref on Proxy as well
+                    let proxies_weak = Rc::downgrade(&proxies);
+                    let streams_weak = Rc::downgrade(&streams);
+                    let sender_weak = Rc::downgrade(&sender);
+                    let error_sender_weak = Rc::downgrade(&sender);
+                    let listener = proxy
+                        .add_listener_local()
+                        .error(move |_seq, _res, message| {
+                            if let Some(sender) = error_sender_weak.upgrade() {
+                                sender.send(MonitorEvent::ObjectError(
+                                    obj_id,
+                                    message.to_string(),
+                                ));
+                            };
+                        })
+                        .removed(move || {
+                            if let Some(sender) = sender_weak.upgrade() {
+                                sender.send(MonitorEvent::Removed(obj_id));
+                            };
+                            if let Some(proxies) = proxies_weak.upgrade() {
+                                proxies.borrow_mut().remove(obj_id);
+                            };
+                            if let Some(streams) = streams_weak.upgrade() {
+                                streams.borrow_mut().remove(obj_id);
+                            };
+                        })
+                        .register();
+
+                    proxies.borrow_mut().add_proxy_listener(obj_id, listener);
+
+                    syncs.borrow_mut().global(&core);
+                }
+            })
+            .register();
+
+        let sources = Sources {
+            io: vec![proxy_gc_watch, streams_gc_watch],
+            ..Default::default()
+        };
+        let pipewire = Self {
+            sender: Rc::clone(sender),
+            _registry_listener: registry_listener,
+            streams,
+            proxies,
+            _registry: registry,
+            _core_listener: core_listener,
+            _syncs: syncs,
+            core,
+            _context: context,
+        };
+
+        Ok((Rc::new(pipewire), sources))
+    }
+
+    fn command(&self, command: Command) -> Result<(), CommandError> {
+        execute::execute_command(&self.proxies.borrow(), command)
+    }
+
+    fn capture_start(
+        &self,
+        node_id: ObjectId,
+        object_serial: i32,
+        capture_sink: bool,
+    ) {
+        let result = stream::capture_node(
+            &self.core,
+            &self.sender,
+            node_id,
+            &object_serial.to_string(),
+            capture_sink,
+        );
+        match result {
+            Ok((stream, listener)) => {
+                self.streams
+                    .borrow_mut()
+                    .add_stream(node_id, stream, listener);
+            }
+            Err(err) => {
+                let message = format!("{:#}", err);
+                self.sender.send_error(Error::Capture(node_id, message));
+            }
+        }
+    }
+
+    fn capture_stop(&self, node_id: ObjectId) {
+        self.streams.borrow_mut().remove(node_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_timer_armed_only_while_pending() {
+        let retries = Retries::try_new().unwrap();
+        assert_eq!(retries.timer.get().unwrap(), None);
+
+        for id in 0..2 {
+            let command = MixCommand {
+                id,
+                command: Command::NodeMute(ObjectId::from_raw_id(1), true),
+            };
+            retries.push(command, 1);
+        }
+        assert!(retries.timer.get().unwrap().is_some());
+
+        thread::sleep(COMMAND_RETRY_INTERVAL);
+        assert_eq!(retries.take().len(), 2);
+        assert_eq!(retries.timer.get().unwrap(), None);
+        assert!(retries.take().is_empty());
+    }
+}
@@ -0,0 +1,418 @@
+//! Minimal ALSA mixer monitoring for systems without PipeWire.
+//!
+//! The Master and Capture controls of the default card are presented as a
+//! single output and input device. Controls are read and set with amixer on a
+//! worker thread, and reread whenever `amixer events` reports a change.
+
+use std::io::Read;
+use std::os::fd::AsRawFd;
+use std::process;
+use std::rc::Rc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context, Result};
+use pipewire::main_loop::MainLoop;
+
+use crate::command::{Command, CommandError};
+use crate::error::Error;
+use crate::event::MonitorEvent;
+use crate::media_class::MediaClass;
+use crate::monitor::worker::Worker;
+use crate::monitor::{Backend, EventSender, Sources};
+use crate::object::ObjectId;
+
+/// How long amixer can take to read or set a control before it's killed, so
+/// that a stuck mixer doesn't stall the worker.
+const AMIXER_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How long to wait for more work after `amixer events` reports a change or a
+/// control is set, since they tend to come in bursts.
+const DEBOUNCE: Duration = Duration::from_millis(20);
+
+/// A mixer control presented as a node.
+struct Control {
+    name: &'static str,
+    media_class: &'static str,
+    description: &'static str,
+    mute: &'static str,
+    unmute: &'static str,
+}
+
+const CONTROLS: [Control; 2] = [
+    Control {
+        name: "Master",
+        media_class: "Audio/Sink",
+        description: "ALSA Master",
+        mute: "mute",
+        unmute: "unmute",
+    },
+    Control {
+        name: "Capture",
+        media_class: "Audio/Source",
+        description: "ALSA Capture",
+        mute: "nocap",
+        unmute: "cap",
+    },
+];
+
+/// Work for the worker thread.
+enum Job {
+    /// Reread every control.
+    Refresh,
+    /// Set the control with an index in [`CONTROLS`] to an amixer value.
+    /// Volume and switch values are set separately, as indicated by the flag.
+    Set(usize, bool, String),
+    /// `amixer events` stopped, with the reason.
+    Exited(String),
+}
+
+/// Results from the worker thread.
+enum Report {
+    /// The volumes and mute state of a control.
+    Control(usize, Vec<f32>, bool),
+    /// Setting a control failed.
+    Failed(String),
+    /// `amixer events` stopped, with the reason.
+    Exited(String),
+}
+
+/// The ALSA mixer backend. Only volume and mute [`Command`]s have any
+/// effect, and since amixer runs on a worker thread, their failures are
+/// reported afterwards as [`Error::Command`]s.
+pub struct Alsa {
+    /// `amixer events`, which is killed on drop.
+    events: process::Child,
+    worker: Worker<Job, Report>,
+}
+
+impl Drop for Alsa {
+    fn drop(&mut self) {
+        let _ = self.events.kill();
+        let _ = self.events.wait();
+    }
+}
+
+impl Backend for Alsa {
+    type Options = ();
+
+    fn subscribe<'l>(
+        _options: (),
+        main_loop: &'l MainLoop,
+        sender: &Rc<EventSender>,
+    ) -> Result<(Rc<Self>, Sources<'l>)> {
+        for (index, control) in CONTROLS.iter().enumerate() {
+            let object_id = ObjectId::from_raw_id(index as u32);
+            sender.send(MonitorEvent::NodeMediaClass(
+                object_id,
+                MediaClass::from(control.media_class),
+            ));
+            sender.send(MonitorEvent::NodeName(
+                object_id,
+                format!("alsa.{}", control.name),
+            ));
+            sender.send(MonitorEvent::NodeDescription(
+                object_id,
+                String::from(control.description),
+            ));
+            sender
+                .send(MonitorEvent::NodeObjectSerial(object_id, index as i32));
+        }
+        let worker = Worker::spawn(DEBOUNCE, handle)?;
+        let mut events = process::Command::new("amixer")
+            .arg("events")
+            .stdout(process::Stdio::piped())
+            .stderr(process::Stdio::null())
+            .spawn()
+            .context("Failed to run amixer")?;
+        let stdout = events.stdout.take();
+        let alsa = Rc::new(Self { events, worker });
+        let mut stdout = stdout
+            .ok_or_else(|| anyhow!("Failed to read from amixer events"))?;
+
+        // The main loop isn't running yet, so the initial state can be read
+        // here to send it before Ready.
+        for (index, control) in CONTROLS.iter().enumerate() {
+            if let Some((volumes, mute)) = read(control) {
+                send_control(sender, index, volumes, mute);
+            }
+        }
+        sender.send_ready();
+
+        thread::spawn({
+            let jobs = alsa.worker.sender();
+            move || {
+                let mut buf = [0; 4096];
+                let reason = loop {
+                    match stdout.read(&mut buf) {
+                        Ok(0) => break String::from("amixer events exited"),
+                        Ok(_) => {
+                            if jobs.send(Job::Refresh).is_err() {
+                                return;
+                            }
+                        }
+                        Err(e) => break e.to_string(),
+                    }
+                };
+                let _ = jobs.send(Job::Exited(reason));
+            }
+        });
+
+        let worker_watch = main_loop.loop_().add_io(
+            alsa.worker.fd.as_raw_fd(),
+            libspa::support::system::IoFlags::IN,
+            {
+                let alsa = Rc::downgrade(&alsa);
+                let sender = Rc::clone(sender);
+                let main_loop_weak = main_loop.downgrade();
+                move |_status| {
+                    let Some(alsa) = alsa.upgrade() else {
+                        return;
+                    };
+                    for report in alsa.worker.take() {
+                        match report {
+                            Report::Control(index, volumes, mute) => {
+                                send_control(&sender, index, volumes, mute);
+                            }
+                            Report::Failed(message) => sender.send_error(
+                                CommandError::Failed(message).into(),
+                            ),
+                            Report::Exited(reason) => {
+                                sender.send_error(Error::Protocol(reason));
+                                if let Some(main_loop) =
+                                    main_loop_weak.upgrade()
+                                {
+                                    main_loop.quit();
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+        );
+
+        let sources = Sources {
+            io: vec![worker_watch],
+            ..Default::default()
+        };
+
+        Ok((alsa, sources))
+    }
+
+    fn command(&self, command: Command) -> Result<(), CommandError> {
+        match command {
+            Command::NodeVolumes(object_id, volumes) => {
+                let index = control(object_id)?;
+                // amixer's mapped percentages use the same cubic scale as
+                // wiremix's volumes.
+                let percents = volumes
+                    .iter()
+                    .map(|volume| {
+                        format!("{}%", (volume.cbrt() * 100.0).round())
+                    })
+                    .collect::<Vec<_>>()
+                    .join(",");
+                self.worker.push(Job::Set(index, true, percents));
+            }
+            Command::NodeMute(object_id, mute) => {
+                let index = control(object_id)?;
+                let control = &CONTROLS[index];
+                let switch = if mute { control.mute } else { control.unmute };
+                self.worker
+                    .push(Job::Set(index, false, String::from(switch)));
+            }
+            _ => {}
+        }
+
+        // amixer events reports the change, which refreshes the controls
+        Ok(())
+    }
+}
+
+/// Handles a batch of jobs on the worker thread. Only the last value set for
+/// each control is applied, and the controls are reread once at the end.
+fn handle(jobs: Vec<Job>, report: &dyn Fn(Report)) {
+    let mut refresh = false;
+    let mut sets: Vec<(usize, bool, String)> = Vec::new();
+    for job in jobs {
+        match job {
+            Job::Refresh => refresh = true,
+            Job::Set(index, volume, value) => {
+                sets.retain(|&(i, v, _)| (i, v) != (index, volume));
+                sets.push((index, volume, value));
+            }
+            Job::Exited(reason) => report(Report::Exited(reason)),
+        }
+    }
+
+    for (index, _, value) in sets {
+        let name = CONTROLS[index].name;
+        if amixer(&["sset", name, &value]).is_none() {
+            report(Report::Failed(format!("Failed to set ALSA {}", name)));
+        }
+    }
+
+    if refresh {
+        for (index, control) in CONTROLS.iter().enumerate() {
+            if let Some((volumes, mute)) = read(control) {
+                report(Report::Control(index, volumes, mute));
+            }
+        }
+    }
+}
+
+/// Reads the volumes and mute state of a control.
+fn read(control: &Control) -> Option<(Vec<f32>, bool)> {
+    parse_sget(&amixer(&["sget", control.name])?)
+}
+
+fn send_control(
+    sender: &EventSender,
+    index: usize,
+    volumes: Vec<f32>,
+    mute: bool,
+) {
+    let object_id = ObjectId::from_raw_id(index as u32);
+    sender.send(MonitorEvent::NodeVolumes(object_id, volumes));
+    sender.send(MonitorEvent::NodeMute(object_id, mute));
+}
+
+fn control(object_id: ObjectId) -> Result<usize, CommandError> {
+    let index = u32::from(object_id) as usize;
+    if index < CONTROLS.len() {
+        Ok(index)
+    } else {
+        Err(CommandError::NotFound(object_id))
+    }
+}
+
+/// Runs amixer with mapped volumes on the default card, returning its output.
+fn amixer(args: &[&str]) -> Option<String> {
+    let mut command = process::Command::new("amixer");
+    command.arg("-M").args(args);
+    output(&mut command, AMIXER_TIMEOUT)
+}
+
+/// Runs a command which prints little, returning its output if it succeeds
+/// within `timeout`. It's killed if it takes longer.
+fn output(command: &mut process::Command, timeout: Duration) -> Option<String> {
+    let mut child = command
+        .stdin(process::Stdio::null())
+        .stdout(process::Stdio::piped())
+        .stderr(process::Stdio::null())
+        .spawn()
+        .ok()?;
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() < deadline => {
+                thread::sleep(Duration::from_millis(5));
+            }
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return None;
+            }
+        }
+    };
+    let mut stdout = String::new();
+    child.stdout.take()?.read_to_string(&mut stdout).ok()?;
+    status.success().then_some(stdout)
+}
+
+/// Parses the output of `amixer sget` into cubic volumes for each channel and
+/// whether every channel is switched off.
+fn parse_sget(output: &str) -> Option<(Vec<f32>, bool)> {
+    let mut volumes = Vec::new();
+    let mut mute = true;
+
+    for line in output.lines() {
+        let Some((_channel, values)) = line.trim().split_once(": ") else {
+            continue;
+        };
+        if !values.starts_with("Playback ") && !values.starts_with("Capture ") {
+            continue;
+        }
+        let fields = values
+            .split('[')
+            .skip(1)
+            .filter_map(|field| field.split(']').next())
+            .collect::<Vec<_>>();
+        let Some(percent): Option<f32> = fields.iter().find_map(|field| {
+            field
+                .strip_suffix('%')
+                .and_then(|percent| percent.parse().ok())
+        }) else {
+            continue;
+        };
+        volumes.push((percent / 100.0).powi(3));
+        if !fields.contains(&"off") {
+            mute = false;
+        }
+    }
+
+    (!volumes.is_empty()).then_some((volumes, mute))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_sget_playback() {
+        let output = "\
+Simple mixer control 'Master',0
+  Capabilities: pvolume pswitch pswitch-joined
+  Playback channels: Front Left - Front Right
+  Limits: Playback 0 - 87
+  Mono:
+  Front Left: Playback 87 [100%] [0.00dB] [on]
+  Front Right: Playback 43 [50%] [-20.00dB] [on]
+";
+        assert_eq!(parse_sget(output), Some((vec![1.0, 0.125], false)));
+    }
+
+    #[test]
+    fn parse_sget_capture_off() {
+        let output = "\
+Simple mixer control 'Capture',0
+  Capabilities: cvolume cswitch
+  Capture channels: Mono
+  Limits: Capture 0 - 63
+  Mono: Capture 0 [0%] [-17.25dB] [off]
+";
+        assert_eq!(parse_sget(output), Some((vec![0.0], true)));
+    }
+
+    #[test]
+    fn output_times_out() {
+        let mut echo = process::Command::new("echo");
+        echo.arg("Mono: Capture [on]");
+        assert_eq!(
+            output(&mut echo, AMIXER_TIMEOUT).as_deref(),
+            Some("Mono: Capture [on]\n")
+        );
+
+        let mut sleep = process::Command::new("sleep");
+        sleep.arg("10");
+        let start = Instant::now();
+        assert_eq!(output(&mut sleep, Duration::from_millis(50)), None);
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        assert_eq!(
+            output(&mut process::Command::new("false"), AMIXER_TIMEOUT),
+            None
+        );
+    }
+
+    #[test]
+    fn parse_sget_no_volume() {
+        let output = "\
+Simple mixer control 'Capture',0
+  Capabilities: cswitch
+  Mono: Capture [on]
+";
+        assert_eq!(parse_sget(output), None);
+    }
+}
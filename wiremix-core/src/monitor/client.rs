@@ -0,0 +1,136 @@
+use std::rc::Rc;
+
+use pipewire::{
+    client::{Client, ClientChangeMask, ClientInfoRef},
+    proxy::Listener,
+    registry::{GlobalObject, Registry},
+};
+
+use libspa::utils::dict::DictRef;
+
+use crate::event::MonitorEvent;
+use crate::monitor::{process, EventSender};
+use crate::object::ObjectId;
+
+pub fn monitor_client(
+    registry: &Registry,
+    obj: &GlobalObject<&DictRef>,
+    sender: &Rc<EventSender>,
+) -> Option<(Rc<Client>, Box<dyn Listener>)> {
+    let obj_id = ObjectId::from(obj);
+
+    let client: Client = registry.bind(obj).ok()?;
+    let client = Rc::new(client);
+
+    let listener = client
+        .add_listener_local()
+        .info({
+            let sender_weak = Rc::downgrade(sender);
+            move |info| {
+                let Some(sender) = sender_weak.upgrade() else {
+                    return;
+                };
+                for change in info.change_mask().iter() {
+                    if change == ClientChangeMask::PROPS {
+                        client_info_props(&sender, obj_id, info);
+                    }
+                }
+            }
+        })
+        .register();
+
+    Some((client, Box::new(listener)))
+}
+
+fn client_info_props(
+    sender: &EventSender,
+    id: ObjectId,
+    client_info: &ClientInfoRef,
+) {
+    let Some(props) = client_info.props() else {
+        return;
+    };
+
+    if let Some(application_name) = props.get("application.name") {
+        sender.send(MonitorEvent::ClientApplicationName(
+            id,
+            String::from(application_name),
+        ));
+    }
+
+    if let Some(application_process_binary) =
+        props.get("application.process.binary")
+    {
+        sender.send(MonitorEvent::ClientApplicationProcessBinary(
+            id,
+            String::from(application_process_binary),
+        ));
+    }
+
+    if let Some(access) = props.get("pipewire.access") {
+        sender
+            .send(MonitorEvent::ClientPipewireAccess(id, String::from(access)));
+    }
+
+    // Sandboxed clients connecting through the portal identify the real
+    // application here rather than in application.name.
+    if let Some(app_id) = props.get("pipewire.access.portal.app_id") {
+        sender.send(MonitorEvent::ClientPortalAppId(id, String::from(app_id)));
+    }
+
+    if let Some(app_id) = props.get("pipewire.snap.app.id") {
+        sender.send(MonitorEvent::ClientSnapAppId(id, String::from(app_id)));
+    }
+
+    if let Some(pid) = props.get("application.process.id") {
+        sender.send(MonitorEvent::ClientApplicationProcessId(
+            id,
+            String::from(pid),
+        ));
+
+        for event in process_events(id, pid).into_iter().flatten() {
+            sender.send(event);
+        }
+    }
+}
+
+/// Events for the information in /proc about a client's process. The ID is
+/// set by the client, so anything but a number is ignored rather than being
+/// used in a path.
+fn process_events(id: ObjectId, pid: &str) -> Option<Vec<MonitorEvent>> {
+    let pid = pid.parse::<u32>().ok()?;
+    let mut events = Vec::new();
+
+    if let Some(cmdline) = process::cmdline(pid) {
+        events.push(MonitorEvent::ClientProcessCmdline(id, cmdline));
+    }
+
+    if let Some(cgroup) = process::cgroup(pid) {
+        if let Some(app_id) = process::flatpak_app_id(&cgroup) {
+            events.push(MonitorEvent::ClientFlatpakAppId(id, app_id));
+        }
+        events.push(MonitorEvent::ClientProcessCgroup(id, cgroup));
+    }
+
+    Some(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn process_events_need_numeric_pid() {
+        let id = ObjectId::from_raw_id(1);
+        assert!(process_events(id, "self").is_none());
+        assert!(process_events(id, "self/../../etc").is_none());
+        assert!(process_events(id, "").is_none());
+
+        let pid = std::process::id().to_string();
+        let events = process_events(id, &pid).unwrap();
+        assert!(events.iter().any(|event| matches!(
+            event,
+            MonitorEvent::ClientProcessCmdline(..)
+        )));
+    }
+}
@@ -249,6 +249,7 @@ fn parse_class(value: &Value) -> Option<(MediaClass, Vec<i32>)> {
 
 fn device_enum_profile(id: ObjectId, param: Object) -> Option<MonitorEvent> {
     let mut index = None;
+    let mut name = None;
     let mut description = None;
     let mut available = None;
     let mut classes = None;
@@ -260,6 +261,11 @@ fn device_enum_profile(id: ObjectId, param: Object) -> Option<MonitorEvent> {
                     index = Some(value);
                 }
             }
+            libspa_sys::SPA_PARAM_PROFILE_name => {
+                if let Value::String(value) = prop.value {
+                    name = Some(value);
+                }
+            }
             libspa_sys::SPA_PARAM_PROFILE_description => {
                 if let Value::String(value) = prop.value {
                     description = Some(value);
@@ -293,6 +299,7 @@ fn device_enum_profile(id: ObjectId, param: Object) -> Option<MonitorEvent> {
     Some(MonitorEvent::DeviceEnumProfile(
         id,
         index?,
+        name?,
         description?,
         available?,
         classes?,
@@ -328,4 +335,11 @@ fn device_info_props(
             sender.send(MonitorEvent::DeviceObjectSerial(id, object_serial));
         }
     }
+
+    // Bluetooth devices which report their battery level
+    if let Some(battery) = props.get("api.bluez5.battery") {
+        if let Ok(battery) = battery.trim_end_matches('%').parse() {
+            sender.send(MonitorEvent::DeviceBattery(id, battery));
+        }
+    }
 }
@@ -2,6 +2,8 @@ use std::sync::{mpsc, Arc};
 
 use pipewire::main_loop::WeakMainLoop;
 
+use crate::command::CommandError;
+use crate::error::Error;
 use crate::monitor::{Event, MonitorEvent};
 
 pub struct EventSender {
@@ -33,11 +35,23 @@ impl EventSender {
         }
     }
 
-    pub fn send_error(&self, error: String) {
+    pub fn send_error(&self, error: Error) {
         if self.tx.send(Event::Error(error)).is_err() {
             if let Some(main_loop) = self.main_loop_weak.upgrade() {
                 main_loop.quit();
             }
         }
     }
+
+    pub fn send_command_result(
+        &self,
+        id: u64,
+        result: Result<(), CommandError>,
+    ) {
+        if self.tx.send(Event::CommandResult(id, result)).is_err() {
+            if let Some(main_loop) = self.main_loop_weak.upgrade() {
+                main_loop.quit();
+            }
+        }
+    }
 }
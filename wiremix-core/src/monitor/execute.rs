@@ -1,80 +1,80 @@
-use std::rc::Rc;
+use crate::command::{Command, CommandError};
+use crate::monitor::ProxyRegistry;
 
-use crate::command::Command;
-use crate::monitor::{stream, EventSender, ProxyRegistry, StreamRegistry};
-
-use pipewire::{core::Core, device::Device, node::Node};
+use pipewire::{device::Device, node::Node};
 
 use libspa::param::ParamType;
 use libspa::pod::{
     serialize::PodSerializer, Object, Pod, Property, PropertyFlags, Value,
     ValueArray,
 };
+use libspa::utils::Id;
 
 pub fn execute_command(
-    core: &Core,
-    sender: Rc<EventSender>,
-    streams: &mut StreamRegistry<stream::StreamData>,
     proxies: &ProxyRegistry,
     command: Command,
-) {
+) -> Result<(), CommandError> {
+    let node = |obj_id| {
+        proxies
+            .nodes
+            .get(&obj_id)
+            .ok_or(CommandError::NotFound(obj_id))
+    };
+    let device = |obj_id| {
+        proxies
+            .devices
+            .get(&obj_id)
+            .ok_or(CommandError::NotFound(obj_id))
+    };
+
     match command {
         Command::NodeMute(obj_id, mute) => {
-            if let Some(node) = proxies.nodes.get(&obj_id) {
-                node_set_mute(node, mute);
-            }
+            node_set_mute(node(obj_id)?, mute);
         }
         Command::DeviceMute(obj_id, route_index, route_device, mute) => {
-            if let Some(device) = proxies.devices.get(&obj_id) {
-                device_set_mute(device, route_index, route_device, mute);
-            }
+            device_set_mute(device(obj_id)?, route_index, route_device, mute);
         }
         Command::NodeVolumes(obj_id, volumes) => {
-            if let Some(node) = proxies.nodes.get(&obj_id) {
-                node_set_volumes(node, volumes);
-            }
+            node_set_volumes(node(obj_id)?, volumes);
+        }
+        Command::NodeChannelMap(obj_id, input, positions) => {
+            node_set_channel_map(node(obj_id)?, input, positions);
+        }
+        Command::NodeUpmix(obj_id, upmix) => {
+            node_set_upmix(node(obj_id)?, upmix);
         }
         Command::DeviceVolumes(obj_id, route_index, route_device, volumes) => {
-            if let Some(device) = proxies.devices.get(&obj_id) {
-                device_set_volumes(device, route_index, route_device, volumes);
-            }
+            device_set_volumes(
+                device(obj_id)?,
+                route_index,
+                route_device,
+                volumes,
+            );
         }
         Command::DeviceSetRoute(obj_id, route_index, route_device) => {
-            if let Some(device) = proxies.devices.get(&obj_id) {
-                device_set_route(device, route_index, route_device);
-            }
+            device_set_route(device(obj_id)?, route_index, route_device);
         }
         Command::DeviceSetProfile(obj_id, profile_index) => {
-            if let Some(device) = proxies.devices.get(&obj_id) {
-                device_set_profile(device, profile_index);
-            }
-        }
-        Command::NodeCaptureStart(obj_id, object_serial, capture_sink) => {
-            let result = stream::capture_node(
-                core,
-                &sender,
-                obj_id,
-                &object_serial.to_string(),
-                capture_sink,
-            );
-            if let Some((stream, listener)) = result {
-                streams.add_stream(obj_id, stream, listener);
-            }
-        }
-        Command::NodeCaptureStop(obj_id) => {
-            streams.remove(obj_id);
+            device_set_profile(device(obj_id)?, profile_index);
         }
+        // Capture is handled by the backend's capture_start() and
+        // capture_stop().
+        Command::NodeCaptureStart(..) | Command::NodeCaptureStop(..) => {}
         Command::MetadataSetProperty(obj_id, subject, key, type_, value) => {
-            if let Some(metadata) = proxies.metadatas.get(&obj_id) {
-                metadata.set_property(
-                    subject,
-                    &key,
-                    type_.as_deref(),
-                    value.as_deref(),
-                );
-            }
+            let metadata = proxies
+                .metadatas
+                .get(&obj_id)
+                .ok_or(CommandError::NotFound(obj_id))?;
+            metadata.set_property(
+                subject,
+                &key,
+                type_.as_deref(),
+                value.as_deref(),
+            );
         }
     }
+
+    Ok(())
 }
 
 fn node_set_mute(node: &Node, mute: bool) {
@@ -106,6 +106,91 @@ fn node_set_volumes(node: &Node, volumes: Vec<f32>) {
     );
 }
 
+fn node_set_upmix(node: &Node, upmix: bool) {
+    node_set_properties(
+        node,
+        vec![Property {
+            key: libspa_sys::SPA_PROP_params,
+            flags: PropertyFlags::empty(),
+            value: Value::Struct(vec![
+                Value::String(String::from("channelmix.upmix")),
+                Value::Bool(upmix),
+            ]),
+        }],
+    );
+}
+
+/// Reconfigure the node's ports with the positions in a new order. The ports
+/// stay in DSP mode, so links follow the new positions.
+fn node_set_channel_map(node: &Node, input: bool, positions: Vec<u32>) {
+    let direction = if input {
+        libspa_sys::SPA_DIRECTION_INPUT
+    } else {
+        libspa_sys::SPA_DIRECTION_OUTPUT
+    };
+    let channels = positions.len() as i32;
+    let format = Object {
+        type_: libspa_sys::SPA_TYPE_OBJECT_Format,
+        id: libspa_sys::SPA_PARAM_Format,
+        properties: vec![
+            Property {
+                key: libspa_sys::SPA_FORMAT_mediaType,
+                flags: PropertyFlags::empty(),
+                value: Value::Id(Id(libspa_sys::SPA_MEDIA_TYPE_audio)),
+            },
+            Property {
+                key: libspa_sys::SPA_FORMAT_mediaSubtype,
+                flags: PropertyFlags::empty(),
+                value: Value::Id(Id(libspa_sys::SPA_MEDIA_SUBTYPE_raw)),
+            },
+            Property {
+                key: libspa_sys::SPA_FORMAT_AUDIO_channels,
+                flags: PropertyFlags::empty(),
+                value: Value::Int(channels),
+            },
+            Property {
+                key: libspa_sys::SPA_FORMAT_AUDIO_position,
+                flags: PropertyFlags::empty(),
+                value: Value::ValueArray(ValueArray::Id(
+                    positions.into_iter().map(Id).collect(),
+                )),
+            },
+        ],
+    };
+    let properties = vec![
+        Property {
+            key: libspa_sys::SPA_PARAM_PORT_CONFIG_direction,
+            flags: PropertyFlags::empty(),
+            value: Value::Id(Id(direction)),
+        },
+        Property {
+            key: libspa_sys::SPA_PARAM_PORT_CONFIG_mode,
+            flags: PropertyFlags::empty(),
+            value: Value::Id(Id(libspa_sys::SPA_PARAM_PORT_CONFIG_MODE_dsp)),
+        },
+        Property {
+            key: libspa_sys::SPA_PARAM_PORT_CONFIG_format,
+            flags: PropertyFlags::empty(),
+            value: Value::Object(format),
+        },
+    ];
+
+    let values = PodSerializer::serialize(
+        std::io::Cursor::new(Vec::new()),
+        &Value::Object(Object {
+            type_: libspa_sys::SPA_TYPE_OBJECT_ParamPortConfig,
+            id: libspa_sys::SPA_PARAM_PortConfig,
+            properties,
+        }),
+    );
+
+    if let Ok((values, _)) = values {
+        if let Some(pod) = Pod::from_bytes(&values.into_inner()) {
+            node.set_param(ParamType::PortConfig, 0, pod);
+        }
+    }
+}
+
 fn node_set_properties(node: &Node, properties: Vec<Property>) {
     let values = PodSerializer::serialize(
         std::io::Cursor::new(Vec::new()),
@@ -20,7 +20,8 @@ pub fn monitor_metadata(
 
     let props = obj.props?;
     let metadata_name = props.get("metadata.name")?;
-    if metadata_name != "default" {
+    // "settings" has the graph's clock rate
+    if !matches!(metadata_name, "default" | "settings") {
         return None;
     }
 
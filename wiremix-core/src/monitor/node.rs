@@ -0,0 +1,364 @@
+use std::rc::Rc;
+
+use pipewire::{
+    node::{Node, NodeChangeMask, NodeInfoRef, NodeState},
+    proxy::Listener,
+    registry::{GlobalObject, Registry},
+};
+
+use libspa::{
+    param::ParamType,
+    pod::{Object, Value, ValueArray},
+    utils::dict::DictRef,
+};
+
+use crate::channel_map;
+use crate::event::MonitorEvent;
+use crate::media_class::MediaClass;
+use crate::monitor::{deserialize::deserialize, EventSender};
+use crate::object::ObjectId;
+
+pub fn monitor_node(
+    registry: &Registry,
+    obj: &GlobalObject<&DictRef>,
+    sender: &Rc<EventSender>,
+) -> Option<(Rc<Node>, Box<dyn Listener>)> {
+    let obj_id = ObjectId::from(obj);
+
+    let props = obj.props?;
+    let is_jack = props.get("client.api") == Some("jack");
+    let media_class = match props.get("media.class") {
+        Some(media_class) => media_class,
+        // JACK clients don't always have a media class, and they can both
+        // produce and consume audio.
+        None if is_jack => "Stream/Duplex/Audio",
+        None => return None,
+    };
+    match media_class {
+        "Audio/Sink" => (),
+        "Audio/Source" => (),
+        "Stream/Output/Audio" => (),
+        "Stream/Input/Audio" => (),
+        // Video nodes are only tracked for showing which applications are
+        // using a camera.
+        "Video/Source" => (),
+        "Stream/Input/Video" => (),
+        _ if is_jack => (),
+        _ => return None,
+    }
+
+    // Don't monitor capture streams to avoid clutter.
+    match props.get("node.name") {
+        // We especially don't want to capture our own capture streams.
+        Some("wiremix-capture") => return None,
+        Some("PulseAudio Volume Control") => return None,
+        Some("ncpamixer") => return None,
+        _ => (),
+    }
+
+    sender.send(MonitorEvent::NodeMediaClass(
+        obj_id,
+        MediaClass::from(media_class),
+    ));
+
+    let node: Node = registry.bind(obj).ok()?;
+    let node = Rc::new(node);
+
+    let listener = node
+        .add_listener_local()
+        .info({
+            let sender_weak = Rc::downgrade(sender);
+            move |info| {
+                let Some(sender) = sender_weak.upgrade() else {
+                    return;
+                };
+                for change in info.change_mask().iter() {
+                    if change == NodeChangeMask::PROPS {
+                        node_info_props(&sender, obj_id, info);
+                    } else if change == NodeChangeMask::STATE {
+                        node_info_state(&sender, obj_id, info);
+                    }
+                }
+            }
+        })
+        .param({
+            let sender_weak = Rc::downgrade(sender);
+            move |_seq, id, _index, _next, param| {
+                let Some(sender) = sender_weak.upgrade() else {
+                    return;
+                };
+                if let Some(param) = deserialize(param) {
+                    match id {
+                        ParamType::Props => {
+                            node_param_props(&sender, obj_id, param);
+                        }
+                        ParamType::PortConfig => {
+                            node_param_port_config(&sender, obj_id, param);
+                        }
+                        ParamType::Format => {
+                            node_param_format(&sender, obj_id, param);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        })
+        .register();
+    // Video nodes have no volumes, and their formats aren't audio formats.
+    if !matches!(media_class, "Video/Source" | "Stream/Input/Video") {
+        node.subscribe_params(&[
+            ParamType::Props,
+            ParamType::PortConfig,
+            ParamType::Format,
+        ]);
+    }
+
+    Some((node, Box::new(listener)))
+}
+
+fn node_info_props(
+    sender: &EventSender,
+    id: ObjectId,
+    node_info: &NodeInfoRef,
+) {
+    let Some(props) = node_info.props() else {
+        return;
+    };
+
+    if let Some(node_name) = props.get("node.name") {
+        sender.send(MonitorEvent::NodeName(id, String::from(node_name)));
+    }
+
+    if let Some(node_nick) = props.get("node.nick") {
+        sender.send(MonitorEvent::NodeNick(id, String::from(node_nick)));
+    }
+
+    if let Some(node_description) = props.get("node.description") {
+        sender.send(MonitorEvent::NodeDescription(
+            id,
+            String::from(node_description),
+        ));
+    }
+
+    if let Some(media_name) = props.get("media.name") {
+        sender.send(MonitorEvent::NodeMediaName(id, String::from(media_name)));
+    }
+
+    if let Some(media_role) = props.get("media.role") {
+        sender.send(MonitorEvent::NodeMediaRole(id, String::from(media_role)));
+    }
+
+    if let Some(latency) = props.get("node.latency") {
+        sender.send(MonitorEvent::NodeLatency(id, String::from(latency)));
+    }
+
+    if let Some(client_api) = props.get("client.api") {
+        sender.send(MonitorEvent::NodeClientApi(id, String::from(client_api)));
+    }
+
+    if let Some(network) = props.get("node.network") {
+        sender.send(MonitorEvent::NodeNetwork(id, network == "true"));
+    }
+
+    if let Some(resample_quality) = props.get("resample.quality") {
+        sender.send(MonitorEvent::NodeResampleQuality(
+            id,
+            String::from(resample_quality),
+        ));
+    }
+
+    if let Some(device_id) = props.get("device.id") {
+        if let Ok(device_id) = device_id.parse() {
+            sender.send(MonitorEvent::NodeDeviceId(
+                id,
+                ObjectId::from_raw_id(device_id),
+            ));
+        }
+    }
+
+    if let Some(client_id) = props.get("client.id") {
+        if let Ok(client_id) = client_id.parse() {
+            sender.send(MonitorEvent::NodeClientId(
+                id,
+                ObjectId::from_raw_id(client_id),
+            ));
+        }
+    }
+
+    if let Some(object_serial) = props.get("object.serial") {
+        if let Ok(object_serial) = object_serial.parse() {
+            sender.send(MonitorEvent::NodeObjectSerial(id, object_serial));
+        }
+    }
+
+    if let Some(card_profile_device) = props.get("card.profile.device") {
+        if let Ok(card_profile_device) = card_profile_device.parse() {
+            sender.send(MonitorEvent::NodeCardProfileDevice(
+                id,
+                card_profile_device,
+            ));
+        }
+    }
+}
+
+fn node_info_state(
+    sender: &EventSender,
+    id: ObjectId,
+    node_info: &NodeInfoRef,
+) {
+    let (state, error) = match node_info.state() {
+        NodeState::Error(error) => ("error", Some(String::from(error))),
+        NodeState::Creating => ("creating", None),
+        NodeState::Suspended => ("suspended", None),
+        NodeState::Idle => ("idle", None),
+        NodeState::Running => ("running", None),
+    };
+    sender.send(MonitorEvent::NodeState(id, String::from(state), error));
+}
+
+fn node_param_props(sender: &EventSender, id: ObjectId, param: Object) {
+    for prop in param.properties {
+        match prop.key {
+            libspa_sys::SPA_PROP_channelVolumes => {
+                if let Value::ValueArray(ValueArray::Float(value)) = prop.value
+                {
+                    sender.send(MonitorEvent::NodeVolumes(id, value));
+                }
+            }
+            libspa_sys::SPA_PROP_mute => {
+                if let Value::Bool(value) = prop.value {
+                    sender.send(MonitorEvent::NodeMute(id, value));
+                }
+            }
+            libspa_sys::SPA_PROP_params => {
+                if let Value::Struct(params) = prop.value {
+                    node_props_params(sender, id, params);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Handle the key and value pairs of a node's params, such as the
+/// audioconvert settings.
+fn node_props_params(sender: &EventSender, id: ObjectId, params: Vec<Value>) {
+    for pair in params.chunks_exact(2) {
+        let [Value::String(key), value] = pair else {
+            continue;
+        };
+        if let ("channelmix.upmix", Value::Bool(upmix)) = (key.as_str(), value)
+        {
+            sender.send(MonitorEvent::NodeUpmix(id, *upmix));
+        }
+    }
+}
+
+fn node_param_port_config(sender: &EventSender, id: ObjectId, param: Object) {
+    let Some(format_prop) = param
+        .properties
+        .into_iter()
+        .find(|prop| prop.key == libspa_sys::SPA_PARAM_PORT_CONFIG_format)
+    else {
+        return;
+    };
+
+    let Value::Object(Object { properties, .. }) = format_prop.value else {
+        return;
+    };
+
+    let Some(position_prop) = properties
+        .into_iter()
+        .find(|prop| prop.key == libspa_sys::SPA_FORMAT_AUDIO_position)
+    else {
+        return;
+    };
+
+    let Value::ValueArray(ValueArray::Id(value)) = position_prop.value else {
+        return;
+    };
+
+    let positions = value.into_iter().map(|x| x.0).collect();
+    sender.send(MonitorEvent::NodePositions(id, positions));
+}
+
+fn node_param_format(sender: &EventSender, id: ObjectId, param: Object) {
+    let mut sample_format = None;
+    let mut rate = None;
+    let mut channels = None;
+    let mut channel_map = Vec::new();
+
+    for prop in param.properties {
+        match prop.key {
+            libspa_sys::SPA_FORMAT_AUDIO_format => {
+                if let Value::Id(libspa::utils::Id(value)) = prop.value {
+                    sample_format = Some(sample_format_name(value));
+                }
+            }
+            libspa_sys::SPA_FORMAT_AUDIO_rate => {
+                if let Value::Int(value) = prop.value {
+                    rate = u32::try_from(value).ok();
+                }
+            }
+            libspa_sys::SPA_FORMAT_AUDIO_channels => {
+                if let Value::Int(value) = prop.value {
+                    channels = u32::try_from(value).ok();
+                }
+            }
+            libspa_sys::SPA_FORMAT_AUDIO_position => {
+                if let Value::ValueArray(ValueArray::Id(value)) = prop.value {
+                    channel_map = value
+                        .into_iter()
+                        .map(|x| channel_map::name(x.0))
+                        .collect();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Only raw audio formats have all of these
+    let (Some(sample_format), Some(rate), Some(channels)) =
+        (sample_format, rate, channels)
+    else {
+        return;
+    };
+
+    sender.send(MonitorEvent::NodeFormat(
+        id,
+        sample_format,
+        rate,
+        channels,
+        channel_map,
+    ));
+}
+
+/// Short name of a raw audio sample format, as used by pw-dump.
+fn sample_format_name(format: u32) -> String {
+    let name = match format {
+        libspa_sys::SPA_AUDIO_FORMAT_U8 => "U8",
+        libspa_sys::SPA_AUDIO_FORMAT_S8 => "S8",
+        libspa_sys::SPA_AUDIO_FORMAT_S16_LE => "S16LE",
+        libspa_sys::SPA_AUDIO_FORMAT_S16_BE => "S16BE",
+        libspa_sys::SPA_AUDIO_FORMAT_S24_LE => "S24LE",
+        libspa_sys::SPA_AUDIO_FORMAT_S24_BE => "S24BE",
+        libspa_sys::SPA_AUDIO_FORMAT_S24_32_LE => "S24_32LE",
+        libspa_sys::SPA_AUDIO_FORMAT_S24_32_BE => "S24_32BE",
+        libspa_sys::SPA_AUDIO_FORMAT_S32_LE => "S32LE",
+        libspa_sys::SPA_AUDIO_FORMAT_S32_BE => "S32BE",
+        libspa_sys::SPA_AUDIO_FORMAT_F32_LE => "F32LE",
+        libspa_sys::SPA_AUDIO_FORMAT_F32_BE => "F32BE",
+        libspa_sys::SPA_AUDIO_FORMAT_F64_LE => "F64LE",
+        libspa_sys::SPA_AUDIO_FORMAT_F64_BE => "F64BE",
+        libspa_sys::SPA_AUDIO_FORMAT_U8P => "U8P",
+        libspa_sys::SPA_AUDIO_FORMAT_S8P => "S8P",
+        libspa_sys::SPA_AUDIO_FORMAT_S16P => "S16P",
+        libspa_sys::SPA_AUDIO_FORMAT_S24P => "S24P",
+        libspa_sys::SPA_AUDIO_FORMAT_S24_32P => "S24_32P",
+        libspa_sys::SPA_AUDIO_FORMAT_S32P => "S32P",
+        libspa_sys::SPA_AUDIO_FORMAT_F32P => "F32P",
+        libspa_sys::SPA_AUDIO_FORMAT_F64P => "F64P",
+        _ => return format!("0x{format:x}"),
+    };
+    String::from(name)
+}
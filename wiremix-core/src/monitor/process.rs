@@ -0,0 +1,83 @@
+//! Information about client processes read from /proc.
+
+use std::fs;
+
+/// Command line of a process with its arguments separated by spaces.
+pub fn cmdline(pid: u32) -> Option<String> {
+    parse_cmdline(&fs::read(format!("/proc/{}/cmdline", pid)).ok()?)
+}
+
+/// Control group of a process in the unified hierarchy.
+pub fn cgroup(pid: u32) -> Option<String> {
+    parse_cgroup(&fs::read_to_string(format!("/proc/{}/cgroup", pid)).ok()?)
+}
+
+/// Flatpak application ID, derived from the scope Flatpak runs apps in.
+pub fn flatpak_app_id(cgroup: &str) -> Option<String> {
+    let scope = cgroup.rsplit('/').next()?;
+    let app_and_instance =
+        scope.strip_prefix("app-flatpak-")?.strip_suffix(".scope")?;
+    let (app_id, _instance) = app_and_instance.rsplit_once('-')?;
+    (!app_id.is_empty()).then(|| String::from(app_id))
+}
+
+fn parse_cmdline(bytes: &[u8]) -> Option<String> {
+    let args: Vec<_> = bytes
+        .split(|&byte| byte == 0)
+        .filter(|arg| !arg.is_empty())
+        .map(String::from_utf8_lossy)
+        .collect();
+    (!args.is_empty()).then(|| args.join(" "))
+}
+
+fn parse_cgroup(contents: &str) -> Option<String> {
+    contents
+        .lines()
+        .find_map(|line| line.strip_prefix("0::"))
+        .filter(|path| !path.is_empty())
+        .map(String::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cmdline_args() {
+        assert_eq!(
+            parse_cmdline(b"/usr/bin/mpv\0--no-video\0song.flac\0"),
+            Some(String::from("/usr/bin/mpv --no-video song.flac"))
+        );
+    }
+
+    #[test]
+    fn cmdline_empty() {
+        assert_eq!(parse_cmdline(b""), None);
+    }
+
+    #[test]
+    fn cgroup_unified() {
+        let contents =
+            "1:name=systemd:/legacy\n0::/user.slice/app.slice/a.scope\n";
+        assert_eq!(
+            parse_cgroup(contents),
+            Some(String::from("/user.slice/app.slice/a.scope"))
+        );
+    }
+
+    #[test]
+    fn flatpak() {
+        let cgroup = "/user.slice/user-1000.slice/user@1000.service/app.slice/\
+                      app-flatpak-org.mozilla.firefox-12345.scope";
+        assert_eq!(
+            flatpak_app_id(cgroup),
+            Some(String::from("org.mozilla.firefox"))
+        );
+    }
+
+    #[test]
+    fn not_flatpak() {
+        let cgroup = "/user.slice/user-1000.slice/session-2.scope";
+        assert_eq!(flatpak_app_id(cgroup), None);
+    }
+}
@@ -0,0 +1,1086 @@
+//! Monitoring of PulseAudio servers.
+//!
+//! Sinks, sources, sink inputs and source outputs are presented as nodes,
+//! with links between streams and their devices and a "default" metadata
+//! object holding the default sink and source. Cards are presented as devices
+//! whose ports are the routes of their sinks and sources.
+//!
+//! Objects are listed and controlled with pactl on a worker thread. When
+//! `pactl subscribe` reports a change to an object, its kind is listed again
+//! and only the events for that object are sent. Listing uses pactl's JSON
+//! output, which needs pactl 16 or newer.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
+use std::os::fd::AsRawFd;
+use std::process;
+use std::rc::Rc;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use pipewire::main_loop::MainLoop;
+use scopeguard::ScopeGuard;
+use serde_json::{json, Value};
+
+use crate::command::{Command, CommandError};
+use crate::error::Error;
+use crate::event::MonitorEvent;
+use crate::media_class::MediaClass;
+use crate::monitor::worker::Worker;
+use crate::monitor::{Backend, EventSender, Sources};
+use crate::object::ObjectId;
+
+/// PulseAudio's volume for 100%, on a cubic scale.
+const VOLUME_NORM: f32 = 65536.0;
+
+/// How long to wait for more work after a change is reported or a command is
+/// sent, since they tend to come in bursts.
+const DEBOUNCE: Duration = Duration::from_millis(20);
+
+/// The kinds of PulseAudio objects. Each has its own index space, so the
+/// kind is stored in the high bits of the [`ObjectId`]s presented to wiremix.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Kind {
+    Sink,
+    Source,
+    SinkInput,
+    SourceOutput,
+    /// The link from a sink input to its sink, with the same index.
+    SinkInputLink,
+    /// The link from a source to a source output, with the same index.
+    SourceOutputLink,
+    /// The server, presented as metadata.
+    Metadata,
+    Card,
+}
+
+impl Kind {
+    const SHIFT: u32 = 24;
+    const ALL: [Kind; 8] = [
+        Kind::Sink,
+        Kind::Source,
+        Kind::SinkInput,
+        Kind::SourceOutput,
+        Kind::SinkInputLink,
+        Kind::SourceOutputLink,
+        Kind::Metadata,
+        Kind::Card,
+    ];
+
+    fn object_id(self, index: u32) -> ObjectId {
+        ObjectId::from_raw_id(((self as u32) << Self::SHIFT) | index)
+    }
+
+    /// The kind and PulseAudio index of an [`ObjectId`].
+    fn from_object_id(object_id: ObjectId) -> Option<(Kind, u32)> {
+        let raw = u32::from(object_id);
+        let kind = *Self::ALL.get((raw >> Self::SHIFT) as usize)?;
+        Some((kind, raw & ((1 << Self::SHIFT) - 1)))
+    }
+
+    /// The kind of object a `pactl subscribe` event is about.
+    fn from_facility(facility: &str) -> Option<Kind> {
+        match facility {
+            "sink" => Some(Kind::Sink),
+            "source" => Some(Kind::Source),
+            "sink-input" => Some(Kind::SinkInput),
+            "source-output" => Some(Kind::SourceOutput),
+            "server" => Some(Kind::Metadata),
+            "card" => Some(Kind::Card),
+            _ => None,
+        }
+    }
+
+    /// The pactl list type for the kind.
+    fn list_type(self) -> Option<&'static str> {
+        match self {
+            Kind::Sink => Some("sinks"),
+            Kind::Source => Some("sources"),
+            Kind::SinkInput => Some("sink-inputs"),
+            Kind::SourceOutput => Some("source-outputs"),
+            Kind::Card => Some("cards"),
+            _ => None,
+        }
+    }
+
+    /// The name pactl uses for the kind in its set-* and move-* commands.
+    fn command_name(self) -> Option<&'static str> {
+        match self {
+            Kind::Sink => Some("sink"),
+            Kind::Source => Some("source"),
+            Kind::SinkInput => Some("sink-input"),
+            Kind::SourceOutput => Some("source-output"),
+            _ => None,
+        }
+    }
+
+    fn media_class(self) -> &'static str {
+        match self {
+            Kind::Sink => "Audio/Sink",
+            Kind::Source => "Audio/Source",
+            Kind::SinkInput => "Stream/Output/Audio",
+            Kind::SourceOutput => "Stream/Input/Audio",
+            _ => "",
+        }
+    }
+
+    /// The card device of a sink or source, which is what routes and profiles
+    /// refer to it by.
+    fn card_device(self, index: u32) -> Option<i32> {
+        match self {
+            Kind::Sink => Some((index * 2) as i32),
+            Kind::Source => Some((index * 2 + 1) as i32),
+            _ => None,
+        }
+    }
+
+    /// The sink or source with a card device.
+    fn from_card_device(card_device: i32) -> Option<(Kind, u32)> {
+        let card_device = u32::try_from(card_device).ok()?;
+        let kind = if card_device % 2 == 0 {
+            Kind::Sink
+        } else {
+            Kind::Source
+        };
+        Some((kind, card_device / 2))
+    }
+}
+
+/// Whether there is a PulseAudio server to connect to, or if `server` is
+/// provided, whether it is an existing socket or a network address.
+pub fn server_exists(server: Option<&str>) -> bool {
+    use std::env;
+    use std::path::{Path, PathBuf};
+
+    if let Some(server) = server
+        .map(String::from)
+        .or_else(|| env::var("PULSE_SERVER").ok())
+    {
+        let path = server.strip_prefix("unix:").unwrap_or(&server);
+        return !path.starts_with('/') || Path::new(path).exists();
+    }
+
+    let runtime_path = env::var_os("PULSE_RUNTIME_PATH")
+        .map(PathBuf::from)
+        .or_else(|| {
+            env::var_os("XDG_RUNTIME_DIR")
+                .map(|dir| PathBuf::from(dir).join("pulse"))
+        });
+    runtime_path.is_some_and(|path| path.join("native").exists())
+}
+
+/// Work for the worker thread.
+enum Job {
+    /// An object was added or changed, or removed if the flag is set.
+    Changed(Kind, u32, bool),
+    Command(Command),
+    /// `pactl subscribe` stopped, with the reason.
+    Exited(String),
+}
+
+/// Results from the worker thread.
+enum Report {
+    Events(Vec<MonitorEvent>),
+    /// A command failed.
+    Failed(String),
+    /// The server couldn't be listed or `pactl subscribe` stopped.
+    Lost(String),
+}
+
+/// The PulseAudio backend. Capture [`Command`]s have no effect, and since
+/// pactl runs on a worker thread, failures of the others are reported
+/// afterwards as [`Error::Command`]s.
+pub struct Pulse {
+    /// `pactl subscribe`, which is killed on drop.
+    subscribe: process::Child,
+    worker: Worker<Job, Report>,
+}
+
+impl Drop for Pulse {
+    fn drop(&mut self) {
+        let _ = self.subscribe.kill();
+        let _ = self.subscribe.wait();
+    }
+}
+
+impl Backend for Pulse {
+    /// The PulseAudio server to connect to, as in `PULSE_SERVER`.
+    type Options = Option<String>;
+
+    fn subscribe<'l>(
+        server: Option<String>,
+        main_loop: &'l MainLoop,
+        sender: &Rc<EventSender>,
+    ) -> Result<(Rc<Self>, Sources<'l>)> {
+        let mut server = Server::new(server);
+        let mut subscribe = process::Command::new("pactl")
+            .args(server_args(&server.server))
+            .arg("subscribe")
+            .stdout(process::Stdio::piped())
+            .stderr(process::Stdio::null())
+            .spawn()
+            .context("Failed to run pactl")?;
+        let stdout = subscribe.stdout.take();
+        let subscribe = scopeguard::guard(subscribe, |mut subscribe| {
+            let _ = subscribe.kill();
+            let _ = subscribe.wait();
+        });
+
+        // The main loop isn't running yet, so the initial objects can be
+        // listed here to send them before Ready.
+        let events = server.refresh().context(
+            "Failed to list PulseAudio objects (pactl 16 or newer is needed)",
+        )?;
+        let worker = Worker::spawn(DEBOUNCE, move |jobs, report| {
+            server.handle(jobs, report)
+        })?;
+        let pulse = Rc::new(Self {
+            subscribe: ScopeGuard::into_inner(subscribe),
+            worker,
+        });
+        for event in events {
+            sender.send(event);
+        }
+        sender.send_ready();
+
+        let stdout = stdout
+            .ok_or_else(|| anyhow!("Failed to read from pactl subscribe"))?;
+        thread::spawn({
+            let jobs = pulse.worker.sender();
+            move || {
+                for line in BufReader::new(stdout).lines() {
+                    let line = match line {
+                        Ok(line) => line,
+                        Err(e) => {
+                            let _ = jobs.send(Job::Exited(e.to_string()));
+                            return;
+                        }
+                    };
+                    let Some(job) = parse_event(&line) else {
+                        continue;
+                    };
+                    if jobs.send(job).is_err() {
+                        return;
+                    }
+                }
+                let reason = String::from("pactl subscribe exited");
+                let _ = jobs.send(Job::Exited(reason));
+            }
+        });
+
+        let worker_watch = main_loop.loop_().add_io(
+            pulse.worker.fd.as_raw_fd(),
+            libspa::support::system::IoFlags::IN,
+            {
+                let pulse = Rc::downgrade(&pulse);
+                let sender = Rc::clone(sender);
+                let main_loop_weak = main_loop.downgrade();
+                move |_status| {
+                    let Some(pulse) = pulse.upgrade() else {
+                        return;
+                    };
+                    for report in pulse.worker.take() {
+                        match report {
+                            Report::Events(events) => {
+                                for event in events {
+                                    sender.send(event);
+                                }
+                            }
+                            Report::Failed(message) => sender.send_error(
+                                CommandError::Failed(message).into(),
+                            ),
+                            Report::Lost(reason) => {
+                                sender.send_error(Error::Protocol(reason));
+                                if let Some(main_loop) =
+                                    main_loop_weak.upgrade()
+                                {
+                                    main_loop.quit();
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+        );
+
+        let sources = Sources {
+            io: vec![worker_watch],
+            ..Default::default()
+        };
+
+        Ok((pulse, sources))
+    }
+
+    fn command(&self, command: Command) -> Result<(), CommandError> {
+        self.worker.push(Job::Command(command));
+        Ok(())
+    }
+}
+
+fn server_args(server: &Option<String>) -> Vec<&str> {
+    match server {
+        Some(server) => vec!["--server", server],
+        None => Vec::new(),
+    }
+}
+
+/// Parses a line from `pactl subscribe`, like `Event 'change' on sink #1`,
+/// into a job for the worker if it's about an object wiremix shows.
+fn parse_event(line: &str) -> Option<Job> {
+    let (event_type, rest) =
+        line.trim().strip_prefix("Event '")?.split_once("' on ")?;
+    let (facility, index) = match rest.split_once(" #") {
+        Some((facility, index)) => (facility, Some(index.parse().ok()?)),
+        None => (rest, None),
+    };
+    let kind = Kind::from_facility(facility)?;
+    // There is only one server
+    let index = if kind == Kind::Metadata { 0 } else { index? };
+    Some(Job::Changed(kind, index, event_type == "remove"))
+}
+
+/// The server's objects as last listed, which the worker thread keeps for
+/// sending the events of single objects and for executing commands.
+struct Server {
+    server: Option<String>,
+    info: Value,
+    lists: HashMap<Kind, Vec<Value>>,
+}
+
+impl Server {
+    fn new(server: Option<String>) -> Self {
+        Self {
+            server,
+            info: Value::Null,
+            lists: HashMap::new(),
+        }
+    }
+
+    /// Runs pactl with args, returning its output.
+    fn pactl(&self, args: &[&str]) -> Result<String> {
+        let output = process::Command::new("pactl")
+            .args(server_args(&self.server))
+            .args(args)
+            .output()
+            .context("Failed to run pactl")?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("pactl {}: {}", args.join(" "), stderr.trim()));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    fn json(&self, args: &[&str]) -> Result<Value> {
+        let output = self.pactl(&[&["--format", "json"], args].concat())?;
+        serde_json::from_str(&output).with_context(|| {
+            format!("Unexpected output from pactl {}", args.join(" "))
+        })
+    }
+
+    /// Lists the objects of a kind again, or the server's info for
+    /// [`Kind::Metadata`].
+    fn list(&mut self, kind: Kind) -> Result<()> {
+        if kind == Kind::Metadata {
+            self.info = self.json(&["info"])?;
+        } else if let Some(list_type) = kind.list_type() {
+            let list = self.json(&["list", list_type])?;
+            let list = list.as_array().cloned().unwrap_or_default();
+            self.lists.insert(kind, list);
+        }
+        Ok(())
+    }
+
+    fn objects(&self, kind: Kind) -> &[Value] {
+        self.lists.get(&kind).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    fn find(&self, kind: Kind, index: u32) -> Option<&Value> {
+        self.objects(kind)
+            .iter()
+            .find(|object| object["index"].as_u64() == Some(index.into()))
+    }
+
+    /// The index of the card a sink or source belongs to, which is the card
+    /// created by the same module.
+    fn card_of(&self, kind: Kind, index: u32) -> Option<u32> {
+        let module = &self.find(kind, index)?["owner_module"];
+        if module.is_null() {
+            return None;
+        }
+        let card = self
+            .objects(Kind::Card)
+            .iter()
+            .find(|card| &card["owner_module"] == module)?;
+        card["index"].as_u64().map(|index| index as u32)
+    }
+
+    /// Lists every object, returning the events for all of them.
+    fn refresh(&mut self) -> Result<Vec<MonitorEvent>> {
+        let mut objects = vec![(Kind::Metadata, 0)];
+        for kind in Kind::ALL {
+            if kind.list_type().is_none() {
+                continue;
+            }
+            self.list(kind)?;
+            for object in self.objects(kind) {
+                if let Some(index) = object["index"].as_u64() {
+                    objects.push((kind, index as u32));
+                }
+            }
+        }
+        self.list(Kind::Metadata)?;
+
+        Ok(objects
+            .into_iter()
+            .flat_map(|(kind, index)| self.events(kind, index))
+            .collect())
+    }
+
+    /// Handles a batch of jobs, listing each kind of object which changed
+    /// once and sending the events for only the objects which changed.
+    fn handle(&mut self, jobs: Vec<Job>, report: &dyn Fn(Report)) {
+        let mut changed: Vec<(Kind, u32)> = Vec::new();
+        let mut events = Vec::new();
+        for job in jobs {
+            match job {
+                Job::Changed(kind, index, removed) => {
+                    // Cards hold the routes of their sinks and sources
+                    if let Some(card) = self.card_of(kind, index) {
+                        changed.push((Kind::Card, card));
+                    }
+                    if removed {
+                        changed.retain(|&object| object != (kind, index));
+                        if let Some(list) = self.lists.get_mut(&kind) {
+                            list.retain(|object| {
+                                object["index"].as_u64() != Some(index.into())
+                            });
+                        }
+                        events.extend(removed_events(kind, index));
+                    } else {
+                        changed.push((kind, index));
+                    }
+                }
+                Job::Command(command) => {
+                    if let Err(e) = self.execute(command) {
+                        report(Report::Failed(format!("{:#}", e)));
+                    }
+                }
+                Job::Exited(reason) => report(Report::Lost(reason)),
+            }
+        }
+
+        let mut kinds: Vec<Kind> =
+            changed.iter().map(|&(kind, _)| kind).collect();
+        kinds.sort_by_key(|&kind| kind as u32);
+        kinds.dedup();
+        for kind in kinds {
+            if let Err(e) = self.list(kind) {
+                return report(Report::Lost(format!("{:#}", e)));
+            }
+        }
+
+        // Sinks and sources which were just added aren't known to be on a
+        // card until they have been listed.
+        for (kind, index) in changed.clone() {
+            if let Some(card) = self.card_of(kind, index) {
+                changed.push((Kind::Card, card));
+            }
+        }
+        let mut sent = Vec::new();
+        for object in changed {
+            if !sent.contains(&object) {
+                events.extend(self.events(object.0, object.1));
+                sent.push(object);
+            }
+        }
+
+        if !events.is_empty() {
+            report(Report::Events(events));
+        }
+    }
+
+    /// The events for an object as last listed, or for its removal if it
+    /// wasn't listed.
+    fn events(&self, kind: Kind, index: u32) -> Vec<MonitorEvent> {
+        if kind == Kind::Metadata {
+            return metadata_events(&self.info);
+        }
+        let Some(object) = self.find(kind, index) else {
+            return removed_events(kind, index);
+        };
+        if kind == Kind::Card {
+            return card_events(
+                object,
+                self.objects(Kind::Sink),
+                self.objects(Kind::Source),
+            );
+        }
+
+        let mut events = object_events(kind, object);
+        let card = self.card_of(kind, index);
+        if let (Some(card), Some(card_device)) = (card, kind.card_device(index))
+        {
+            let object_id = kind.object_id(index);
+            events.push(MonitorEvent::NodeDeviceId(
+                object_id,
+                Kind::Card.object_id(card),
+            ));
+            events.push(MonitorEvent::NodeCardProfileDevice(
+                object_id,
+                card_device,
+            ));
+        }
+        events
+    }
+
+    fn card(&self, device_id: ObjectId) -> Result<&Value> {
+        Kind::from_object_id(device_id)
+            .filter(|&(kind, _)| kind == Kind::Card)
+            .and_then(|(kind, index)| self.find(kind, index))
+            .ok_or_else(|| anyhow!("Card {} not found", device_id.to_string()))
+    }
+
+    fn execute(&self, command: Command) -> Result<()> {
+        match command {
+            Command::NodeVolumes(object_id, volumes) => {
+                if let Some((kind, index)) = Kind::from_object_id(object_id) {
+                    self.set_volumes(kind, index, &volumes)?;
+                }
+            }
+            Command::NodeMute(object_id, mute) => {
+                if let Some((kind, index)) = Kind::from_object_id(object_id) {
+                    self.set_mute(kind, index, mute)?;
+                }
+            }
+            Command::DeviceVolumes(_, _, card_device, volumes) => {
+                if let Some((kind, index)) = Kind::from_card_device(card_device)
+                {
+                    self.set_volumes(kind, index, &volumes)?;
+                }
+            }
+            Command::DeviceMute(_, _, card_device, mute) => {
+                if let Some((kind, index)) = Kind::from_card_device(card_device)
+                {
+                    self.set_mute(kind, index, mute)?;
+                }
+            }
+            Command::DeviceSetRoute(device_id, route_index, card_device) => {
+                let card = self.card(device_id)?;
+                let port = card["ports"]
+                    .as_object()
+                    .and_then(|ports| ports.keys().nth(route_index as usize))
+                    .ok_or_else(|| anyhow!("Port {} not found", route_index))?;
+                let Some((kind, index)) = Kind::from_card_device(card_device)
+                else {
+                    return Ok(());
+                };
+                let Some(name) = kind.command_name() else {
+                    return Ok(());
+                };
+                self.pactl(&[
+                    &format!("set-{}-port", name),
+                    &index.to_string(),
+                    port,
+                ])?;
+            }
+            Command::DeviceSetProfile(device_id, profile_index) => {
+                let card = self.card(device_id)?;
+                let profile = card["profiles"]
+                    .as_object()
+                    .and_then(|profiles| {
+                        profiles.keys().nth(profile_index as usize)
+                    })
+                    .ok_or_else(|| {
+                        anyhow!("Profile {} not found", profile_index)
+                    })?;
+                let card_index = card["index"].to_string();
+                self.pactl(&["set-card-profile", &card_index, profile])?;
+            }
+            Command::MetadataSetProperty(_, 0, key, _, Some(value)) => {
+                let command = match key.as_str() {
+                    "default.configured.audio.sink" => "set-default-sink",
+                    "default.configured.audio.source" => "set-default-source",
+                    _ => return Ok(()),
+                };
+                let value: Value = serde_json::from_str(&value)?;
+                if let Some(name) = value["name"].as_str() {
+                    self.pactl(&[command, name])?;
+                }
+            }
+            Command::MetadataSetProperty(_, subject, key, _, value) => {
+                if key != "target.node" {
+                    return Ok(());
+                }
+                let Some((kind, index)) =
+                    Kind::from_object_id(ObjectId::from_raw_id(subject))
+                else {
+                    return Ok(());
+                };
+                let (command, default) = match kind {
+                    Kind::SinkInput => ("move-sink-input", "@DEFAULT_SINK@"),
+                    Kind::SourceOutput => {
+                        ("move-source-output", "@DEFAULT_SOURCE@")
+                    }
+                    _ => return Ok(()),
+                };
+                let target = value
+                    .and_then(|value| value.parse::<u32>().ok())
+                    .and_then(|target| {
+                        Kind::from_object_id(ObjectId::from_raw_id(target))
+                    })
+                    .map(|(_, target)| target.to_string())
+                    .unwrap_or_else(|| String::from(default));
+                self.pactl(&[command, &index.to_string(), &target])?;
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn set_volumes(
+        &self,
+        kind: Kind,
+        index: u32,
+        volumes: &[f32],
+    ) -> Result<()> {
+        let Some(name) = kind.command_name() else {
+            return Ok(());
+        };
+        let mut args = vec![format!("set-{}-volume", name), index.to_string()];
+        args.extend(volumes.iter().map(|volume| {
+            ((volume.cbrt() * VOLUME_NORM).round() as u32).to_string()
+        }));
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        self.pactl(&args)?;
+        Ok(())
+    }
+
+    fn set_mute(&self, kind: Kind, index: u32, mute: bool) -> Result<()> {
+        let Some(name) = kind.command_name() else {
+            return Ok(());
+        };
+        self.pactl(&[
+            &format!("set-{}-mute", name),
+            &index.to_string(),
+            if mute { "1" } else { "0" },
+        ])?;
+        Ok(())
+    }
+}
+
+/// The events for removing an object, along with its link if it's a stream.
+fn removed_events(kind: Kind, index: u32) -> Vec<MonitorEvent> {
+    let mut events = vec![MonitorEvent::Removed(kind.object_id(index))];
+    let link = match kind {
+        Kind::SinkInput => Some(Kind::SinkInputLink),
+        Kind::SourceOutput => Some(Kind::SourceOutputLink),
+        _ => None,
+    };
+    if let Some(link) = link {
+        events.push(MonitorEvent::Removed(link.object_id(index)));
+    }
+    events
+}
+
+/// Events for the "default" metadata from the output of `pactl info`.
+fn metadata_events(info: &Value) -> Vec<MonitorEvent> {
+    let metadata_id = Kind::Metadata.object_id(0);
+    let mut events = vec![MonitorEvent::MetadataMetadataName(
+        metadata_id,
+        String::from("default"),
+    )];
+    for (key, field) in [
+        ("default.audio.sink", "default_sink_name"),
+        ("default.audio.source", "default_source_name"),
+    ] {
+        let value = info[field]
+            .as_str()
+            .map(|name| json!({ "name": name }).to_string());
+        events.push(MonitorEvent::MetadataProperty(
+            metadata_id,
+            0,
+            Some(String::from(key)),
+            value,
+        ));
+    }
+    events
+}
+
+/// Events for an object from the output of `pactl list`.
+fn object_events(kind: Kind, object: &Value) -> Vec<MonitorEvent> {
+    let Some(index) = object["index"].as_u64() else {
+        return Vec::new();
+    };
+    let index = index as u32;
+    let object_id = kind.object_id(index);
+    let properties = &object["properties"];
+
+    let mut events = vec![
+        MonitorEvent::NodeMediaClass(
+            object_id,
+            MediaClass::from(kind.media_class()),
+        ),
+        MonitorEvent::NodeObjectSerial(
+            object_id,
+            ((kind as u32) << Kind::SHIFT | index) as i32,
+        ),
+    ];
+
+    let name = object["name"]
+        .as_str()
+        .or_else(|| properties["node.name"].as_str())
+        .or_else(|| properties["application.name"].as_str());
+    if let Some(name) = name {
+        events.push(MonitorEvent::NodeName(object_id, String::from(name)));
+    }
+    if let Some(description) = object["description"].as_str() {
+        events.push(MonitorEvent::NodeDescription(
+            object_id,
+            String::from(description),
+        ));
+    }
+    if let Some(media_name) = properties["media.name"].as_str() {
+        events.push(MonitorEvent::NodeMediaName(
+            object_id,
+            String::from(media_name),
+        ));
+    }
+    if let Some(media_role) = properties["media.role"].as_str() {
+        events.push(MonitorEvent::NodeMediaRole(
+            object_id,
+            String::from(media_role),
+        ));
+    }
+    if let Some(volumes) = volumes(object) {
+        events.push(MonitorEvent::NodeVolumes(object_id, volumes));
+    }
+    if let Some(mute) = object["mute"].as_bool() {
+        events.push(MonitorEvent::NodeMute(object_id, mute));
+    }
+    // Streams are corked when paused
+    if let Some(corked) = object["corked"].as_bool() {
+        let state = if corked { "idle" } else { "running" };
+        events.push(MonitorEvent::NodeState(
+            object_id,
+            String::from(state),
+            None,
+        ));
+    }
+
+    let link = match kind {
+        Kind::SinkInput => object["sink"].as_u64().map(|sink| {
+            (
+                Kind::SinkInputLink,
+                object_id,
+                Kind::Sink.object_id(sink as u32),
+            )
+        }),
+        Kind::SourceOutput => object["source"].as_u64().map(|source| {
+            (
+                Kind::SourceOutputLink,
+                Kind::Source.object_id(source as u32),
+                object_id,
+            )
+        }),
+        _ => None,
+    };
+    if let Some((link_kind, output, input)) = link {
+        events.push(MonitorEvent::Link(
+            link_kind.object_id(index),
+            output,
+            input,
+        ));
+    }
+
+    events
+}
+
+/// Events for a card from the output of `pactl list cards`, including the
+/// routes of its sinks and sources. Profiles and ports are indexed by their
+/// order in the output, which is sorted by name.
+fn card_events(
+    card: &Value,
+    sinks: &[Value],
+    sources: &[Value],
+) -> Vec<MonitorEvent> {
+    let Some(index) = card["index"].as_u64() else {
+        return Vec::new();
+    };
+    let index = index as u32;
+    let device_id = Kind::Card.object_id(index);
+
+    let mut events = vec![
+        MonitorEvent::DeviceMediaClass(
+            device_id,
+            MediaClass::from("Audio/Device"),
+        ),
+        MonitorEvent::DeviceObjectSerial(
+            device_id,
+            ((Kind::Card as u32) << Kind::SHIFT | index) as i32,
+        ),
+    ];
+    if let Some(name) = card["name"].as_str() {
+        events.push(MonitorEvent::DeviceName(device_id, String::from(name)));
+    }
+    if let Some(description) = card["properties"]["device.description"].as_str()
+    {
+        events.push(MonitorEvent::DeviceDescription(
+            device_id,
+            String::from(description),
+        ));
+    }
+
+    // The card's sinks and sources with their card devices
+    let module = &card["owner_module"];
+    let devices: Vec<(Kind, i32, &Value)> =
+        [(Kind::Sink, sinks), (Kind::Source, sources)]
+            .into_iter()
+            .flat_map(|(kind, objects)| {
+                objects
+                    .iter()
+                    .filter(|object| {
+                        !module.is_null() && &object["owner_module"] == module
+                    })
+                    .filter_map(move |object| {
+                        let index = object["index"].as_u64()? as u32;
+                        Some((kind, kind.card_device(index)?, object))
+                    })
+            })
+            .collect();
+
+    let profiles = card["profiles"].as_object();
+    let profile_index = |name: &str| {
+        profiles
+            .and_then(|profiles| profiles.keys().position(|key| key == name))
+            .map(|index| index as i32)
+    };
+    let active = card["active_profile"].as_str().and_then(profile_index);
+    for (index, (name, profile)) in profiles.into_iter().flatten().enumerate() {
+        let index = index as i32;
+        // Only the active profile's sinks and sources exist to be listed
+        let classes = if Some(index) == active {
+            [Kind::Sink, Kind::Source]
+                .into_iter()
+                .map(|kind| {
+                    let card_devices = devices
+                        .iter()
+                        .filter(|&&(device_kind, ..)| device_kind == kind)
+                        .map(|&(_, card_device, _)| card_device)
+                        .collect();
+                    (MediaClass::from(kind.media_class()), card_devices)
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        events.push(MonitorEvent::DeviceEnumProfile(
+            device_id,
+            index,
+            name.clone(),
+            String::from(profile["description"].as_str().unwrap_or(name)),
+            profile["available"].as_bool().unwrap_or(true),
+            classes,
+        ));
+    }
+    if let Some(active) = active {
+        events.push(MonitorEvent::DeviceProfile(device_id, active));
+    }
+
+    let ports = card["ports"].as_object();
+    for (index, (name, port)) in ports.into_iter().flatten().enumerate() {
+        let index = index as i32;
+        let description = port["description"].as_str().unwrap_or(name);
+        let available = port["availability"].as_str() != Some("not available");
+        let port_profiles = port["profiles"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|profile| profile_index(profile.as_str()?))
+            .collect();
+        let has_port = |object: &Value| {
+            object["ports"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .any(|port| port["name"].as_str() == Some(name))
+        };
+        let port_devices = devices
+            .iter()
+            .filter(|(_, _, object)| has_port(object))
+            .map(|&(_, card_device, _)| card_device)
+            .collect();
+        events.push(MonitorEvent::DeviceEnumRoute(
+            device_id,
+            index,
+            String::from(description),
+            available,
+            port_profiles,
+            port_devices,
+        ));
+
+        // The active ports are the routes of the sinks and sources, which
+        // hold their volumes for wiremix.
+        let Some(active) = active else {
+            continue;
+        };
+        for &(_, card_device, object) in &devices {
+            if object["active_port"].as_str() != Some(name) {
+                continue;
+            }
+            events.push(MonitorEvent::DeviceRoute(
+                device_id,
+                index,
+                card_device,
+                vec![active],
+                String::from(description),
+                available,
+                volumes(object).unwrap_or_default(),
+                object["mute"].as_bool().unwrap_or_default(),
+            ));
+        }
+    }
+
+    events
+}
+
+/// Linear channel volumes of an object, in the order of its channel map.
+fn volumes(object: &Value) -> Option<Vec<f32>> {
+    let volume = object["volume"].as_object()?;
+    object["channel_map"]
+        .as_str()?
+        .split(',')
+        .map(|channel| {
+            let value = volume.get(channel)?["value"].as_f64()? as f32;
+            Some((value / VOLUME_NORM).powi(3))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kind_object_id_round_trip() {
+        for kind in Kind::ALL {
+            let object_id = kind.object_id(42);
+            assert_eq!(Kind::from_object_id(object_id), Some((kind, 42)));
+        }
+        assert_ne!(Kind::Sink.object_id(1), Kind::Source.object_id(1));
+    }
+
+    #[test]
+    fn parse_subscribe_events() {
+        assert!(matches!(
+            parse_event("Event 'change' on sink-input #12"),
+            Some(Job::Changed(Kind::SinkInput, 12, false))
+        ));
+        assert!(matches!(
+            parse_event("Event 'remove' on card #3"),
+            Some(Job::Changed(Kind::Card, 3, true))
+        ));
+        assert!(matches!(
+            parse_event("Event 'change' on server #4294967295"),
+            Some(Job::Changed(Kind::Metadata, 0, false))
+        ));
+        assert!(parse_event("Event 'new' on client #40").is_none());
+        assert!(parse_event("Event 'change' on sink").is_none());
+    }
+
+    #[test]
+    fn card_device_round_trip() {
+        for kind in [Kind::Sink, Kind::Source] {
+            let card_device = kind.card_device(7).unwrap();
+            assert_eq!(Kind::from_card_device(card_device), Some((kind, 7)));
+        }
+        assert_eq!(Kind::SinkInput.card_device(7), None);
+    }
+
+    #[test]
+    fn card_routes_of_sinks() {
+        let card = json!({
+            "index": 1,
+            "name": "alsa_card.pci-0000_00_1f.3",
+            "owner_module": 7,
+            "properties": { "device.description": "Built-in Audio" },
+            "profiles": {
+                "off": { "description": "Off", "available": true },
+                "output:analog-stereo": {
+                    "description": "Analog Stereo Output",
+                    "available": true,
+                },
+            },
+            "active_profile": "output:analog-stereo",
+            "ports": {
+                "analog-output-headphones": {
+                    "description": "Headphones",
+                    "availability": "not available",
+                    "profiles": ["output:analog-stereo"],
+                },
+                "analog-output-speaker": {
+                    "description": "Speakers",
+                    "availability": "available",
+                    "profiles": ["output:analog-stereo"],
+                },
+            },
+        });
+        let sink = json!({
+            "index": 4,
+            "owner_module": 7,
+            "channel_map": "mono",
+            "volume": { "mono": { "value": 65536 } },
+            "mute": false,
+            "ports": [
+                { "name": "analog-output-headphones" },
+                { "name": "analog-output-speaker" },
+            ],
+            "active_port": "analog-output-speaker",
+        });
+        let other = json!({ "index": 5, "owner_module": 8 });
+        let card_device = Kind::Sink.card_device(4).unwrap();
+
+        let events = card_events(&card, &[sink, other], &[]);
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, MonitorEvent::DeviceProfile(_, 1))));
+        assert!(events.iter().any(|event| matches!(
+            event,
+            MonitorEvent::DeviceEnumRoute(_, 0, _, false, profiles, devices)
+                if profiles == &[1] && devices == &[card_device]
+        )));
+        let routes = events
+            .iter()
+            .filter_map(|event| match event {
+                MonitorEvent::DeviceRoute(_, index, device, profiles, ..) => {
+                    Some((*index, *device, profiles.clone()))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(routes, vec![(1, card_device, vec![1])]);
+    }
+
+    #[test]
+    fn volumes_in_channel_map_order() {
+        let object = json!({
+            "channel_map": "front-left,front-right",
+            "volume": {
+                "front-right": { "value": 32768 },
+                "front-left": { "value": 65536 },
+            },
+        });
+        assert_eq!(volumes(&object), Some(vec![1.0, 0.125]));
+    }
+
+    #[test]
+    fn volumes_missing_channel() {
+        let object = json!({
+            "channel_map": "front-left,front-right",
+            "volume": { "front-left": { "value": 65536 } },
+        });
+        assert_eq!(volumes(&object), None);
+    }
+}
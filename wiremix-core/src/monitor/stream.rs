@@ -1,6 +1,8 @@
 use std::mem;
 use std::rc::Rc;
 
+use anyhow::{anyhow, Result};
+
 use pipewire::{
     core::Core,
     properties::properties,
@@ -30,7 +32,7 @@ pub fn capture_node(
     obj_id: ObjectId,
     serial: &str,
     capture_sink: bool,
-) -> Option<(Rc<Stream>, StreamListener<StreamData>)> {
+) -> Result<(Rc<Stream>, StreamListener<StreamData>)> {
     let mut props = properties! {
         *pipewire::keys::TARGET_OBJECT => String::from(serial),
         *pipewire::keys::STREAM_MONITOR => "true",
@@ -45,7 +47,7 @@ pub fn capture_node(
         cursor_move: false,
     };
 
-    let stream = Stream::new(core, "wiremix-capture", props).ok()?;
+    let stream = Stream::new(core, "wiremix-capture", props)?;
     let stream = Rc::new(stream);
     let listener = stream
         .add_local_listener_with_user_data(data)
@@ -129,8 +131,7 @@ pub fn capture_node(
                 }
             }
         })
-        .register()
-        .ok()?;
+        .register()?;
 
     let mut audio_info = AudioInfoRaw::new();
     audio_info.set_format(AudioFormat::F32LE);
@@ -144,21 +145,20 @@ pub fn capture_node(
             std::io::Cursor::new(Vec::new()),
             &pipewire::spa::pod::Value::Object(pod_obj),
         )
-        .ok()?
+        .map_err(|_| anyhow!("Couldn't serialize the capture format"))?
         .0
         .into_inner();
 
-    let mut params = [Pod::from_bytes(&values)?];
+    let mut params = [Pod::from_bytes(&values)
+        .ok_or_else(|| anyhow!("Invalid capture format"))?];
 
-    stream
-        .connect(
-            libspa::utils::Direction::Input,
-            None,
-            pipewire::stream::StreamFlags::AUTOCONNECT
-                | pipewire::stream::StreamFlags::MAP_BUFFERS,
-            &mut params,
-        )
-        .ok()?;
+    stream.connect(
+        libspa::utils::Direction::Input,
+        None,
+        pipewire::stream::StreamFlags::AUTOCONNECT
+            | pipewire::stream::StreamFlags::MAP_BUFFERS,
+        &mut params,
+    )?;
 
-    Some((stream, listener))
+    Ok((stream, listener))
 }
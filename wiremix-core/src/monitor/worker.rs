@@ -0,0 +1,104 @@
+//! A thread for the blocking work of backends which control their server by
+//! running other programs, so that it stays out of the main loop.
+
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use nix::sys::eventfd::{EfdFlags, EventFd};
+
+/// A thread handling jobs in batches, whose results are taken on the main
+/// loop once [`Worker::fd`] is readable.
+pub struct Worker<J, R> {
+    jobs: mpsc::Sender<J>,
+    results: mpsc::Receiver<R>,
+    /// EventFd for signalling to the main loop that there are results.
+    pub fd: Arc<EventFd>,
+}
+
+impl<J: Send + 'static, R: Send + 'static> Worker<J, R> {
+    /// Spawns a thread which waits for a job, collects any more which arrive
+    /// within `debounce`, and passes them to `handle` along with a function
+    /// for reporting results. The thread exits once every sender of jobs has
+    /// been dropped.
+    pub fn spawn(
+        debounce: Duration,
+        mut handle: impl FnMut(Vec<J>, &dyn Fn(R)) + Send + 'static,
+    ) -> Result<Self> {
+        let fd =
+            Arc::new(EventFd::from_value_and_flags(0, EfdFlags::EFD_NONBLOCK)?);
+        let (jobs_tx, jobs_rx) = mpsc::channel();
+        let (results_tx, results_rx) = mpsc::channel();
+
+        thread::spawn({
+            let fd = Arc::clone(&fd);
+            move || {
+                let report = |result| {
+                    if results_tx.send(result).is_ok() {
+                        let _ = fd.arm();
+                    }
+                };
+                while let Ok(job) = jobs_rx.recv() {
+                    let deadline = Instant::now() + debounce;
+                    let mut jobs = vec![job];
+                    while let Some(timeout) =
+                        deadline.checked_duration_since(Instant::now())
+                    {
+                        match jobs_rx.recv_timeout(timeout) {
+                            Ok(job) => jobs.push(job),
+                            Err(_) => break,
+                        }
+                    }
+                    handle(jobs, &report);
+                }
+            }
+        });
+
+        Ok(Self {
+            jobs: jobs_tx,
+            results: results_rx,
+            fd,
+        })
+    }
+
+    /// Queues a job for the thread.
+    pub fn push(&self, job: J) {
+        let _ = self.jobs.send(job);
+    }
+
+    /// A sender for queueing jobs from other threads.
+    pub fn sender(&self) -> mpsc::Sender<J> {
+        self.jobs.clone()
+    }
+
+    /// Takes the results reported so far.
+    pub fn take(&self) -> Vec<R> {
+        let _ = self.fd.read();
+        self.results.try_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jobs_are_debounced_into_batches() {
+        let worker = Worker::spawn(
+            Duration::from_millis(100),
+            |jobs: Vec<u32>, report| report(jobs),
+        )
+        .unwrap();
+        worker.push(1);
+        worker.push(2);
+
+        let start = Instant::now();
+        let mut results = Vec::new();
+        while results.is_empty() && start.elapsed() < Duration::from_secs(5) {
+            thread::sleep(Duration::from_millis(10));
+            results = worker.take();
+        }
+        assert_eq!(results, vec![vec![1, 2]]);
+    }
+}
@@ -26,7 +26,7 @@ impl ToString for ObjectId {
 }
 
 impl ObjectId {
-    pub fn from_raw_id(id: u32) -> Self {
+    pub const fn from_raw_id(id: u32) -> Self {
         ObjectId(id)
     }
 }
@@ -10,6 +10,7 @@ use crate::object::ObjectId;
 #[derive(Debug)]
 pub struct Profile {
     pub index: i32,
+    pub name: String,
     pub description: String,
     pub available: bool,
     pub classes: Vec<(MediaClass, Vec<i32>)>,
@@ -47,6 +48,8 @@ pub struct Device {
     pub profiles: HashMap<i32, Profile>,
     pub routes: HashMap<i32, Route>,
     pub enum_routes: HashMap<i32, EnumRoute>,
+    /// Battery percentage of a Bluetooth device
+    pub battery: Option<u8>,
 }
 
 #[derive(Default, Debug)]
@@ -54,6 +57,25 @@ pub struct Client {
     pub id: ObjectId,
     pub application_name: Option<String>,
     pub application_process_binary: Option<String>,
+    pub application_process_id: Option<String>,
+    /// Read from /proc using application_process_id
+    pub process_cmdline: Option<String>,
+    pub process_cgroup: Option<String>,
+    pub flatpak_app_id: Option<String>,
+    pub pipewire_access: Option<String>,
+    pub portal_app_id: Option<String>,
+    pub snap_app_id: Option<String>,
+}
+
+/// Negotiated format of a node's audio
+#[derive(Debug, Clone, PartialEq)]
+pub struct Format {
+    /// Sample format, e.g. S16LE or F32LE
+    pub sample_format: String,
+    pub rate: u32,
+    pub channels: u32,
+    /// Channel positions, e.g. FL and FR
+    pub channel_map: Vec<String>,
 }
 
 #[derive(Default, Debug)]
@@ -64,20 +86,40 @@ pub struct Node {
     pub description: Option<String>,
     pub media_class: Option<MediaClass>,
     pub media_name: Option<String>,
+    /// What a stream is for, e.g. "Music" or "Notification"
+    pub media_role: Option<String>,
     pub object_serial: Option<i32>,
     pub volumes: Option<Vec<f32>>,
     pub mute: Option<bool>,
     pub peaks: Option<Vec<f32>>,
     pub rate: Option<u32>,
     pub positions: Option<Vec<u32>>,
+    pub format: Option<Format>,
+    pub resample_quality: Option<String>,
+    /// Whether the node spreads stereo over all its channels
+    pub upmix: Option<bool>,
+    /// Whether this is a network sink or source, e.g. AirPlay or a tunnel
+    pub network: Option<bool>,
+    /// API used by the node's client, e.g. "jack" or "pipewire-pulse"
+    pub client_api: Option<String>,
+    /// State like "running" or "suspended", and the error for "error"
+    pub state: Option<(String, Option<String>)>,
+    /// Requested latency as a fraction of a second, e.g. 1024/48000
+    pub latency: Option<String>,
     pub device_id: Option<ObjectId>,
     pub client_id: Option<ObjectId>,
     pub card_profile_device: Option<i32>,
 }
 
 impl Node {
-    /// Update peaks with VU-meter-style ballistics
-    pub fn update_peaks(&mut self, peaks: &Vec<f32>, samples: u32) {
+    /// Whether the node belongs to a client of the JACK compatibility layer.
+    pub fn is_jack(&self) -> bool {
+        self.client_api.as_deref() == Some("jack")
+    }
+
+    /// Update peaks with VU-meter-style ballistics, or hold the highest peak
+    /// if `hold` is set.
+    pub fn update_peaks(&mut self, peaks: &Vec<f32>, samples: u32, hold: bool) {
         let Some(rate) = self.rate else {
             return;
         };
@@ -98,7 +140,11 @@ impl Node {
 
         // Update the peaks in-place.
         for (current_peak, new_peak) in peaks_ref.iter_mut().zip(peaks) {
-            *current_peak += (new_peak - *current_peak) * coef
+            if hold {
+                *current_peak = current_peak.max(*new_peak);
+            } else {
+                *current_peak += (new_peak - *current_peak) * coef
+            }
         }
     }
 }
@@ -144,9 +190,22 @@ pub struct State {
     pub metadatas_by_name: HashMap<String, ObjectId>,
     /// Used to optimize view rebuilding based on what has changed
     pub dirty: StateDirty,
+    /// Hold the highest peaks until [`State::reset_held_peaks`] instead of
+    /// smoothing them
+    pub hold_peaks: bool,
 }
 
 impl State {
+    /// Start holding peaks afresh. Used with [`State::hold_peaks`] after the
+    /// held peaks have been displayed.
+    pub fn reset_held_peaks(&mut self) {
+        for node in self.nodes.values_mut() {
+            if let Some(peaks) = &mut node.peaks {
+                peaks.fill(0.0);
+            }
+        }
+    }
+
     /// Update the state based on the supplied event.
     ///
     /// Returns a list of [`Command`](`crate::command::Command`)s to be
@@ -184,12 +243,16 @@ impl State {
             MonitorEvent::DeviceNick(id, nick) => {
                 self.device_entry(id).nick = Some(nick);
             }
+            MonitorEvent::DeviceBattery(id, battery) => {
+                self.device_entry(id).battery = Some(battery);
+            }
             MonitorEvent::DeviceObjectSerial(id, object_serial) => {
                 self.device_entry(id).object_serial = Some(object_serial);
             }
             MonitorEvent::DeviceEnumProfile(
                 id,
                 index,
+                name,
                 description,
                 available,
                 classes,
@@ -198,6 +261,7 @@ impl State {
                     index,
                     Profile {
                         index,
+                        name,
                         description,
                         available,
                         classes,
@@ -259,6 +323,31 @@ impl State {
                 self.client_entry(id).application_process_binary =
                     Some(application_process_binary);
             }
+            MonitorEvent::ClientApplicationProcessId(
+                id,
+                application_process_id,
+            ) => {
+                self.client_entry(id).application_process_id =
+                    Some(application_process_id);
+            }
+            MonitorEvent::ClientFlatpakAppId(id, flatpak_app_id) => {
+                self.client_entry(id).flatpak_app_id = Some(flatpak_app_id);
+            }
+            MonitorEvent::ClientPipewireAccess(id, pipewire_access) => {
+                self.client_entry(id).pipewire_access = Some(pipewire_access);
+            }
+            MonitorEvent::ClientPortalAppId(id, portal_app_id) => {
+                self.client_entry(id).portal_app_id = Some(portal_app_id);
+            }
+            MonitorEvent::ClientSnapAppId(id, snap_app_id) => {
+                self.client_entry(id).snap_app_id = Some(snap_app_id);
+            }
+            MonitorEvent::ClientProcessCgroup(id, process_cgroup) => {
+                self.client_entry(id).process_cgroup = Some(process_cgroup);
+            }
+            MonitorEvent::ClientProcessCmdline(id, process_cmdline) => {
+                self.client_entry(id).process_cmdline = Some(process_cmdline);
+            }
             MonitorEvent::NodeCardProfileDevice(id, card_profile_device) => {
                 self.node_entry(id).card_profile_device =
                     Some(card_profile_device);
@@ -282,12 +371,18 @@ impl State {
             MonitorEvent::NodeMediaName(id, media_name) => {
                 self.node_entry(id).media_name = Some(media_name);
             }
+            MonitorEvent::NodeMediaRole(id, media_role) => {
+                self.node_entry(id).media_role = Some(media_role);
+            }
             MonitorEvent::NodeMute(id, mute) => {
                 self.node_entry(id).mute = Some(mute);
             }
             MonitorEvent::NodeName(id, name) => {
                 self.node_entry(id).name = Some(name);
             }
+            MonitorEvent::NodeLatency(id, latency) => {
+                self.node_entry(id).latency = Some(latency);
+            }
             MonitorEvent::NodeNick(id, nick) => {
                 self.node_entry(id).nick = Some(nick);
             }
@@ -299,11 +394,41 @@ impl State {
                 }
             }
             MonitorEvent::NodePeaks(id, peaks, samples) => {
-                self.node_entry(id).update_peaks(&peaks, samples);
+                let hold = self.hold_peaks;
+                self.node_entry(id).update_peaks(&peaks, samples, hold);
+            }
+            MonitorEvent::NodeFormat(
+                id,
+                sample_format,
+                rate,
+                channels,
+                channel_map,
+            ) => {
+                self.node_entry(id).format = Some(Format {
+                    sample_format,
+                    rate,
+                    channels,
+                    channel_map,
+                });
             }
             MonitorEvent::NodeRate(id, rate) => {
                 self.node_entry(id).rate = Some(rate);
             }
+            MonitorEvent::NodeResampleQuality(id, resample_quality) => {
+                self.node_entry(id).resample_quality = Some(resample_quality);
+            }
+            MonitorEvent::NodeClientApi(id, client_api) => {
+                self.node_entry(id).client_api = Some(client_api);
+            }
+            MonitorEvent::NodeNetwork(id, network) => {
+                self.node_entry(id).network = Some(network);
+            }
+            MonitorEvent::NodeState(id, state, error) => {
+                self.node_entry(id).state = Some((state, error));
+            }
+            MonitorEvent::NodeUpmix(id, upmix) => {
+                self.node_entry(id).upmix = Some(upmix);
+            }
             MonitorEvent::NodePositions(id, positions) => {
                 if let Some(node) = self.nodes.get(&id) {
                     let changed = node
@@ -351,6 +476,8 @@ impl State {
                     None => properties.clear(),
                 };
             }
+            // Errors are reported to the user without changing the state.
+            MonitorEvent::ObjectError(..) => {}
             MonitorEvent::StreamStopped(id) => {
                 // It's likely that the node doesn't exist anymore.
                 self.nodes.entry(id).and_modify(|node| node.peaks = None);
@@ -582,4 +709,29 @@ mod tests {
         assert!(get_metadata_properties(&state, &obj_id, 0).is_empty());
         assert!(!get_metadata_properties(&state, &obj_id, 1).is_empty());
     }
+
+    #[test]
+    fn state_hold_peaks() {
+        let mut state = State {
+            hold_peaks: true,
+            ..Default::default()
+        };
+        let mut capture_manager = CaptureManager::default();
+        let obj_id = ObjectId::from_raw_id(0);
+
+        state.update(
+            &mut capture_manager,
+            MonitorEvent::NodeRate(obj_id, 48000),
+        );
+        for peak in [0.5, 0.8, 0.2] {
+            state.update(
+                &mut capture_manager,
+                MonitorEvent::NodePeaks(obj_id, vec![peak], 512),
+            );
+        }
+        assert_eq!(state.nodes[&obj_id].peaks, Some(vec![0.8]));
+
+        state.reset_held_peaks();
+        assert_eq!(state.nodes[&obj_id].peaks, Some(vec![0.0]));
+    }
 }